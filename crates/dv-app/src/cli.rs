@@ -0,0 +1,645 @@
+//! Headless command-line mode
+//!
+//! `frog inspect`, `frog convert`, `frog profile` and `frog render` run
+//! without opening a window, print plain text or JSON (`--json`), and report the outcome in the
+//! exit code so they can be used from scripts. Without a subcommand `frog`
+//! starts the UI, optionally opening files, a workspace and a position given
+//! on the command line (see [`parse_launch`]).
+
+use std::path::{Path, PathBuf};
+use arrow::array::{Array, Float64Array, StringArray};
+use arrow::datatypes::DataType;
+use chrono::{NaiveDate, NaiveDateTime};
+use arrow::util::display::array_value_to_string;
+use serde::Serialize;
+use dv_core::data::DataSource;
+use dv_core::navigation::NavigationRange;
+use dv_data::config::{FileConfig, FileType};
+use dv_data::export::write_batch;
+use dv_data::profile::{profile_csv, profile_source, DataProfile};
+use dv_data::schema::SchemaDetector;
+use dv_data::sources::{read_all, ConfiguredCsvSource, SqliteSource};
+use dv_data::{DataError, SqlEngine};
+
+/// The command succeeded
+pub const EXIT_OK: i32 = 0;
+
+/// The input could not be read or the output could not be written
+pub const EXIT_FAILURE: i32 = 1;
+
+/// The command line was invalid
+pub const EXIT_USAGE: i32 = 2;
+
+/// `profile` found values that failed to parse for their column type
+pub const EXIT_QUALITY: i32 = 3;
+
+/// Rows sampled by `inspect` when `--sample` isn't given
+const DEFAULT_SAMPLE: usize = 1000;
+
+/// Image size of `render` when `--size` isn't given
+const DEFAULT_IMAGE_SIZE: [u32; 2] = [1600, 1000];
+
+const USAGE: &str = "\
+Usage:
+  frog [files...] [launch options]       Start the user interface
+  frog inspect <file> [options]          Print the inferred schema and column statistics
+  frog convert <input> <output> [options]
+                                         Convert to .csv, .parquet, .arrow or .db (SQLite)
+  frog profile <file> [options]          Run data-quality checks
+  frog render <image> [files...] [launch options] [--size WxH] [--range a..b]
+                                         Draw the dashboard to a .png or .svg image, or
+                                         write an interactive .html report
+
+Options:
+  --table <name>    Table to read from a SQLite input (needed when it has several)
+  --sample <rows>   Rows sampled by inspect (default 1000)
+  --json            Print JSON instead of text
+  --size <WxH>      Image size for render (default 1600x1000)
+  --range <a..b>    Rows or times render selects, e.g. 2024-01-01T10:00..2024-01-01T11:00
+  -h, --help        Show this help
+
+Launch options:
+  --table <name>        Table of the SQLite file that follows
+  --workspace <file>    Open a saved workspace instead of the automatic dashboard
+  --nav-column <name>   Navigate by this column
+  --seek <position>     Jump to a row number, or to the first row at or after a
+                        time such as 2024-01-01T10:00
+
+Exit codes: 0 success, 1 failure, 2 invalid arguments, 3 profile found parse failures";
+
+/// A headless command
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Inspect { input: PathBuf, options: Options },
+    Convert { input: PathBuf, output: PathBuf, options: Options },
+    Profile { input: PathBuf, options: Options },
+    Render { output: PathBuf, launch: Launch, size: [u32; 2], range: Option<String> },
+    Help,
+}
+
+/// Flags shared by the commands
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub table: Option<String>,
+    pub sample: usize,
+    pub json: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            table: None,
+            sample: DEFAULT_SAMPLE,
+            json: false,
+        }
+    }
+}
+
+/// Parse the arguments after the program name. Returns `None` when they don't
+/// start with a subcommand, meaning the UI should start.
+pub fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let (name, rest) = args.split_first()?;
+    if !matches!(name.as_str(), "inspect" | "convert" | "profile" | "render" | "help" | "-h" | "--help") {
+        return None;
+    }
+    Some(parse_command(name, rest))
+}
+
+fn parse_command(name: &str, args: &[String]) -> Result<Command, String> {
+    if name == "render" {
+        return parse_render(args);
+    }
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--json" => options.json = true,
+            "--table" => {
+                options.table = Some(args.next().ok_or("--table needs a table name")?.clone());
+            }
+            "--sample" => {
+                let value = args.next().ok_or("--sample needs a row count")?;
+                options.sample = value.parse().map_err(|_| format!("Invalid --sample value: {}", value))?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            _ => positional.push(PathBuf::from(arg)),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let mut next = |what: &str| positional.next().ok_or_else(|| format!("{} needs {}", name, what));
+    let command = match name {
+        "inspect" => Command::Inspect { input: next("an input file")?, options },
+        "profile" => Command::Profile { input: next("an input file")?, options },
+        "convert" => Command::Convert {
+            input: next("an input file")?,
+            output: next("an output file")?,
+            options,
+        },
+        _ => Command::Help,
+    };
+    match positional.next() {
+        Some(extra) => Err(format!("Unexpected argument: {}", extra.display())),
+        None => Ok(command),
+    }
+}
+
+/// `render <image> [launch arguments] [--size WxH] [--range a..b]`
+fn parse_render(args: &[String]) -> Result<Command, String> {
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(Command::Help);
+    }
+    let (output, rest) = args.split_first().ok_or("render needs an output image")?;
+    if output.starts_with("--") {
+        return Err("render needs an output image before the options".to_string());
+    }
+
+    let mut size = DEFAULT_IMAGE_SIZE;
+    let mut range = None;
+    let mut launch_args = Vec::new();
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--size" => {
+                let value = rest.next().ok_or("--size needs WIDTHxHEIGHT")?;
+                size = value.split_once('x')
+                    .and_then(|(w, h)| Some([w.parse().ok()?, h.parse().ok()?]))
+                    .filter(|[w, h]: &[u32; 2]| *w > 0 && *h > 0)
+                    .ok_or_else(|| format!("Invalid --size value: {}", value))?;
+            }
+            "--range" => range = Some(rest.next().ok_or("--range needs start..end")?.clone()),
+            _ => launch_args.push(arg.clone()),
+        }
+    }
+    Ok(Command::Render {
+        output: PathBuf::from(output),
+        launch: parse_launch(&launch_args)?,
+        size,
+        range,
+    })
+}
+
+/// Parse and run a command line, returning the process exit code
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = match parse(args)? {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("frog: {}\n\n{}", message, USAGE);
+            return Some(EXIT_USAGE);
+        }
+    };
+
+    // Keep stdout for results; only warnings go to stderr
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(tracing::Level::WARN)
+        .init();
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("frog: {}", e);
+            return Some(EXIT_FAILURE);
+        }
+    };
+
+    // Views block on the runtime while drawing, so rendering runs outside it
+    if let Command::Render { output, launch, size, range } = &command {
+        return Some(crate::render::run(&runtime, output, launch, *size, range.as_deref()).unwrap_or_else(|e| {
+            eprintln!("frog: {}", e);
+            EXIT_FAILURE
+        }));
+    }
+
+    let result = runtime.block_on(async {
+        match &command {
+            Command::Help => {
+                println!("{}", USAGE);
+                Ok(EXIT_OK)
+            }
+            Command::Inspect { input, options } => inspect(input, options).await,
+            Command::Convert { input, output, options } => convert(input, output, options).await,
+            Command::Profile { input, options } => profile(input, options).await,
+            Command::Render { .. } => unreachable!("rendering runs outside the runtime"),
+        }
+    });
+    Some(result.unwrap_or_else(|e| {
+        eprintln!("frog: {}", e);
+        EXIT_FAILURE
+    }))
+}
+
+/// A file to open in the UI
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchSource {
+    pub path: PathBuf,
+    /// Table to open from a SQLite file
+    pub table: Option<String>,
+}
+
+impl LaunchSource {
+    pub fn is_sqlite(&self) -> bool {
+        is_sqlite(&self.path)
+    }
+}
+
+/// What the UI should open at startup
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Launch {
+    pub sources: Vec<LaunchSource>,
+    pub workspace: Option<PathBuf>,
+    pub nav_column: Option<String>,
+    pub seek: Option<String>,
+}
+
+impl Launch {
+    pub fn is_empty(&self) -> bool {
+        *self == Launch::default()
+    }
+}
+
+/// Parse the arguments of a UI launch, such as
+/// `frog data.csv --table readings db.sqlite --workspace dash.json --seek 2024-01-01T10:00`.
+/// `--table` applies to the SQLite file that follows it.
+pub fn parse_launch(args: &[String]) -> Result<Launch, String> {
+    let mut launch = Launch::default();
+    let mut table = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |what: &str| args.next().cloned().ok_or_else(|| format!("{} needs {}", arg, what));
+        match arg.as_str() {
+            "--table" => table = Some(value("a table name")?),
+            "--workspace" => launch.workspace = Some(PathBuf::from(value("a workspace file")?)),
+            "--nav-column" => launch.nav_column = Some(value("a column name")?),
+            "--seek" => launch.seek = Some(value("a row number or time")?),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            _ => {
+                let source = LaunchSource { path: PathBuf::from(arg), table: table.take() };
+                if source.table.is_some() && !source.is_sqlite() {
+                    return Err(format!("--table applies to SQLite files, not {}", arg));
+                }
+                launch.sources.push(source);
+            }
+        }
+    }
+    if let Some(table) = table {
+        return Err(format!("--table {} must come before a SQLite file", table));
+    }
+    Ok(launch)
+}
+
+/// Print an invalid launch command line with the usage
+pub fn launch_usage_error(message: &str) {
+    eprintln!("frog: {}\n\n{}", message, USAGE);
+}
+
+/// Parse a time given on the command line into milliseconds since the epoch
+pub fn parse_time(text: &str) -> Option<i64> {
+    let text = text.trim().trim_end_matches('Z');
+    let formats = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d %H:%M",
+    ];
+    formats.iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            ["%Y-%m-%d", "%Y/%m/%d"].iter()
+                .find_map(|format| NaiveDate::parse_from_str(text, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|time| time.and_utc().timestamp_millis())
+}
+
+/// Values of a navigation column as numbers: milliseconds for timestamps,
+/// dates and time strings, otherwise the numeric value. The flag tells
+/// whether the column holds times.
+pub fn column_positions(column: &dyn Array) -> Option<(Vec<Option<f64>>, bool)> {
+    if matches!(column.data_type(), DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64) {
+        let millis = arrow::compute::cast(column, &DataType::Timestamp(arrow::datatypes::TimeUnit::Millisecond, None)).ok()?;
+        let millis = arrow::compute::cast(&millis, &DataType::Float64).ok()?;
+        let millis = millis.as_any().downcast_ref::<Float64Array>()?;
+        return Some((millis.iter().collect(), true));
+    }
+    if let Some(strings) = column.as_any().downcast_ref::<StringArray>() {
+        let values: Vec<Option<f64>> = strings.iter()
+            .map(|value| value.and_then(parse_time).map(|ms| ms as f64))
+            .collect();
+        return values.iter().any(Option::is_some).then_some((values, true));
+    }
+    let numbers = arrow::compute::cast(column, &DataType::Float64).ok()?;
+    let numbers = numbers.as_any().downcast_ref::<Float64Array>()?;
+    Some((numbers.iter().collect(), false))
+}
+
+/// Resolve `--seek` to a row. Without a navigation column it must be a row
+/// number; otherwise it's a time (or number, for numeric columns) and the
+/// first row whose value is at or after it is chosen, or the last row when
+/// every value is earlier.
+pub fn seek_row(seek: &str, positions: Option<&(Vec<Option<f64>>, bool)>) -> Result<usize, String> {
+    let Some((values, is_time)) = positions else {
+        return seek.parse().map_err(|_| format!(
+            "--seek {} is not a row number; give --nav-column to seek by time", seek
+        ));
+    };
+    let target = if *is_time {
+        match parse_time(seek) {
+            Some(ms) => ms as f64,
+            // A plain row number still works on a time column
+            None => return seek.parse().map_err(|_| format!("--seek {} is not a time or row number", seek)),
+        }
+    } else {
+        seek.parse::<f64>().map_err(|_| format!("--seek {} is not a number", seek))?
+    };
+    Ok(values.iter()
+        .position(|value| value.is_some_and(|v| v >= target))
+        .unwrap_or(values.len().saturating_sub(1)))
+}
+
+fn is_sqlite(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref(),
+        Some("db" | "sqlite" | "sqlite3")
+    )
+}
+
+/// Configuration selecting every column of a CSV file
+pub fn csv_config(path: &Path) -> Result<FileConfig, DataError> {
+    let mut config = FileConfig::new(path.to_path_buf(), FileType::Csv);
+    config.select_all_columns()?;
+    Ok(config)
+}
+
+/// Table to read from a SQLite file: `table` if given, otherwise its only table
+pub fn sqlite_table(path: &Path, table: Option<&str>) -> Result<String, DataError> {
+    if let Some(table) = table {
+        return Ok(table.to_string());
+    }
+    let tables = SqliteSource::list_tables(path)?;
+    match tables.as_slice() {
+        [table] => Ok(table.clone()),
+        [] => Err(DataError::Other(format!("{} has no tables", path.display()))),
+        _ => Err(DataError::Other(format!(
+            "{} has several tables; choose one with --table: {}",
+            path.display(),
+            tables.join(", ")
+        ))),
+    }
+}
+
+/// Open a CSV file or a SQLite table
+pub async fn open_source(path: &Path, table: Option<&str>) -> Result<Box<dyn DataSource>, DataError> {
+    if !is_sqlite(path) {
+        return Ok(Box::new(ConfiguredCsvSource::new(csv_config(path)?).await?));
+    }
+    Ok(Box::new(SqliteSource::new(path, sqlite_table(path, table)?).await?))
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), DataError> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| DataError::Other(e.to_string()))?;
+    println!("{}", json);
+    Ok(())
+}
+
+/// Column entry in `inspect` output
+#[derive(Serialize)]
+struct ColumnReport {
+    name: String,
+    data_type: String,
+    null_count: usize,
+    distinct_count: usize,
+    is_sorted: bool,
+    is_unique: bool,
+    min_value: Option<String>,
+    max_value: Option<String>,
+}
+
+/// `inspect` output
+#[derive(Serialize)]
+struct InspectReport {
+    source: String,
+    row_count: usize,
+    sampled_rows: usize,
+    suggested_navigation_column: Option<String>,
+    columns: Vec<ColumnReport>,
+}
+
+async fn inspect(input: &Path, options: &Options) -> Result<i32, DataError> {
+    let source = open_source(input, options.table.as_deref()).await?;
+    let schema = source.schema().await;
+    let row_count = source.row_count().await.map_err(|e| DataError::Other(e.to_string()))?;
+
+    // Statistics come from the schema detector, run over the loaded sample as text
+    let sample = source.query_range(&NavigationRange::rows(0, options.sample.min(row_count))).await
+        .map_err(|e| DataError::Other(e.to_string()))?;
+    let mut rows = vec![Vec::with_capacity(sample.num_columns()); sample.num_rows()];
+    for column in sample.columns() {
+        for (row, values) in rows.iter_mut().enumerate() {
+            values.push(if column.is_null(row) { String::new() } else { array_value_to_string(column, row)? });
+        }
+    }
+    let headers: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
+    let info = SchemaDetector::new()
+        .with_sample_size(options.sample)
+        .detect_from_samples(&headers, &rows);
+
+    let report = InspectReport {
+        source: source.source_name().to_string(),
+        row_count,
+        sampled_rows: sample.num_rows(),
+        suggested_navigation_column: info.suggested_navigation_column,
+        // By position, since CSV headers may repeat
+        columns: schema.fields().iter().zip(&info.columns)
+            .map(|(field, stats)| {
+                ColumnReport {
+                    name: field.name().clone(),
+                    data_type: field.data_type().to_string(),
+                    null_count: stats.null_count,
+                    distinct_count: stats.distinct_count,
+                    is_sorted: stats.is_sorted,
+                    is_unique: stats.is_unique,
+                    min_value: stats.min_value.clone(),
+                    max_value: stats.max_value.clone(),
+                }
+            })
+            .collect(),
+    };
+
+    if options.json {
+        print_json(&report)?;
+        return Ok(EXIT_OK);
+    }
+
+    println!("{}: {} rows × {} columns (statistics from {} sampled rows)",
+        report.source, report.row_count, report.columns.len(), report.sampled_rows);
+    if let Some(column) = &report.suggested_navigation_column {
+        println!("Suggested navigation column: {}", column);
+    }
+    println!();
+    println!("{:<24} {:<28} {:>8} {:>9}  {:<7} {:<7} {:<20} {:<20}",
+        "COLUMN", "TYPE", "NULLS", "DISTINCT", "SORTED", "UNIQUE", "MIN", "MAX");
+    for column in &report.columns {
+        println!("{:<24} {:<28} {:>8} {:>9}  {:<7} {:<7} {:<20} {:<20}",
+            column.name,
+            column.data_type,
+            column.null_count,
+            column.distinct_count,
+            if column.is_sorted { "yes" } else { "no" },
+            if column.is_unique { "yes" } else { "no" },
+            column.min_value.as_deref().unwrap_or("-"),
+            column.max_value.as_deref().unwrap_or("-"));
+    }
+    Ok(EXIT_OK)
+}
+
+async fn convert(input: &Path, output: &Path, options: &Options) -> Result<i32, DataError> {
+    let source = open_source(input, options.table.as_deref()).await?;
+    let batch = read_all(source.as_ref()).await?;
+    let table = SqlEngine::new()?.table_name_for(source.source_name());
+    write_batch(&batch, output, &table)?;
+
+    if options.json {
+        print_json(&serde_json::json!({
+            "input": input,
+            "output": output,
+            "rows": batch.num_rows(),
+            "columns": batch.num_columns(),
+        }))?;
+    } else {
+        println!("Wrote {} rows × {} columns to {}", batch.num_rows(), batch.num_columns(), output.display());
+    }
+    Ok(EXIT_OK)
+}
+
+async fn profile(input: &Path, options: &Options) -> Result<i32, DataError> {
+    // CSV files are profiled from their raw text so parse failures are exact
+    let profile = if is_sqlite(input) {
+        let source = open_source(input, options.table.as_deref()).await?;
+        profile_source(source.as_ref()).await?
+    } else {
+        profile_csv(&csv_config(input)?).await?
+    };
+
+    if options.json {
+        println!("{}", profile.to_json()?);
+    } else {
+        print_profile(&profile);
+    }
+
+    let failures: usize = profile.columns.iter().map(|c| c.parse_failures).sum();
+    Ok(if failures > 0 { EXIT_QUALITY } else { EXIT_OK })
+}
+
+fn print_profile(profile: &DataProfile) {
+    println!("{}: {} rows, {} duplicate rows", profile.source, profile.row_count, profile.duplicate_rows);
+    println!();
+    println!("{:<24} {:<28} {:>8} {:>16} {:>9} {:>9} {:>9}  {:<20}",
+        "COLUMN", "TYPE", "NULLS", "FAILED PARSE", "DISTINCT", "DUPES", "OUTLIERS", "ORDER");
+    for column in &profile.columns {
+        let order = column.monotonicity.map(|m| format!("{:?}", m)).unwrap_or_else(|| "-".to_string());
+        println!("{:<24} {:<28} {:>8} {:>16} {:>9} {:>9} {:>9}  {:<20}",
+            column.name,
+            column.data_type,
+            column.null_count,
+            format!("{} ({:.1}%)", column.parse_failures, 100.0 * column.failure_rate()),
+            column.distinct_count,
+            column.duplicate_count,
+            column.outlier_count,
+            order);
+    }
+
+    for column in &profile.columns {
+        if !column.failed_examples.is_empty() {
+            println!("\n{}: {} values failed to parse as {}, e.g. {}",
+                column.name, column.parse_failures, column.data_type, column.failed_examples.join(", "));
+        }
+        if let Some(gaps) = &column.time_gaps {
+            if gaps.gap_count > 0 || gaps.backward_jumps > 0 {
+                println!("\n{}: median step {} ms, {} gaps (largest {} ms), {} backward jumps",
+                    column.name, gaps.median_interval_ms, gaps.gap_count, gaps.largest_gap_ms, gaps.backward_jumps);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_subcommands_and_falls_back_to_the_ui() {
+        assert_eq!(parse(&args("")), None);
+        assert_eq!(parse(&args("data.csv")), None);
+        assert_eq!(
+            parse(&args("convert in.db out.parquet --table readings --json")),
+            Some(Ok(Command::Convert {
+                input: "in.db".into(),
+                output: "out.parquet".into(),
+                options: Options { table: Some("readings".to_string()), json: true, ..Options::default() },
+            }))
+        );
+        assert_eq!(parse(&args("inspect a.csv --help")), Some(Ok(Command::Help)));
+        assert!(matches!(parse(&args("inspect")), Some(Err(_))));
+        assert!(matches!(parse(&args("profile a.csv b.csv")), Some(Err(_))));
+        assert!(matches!(parse(&args("inspect a.csv --sample x")), Some(Err(_))));
+    }
+
+    #[test]
+    fn parses_launch_arguments() {
+        let launch = parse_launch(&args(
+            "data.csv other.csv --table readings db.sqlite --workspace dash.json --nav-column timestamp --seek 2024-01-01T10:00"
+        )).unwrap();
+        assert_eq!(launch.sources, vec![
+            LaunchSource { path: "data.csv".into(), table: None },
+            LaunchSource { path: "other.csv".into(), table: None },
+            LaunchSource { path: "db.sqlite".into(), table: Some("readings".to_string()) },
+        ]);
+        assert_eq!(launch.workspace, Some("dash.json".into()));
+        assert_eq!(launch.nav_column.as_deref(), Some("timestamp"));
+        assert_eq!(launch.seek.as_deref(), Some("2024-01-01T10:00"));
+        assert!(parse_launch(&[]).unwrap().is_empty());
+        assert!(parse_launch(&args("--table readings data.csv")).is_err());
+        assert!(parse_launch(&args("db.sqlite --table readings")).is_err());
+        assert!(parse_launch(&args("--seek")).is_err());
+
+        assert_eq!(
+            parse(&args("render out.png data.csv --size 800x600 --range 10..20 --seek 15")),
+            Some(Ok(Command::Render {
+                output: "out.png".into(),
+                launch: Launch {
+                    sources: vec![LaunchSource { path: "data.csv".into(), table: None }],
+                    seek: Some("15".to_string()),
+                    ..Launch::default()
+                },
+                size: [800, 600],
+                range: Some("10..20".to_string()),
+            }))
+        );
+        assert!(matches!(parse(&args("render --size 10x10")), Some(Err(_))));
+        assert!(matches!(parse(&args("render out.png --size 0x10")), Some(Err(_))));
+    }
+
+    #[test]
+    fn seeks_to_the_first_row_at_or_after_a_time() {
+        let times = StringArray::from(vec![Some("2024-01-01 09:00:00"), None, Some("2024-01-01 10:00:00"), Some("2024-01-01 11:00:00")]);
+        let positions = column_positions(&times).unwrap();
+        assert!(positions.1);
+        assert_eq!(seek_row("2024-01-01T10:00", Some(&positions)), Ok(2));
+        assert_eq!(seek_row("2024-01-01T09:30", Some(&positions)), Ok(2));
+        assert_eq!(seek_row("2025-01-01", Some(&positions)), Ok(3));
+        assert_eq!(seek_row("1", Some(&positions)), Ok(1));
+        assert_eq!(seek_row("7", None), Ok(7));
+        assert!(seek_row("2024-01-01", None).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+//! Dockable data-quality report for one source
+
+use egui::{Color32, RichText, ScrollArea, Ui};
+use serde_json::{json, Value};
+use dv_data::profile::{DataProfile, ColumnProfile, Monotonicity};
+use dv_views::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+
+/// Shows a [`DataProfile`] computed when the view was opened
+pub struct DataQualityView {
+    id: SpaceViewId,
+    title: String,
+    source_id: String,
+    profile: DataProfile,
+
+    /// Result of the last JSON export
+    export_status: Option<Result<String, String>>,
+}
+
+impl DataQualityView {
+    /// Create a view over a finished profile of `source_id`
+    pub fn new(id: SpaceViewId, source_id: String, profile: DataProfile) -> Self {
+        Self {
+            id,
+            title: format!("Data Quality: {}", profile.source),
+            source_id,
+            profile,
+            export_status: None,
+        }
+    }
+
+    fn export_json(&mut self) {
+        let file_name = format!("{}_profile.json", self.profile.source.trim_end_matches(".csv"));
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+
+        self.export_status = Some(
+            self.profile.to_json()
+                .map_err(|e| e.to_string())
+                .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()))
+                .map(|_| format!("Saved {}", path.display()))
+        );
+    }
+}
+
+fn format_count(count: usize, total: usize) -> String {
+    if count == 0 || total == 0 {
+        count.to_string()
+    } else {
+        format!("{} ({:.1}%)", count, 100.0 * count as f64 / total as f64)
+    }
+}
+
+fn format_duration(ms: f64) -> String {
+    let seconds = ms / 1000.0;
+    if seconds < 1.0 {
+        format!("{:.0} ms", ms)
+    } else if seconds < 120.0 {
+        format!("{:.1} s", seconds)
+    } else if seconds < 7200.0 {
+        format!("{:.1} min", seconds / 60.0)
+    } else if seconds < 172_800.0 {
+        format!("{:.1} h", seconds / 3600.0)
+    } else {
+        format!("{:.1} d", seconds / 86_400.0)
+    }
+}
+
+fn format_monotonicity(monotonicity: Option<Monotonicity>) -> &'static str {
+    match monotonicity {
+        None => "-",
+        Some(Monotonicity::Constant) => "constant",
+        Some(Monotonicity::StrictlyIncreasing) => "strictly ↑",
+        Some(Monotonicity::Increasing) => "↑",
+        Some(Monotonicity::StrictlyDecreasing) => "strictly ↓",
+        Some(Monotonicity::Decreasing) => "↓",
+        Some(Monotonicity::Unordered) => "unordered",
+    }
+}
+
+/// Label that turns orange when `count` is non-zero
+fn warning_label(ui: &mut Ui, text: String, count: usize) -> egui::Response {
+    if count > 0 {
+        ui.label(RichText::new(text).color(Color32::from_rgb(255, 170, 60)))
+    } else {
+        ui.label(text)
+    }
+}
+
+fn column_row(row: &mut egui_extras::TableRow, column: &ColumnProfile, rows: usize) {
+    row.col(|ui| { ui.label(&column.name); });
+    row.col(|ui| { ui.label(&column.data_type); });
+    row.col(|ui| {
+        let response = ui.label(format_count(column.null_count, rows));
+        if !column.nulls_by_pattern.is_empty() || column.missing_fields > 0 || column.parse_failures > 0 {
+            response.on_hover_ui(|ui| {
+                for (pattern, count) in &column.nulls_by_pattern {
+                    ui.label(format!("{:?}: {}", pattern, count));
+                }
+                if column.missing_fields > 0 {
+                    ui.label(format!("missing field: {}", column.missing_fields));
+                }
+                if column.parse_failures > 0 {
+                    ui.label(format!("failed to parse: {}", column.parse_failures));
+                }
+            });
+        }
+    });
+    row.col(|ui| {
+        let text = format!("{} ({:.1}%)", column.parse_failures, 100.0 * column.failure_rate());
+        let response = warning_label(ui, text, column.parse_failures);
+        if !column.failed_examples.is_empty() {
+            response.on_hover_text(format!("e.g. {}", column.failed_examples.join(", ")));
+        }
+    });
+    row.col(|ui| { ui.label(column.distinct_count.to_string()); });
+    row.col(|ui| { ui.label(column.duplicate_count.to_string()); });
+    row.col(|ui| { ui.label(format_monotonicity(column.monotonicity)); });
+    row.col(|ui| { warning_label(ui, column.outlier_count.to_string(), column.outlier_count); });
+    row.col(|ui| {
+        match &column.time_gaps {
+            Some(gaps) => {
+                let text = format!(
+                    "every {} · {} gaps · {} back",
+                    format_duration(gaps.median_interval_ms), gaps.gap_count, gaps.backward_jumps
+                );
+                warning_label(ui, text, gaps.gap_count + gaps.backward_jumps)
+                    .on_hover_text(format!("Largest gap: {}", format_duration(gaps.largest_gap_ms as f64)));
+            }
+            None => { ui.label("-"); }
+        }
+    });
+}
+
+impl SpaceView for DataQualityView {
+    fn id(&self) -> SpaceViewId {
+        self.id
+    }
+
+    fn display_name(&self) -> &str {
+        &self.title
+    }
+
+    fn view_type(&self) -> &str {
+        "DataQualityView"
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn data_source_id(&self) -> Option<&str> {
+        Some(&self.source_id)
+    }
+
+    fn ui(&mut self, _ctx: &ViewerContext, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("Rows: {}", self.profile.row_count));
+            ui.separator();
+            warning_label(
+                ui,
+                format!("Duplicate rows: {}", format_count(self.profile.duplicate_rows, self.profile.row_count)),
+                self.profile.duplicate_rows,
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("💾 Export JSON").clicked() {
+                    self.export_json();
+                }
+                match &self.export_status {
+                    Some(Ok(message)) => { ui.label(RichText::new(message).weak()); }
+                    Some(Err(error)) => { ui.colored_label(Color32::from_rgb(255, 100, 100), error); }
+                    None => {}
+                }
+            });
+        });
+
+        ui.add_space(4.0);
+
+        let rows = self.profile.row_count;
+        ScrollArea::both()
+            .id_source(format!("quality_{:?}", self.id))
+            .show(ui, |ui| {
+                use egui_extras::{TableBuilder, Column};
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .resizable(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::initial(120.0).at_least(80.0)) // Column name
+                    .column(Column::initial(90.0).at_least(60.0))  // Type
+                    .column(Column::initial(90.0).at_least(60.0))  // Nulls
+                    .column(Column::initial(90.0).at_least(60.0))  // Parse failures
+                    .column(Column::initial(70.0).at_least(50.0))  // Distinct
+                    .column(Column::initial(70.0).at_least(50.0))  // Duplicates
+                    .column(Column::initial(80.0).at_least(60.0))  // Monotonicity
+                    .column(Column::initial(60.0).at_least(50.0))  // Outliers
+                    .column(Column::remainder().at_least(120.0))   // Time gaps
+                    .header(20.0, |mut header| {
+                        for title in ["Column", "Type", "Nulls", "Failed Parse", "Distinct", "Duplicates", "Order", "Outliers", "Time Steps"] {
+                            header.col(|ui| { ui.strong(title); });
+                        }
+                    })
+                    .body(|mut body| {
+                        for column in &self.profile.columns {
+                            body.row(18.0, |mut row| column_row(&mut row, column, rows));
+                        }
+                    });
+            });
+    }
+
+    fn save_config(&self) -> Value {
+        json!({ "data_source_id": self.source_id })
+    }
+
+    fn load_config(&mut self, _config: Value) {
+        // The profile is computed when the view is opened
+    }
+
+    fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {
+        // The profile covers every row, not the selection
+    }
+
+    fn on_frame_update(&mut self, _ctx: &ViewerContext, _dt: f32) {
+        // Nothing to update per frame
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
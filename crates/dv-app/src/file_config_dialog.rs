@@ -1,5 +1,5 @@
-use dv_data::config::{FileConfigManager, FileType, SerializableDataType};
-use std::path::PathBuf;
+use dv_data::config::{Aggregation, ComputedColumn, FileConfigManager, FileType, GapFill, ReshapeSpec, ResampleSpec, SerializableDataType};
+use std::path::{Path, PathBuf};
 use egui::{Context, Ui, RichText, Color32, ScrollArea, DragValue, Grid};
 use tokio::runtime::Handle;
 
@@ -14,6 +14,12 @@ pub struct FileConfigDialog {
     /// Null pattern input field
     null_pattern_input: String,
     
+    /// Computed column name input field
+    computed_name_input: String,
+    
+    /// Computed column expression input field
+    computed_expression_input: String,
+    
     /// Type inference results
     inference_results: Vec<String>,
     
@@ -43,6 +49,8 @@ impl FileConfigDialog {
             config_manager,
             show: true,
             null_pattern_input: String::new(),
+            computed_name_input: String::new(),
+            computed_expression_input: String::new(),
             inference_results: Vec::new(),
             error_message: None,
             runtime,
@@ -704,10 +712,72 @@ impl FileConfigDialog {
                         .size(12.0)
                         .color(Color32::from_gray(150)));
                 }
+                
+                ui.add_space(20.0);
+                self.show_computed_columns(ui, &active_path);
             });
         });
     }
     
+    /// Editor for the computed columns of the config at `active_path`
+    fn show_computed_columns(&mut self, ui: &mut Ui, active_path: &Path) {
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.label(RichText::new("🧮 Computed Columns").size(18.0).strong());
+            ui.add_space(8.0);
+            
+            let computed_columns = self.config_manager.configs.get(active_path)
+                .map(|c| c.computed_columns.clone())
+                .unwrap_or_default();
+            
+            let mut to_remove = None;
+            for (idx, column) in computed_columns.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui.small_button("×").clicked() {
+                        to_remove = Some(idx);
+                    }
+                    ui.monospace(format!("{} = {}", column.name, column.expression));
+                });
+            }
+            if let Some(idx) = to_remove {
+                if let Some(config) = self.config_manager.configs.get_mut(active_path) {
+                    config.computed_columns.remove(idx);
+                }
+            }
+            
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.computed_name_input)
+                    .hint_text("name")
+                    .desired_width(100.0));
+                ui.label("=");
+                ui.add(egui::TextEdit::singleline(&mut self.computed_expression_input)
+                    .hint_text("voltage * current")
+                    .desired_width(220.0));
+                
+                let parsed = dv_data::expr::parse(&self.computed_expression_input);
+                let can_add = !self.computed_name_input.trim().is_empty() && parsed.is_ok();
+                if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+                    if let Some(config) = self.config_manager.configs.get_mut(active_path) {
+                        config.computed_columns.push(ComputedColumn {
+                            name: self.computed_name_input.trim().to_string(),
+                            expression: self.computed_expression_input.trim().to_string(),
+                        });
+                        self.computed_name_input.clear();
+                        self.computed_expression_input.clear();
+                    }
+                }
+                
+                if let (false, Err(e)) = (self.computed_expression_input.trim().is_empty(), parsed) {
+                    ui.colored_label(Color32::from_rgb(255, 150, 100), e.to_string());
+                }
+            });
+            
+            ui.label(RichText::new("Functions: abs, sqrt, ln, exp, if, lag, rolling_mean, rolling_sum, year, month, day, weekday, hour, minute, second")
+                .size(11.0)
+                .color(Color32::from_gray(150)));
+        });
+    }
+    
     /// Run type inference for a specific config
     fn run_type_inference_for_config(&mut self, path: &PathBuf) {
         
@@ -1160,6 +1230,178 @@ impl FileConfigDialog {
                                 }
                             }
                         });
+
+                        ui.add_space(8.0);
+                        if let Some(config) = self.config_manager.configs.get_mut(&active_path) {
+                            ui.checkbox(&mut config.strict_parsing, "Strict parsing")
+                                .on_hover_text("Fail loading on values that don't match their column type, instead of loading them as null");
+                        }
+                    });
+
+                    ui.add_space(12.0);
+
+                    self.show_computed_columns(ui, &active_path);
+                    
+                    ui.add_space(12.0);
+                    
+                    // Resampling onto a fixed time grid
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.label(RichText::new("⏱ Resample").size(18.0).strong());
+                        ui.add_space(8.0);
+                        
+                        let Some(config) = self.config_manager.configs.get(&active_path) else {
+                            return;
+                        };
+                        let columns: Vec<String> = config.detected_columns.iter()
+                            .filter(|c| config.selected_columns.contains(*c))
+                            .cloned()
+                            .chain(config.computed_columns.iter().map(|c| c.name.clone()))
+                            .collect();
+                        let current = config.resample.clone();
+                        let mut resample = current.clone();
+                        
+                        let mut enabled = resample.is_some();
+                        if ui.checkbox(&mut enabled, "Resample to a fixed interval").changed() {
+                            resample = enabled.then(|| ResampleSpec::new(60_000));
+                        }
+                        
+                        if let Some(spec) = &mut resample {
+                            Grid::new("resample_grid")
+                                .num_columns(2)
+                                .spacing([8.0, 4.0])
+                                .show(ui, |ui| {
+                                    ui.label("Time column:");
+                                    egui::ComboBox::from_id_source("resample_time_column")
+                                        .selected_text(spec.time_column.as_deref().unwrap_or("Auto (first timestamp)"))
+                                        .show_ui(ui, |ui| {
+                                            ui.selectable_value(&mut spec.time_column, None, "Auto (first timestamp)");
+                                            for column in &columns {
+                                                ui.selectable_value(&mut spec.time_column, Some(column.clone()), column);
+                                            }
+                                        });
+                                    ui.end_row();
+                                    
+                                    ui.label("Interval:");
+                                    egui::ComboBox::from_id_source("resample_interval")
+                                        .selected_text(format_interval(spec.interval_ms))
+                                        .show_ui(ui, |ui| {
+                                            for interval in RESAMPLE_INTERVALS {
+                                                ui.selectable_value(&mut spec.interval_ms, interval, format_interval(interval));
+                                            }
+                                        });
+                                    ui.end_row();
+                                    
+                                    ui.label("Aggregation:");
+                                    aggregation_combo(ui, "resample_aggregation", &mut spec.aggregation);
+                                    ui.end_row();
+                                    
+                                    ui.label("Gap filling:");
+                                    egui::ComboBox::from_id_source("resample_gap_fill")
+                                        .selected_text(format_gap_fill(spec.gap_fill))
+                                        .show_ui(ui, |ui| {
+                                            for gap_fill in [GapFill::None, GapFill::ForwardFill, GapFill::Linear] {
+                                                ui.selectable_value(&mut spec.gap_fill, gap_fill, format_gap_fill(gap_fill));
+                                            }
+                                        });
+                                    ui.end_row();
+                                });
+                            
+                            ui.collapsing("Per-column aggregation", |ui| {
+                                Grid::new("resample_column_grid")
+                                    .num_columns(2)
+                                    .spacing([8.0, 4.0])
+                                    .show(ui, |ui| {
+                                        for column in &columns {
+                                            ui.label(column);
+                                            let mut aggregation = spec.aggregation_for(column);
+                                            if aggregation_combo(ui, &format!("resample_{}", column), &mut aggregation) {
+                                                spec.column_aggregations.insert(column.clone(), aggregation);
+                                            }
+                                            ui.end_row();
+                                        }
+                                    });
+                            });
+                        }
+                        
+                        if resample != current {
+                            if let Some(config) = self.config_manager.configs.get_mut(&active_path) {
+                                config.resample = resample;
+                            }
+                        }
+                    });
+                    
+                    ui.add_space(12.0);
+                    
+                    // Wide/long reshaping
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        ui.label(RichText::new("🔀 Reshape").size(18.0).strong());
+                        ui.add_space(8.0);
+                        
+                        let Some(config) = self.config_manager.configs.get(&active_path) else {
+                            return;
+                        };
+                        // Columns available after loading: selected ones in file order, then computed ones
+                        let columns: Vec<String> = config.detected_columns.iter()
+                            .filter(|c| config.selected_columns.contains(*c))
+                            .cloned()
+                            .chain(config.computed_columns.iter().map(|c| c.name.clone()))
+                            .collect();
+                        let current = config.reshape.clone();
+                        let mut reshape = current.clone();
+                        
+                        ui.horizontal(|ui| {
+                            if ui.radio(reshape.is_none(), "None").clicked() {
+                                reshape = None;
+                            }
+                            let is_melt = matches!(reshape, Some(ReshapeSpec::Melt { .. }));
+                            if ui.radio(is_melt, "Wide → long (melt)").clicked() && !is_melt {
+                                reshape = Some(ReshapeSpec::Melt {
+                                    value_columns: Vec::new(),
+                                    key_name: "series".to_string(),
+                                    value_name: "value".to_string(),
+                                });
+                            }
+                            let is_pivot = matches!(reshape, Some(ReshapeSpec::Pivot { .. }));
+                            if ui.radio(is_pivot, "Long → wide (pivot)").clicked() && !is_pivot {
+                                reshape = Some(ReshapeSpec::Pivot {
+                                    index_columns: columns.first().cloned().into_iter().collect(),
+                                    key_column: columns.get(1).cloned().unwrap_or_default(),
+                                    value_column: columns.last().cloned().unwrap_or_default(),
+                                });
+                            }
+                        });
+                        
+                        match &mut reshape {
+                            None => {}
+                            Some(ReshapeSpec::Melt { value_columns, key_name, value_name }) => {
+                                ui.horizontal(|ui| {
+                                    ui.label("Key column:");
+                                    ui.add(egui::TextEdit::singleline(key_name).desired_width(100.0));
+                                    ui.label("Value column:");
+                                    ui.add(egui::TextEdit::singleline(value_name).desired_width(100.0));
+                                });
+                                ui.label("Columns to melt:");
+                                column_checklist(ui, &columns, value_columns);
+                            }
+                            Some(ReshapeSpec::Pivot { index_columns, key_column, value_column }) => {
+                                ui.horizontal(|ui| {
+                                    ui.label("Key column:");
+                                    column_combo(ui, "pivot_key_column", &columns, key_column);
+                                    ui.label("Value column:");
+                                    column_combo(ui, "pivot_value_column", &columns, value_column);
+                                });
+                                ui.label("Index columns (one row per combination):");
+                                column_checklist(ui, &columns, index_columns);
+                            }
+                        }
+                        
+                        if reshape != current {
+                            if let Some(config) = self.config_manager.configs.get_mut(&active_path) {
+                                config.reshape = reshape;
+                            }
+                        }
                     });
                     
                     ui.add_space(12.0);
@@ -1338,6 +1580,68 @@ impl FileConfigDialog {
 }
 
 /// Format serializable data type for display
+/// Interval choices offered for resampling, in milliseconds
+const RESAMPLE_INTERVALS: [i64; 7] = [100, 1_000, 10_000, 60_000, 300_000, 3_600_000, 86_400_000];
+
+fn format_interval(interval_ms: i64) -> String {
+    match interval_ms {
+        ms if ms % 86_400_000 == 0 => format!("{} d", ms / 86_400_000),
+        ms if ms % 3_600_000 == 0 => format!("{} h", ms / 3_600_000),
+        ms if ms % 60_000 == 0 => format!("{} min", ms / 60_000),
+        ms if ms % 1_000 == 0 => format!("{} s", ms / 1_000),
+        ms => format!("{} ms", ms),
+    }
+}
+
+fn format_gap_fill(gap_fill: GapFill) -> &'static str {
+    match gap_fill {
+        GapFill::None => "None",
+        GapFill::ForwardFill => "Forward fill",
+        GapFill::Linear => "Linear",
+    }
+}
+
+/// Aggregation picker; returns true when the choice changed
+fn aggregation_combo(ui: &mut Ui, id: &str, aggregation: &mut Aggregation) -> bool {
+    let before = *aggregation;
+    egui::ComboBox::from_id_source(id)
+        .selected_text(format!("{:?}", aggregation))
+        .width(80.0)
+        .show_ui(ui, |ui| {
+            for choice in [Aggregation::Mean, Aggregation::Last, Aggregation::Max, Aggregation::Sum] {
+                ui.selectable_value(aggregation, choice, format!("{:?}", choice));
+            }
+        });
+    *aggregation != before
+}
+
+/// Checkboxes toggling membership of each column in `chosen`
+fn column_checklist(ui: &mut Ui, columns: &[String], chosen: &mut Vec<String>) {
+    ui.horizontal_wrapped(|ui| {
+        for column in columns {
+            let mut checked = chosen.contains(column);
+            if ui.checkbox(&mut checked, column).changed() {
+                if checked {
+                    chosen.push(column.clone());
+                } else {
+                    chosen.retain(|c| c != column);
+                }
+            }
+        }
+    });
+}
+
+fn column_combo(ui: &mut Ui, id: &str, columns: &[String], selected: &mut String) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(selected.as_str())
+        .width(120.0)
+        .show_ui(ui, |ui| {
+            for column in columns {
+                ui.selectable_value(selected, column.clone(), column);
+            }
+        });
+}
+
 fn format_serializable_type(dtype: &SerializableDataType) -> &'static str {
     match dtype {
         SerializableDataType::Boolean => "Boolean",
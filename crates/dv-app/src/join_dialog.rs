@@ -0,0 +1,252 @@
+//! Dialog for joining two loaded data sources
+
+use std::sync::Arc;
+use arrow::datatypes::Schema;
+use egui::{Context, ComboBox, DragValue, Grid, RichText, Color32};
+use dv_data::sources::{JoinKind, JoinSpec};
+
+/// How the dialog matches rows
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum JoinMethod {
+    Inner,
+    Left,
+    AsOf,
+}
+
+impl JoinMethod {
+    fn label(&self) -> &'static str {
+        match self {
+            JoinMethod::Inner => "Inner join on key",
+            JoinMethod::Left => "Left join on key",
+            JoinMethod::AsOf => "As-of join on time",
+        }
+    }
+}
+
+/// A loaded source the dialog can join
+struct SourceEntry {
+    id: String,
+    name: String,
+    columns: Vec<String>,
+}
+
+/// Join confirmed by the user
+pub struct JoinRequest {
+    pub left_id: String,
+    pub right_id: String,
+    pub spec: JoinSpec,
+}
+
+/// Dialog to join two loaded data sources into a new one
+pub struct JoinDialog {
+    /// Available sources
+    sources: Vec<SourceEntry>,
+
+    /// Index of the left source
+    left: usize,
+
+    /// Index of the right source
+    right: usize,
+
+    /// Selected join method
+    method: JoinMethod,
+
+    /// Key or time column on the left source
+    left_column: String,
+
+    /// Key or time column on the right source
+    right_column: String,
+
+    /// Whether as-of matches are limited to `tolerance`
+    use_tolerance: bool,
+
+    /// Largest gap accepted by an as-of join (ms for temporal columns)
+    tolerance: f64,
+
+    /// Show dialog
+    pub show: bool,
+}
+
+impl JoinDialog {
+    /// Create a dialog over `(id, name, schema)` for each loaded source
+    pub fn new(sources: Vec<(String, String, Arc<Schema>)>) -> Self {
+        let sources = sources.into_iter()
+            .map(|(id, name, schema)| SourceEntry {
+                id,
+                name,
+                columns: schema.fields().iter().map(|f| f.name().clone()).collect(),
+            })
+            .collect();
+
+        let mut dialog = Self {
+            sources,
+            left: 0,
+            right: 1,
+            method: JoinMethod::Left,
+            left_column: String::new(),
+            right_column: String::new(),
+            use_tolerance: false,
+            tolerance: 1000.0,
+            show: true,
+        };
+        dialog.guess_columns();
+        dialog
+    }
+
+    /// Pick a column present on both sides, falling back to the first columns
+    fn guess_columns(&mut self) {
+        let (Some(left), Some(right)) = (self.sources.get(self.left), self.sources.get(self.right)) else {
+            return;
+        };
+        let shared = left.columns.iter().find(|c| right.columns.contains(c));
+        match shared {
+            Some(column) => {
+                self.left_column = column.clone();
+                self.right_column = column.clone();
+            }
+            None => {
+                self.left_column = left.columns.first().cloned().unwrap_or_default();
+                self.right_column = right.columns.first().cloned().unwrap_or_default();
+            }
+        }
+    }
+
+    /// Show the dialog and return the join if confirmed
+    pub fn show_dialog(&mut self, ctx: &Context) -> Option<JoinRequest> {
+        let mut result = None;
+        let mut close = false;
+
+        egui::Window::new("Join Sources")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if self.sources.len() < 2 {
+                    ui.label("Load at least two data sources to join them.");
+                    if ui.button("Close").clicked() {
+                        close = true;
+                    }
+                    return;
+                }
+
+                let (left_before, right_before) = (self.left, self.right);
+
+                Grid::new("join_dialog_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("Left source:");
+                        source_combo(ui, "join_left_source", &self.sources, &mut self.left);
+                        ui.end_row();
+
+                        ui.label("Right source:");
+                        source_combo(ui, "join_right_source", &self.sources, &mut self.right);
+                        ui.end_row();
+
+                        ui.label("Method:");
+                        ComboBox::from_id_source("join_method")
+                            .selected_text(self.method.label())
+                            .show_ui(ui, |ui| {
+                                for method in [JoinMethod::Inner, JoinMethod::Left, JoinMethod::AsOf] {
+                                    ui.selectable_value(&mut self.method, method, method.label());
+                                }
+                            });
+                        ui.end_row();
+
+                        let column_label = if self.method == JoinMethod::AsOf { "time" } else { "key" };
+
+                        ui.label(format!("Left {} column:", column_label));
+                        column_combo(ui, "join_left_column", &self.sources[self.left].columns, &mut self.left_column);
+                        ui.end_row();
+
+                        ui.label(format!("Right {} column:", column_label));
+                        column_combo(ui, "join_right_column", &self.sources[self.right].columns, &mut self.right_column);
+                        ui.end_row();
+
+                        if self.method == JoinMethod::AsOf {
+                            ui.checkbox(&mut self.use_tolerance, "Tolerance:");
+                            ui.add_enabled(
+                                self.use_tolerance,
+                                DragValue::new(&mut self.tolerance).clamp_range(0.0..=f64::MAX).suffix(" ms"),
+                            ).on_hover_text("Largest gap to the matched row (column units for numeric columns)");
+                            ui.end_row();
+                        }
+                    });
+
+                if (self.left, self.right) != (left_before, right_before) {
+                    self.guess_columns();
+                }
+
+                if self.method == JoinMethod::AsOf {
+                    ui.label(
+                        RichText::new("Each left row takes the latest right row at or before its time.")
+                            .small()
+                            .color(Color32::from_gray(160))
+                    );
+                }
+
+                ui.separator();
+
+                let valid = self.left != self.right
+                    && !self.left_column.is_empty()
+                    && !self.right_column.is_empty();
+                if self.left == self.right {
+                    ui.colored_label(Color32::from_rgb(255, 150, 100), "Pick two different sources.");
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(valid, egui::Button::new("Join")).clicked() {
+                        result = Some(JoinRequest {
+                            left_id: self.sources[self.left].id.clone(),
+                            right_id: self.sources[self.right].id.clone(),
+                            spec: self.spec(),
+                        });
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if close || result.is_some() {
+            self.show = false;
+        }
+        result
+    }
+
+    /// Join spec for the current selections
+    fn spec(&self) -> JoinSpec {
+        match self.method {
+            JoinMethod::Inner | JoinMethod::Left => JoinSpec::Key {
+                kind: if self.method == JoinMethod::Inner { JoinKind::Inner } else { JoinKind::Left },
+                left_on: vec![self.left_column.clone()],
+                right_on: vec![self.right_column.clone()],
+            },
+            JoinMethod::AsOf => JoinSpec::AsOf {
+                left_on: self.left_column.clone(),
+                right_on: self.right_column.clone(),
+                tolerance: self.use_tolerance.then_some(self.tolerance),
+            },
+        }
+    }
+}
+
+fn source_combo(ui: &mut egui::Ui, id: &str, sources: &[SourceEntry], selected: &mut usize) {
+    ComboBox::from_id_source(id)
+        .selected_text(sources[*selected].name.as_str())
+        .show_ui(ui, |ui| {
+            for (index, source) in sources.iter().enumerate() {
+                ui.selectable_value(selected, index, source.name.as_str());
+            }
+        });
+}
+
+fn column_combo(ui: &mut egui::Ui, id: &str, columns: &[String], selected: &mut String) {
+    ComboBox::from_id_source(id)
+        .selected_text(selected.as_str())
+        .show_ui(ui, |ui| {
+            for column in columns {
+                ui.selectable_value(selected, column.clone(), column);
+            }
+        });
+}
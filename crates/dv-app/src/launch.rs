@@ -0,0 +1,213 @@
+//! Setting up the dashboard and position requested on the command line
+//!
+//! Shared by the window (`frog data.csv --seek …`) and headless rendering
+//! (`frog render out.png data.csv --seek …`).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::datatypes::Schema;
+use egui_dock::DockState;
+use tracing::warn;
+use uuid::Uuid;
+use dv_core::data::DataSource;
+use dv_core::navigation::{NavigationMode, NavigationPosition, NavigationRange, NavigationSpec};
+use dv_templates::TemplateLibrary;
+use dv_views::{SpaceView, SpaceViewId, TimeSeriesView, ViewRegistry, Viewport, ViewerContext};
+
+use crate::cli::{self, Launch};
+use crate::workspace::Workspace;
+
+/// Views to show and how to arrange them
+pub struct DashboardViews {
+    pub views: Vec<Box<dyn SpaceView>>,
+    /// Dock arrangement, or `None` for a grid
+    pub layout: Option<DockState<SpaceViewId>>,
+}
+
+impl DashboardViews {
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+
+    /// Replace the views of `viewport` with these
+    pub fn show(self, viewport: &mut Viewport) {
+        match self.layout {
+            Some(layout) => viewport.create_docked_layout(self.views, layout),
+            None => viewport.create_grid_layout(self.views),
+        }
+    }
+}
+
+/// Views of `workspace`, or the best fitting template's dashboard for each
+/// loaded source in `source_ids`, plotting against the navigation column
+/// where there is one. A single source keeps its template's arrangement;
+/// the views of several are put in a grid.
+pub fn dashboard_views(
+    viewer_context: &ViewerContext,
+    registry: &ViewRegistry,
+    templates: &TemplateLibrary,
+    source_ids: &[String],
+    workspace: Option<&Workspace>,
+    nav_column: Option<&str>,
+) -> DashboardViews {
+    if let Some(workspace) = workspace {
+        return workspace_views(viewer_context, registry, workspace);
+    }
+
+    let data_sources = viewer_context.data_sources.read();
+    let mut dashboards = Vec::new();
+    for source_id in source_ids {
+        let Some(source) = data_sources.get(source_id) else {
+            continue;
+        };
+        let schema = viewer_context.runtime_handle.block_on(source.schema());
+        if let Some(dashboard) = template_views(registry, templates, &schema, source_id, nav_column) {
+            dashboards.push(dashboard);
+        }
+    }
+    if dashboards.len() == 1 {
+        return dashboards.remove(0);
+    }
+    DashboardViews {
+        views: dashboards.into_iter().flat_map(|dashboard| dashboard.views).collect(),
+        layout: None,
+    }
+}
+
+/// Views of the best fitting template for `schema`, showing `source_id`
+pub fn template_views(
+    registry: &ViewRegistry,
+    templates: &TemplateLibrary,
+    schema: &Schema,
+    source_id: &str,
+    nav_column: Option<&str>,
+) -> Option<DashboardViews> {
+    let dashboard = templates.dashboard(schema)?;
+    let mut ids = HashMap::new();
+    let mut views = Vec::new();
+    for spec in &dashboard.views {
+        let id = Uuid::new_v4();
+        let Some(mut view) = registry.create(&spec.view_type, id, spec.title.clone()) else {
+            warn!("Template '{}' has an unknown view type '{}'", dashboard.template_id, spec.view_type);
+            continue;
+        };
+        view.load_config(spec.config.clone());
+        view.set_data_source(source_id.to_string());
+        if let (Some(column), Some(time_series)) = (nav_column, view.as_any_mut().downcast_mut::<TimeSeriesView>()) {
+            if schema.field_with_name(column).is_ok() {
+                time_series.config.x_column = Some(column.to_string());
+            }
+        }
+        ids.insert(spec.id.as_str(), id);
+        views.push(view);
+    }
+    // Tabs of unknown view types are dropped from the arrangement
+    let mut layout = dashboard.layout.clone();
+    let unknown: Vec<String> = layout.iter_all_tabs()
+        .filter(|(_, tab)| !ids.contains_key(tab.as_str()))
+        .map(|(_, tab)| tab.clone())
+        .collect();
+    for tab in unknown {
+        if let Some(location) = layout.find_tab(&tab) {
+            layout.remove_tab(location);
+        }
+    }
+    let layout = layout.map_tabs(|tab| ids[tab.as_str()]);
+    Some(DashboardViews { views, layout: Some(layout) })
+}
+
+/// Recreate the views of a saved workspace as they were docked, warning
+/// about anything missing
+pub fn workspace_views(viewer_context: &ViewerContext, registry: &ViewRegistry, workspace: &Workspace) -> DashboardViews {
+    let (views, unknown) = workspace.build_views(registry);
+    for view_type in unknown {
+        warn!("Workspace has an unknown view type '{}'", view_type);
+    }
+    let data_sources = viewer_context.data_sources.read();
+    for source_id in workspace.data_source_ids() {
+        if !data_sources.contains_key(source_id) {
+            warn!("Workspace uses data source '{}', which isn't loaded", source_id);
+        }
+    }
+    views
+}
+
+/// Navigate the rows of the first source with the launch's navigation column
+/// (or the first source), then apply `--seek` and `range`, a `start..end` pair
+/// of rows or times. Returns the ID of the source navigated by.
+pub fn navigate(
+    viewer_context: &ViewerContext,
+    source_ids: &[String],
+    launch: &Launch,
+    range: Option<&str>,
+) -> Result<Option<String>, String> {
+    let runtime = &viewer_context.runtime_handle;
+    // The sources are cloned out so the map isn't locked while they're read
+    let loaded: Vec<(&String, Arc<dyn DataSource>)> = {
+        let data_sources = viewer_context.data_sources.read();
+        source_ids.iter().filter_map(|id| Some((id, data_sources.get(id)?.clone()))).collect()
+    };
+
+    // The first source that has the navigation column, or the first source
+    let nav_source = match &launch.nav_column {
+        Some(column) => Some(
+            loaded.iter()
+                .find(|(_, source)| runtime.block_on(source.schema()).field_with_name(column).is_ok())
+                .ok_or_else(|| format!("No loaded source has the navigation column '{}'", column))?,
+        ),
+        None => loaded.first(),
+    };
+    let Some((nav_source, source)) = nav_source else {
+        return Ok(None);
+    };
+    let source: &dyn DataSource = source.as_ref();
+
+    let positions = match &launch.nav_column {
+        Some(column) => {
+            let batch = runtime.block_on(dv_data::sources::read_all(source))
+                .map_err(|e| format!("Failed to read navigation column '{}': {}", column, e))?;
+            let array = batch.column_by_name(column)
+                .ok_or_else(|| format!("No column '{}' in {}", column, nav_source))?;
+            Some(cli::column_positions(array.as_ref()).ok_or_else(|| format!(
+                "Can't navigate by column '{}' of type {}", column, array.data_type()
+            ))?)
+        }
+        None => None,
+    };
+
+    // Navigation is by row, since sources can't yet query temporal positions;
+    // the navigation column decides which row a time resolves to
+    let mut spec = match &positions {
+        Some((values, is_time)) => {
+            let bounds = values.iter().flatten().fold(None, |bounds: Option<(f64, f64)>, &v| {
+                Some(bounds.map_or((v, v), |(lo, hi)| (lo.min(v), hi.max(v))))
+            });
+            NavigationSpec {
+                mode: NavigationMode::Sequential,
+                total_rows: values.len(),
+                temporal_bounds: bounds.filter(|_| *is_time).map(|(lo, hi)| (lo as i64, hi as i64)),
+                categories: None,
+            }
+        }
+        None => runtime.block_on(source.navigation_spec()).map_err(|e| e.to_string())?,
+    };
+    if matches!(spec.mode, NavigationMode::Temporal) {
+        spec.mode = NavigationMode::Sequential;
+    }
+    viewer_context.navigation.update_spec(spec);
+
+    if let Some(range) = range {
+        let (start, end) = range.split_once("..")
+            .ok_or_else(|| format!("--range {} should look like start..end", range))?;
+        let start = cli::seek_row(start, positions.as_ref())?;
+        let end = cli::seek_row(end, positions.as_ref())?;
+        viewer_context.navigation.set_range(Some(NavigationRange::rows(start.min(end), start.max(end) + 1)));
+        viewer_context.navigation.seek_to(NavigationPosition::Sequential(start.min(end)))?;
+    }
+    if let Some(seek) = &launch.seek {
+        let row = cli::seek_row(seek, positions.as_ref())?;
+        viewer_context.navigation.seek_to(NavigationPosition::Sequential(row))?;
+    }
+    Ok(Some(nav_source.to_string()))
+}
@@ -5,20 +5,28 @@ use parking_lot::RwLock;
 use eframe::egui::{self, Ui, Vec2, Color32, Rounding, Stroke};
 use tracing::{info, error};
 use uuid::Uuid;
+use tokio::sync::oneshot;
 use std::collections::HashMap;
 use arrow::array::{Float64Array, Float32Array, Int64Array, Int32Array, Array};
 
+use dv_templates::{TemplateError, TemplateLibrary};
 use dv_views::{
-    Viewport, ViewerContext, TimeControl, HoveredData, FrameTime,
-    TimeSeriesView, TableView, SpaceView, SummaryStatsView,
+    Viewport, ViewExport, ViewerContext, TimeControl, HoveredData, FrameTime,
+    CommandHistory, WorkspaceCommand,
+    TimeSeriesView, TableView, SpaceView, ViewRegistry,
     plots::ScatterPlotView
 };
 use dv_core::{
     data::DataSource,
-    navigation::{NavigationEngine, NavigationSpec, NavigationMode},
+    navigation::{NavigationEngine, NavigationSpec, NavigationMode, NavigationContext},
+    events::{EventBus, NavigationEventBridge},
+    events::events::{DataSourceLoading, DataSourceLoaded, DataSourceError, DataSourcesCleared, TaskFinished},
+    playback::{PlaybackEngine, PlaybackMode, PlaybackStep},
 };
 use dv_ui::{NavigationPanel, AppShell, Theme};
-use dv_data::sources::{SqliteSource, CombinedCsvSource};
+use dv_data::ParseErrorLog;
+use dv_data::export::ExportFormat;
+use dv_data::sources::{SqliteSource, CombinedCsvSource, JoinedSource, ComputedSource, ReshapeSource, ResampleSource};
 
 mod demo;
 mod create_sample_db;
@@ -26,73 +34,78 @@ mod view_builder;
 mod frog_animation;
 mod demo_overlay;
 mod file_config_dialog;
+mod join_dialog;
+mod sql_dialog;
+mod data_quality_view;
+mod parse_errors_window;
+mod status;
+mod cli;
+mod workspace;
+mod launch;
+mod render;
+mod report;
+mod save_template_dialog;
+mod templates;
+mod plugins;
 
 use view_builder::ViewBuilderDialog;
 use frog_animation::FrogMascot;
 use demo_overlay::DemoOverlay;
 use file_config_dialog::FileConfigDialog;
+use join_dialog::{JoinDialog, JoinRequest};
+use sql_dialog::{SqlDialog, SqlTable};
+use data_quality_view::DataQualityView;
+use parse_errors_window::ParseErrorsWindow;
+use save_template_dialog::SaveTemplateDialog;
+use status::{StatusBar, StatusLevel};
+use workspace::Workspace;
 
 
-/// Create default views based on schema analysis
-fn create_default_views_for_schema(schema: &arrow::datatypes::Schema) -> Vec<Box<dyn SpaceView>> {
-    let mut views: Vec<Box<dyn SpaceView>> = Vec::new();
-    
-    // Analyze columns
-    let mut numeric_columns = Vec::new();
-    let mut temporal_columns = Vec::new();
-    let mut categorical_columns = Vec::new();
-    
-    for field in schema.fields() {
-        match field.data_type() {
-            arrow::datatypes::DataType::Float64 | 
-            arrow::datatypes::DataType::Float32 | 
-            arrow::datatypes::DataType::Int64 | 
-            arrow::datatypes::DataType::Int32 => {
-                numeric_columns.push(field.name().clone());
-            }
-            arrow::datatypes::DataType::Utf8 => {
-                let name_lower = field.name().to_lowercase();
-                if name_lower.contains("date") || name_lower.contains("time") || name_lower.contains("timestamp") {
-                    temporal_columns.push(field.name().clone());
-                } else {
-                    categorical_columns.push(field.name().clone());
-                }
-            }
-            arrow::datatypes::DataType::Date32 | 
-            arrow::datatypes::DataType::Date64 | 
-            arrow::datatypes::DataType::Timestamp(_, _) => {
-                temporal_columns.push(field.name().clone());
-            }
-            _ => {}
-        }
+/// Publish a `DataSourceLoaded` event describing `source`
+async fn publish_source_loaded(viewer_context: &ViewerContext, source_name: &str, source: &dyn DataSource) {
+    let row_count = source.row_count().await.unwrap_or(0);
+    let column_count = source.schema().await.fields().len();
+    viewer_context.events.publish(DataSourceLoaded {
+        source_name: source_name.to_string(),
+        row_count,
+        column_count,
+    });
+}
+
+/// Register a source computed from the loaded ones (a join or query result)
+/// under a fresh ID
+async fn insert_derived_source(viewer_context: &ViewerContext, source: Arc<dyn DataSource>) {
+    let source_id = Uuid::new_v4().to_string();
+    publish_source_loaded(viewer_context, source.source_name(), source.as_ref()).await;
+    info!("Added derived source '{}'", source.source_name());
+    viewer_context.data_sources.write().insert(source_id, source);
+}
+
+/// Open a configured CSV file, wrapped as configured by [`wrap_configured`].
+/// Values that fail to parse are recorded in `parse_errors`.
+async fn open_configured_csv(
+    config: dv_data::config::FileConfig,
+    parse_errors: ParseErrorLog,
+) -> Result<Box<dyn DataSource>, dv_data::DataError> {
+    let source = dv_data::sources::ConfiguredCsvSource::new(config.clone()).await?.with_parse_errors(parse_errors);
+    wrap_configured(Box::new(source), &config).await
+}
+
+/// Wrap a configured file's source to add any computed columns, resampling and reshaping
+async fn wrap_configured(
+    mut source: Box<dyn DataSource>,
+    config: &dv_data::config::FileConfig,
+) -> Result<Box<dyn DataSource>, dv_data::DataError> {
+    if !config.computed_columns.is_empty() {
+        source = Box::new(ComputedSource::new(source, &config.computed_columns).await?);
     }
-    
-    // Always add a table view
-    let table_view = TableView::new(Uuid::new_v4(), "Data Table".to_string());
-    views.push(Box::new(table_view));
-    
-    // Add time series if we have numeric columns
-    if !numeric_columns.is_empty() {
-        let mut ts_view = TimeSeriesView::new(Uuid::new_v4(), "Time Series".to_string());
-        ts_view.config.x_column = temporal_columns.first().cloned();
-        ts_view.config.y_columns = numeric_columns.iter().take(3).cloned().collect();
-        views.push(Box::new(ts_view));
+    if let Some(resample) = &config.resample {
+        source = Box::new(ResampleSource::new(source, resample).await?);
     }
-    
-    // Add scatter plot if we have at least 2 numeric columns
-    if numeric_columns.len() >= 2 {
-        let mut scatter_view = ScatterPlotView::new(Uuid::new_v4(), "Correlation".to_string());
-        scatter_view.config.x_column = numeric_columns[0].clone();
-        scatter_view.config.y_column = numeric_columns[1].clone();
-        scatter_view.config.color_column = categorical_columns.first().cloned();
-        views.push(Box::new(scatter_view));
+    if let Some(reshape) = &config.reshape {
+        source = Box::new(ReshapeSource::new(source, reshape).await?);
     }
-    
-    // Add summary stats
-    let stats_view = SummaryStatsView::new(Uuid::new_v4(), "Statistics".to_string());
-    views.push(Box::new(stats_view));
-    
-    views
+    Ok(source)
 }
 
 /// Demo example types
@@ -301,14 +314,29 @@ struct FrogApp {
     /// View builder dialog
     view_builder: Option<ViewBuilderDialog>,
     
+    /// Dialog for joining two loaded sources
+    join_dialog: Option<JoinDialog>,
+    
+    /// SQL query window
+    sql_dialog: Option<SqlDialog>,
+    
+    /// SQL engine and tables still being prepared on the runtime
+    pending_sql_dialog: Option<oneshot::Receiver<(dv_data::SqlEngine, Vec<SqlTable>)>>,
+    
     /// Frog mascot
     frog_mascot: FrogMascot,
     
     /// Demo overlay
     demo_overlay: DemoOverlay,
     
-    /// Frame accumulator for smooth playback at all speeds
-    frame_accumulator: f64,
+    /// Playback state machine driving navigation while playing
+    playback: PlaybackEngine,
+    
+    /// Row timestamps (ms) for real-time playback, keyed by data source ID
+    playback_timestamps: Option<(String, Vec<i64>)>,
+    
+    /// Timestamps still being read on the runtime, keyed by data source ID
+    pending_playback_timestamps: Option<(String, oneshot::Receiver<Option<Vec<i64>>>)>,
     
     /// SQLite table selection state
     sqlite_table_selection: Option<(std::path::PathBuf, Vec<String>)>,
@@ -316,8 +344,17 @@ struct FrogApp {
     /// Show floating summary stats window
     show_summary_stats: bool,
     
-    /// Loading state - count of files being loaded
-    is_loading: Arc<RwLock<usize>>,
+    /// Status bar fed by data source events
+    status_bar: StatusBar,
+    
+    /// Forwards navigation changes onto the event bus (kept alive here)
+    _navigation_events: Arc<NavigationEventBridge>,
+    
+    /// Undo/redo history of workspace changes
+    history: CommandHistory,
+    
+    /// Navigation state as of the last recorded seek, for undo history
+    last_navigation: NavigationContext,
     
     /// Flag to open dashboard builder when data is loaded
     open_builder_on_load: bool,
@@ -327,10 +364,45 @@ struct FrogApp {
 
     /// File configuration dialog
     file_config_dialog: Option<FileConfigDialog>,
+
+    /// Configuration each CSV source was loaded with, keyed by data source ID
+    source_configs: HashMap<String, dv_data::config::FileConfig>,
+    
+    /// Values that failed to parse, keyed by data source ID
+    parse_errors: HashMap<String, ParseErrorLog>,
+    
+    /// Window listing parse failures
+    parse_errors_window: Option<ParseErrorsWindow>,
+    
+    /// Command-line request applied once its sources have loaded
+    pending_launch: Option<PendingLaunch>,
+    
+    /// View types that can be created
+    views: Arc<ViewRegistry>,
+    
+    /// Dashboard templates offered for loaded data
+    templates: TemplateLibrary,
+    
+    /// Dialog naming a template saved from the dashboard
+    save_template_dialog: Option<SaveTemplateDialog>,
+}
+
+/// Files, workspace and position requested on the command line
+struct PendingLaunch {
+    launch: cli::Launch,
+    /// IDs of the requested sources, in command-line order
+    source_ids: Vec<String>,
+}
+
+/// What a view exports: its own result table, or rows of its source
+/// (`None` for all of them)
+enum ExportData {
+    Table(arrow::record_batch::RecordBatch),
+    Rows(Arc<dyn DataSource>, Option<dv_core::navigation::NavigationRange>),
 }
 
 impl FrogApp {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    fn new(cc: &eframe::CreationContext<'_>, launch: cli::Launch) -> Self {
         // Setup custom theme
         dv_ui::apply_theme(&cc.egui_ctx, &Theme::default());
         
@@ -346,8 +418,19 @@ impl FrogApp {
             frame_time: Arc::new(RwLock::new(FrameTime::default())),
             runtime_handle: runtime.handle().clone(),
             time_axis_views: Arc::new(RwLock::new(Vec::new())),
+            events: EventBus::new(),
         });
         
+        // Deliver events on the UI thread: wake egui whenever one is queued
+        let repaint_ctx = cc.egui_ctx.clone();
+        viewer_context.events.set_waker(move || repaint_ctx.request_repaint());
+        
+        // Publish navigation and selection changes on the bus
+        let navigation_events = NavigationEventBridge::new(viewer_context.events.clone());
+        viewer_context.navigation.add_subscriber(navigation_events.clone());
+        
+        let status_bar = StatusBar::new(&viewer_context.events);
+        
         // Create navigation panel
         let navigation_panel = dv_ui::NavigationPanel::new(
             viewer_context.navigation.clone(),
@@ -363,7 +446,8 @@ impl FrogApp {
         // Get runtime handle for file config dialog
         let _runtime_handle = runtime.handle().clone();
         
-        Self {
+        let mut app = Self {
+            last_navigation: viewer_context.navigation.get_context(),
             viewport,
             viewer_context,
             _navigation_panel: navigation_panel,
@@ -373,16 +457,125 @@ impl FrogApp {
             demo_mode: false,
             egui_ctx: cc.egui_ctx.clone(),
             view_builder: None,
+            join_dialog: None,
+            sql_dialog: None,
+            pending_sql_dialog: None,
             frog_mascot: FrogMascot::new(),
             demo_overlay: DemoOverlay::new(),
-            frame_accumulator: 0.0,
+            playback: PlaybackEngine::new(),
+            playback_timestamps: None,
+            pending_playback_timestamps: None,
             sqlite_table_selection: None,
             show_summary_stats: false,
-            is_loading: Arc::new(RwLock::new(0)),
+            status_bar,
+            _navigation_events: navigation_events,
+            history: CommandHistory::new(),
             open_builder_on_load: false,
-            dashboard_builder: ViewBuilderDialog::new_multi(Vec::new()),
+            dashboard_builder: ViewBuilderDialog::new_multi(Vec::new(), Arc::new(ViewRegistry::builtin()), TemplateLibrary::builtin()),
+            views: Arc::new(plugins::registry()),
+            templates: templates::library(),
+            save_template_dialog: None,
             file_config_dialog: None,
+            source_configs: HashMap::new(),
+            parse_errors: HashMap::new(),
+            parse_errors_window: None,
+            pending_launch: None,
+        };
+        
+        if !launch.is_empty() {
+            app.start_launch(launch);
+        }
+        app
+    }
+    
+    /// Start loading the files given on the command line. The workspace and
+    /// position are applied by `apply_launch` once loading finishes.
+    fn start_launch(&mut self, launch: cli::Launch) {
+        let mut source_ids = Vec::new();
+        for source in &launch.sources {
+            let source_name = source.path.file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let loaded = if source.is_sqlite() {
+                cli::sqlite_table(&source.path, source.table.as_deref()).map(|table| {
+                    let config = dv_data::config::FileConfig::new(source.path.clone(), dv_data::config::FileType::Sqlite);
+                    let source_id = format!("{}:{}", config.file_name(), table);
+                    self.load_configured_sqlite(source_id.clone(), config, table);
+                    source_id
+                })
+            } else {
+                cli::csv_config(&source.path).map(|config| {
+                    let source_id = config.file_name();
+                    self.load_configured_csv(source_id.clone(), config);
+                    source_id
+                })
+            };
+            match loaded {
+                Ok(source_id) => source_ids.push(source_id),
+                Err(e) => {
+                    error!("Failed to open {}: {}", source.path.display(), e);
+                    self.viewer_context.events.publish(DataSourceLoading { source_name: source_name.clone() });
+                    self.viewer_context.events.publish(DataSourceError { source_name, error: e.to_string() });
+                }
+            }
+        }
+        self.pending_launch = Some(PendingLaunch { launch, source_ids });
+    }
+    
+    /// Show the requested workspace, or a default dashboard per source, then
+    /// set up navigation and seek as requested
+    fn apply_launch(&mut self, pending: PendingLaunch) {
+        let PendingLaunch { launch, source_ids } = pending;
+        
+        let workspace = launch.workspace.as_ref().and_then(|path| match Workspace::load(path) {
+            Ok(workspace) => Some(workspace),
+            Err(e) => {
+                error!("{}; showing the default dashboard", e);
+                None
+            }
+        });
+        let views = launch::dashboard_views(
+            &self.viewer_context,
+            &self.views,
+            &self.templates,
+            &source_ids,
+            workspace.as_ref(),
+            launch.nav_column.as_deref(),
+        );
+        views.show(&mut self.viewport);
+        
+        match launch::navigate(&self.viewer_context, &source_ids, &launch, None) {
+            Ok(Some(source_id)) => info!("Navigating by {}", source_id),
+            Ok(None) => {}
+            Err(e) => error!("{}", e),
+        }
+    }
+    
+    /// Save the open views as a template in the user's template folder and
+    /// offer it from now on. Returns `false`, saving nothing, if a template
+    /// of that name exists and `replace` isn't set.
+    fn save_template(&mut self, name: &str, description: &str, replace: bool) -> bool {
+        let Some(dir) = templates::template_dir() else {
+            error!("No configuration folder to save templates in");
+            return true;
+        };
+        let template = match templates::capture(&self.viewer_context, &self.viewport, name, description) {
+            Ok(template) => template,
+            Err(e) => {
+                error!("Failed to save template: {}", e);
+                return true;
+            }
+        };
+        match template.save(&dir, replace) {
+            Ok(path) => {
+                info!("Saved template '{}' to {}", name, path.display());
+                self.templates.add(template.into_template());
+            }
+            Err(TemplateError::Exists(_)) => return false,
+            Err(e) => error!("Failed to save template: {}", e),
         }
+        true
     }
     
     /// Initialize demo mode with a specific example
@@ -391,7 +584,12 @@ impl FrogApp {
         
         // Clear any existing state when switching demos
         *self.viewer_context.data_sources.write() = HashMap::new();
-        self.viewport = Viewport::new();
+        self.viewer_context.events.publish(DataSourcesCleared);
+        self.source_configs.clear();
+        self.parse_errors.clear();
+        self.viewport.clear(&self.viewer_context.events);
+        self.history.clear();
+        self.playback_timestamps = None;
         
         // Clear hover data and selection state
         {
@@ -411,10 +609,12 @@ impl FrogApp {
             self.viewer_context.navigation.update_spec(spec);
         }
         
+        self.runtime.block_on(publish_source_loaded(&self.viewer_context, example.name(), demo_source.as_ref()));
+        
         // Set it as the current data source
         let mut sources = HashMap::new();
         let demo_source_id = Uuid::new_v4().to_string();
-        sources.insert(demo_source_id.clone(), demo_source as Box<dyn DataSource>);
+        sources.insert(demo_source_id.clone(), Arc::from(demo_source as Box<dyn DataSource>));
         *self.viewer_context.data_sources.write() = sources;
         
         // Create appropriate views based on the example
@@ -450,15 +650,13 @@ impl FrogApp {
     fn open_multiple_csv_files(&mut self, paths: Vec<std::path::PathBuf>) {
         info!("Opening {} CSV files as combined source", paths.len());
         
-        // Increment loading counter
-        *self.is_loading.write() += 1;
+        let source_name = format!("{} CSV files", paths.len());
+        self.viewer_context.events.publish(DataSourceLoading { source_name: source_name.clone() });
         
         let source_future = CombinedCsvSource::new(paths.clone());
         
-        let ctx = self.egui_ctx.clone();
         let viewer_context = self.viewer_context.clone();
         let runtime = self.runtime.handle().clone();
-        let is_loading = self.is_loading.clone();
         
         runtime.spawn(async move {
             match source_future.await {
@@ -468,16 +666,14 @@ impl FrogApp {
                         viewer_context.navigation.update_spec(spec);
                     }
                     
-                    // Update data source
-                    *viewer_context.data_sources.write() = HashMap::from([(Uuid::new_v4().to_string(), Box::new(source) as Box<dyn DataSource>)]);
+                    publish_source_loaded(&viewer_context, &source_name, &source).await;
                     
-                    *is_loading.write() -= 1;
-                    ctx.request_repaint();
+                    // Update data source
+                    *viewer_context.data_sources.write() = HashMap::from([(Uuid::new_v4().to_string(), Arc::new(source) as Arc<dyn DataSource>)]);
                 }
                 Err(e) => {
                     error!("Failed to open multiple CSV files: {}", e);
-                    *is_loading.write() -= 1;
-                    ctx.request_repaint();
+                    viewer_context.events.publish(DataSourceError { source_name, error: e.to_string() });
                 }
             }
         });
@@ -509,9 +705,11 @@ impl FrogApp {
         let path = path.as_ref();
         info!("Opening SQLite table: {} from {:?}", table_name, path);
         
+        let source_name = table_name.to_string();
+        self.viewer_context.events.publish(DataSourceLoading { source_name: source_name.clone() });
+        
         let source_future = SqliteSource::new(path.to_path_buf(), table_name.to_string());
         
-        let ctx = self.egui_ctx.clone();
         let viewer_context = self.viewer_context.clone();
         let runtime = self.runtime.handle().clone();
         
@@ -523,13 +721,14 @@ impl FrogApp {
                         viewer_context.navigation.update_spec(spec);
                     }
                     
-                    // Update data source
-                    *viewer_context.data_sources.write() = HashMap::from([(Uuid::new_v4().to_string(), Box::new(source) as Box<dyn DataSource>)]);
+                    publish_source_loaded(&viewer_context, &source_name, &source).await;
                     
-                    ctx.request_repaint();
+                    // Update data source
+                    *viewer_context.data_sources.write() = HashMap::from([(Uuid::new_v4().to_string(), Arc::new(source) as Arc<dyn DataSource>)]);
                 }
                 Err(e) => {
                     error!("Failed to open SQLite table: {}", e);
+                    viewer_context.events.publish(DataSourceError { source_name, error: e.to_string() });
                 }
             }
         });
@@ -565,16 +764,16 @@ impl FrogApp {
     fn load_configured_csv(&mut self, source_id: String, config: dv_data::config::FileConfig) {
         info!("Loading configured CSV: {} from {:?}", source_id, config.path);
         
-        // Increment loading counter
-        *self.is_loading.write() += 1;
+        self.viewer_context.events.publish(DataSourceLoading { source_name: source_id.clone() });
+        self.source_configs.insert(source_id.clone(), config.clone());
+        let parse_errors = ParseErrorLog::new();
+        self.parse_errors.insert(source_id.clone(), parse_errors.clone());
         
-        let ctx = self.egui_ctx.clone();
         let viewer_context = self.viewer_context.clone();
         let runtime = self.runtime.handle().clone();
-        let is_loading = self.is_loading.clone();
         
         runtime.spawn(async move {
-            match dv_data::sources::ConfiguredCsvSource::new(config).await {
+            match open_configured_csv(config, parse_errors).await {
                 Ok(source) => {
                     // Only update navigation spec if this is the first data source
                     let is_first_source = viewer_context.data_sources.read().is_empty();
@@ -584,24 +783,17 @@ impl FrogApp {
                         }
                     }
                     
+                    publish_source_loaded(&viewer_context, &source_id, source.as_ref()).await;
+                    
                     // Add to data sources map
-                    viewer_context.data_sources.write().insert(
-                        source_id.clone(),
-                        Box::new(source) as Box<dyn DataSource>
-                    );
+                    viewer_context.data_sources.write().insert(source_id.clone(), Arc::from(source));
                     
                     let total_sources = viewer_context.data_sources.read().len();
                     info!("Successfully loaded CSV '{}'. Total data sources: {}", source_id, total_sources);
-                    
-                    // Decrement loading counter
-                    *is_loading.write() -= 1;
-                    ctx.request_repaint();
                 }
                 Err(e) => {
                     error!("Failed to load configured CSV: {}", e);
-                    // Decrement loading counter even on error
-                    *is_loading.write() -= 1;
-                    ctx.request_repaint();
+                    viewer_context.events.publish(DataSourceError { source_name: source_id, error: e.to_string() });
                 }
             }
         });
@@ -613,16 +805,17 @@ impl FrogApp {
     fn load_configured_sqlite(&mut self, source_id: String, config: dv_data::config::FileConfig, table_name: String) {
         info!("Loading configured SQLite table: {} from {:?}", table_name, config.path);
         
-        // Increment loading counter
-        *self.is_loading.write() += 1;
+        self.viewer_context.events.publish(DataSourceLoading { source_name: source_id.clone() });
         
-        let ctx = self.egui_ctx.clone();
         let viewer_context = self.viewer_context.clone();
         let runtime = self.runtime.handle().clone();
-        let is_loading = self.is_loading.clone();
         
         runtime.spawn(async move {
-            match SqliteSource::new(config.path, table_name).await {
+            let source = match SqliteSource::new(config.path.clone(), table_name).await {
+                Ok(source) => wrap_configured(Box::new(source), &config).await,
+                Err(e) => Err(e),
+            };
+            match source {
                 Ok(source) => {
                     // Only update navigation spec if this is the first data source
                     let is_first_source = viewer_context.data_sources.read().is_empty();
@@ -632,27 +825,356 @@ impl FrogApp {
                         }
                     }
                     
+                    publish_source_loaded(&viewer_context, &source_id, source.as_ref()).await;
+                    
                     // Add to data sources map
-                    viewer_context.data_sources.write().insert(
-                        source_id.clone(),
-                        Box::new(source) as Box<dyn DataSource>
-                    );
+                    viewer_context.data_sources.write().insert(source_id.clone(), Arc::from(source));
                     
                     let total_sources = viewer_context.data_sources.read().len();
                     info!("Successfully loaded SQLite table '{}'. Total data sources: {}", source_id, total_sources);
-                    
-                    // Decrement loading counter
-                    *is_loading.write() -= 1;
-                    ctx.request_repaint();
                 }
                 Err(e) => {
                     error!("Failed to load SQLite table: {}", e);
-                    // Decrement loading counter even on error
-                    *is_loading.write() -= 1;
-                    ctx.request_repaint();
+                    viewer_context.events.publish(DataSourceError { source_name: source_id, error: e.to_string() });
+                }
+            }
+        });
+    }
+    
+    /// Add a source computed from the loaded ones (a join or query result)
+    fn add_derived_source(&mut self, source: impl DataSource + 'static) {
+        let viewer_context = self.viewer_context.clone();
+        self.runtime.spawn(async move {
+            insert_derived_source(&viewer_context, Arc::new(source)).await;
+        });
+    }
+    
+    /// Copy every loaded source into a fresh SQL engine on the runtime; the
+    /// query window opens once it's ready
+    fn open_sql_dialog(&mut self) {
+        if self.pending_sql_dialog.is_some() {
+            return;
+        }
+        let sources: Vec<Arc<dyn DataSource>> = self.viewer_context.data_sources.read().values().cloned().collect();
+        self.status_bar.notify(StatusLevel::Info, format!("Preparing {} sources for SQL…", sources.len()));
+        
+        let (sender, receiver) = oneshot::channel();
+        self.pending_sql_dialog = Some(receiver);
+        let events = self.viewer_context.events.clone();
+        self.runtime.spawn(async move {
+            let mut engine = match dv_data::SqlEngine::new() {
+                Ok(engine) => engine,
+                Err(e) => {
+                    error!("Failed to start SQL engine: {}", e);
+                    events.publish(TaskFinished { message: "Failed to start SQL engine".to_string(), error: Some(e.to_string()) });
+                    return;
+                }
+            };
+            
+            let mut tables = Vec::new();
+            let mut failed = Vec::new();
+            for source in &sources {
+                let table = engine.table_name_for(source.source_name());
+                match engine.register(&table, source.as_ref()).await {
+                    Ok(()) => {
+                        let schema = source.schema().await;
+                        tables.push(SqlTable {
+                            table,
+                            source_name: source.source_name().to_string(),
+                            columns: schema.fields().iter().map(|f| f.name().clone()).collect(),
+                        });
+                    }
+                    Err(e) => {
+                        error!("Failed to register '{}' for SQL: {}", source.source_name(), e);
+                        failed.push(source.source_name().to_string());
+                    }
+                }
+            }
+            
+            tables.sort_by(|a, b| a.table.cmp(&b.table));
+            let message = format!("Registered {} tables for SQL", tables.len());
+            let error = (!failed.is_empty()).then(|| format!("couldn't register {}", failed.join(", ")));
+            let _ = sender.send((engine, tables));
+            events.publish(TaskFinished { message, error });
+        });
+    }
+    
+    /// Open the query window if its engine has finished preparing
+    fn poll_sql_dialog(&mut self) {
+        let Some(receiver) = &mut self.pending_sql_dialog else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok((engine, tables)) => {
+                self.sql_dialog = Some(SqlDialog::new(engine, tables));
+                self.pending_sql_dialog = None;
+            }
+            Err(oneshot::error::TryRecvError::Empty) => {}
+            Err(oneshot::error::TryRecvError::Closed) => self.pending_sql_dialog = None,
+        }
+    }
+    
+    /// Join two loaded sources on the runtime and add the result as a new source
+    fn join_sources(&mut self, request: JoinRequest) {
+        let (left, right) = {
+            let data_sources = self.viewer_context.data_sources.read();
+            match (data_sources.get(&request.left_id), data_sources.get(&request.right_id)) {
+                (Some(left), Some(right)) => (left.clone(), right.clone()),
+                _ => return,
+            }
+        };
+        
+        let source_name = "Joined source".to_string();
+        self.viewer_context.events.publish(DataSourceLoading { source_name: source_name.clone() });
+        let viewer_context = self.viewer_context.clone();
+        self.runtime.spawn(async move {
+            match JoinedSource::new(left.as_ref(), right.as_ref(), request.spec).await {
+                Ok(source) => insert_derived_source(&viewer_context, Arc::new(source)).await,
+                Err(e) => {
+                    error!("Failed to join sources: {}", e);
+                    viewer_context.events.publish(DataSourceError { source_name, error: e.to_string() });
+                }
+            }
+        });
+    }
+    
+    /// Profile a loaded source and open the report as a view. CSV files are
+    /// profiled from their raw text so parse failures show up.
+    fn open_data_quality(&mut self, source_id: String) {
+        let profile = match self.source_configs.get(&source_id) {
+            Some(config) => self.runtime.block_on(dv_data::profile::profile_csv(config)),
+            None => {
+                let data_sources = self.viewer_context.data_sources.read();
+                let Some(source) = data_sources.get(&source_id) else {
+                    return;
+                };
+                self.runtime.block_on(dv_data::profile::profile_source(source.as_ref()))
+            }
+        };
+        
+        match profile {
+            Ok(profile) => {
+                let view = DataQualityView::new(Uuid::new_v4(), source_id, profile);
+                self.viewport.add_space_view(Box::new(view));
+            }
+            Err(e) => {
+                error!("Failed to profile {}: {}", source_id, e);
+                self.viewer_context.events.publish(DataSourceError { source_name: source_id, error: e.to_string() });
+            }
+        }
+    }
+    
+    /// Ask where to save a view and write it as SVG (or PNG, by extension),
+    /// drawn as it currently looks
+    fn export_view(&mut self, ctx: &egui::Context, view_id: dv_views::SpaceViewId) {
+        // Only views drawn in the last frame have a size to render at
+        let Some(frame) = self.viewport.view_frame(&view_id) else {
+            if let Some(view) = self.viewport.view_mut(&view_id) {
+                let message = format!("Can't export {}: show its tab first", view.display_name());
+                self.status_bar.notify(StatusLevel::Error, message);
+            }
+            return;
+        };
+        let Some(view) = self.viewport.view_mut(&view_id) else {
+            return;
+        };
+        let file_name: String = view.display_name().chars()
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("SVG image", &["svg"])
+            .add_filter("PNG image", &["png"])
+            .set_file_name(format!("{}.svg", file_name))
+            .save_file()
+        else {
+            return;
+        };
+        
+        let memory = ctx.memory(|m| m.clone());
+        let (shapes, background) = render::capture_view(&self.viewer_context, view.as_mut(), frame, memory);
+        let size = [frame.rect.width().round() as u32, frame.rect.height().round() as u32];
+        match render::save_image(&shapes, background, size, &path) {
+            Ok(()) => {
+                info!("Exported {} to {}", view.display_name(), path.display());
+                self.status_bar.notify(StatusLevel::Info, format!("Exported {} to {}", view.display_name(), path.display()));
+            }
+            Err(e) => {
+                error!("Failed to export {}: {}", view.display_name(), e);
+                self.status_bar.notify(StatusLevel::Error, format!("Failed to export {}: {}", view.display_name(), e));
+            }
+        }
+    }
+    
+    /// Ask where to save the data behind a view and write it in the format of
+    /// the chosen extension. Views without a result table export the
+    /// selected rows of their source, or all of them when nothing is
+    /// selected. Reading and writing run on the runtime and report to the
+    /// status bar when done.
+    fn export_view_data(&mut self, view_id: dv_views::SpaceViewId) {
+        let Some(view) = self.viewport.view_mut(&view_id) else {
+            return;
+        };
+        let name = view.display_name().to_string();
+        let (data, title) = match view.result_table() {
+            Some(batch) => (ExportData::Table(batch), format!("Export the results of {}", name)),
+            None => {
+                let source = view.data_source_id()
+                    .and_then(|id| self.viewer_context.data_sources.read().get(id).cloned());
+                let Some(source) = source else {
+                    error!("Can't export {}: it has no data to export", name);
+                    self.status_bar.notify(StatusLevel::Error, format!("Can't export {}: it has no data to export", name));
+                    return;
+                };
+                let range = self.viewer_context.navigation.get_context().selection_rows();
+                let title = match &range {
+                    Some(range) => {
+                        let (start, end) = range.row_bounds();
+                        format!("Export rows {}–{} of {}", start, end.saturating_sub(1), name)
+                    }
+                    None => format!("Export all rows of {}", name),
+                };
+                (ExportData::Rows(source, range), title)
+            }
+        };
+        
+        let file_name: String = name.chars()
+            .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        let mut dialog = rfd::FileDialog::new()
+            .set_title(title)
+            .set_file_name(format!("{}.csv", file_name));
+        for format in ExportFormat::ALL {
+            dialog = dialog.add_filter(format.name(), &[format.extension()]);
+        }
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        
+        let events = self.viewer_context.events.clone();
+        self.runtime.spawn(async move {
+            let written = async {
+                let batch = match data {
+                    ExportData::Table(batch) => batch,
+                    ExportData::Rows(source, range) => {
+                        let range = match range {
+                            Some(range) => range,
+                            None => dv_core::navigation::NavigationRange::rows(0, source.row_count().await?),
+                        };
+                        source.query_range(&range).await?
+                    }
+                };
+                dv_data::export::write_batch(&batch, &path, &file_name)?;
+                Ok::<_, anyhow::Error>(batch.num_rows())
+            }.await;
+            
+            match written {
+                Ok(rows) => {
+                    info!("Exported {} rows of {} to {}", rows, name, path.display());
+                    events.publish(TaskFinished { message: format!("Exported {} rows of {} to {}", rows, name, path.display()), error: None });
                 }
+                Err(e) => {
+                    error!("Failed to export {}: {}", name, e);
+                    events.publish(TaskFinished { message: format!("Failed to export {}", name), error: Some(e.to_string()) });
+                }
+            }
+        });
+    }
+    
+    /// Undo the last workspace change
+    fn undo(&mut self) {
+        self.viewer_context.time_control.write().playing = false;
+        if let Some(description) = self.history.undo(&mut self.viewport, &self.viewer_context) {
+            info!("Undo: {}", description);
+        }
+        self.last_navigation = self.viewer_context.navigation.get_context();
+    }
+    
+    /// Redo the last undone workspace change
+    fn redo(&mut self) {
+        self.viewer_context.time_control.write().playing = false;
+        if let Some(description) = self.history.redo(&mut self.viewport, &self.viewer_context) {
+            info!("Redo: {}", description);
+        }
+        self.last_navigation = self.viewer_context.navigation.get_context();
+    }
+    
+    /// Record user seeks and selection range changes since the last frame
+    fn record_navigation_change(&mut self) {
+        let before = std::mem::replace(&mut self.last_navigation, self.viewer_context.navigation.get_context());
+        let after = &self.last_navigation;
+        
+        // Loading data resets navigation; that isn't an undoable user action
+        if after.total_rows != before.total_rows
+            || std::mem::discriminant(&after.mode) != std::mem::discriminant(&before.mode)
+        {
+            return;
+        }
+        
+        // Playback moves the position continuously; only record explicit seeks
+        if after.position != before.position && !self.viewer_context.time_control.read().playing {
+            self.history.record(WorkspaceCommand::Seek {
+                from: before.position.clone(),
+                to: after.position.clone(),
+            });
+        }
+        
+        if after.selection_range != before.selection_range {
+            self.history.record(WorkspaceCommand::SetRange {
+                from: before.selection_range.clone(),
+                to: after.selection_range.clone(),
+            });
+        }
+    }
+    
+    /// Load the row timestamps of the primary data source for real-time
+    /// playback, if they aren't cached yet. Sources without a sorted
+    /// timestamp column get an empty list, so playback falls back to a fixed rate.
+    fn ensure_playback_timestamps(&mut self) {
+        let Some((source_id, source)) = self.viewer_context.data_sources.read().iter()
+            .next()
+            .map(|(id, source)| (id.clone(), source.clone()))
+        else {
+            return;
+        };
+        if self.playback_timestamps.as_ref().is_some_and(|(id, _)| *id == source_id) {
+            return;
+        }
+        
+        // Reading a whole source can take a while; playback runs at a fixed
+        // rate until the timestamps arrive
+        if let Some((id, receiver)) = &mut self.pending_playback_timestamps {
+            if *id == source_id {
+                let timestamps = match receiver.try_recv() {
+                    Ok(timestamps) => timestamps,
+                    Err(oneshot::error::TryRecvError::Empty) => return,
+                    Err(oneshot::error::TryRecvError::Closed) => None,
+                };
+                if timestamps.is_none() {
+                    info!("No sorted timestamp column in {}; real-time playback uses a fixed rate", source_id);
+                    self.status_bar.notify(
+                        StatusLevel::Info,
+                        format!("{} has no sorted timestamp column; playing at a fixed rate", source.source_name()),
+                    );
+                }
+                self.playback_timestamps = Some((source_id, timestamps.unwrap_or_default()));
+                self.pending_playback_timestamps = None;
+                return;
             }
+        }
+        
+        let (sender, receiver) = oneshot::channel();
+        self.runtime.spawn(async move {
+            let timestamps = async {
+                let row_count = source.row_count().await.ok()?;
+                let batch = source.query_range(&dv_core::navigation::NavigationRange::rows(0, row_count)).await.ok()?;
+                batch.columns().iter()
+                    .find_map(|column| dv_core::playback::timestamps_ms(column.as_ref()))
+                    // Real-time playback needs timestamps in ascending order
+                    .filter(|ts| ts.windows(2).all(|w| w[0] <= w[1]))
+            }.await;
+            let _ = sender.send(timestamps);
         });
+        self.pending_playback_timestamps = Some((source_id, receiver));
     }
     
     /// Handle menu actions
@@ -681,7 +1203,12 @@ impl FrogApp {
                                 egui::RichText::new("🏠 Home").color(Color32::WHITE)
                             ).on_hover_text("Return to welcome screen (Press H)").clicked() {
                                 *self.viewer_context.data_sources.write() = HashMap::new();
-                                self.viewport = Viewport::new();
+                                self.viewer_context.events.publish(DataSourcesCleared);
+                                self.source_configs.clear();
+                                self.parse_errors.clear();
+                                self.viewport.clear(&self.viewer_context.events);
+                                self.history.clear();
+                                self.playback_timestamps = None;
                                 self.demo_mode = false;
                                 self.view_builder = None;
                                 ui.close_menu();
@@ -737,6 +1264,69 @@ impl FrogApp {
                                 }
                             }
                             
+                            ui.separator();
+                            
+                            if ui.add_enabled(
+                                !self.viewport.is_empty(),
+                                egui::Button::new(egui::RichText::new("💾 Save Workspace...").color(Color32::WHITE))
+                            ).on_hover_text("Save the open views to reopen later or with --workspace").clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("Workspace", &["json"])
+                                    .set_file_name("workspace.json")
+                                    .save_file()
+                                {
+                                    if let Err(e) = Workspace::from_viewport(&self.viewport).save(&path) {
+                                        error!("{}", e);
+                                    }
+                                }
+                                ui.close_menu();
+                            }
+
+                            if ui.add_enabled(
+                                !self.viewport.is_empty(),
+                                egui::Button::new(egui::RichText::new("🧩 Save as Template...").color(Color32::WHITE))
+                            ).on_hover_text("Save the open views as a template for files with the same kinds of columns").clicked() {
+                                self.save_template_dialog = Some(SaveTemplateDialog::new());
+                                ui.close_menu();
+                            }
+
+                            if ui.add_enabled(
+                                !self.viewport.is_empty(),
+                                egui::Button::new(egui::RichText::new("🌐 Export HTML Report...").color(Color32::WHITE))
+                            ).on_hover_text("Save the open views as one interactive HTML file that opens offline").clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("HTML report", &["html"])
+                                    .set_file_name("report.html")
+                                    .save_file()
+                                {
+                                    let memory = ui.ctx().memory(|m| m.clone());
+                                    if let Err(e) = report::save(&self.viewer_context, &mut self.viewport, &path, Some(&memory)) {
+                                        error!("Failed to export report: {}", e);
+                                    }
+                                }
+                                ui.close_menu();
+                            }
+
+                            if ui.button(
+                                egui::RichText::new("📋 Open Workspace...").color(Color32::WHITE)
+                            ).on_hover_text("Replace the open views with a saved workspace").clicked() {
+                                if let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("Workspace", &["json"])
+                                    .pick_file()
+                                {
+                                    match Workspace::load(&path) {
+                                        Ok(workspace) => {
+                                            launch::workspace_views(&self.viewer_context, &self.views, &workspace)
+                                                .show(&mut self.viewport);
+                                        }
+                                        Err(e) => error!("{}", e),
+                                    }
+                                }
+                                ui.close_menu();
+                            }
+                            
+                            ui.separator();
+                            
                             if ui.button(
                                 egui::RichText::new("🚪 Exit").color(Color32::WHITE)
                             ).clicked() {
@@ -745,6 +1335,35 @@ impl FrogApp {
                         }
                     );
                     
+                    ui.menu_button(
+                        egui::RichText::new("Edit").color(Color32::WHITE).size(14.0),
+                        |ui| {
+                            let undo_text = match self.history.undo_description() {
+                                Some(description) => format!("↶ Undo {}", description),
+                                None => "↶ Undo".to_string(),
+                            };
+                            if ui.add_enabled(self.history.can_undo(), egui::Button::new(undo_text))
+                                .on_hover_text("Ctrl+Z")
+                                .clicked()
+                            {
+                                self.undo();
+                                ui.close_menu();
+                            }
+                            
+                            let redo_text = match self.history.redo_description() {
+                                Some(description) => format!("↷ Redo {}", description),
+                                None => "↷ Redo".to_string(),
+                            };
+                            if ui.add_enabled(self.history.can_redo(), egui::Button::new(redo_text))
+                                .on_hover_text("Ctrl+Y or Ctrl+Shift+Z")
+                                .clicked()
+                            {
+                                self.redo();
+                                ui.close_menu();
+                            }
+                        }
+                    );
+                    
                     ui.menu_button(
                         egui::RichText::new("View").color(Color32::WHITE).size(14.0),
                         |ui| {
@@ -766,7 +1385,7 @@ impl FrogApp {
                                     
                                     if !sources_with_schemas.is_empty() {
                                         info!("Creating ViewBuilderDialog with {} data sources", sources_with_schemas.len());
-                                        self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas));
+                                        self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas, self.views.clone(), self.templates.clone()));
                                     }
                                     ui.close_menu();
                                 }
@@ -819,6 +1438,53 @@ impl FrogApp {
                                 }
                             });
                             
+                            // Join two loaded sources into a new one
+                            let can_join = self.viewer_context.data_sources.read().len() >= 2;
+                            ui.add_enabled_ui(can_join, |ui| {
+                                if ui.button(
+                                    egui::RichText::new("🔗 Join Sources...").color(if can_join { Color32::WHITE } else { Color32::from_gray(140) })
+                                ).on_hover_text(if can_join { "Combine the columns of two sources on a key or by time" } else { "Load two data sources to join them" }).clicked() {
+                                    let data_sources = self.viewer_context.data_sources.read();
+                                    let mut sources = Vec::new();
+                                    for (id, source) in data_sources.iter() {
+                                        let schema = self.runtime.block_on(source.schema());
+                                        sources.push((id.clone(), source.source_name().to_string(), schema));
+                                    }
+                                    sources.sort_by(|a, b| a.1.cmp(&b.1));
+                                    self.join_dialog = Some(JoinDialog::new(sources));
+                                    ui.close_menu();
+                                }
+                            });
+                            
+                            // Ad hoc SQL over the loaded sources
+                            ui.add_enabled_ui(has_data, |ui| {
+                                if ui.button(
+                                    egui::RichText::new("🗄 SQL Query...").color(if has_data { Color32::WHITE } else { Color32::from_gray(140) })
+                                ).on_hover_text(if has_data { "Query loaded sources with SQL and keep results as new sources" } else { "Load data first to query it" }).clicked() {
+                                    self.open_sql_dialog();
+                                    ui.close_menu();
+                                }
+                            });
+                            
+                            // Data-quality report for one source
+                            ui.add_enabled_ui(has_data, |ui| {
+                                ui.menu_button(
+                                    egui::RichText::new("🩺 Data Quality").color(if has_data { Color32::WHITE } else { Color32::from_gray(140) }),
+                                    |ui| {
+                                        let mut sources: Vec<(String, String)> = self.viewer_context.data_sources.read().iter()
+                                            .map(|(id, source)| (id.clone(), source.source_name().to_string()))
+                                            .collect();
+                                        sources.sort_by(|a, b| a.1.cmp(&b.1));
+                                        for (id, name) in sources {
+                                            if ui.button(name).on_hover_text("Nulls, parse failures, duplicates, ordering, time gaps and outliers").clicked() {
+                                                self.open_data_quality(id);
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    },
+                                );
+                            });
+                            
                             ui.separator();
                             
                             if ui.button(
@@ -922,6 +1588,23 @@ impl FrogApp {
                                     .color(Color32::from_gray(200))
                                     .size(12.0)
                             );
+                            ui.separator();
+                        }
+                        
+                        // Latest loading status from the event bus
+                        self.status_bar.ui(ui);
+                        
+                        // Values loaded as null because they didn't parse
+                        let parse_failures: usize = self.parse_errors.values().map(|log| log.total()).sum();
+                        if parse_failures > 0 {
+                            ui.separator();
+                            if ui.button(
+                                egui::RichText::new(format!("⚠ {} values failed to parse", parse_failures))
+                                    .color(Color32::from_rgb(255, 170, 60))
+                                    .size(12.0)
+                            ).on_hover_text("Show the rows and values that were loaded as null").clicked() {
+                                self.parse_errors_window = Some(ParseErrorsWindow::new());
+                            }
                         }
                     });
                 });
@@ -1084,7 +1767,7 @@ impl FrogApp {
                                 }
                                 
                                 if !sources_with_schemas.is_empty() {
-                                    self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas));
+                                    self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas, self.views.clone(), self.templates.clone()));
                                 }
                             }
                             
@@ -1102,9 +1785,11 @@ impl FrogApp {
                             );
                             
                             if templates_button.clicked() {
-                                // Create a simple default layout
-                                let views = create_default_views_for_schema(&schema);
-                                self.viewport.create_grid_layout(views);
+                                // Lay out the best fitting template
+                                let source_id = self.viewer_context.data_sources.read().keys().next().cloned();
+                                if let Some(views) = source_id.and_then(|id| launch::template_views(&self.views, &self.templates, &schema, &id, None)) {
+                                    views.show(&mut self.viewport);
+                                }
                             }
                             
                             templates_button.on_hover_text("Automatically create views based on your data");
@@ -1350,9 +2035,9 @@ impl FrogApp {
                 if let Some((_, data_source)) = data_sources.iter().next() {
                     let schema = self.runtime.block_on(data_source.schema());
                     
-                    // Get current data batch
-                    let nav_pos = self.viewer_context.navigation.get_context().position.clone();
-                    if let Ok(batch) = self.runtime.block_on(data_source.query_at(&nav_pos)) {
+                    // Get the selected rows (or all rows if nothing is selected)
+                    let range = self.viewer_context.navigation.get_context().view_range();
+                    if let Ok(batch) = self.runtime.block_on(data_source.query_range(&range)) {
                         self.render_summary_stats(ui, &batch, &schema);
                     } else {
                         ui.label("Failed to load data");
@@ -1620,6 +2305,14 @@ impl FrogApp {
 
 impl eframe::App for FrogApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Deliver events queued since the last frame (loading tasks, navigation, views)
+        self.viewer_context.events.dispatch();
+        
+        // Collect undoable layout and view setting changes from the last frame
+        for change in self.viewport.take_changes() {
+            self.history.record(change);
+        }
+        
         // Only request continuous repaint when needed
         let has_data = !self.viewer_context.data_sources.read().is_empty();
         
@@ -1670,7 +2363,24 @@ impl eframe::App for FrogApp {
         }
         
         // Handle keyboard shortcuts
+        let mut undo_requested = false;
+        let mut redo_requested = false;
+        let text_has_focus = ctx.wants_keyboard_input();
         ctx.input(|i| {
+            // Ctrl+Z to undo, Ctrl+Y or Ctrl+Shift+Z to redo (text fields keep their own undo)
+            if !text_has_focus && i.modifiers.ctrl {
+                if i.key_pressed(egui::Key::Z) {
+                    if i.modifiers.shift {
+                        redo_requested = true;
+                    } else {
+                        undo_requested = true;
+                    }
+                }
+                if i.key_pressed(egui::Key::Y) {
+                    redo_requested = true;
+                }
+            }
+            
             // Playback controls
             if i.key_pressed(egui::Key::Space) {
                 let mut time_control = self.viewer_context.time_control.write();
@@ -1737,7 +2447,12 @@ impl eframe::App for FrogApp {
             // H key (not Ctrl+H) to go home
             if i.key_pressed(egui::Key::H) && !i.modifiers.ctrl {
                 *self.viewer_context.data_sources.write() = HashMap::new();
-                self.viewport = Viewport::new();
+                self.viewer_context.events.publish(DataSourcesCleared);
+                self.source_configs.clear();
+                self.parse_errors.clear();
+                self.viewport.clear(&self.viewer_context.events);
+                self.history.clear();
+                self.playback_timestamps = None;
                 self.demo_mode = false;
                 self.view_builder = None;
             }
@@ -1755,7 +2470,7 @@ impl eframe::App for FrogApp {
                 
                 if !sources_with_schemas.is_empty() {
                     info!("Creating ViewBuilderDialog from B key");
-                    self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas));
+                    self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas, self.views.clone(), self.templates.clone()));
                 }
             }
             
@@ -1775,71 +2490,59 @@ impl eframe::App for FrogApp {
                 ctx.request_repaint();
             }
             
-            // Escape to stop playback
+            // Escape to stop playback and clear the selection range
             if i.key_pressed(egui::Key::Escape) {
                 self.viewer_context.time_control.write().playing = false;
+                if !text_has_focus {
+                    self.viewer_context.navigation.set_range(None);
+                }
             }
         });
         
+        if undo_requested {
+            self.undo();
+        } else if redo_requested {
+            self.redo();
+        }
+        
         // Handle time control playback
-        if self.viewer_context.time_control.read().playing {
-            let speed = self.viewer_context.time_control.read().speed.clamp(0.1, 5.0); // Limit speed to reasonable range
-            let dt = ctx.input(|i| i.stable_dt);
+        let time_control = self.viewer_context.time_control.read().clone();
+        if time_control.playing {
+            let dt = ctx.input(|i| i.stable_dt) as f64;
+            let nav_context = self.viewer_context.navigation.get_context();
+            let bounds = nav_context.view_range().row_bounds();
             
-            // Calculate frames to advance using accumulator for smooth playback
-            // Use a much more reasonable base rate: 0.5 frames per second at 1.0x speed
-            let frames_per_second = speed * 0.5;
-            self.frame_accumulator += frames_per_second * dt as f64;
+            if time_control.mode == PlaybackMode::RealTime {
+                self.ensure_playback_timestamps();
+            }
+            let timestamps = self.playback_timestamps.as_ref().map(|(_, ts)| ts.as_slice());
             
-            // Only advance when we've accumulated at least one frame
-            let frame_advance = self.frame_accumulator as usize;
-            if frame_advance > 0 {
-                // Subtract the frames we're advancing
-                self.frame_accumulator -= frame_advance as f64;
-                
-                // Advance navigation by calculated frames
-                let nav_context = self.viewer_context.navigation.get_context();
-                let current_pos = match &nav_context.position {
-                    dv_core::navigation::NavigationPosition::Sequential(idx) => *idx,
-                    dv_core::navigation::NavigationPosition::Temporal(ts) => *ts as usize,
-                    _ => 0,
-                };
-                
-                let new_pos = current_pos + frame_advance;
-                let total_rows = nav_context.total_rows;
-                
-                if new_pos >= total_rows {
-                    if self.viewer_context.time_control.read().looping {
-                        // Loop back to beginning
-                        let _ = self.viewer_context.navigation.seek_to(
-                            dv_core::navigation::NavigationPosition::Sequential(0)
-                        );
-                        // Reset accumulator when looping
-                        self.frame_accumulator = 0.0;
-                    } else {
-                        // Stop at end
-                        self.viewer_context.time_control.write().playing = false;
-                        self.frame_accumulator = 0.0;
-                    }
-                } else {
-                    // Continue advancing
+            match self.playback.tick(&time_control, nav_context.position.frame_nr(), bounds, timestamps, dt) {
+                PlaybackStep::Hold => {}
+                PlaybackStep::Seek(row) => {
                     let _ = self.viewer_context.navigation.seek_to(
-                        dv_core::navigation::NavigationPosition::Sequential(new_pos)
+                        dv_core::navigation::NavigationPosition::Sequential(row)
                     );
                 }
+                PlaybackStep::Stop(row) => {
+                    let _ = self.viewer_context.navigation.seek_to(
+                        dv_core::navigation::NavigationPosition::Sequential(row)
+                    );
+                    self.viewer_context.time_control.write().playing = false;
+                }
             }
             
             ctx.request_repaint();
         } else {
-            // Reset accumulator when not playing
-            self.frame_accumulator = 0.0;
+            // Forget carried-over time when not playing
+            self.playback.reset();
         }
         
         // Check if we have data loaded
         let has_data = !self.viewer_context.data_sources.read().is_empty();
         
         // Check if we should open dashboard builder automatically
-        if self.open_builder_on_load && has_data && self.view_builder.is_none() && *self.is_loading.read() == 0 {
+        if self.open_builder_on_load && has_data && self.view_builder.is_none() && self.status_bar.loading_count() == 0 {
             // Data is loaded, open dashboard builder
             self.open_builder_on_load = false;
             
@@ -1854,7 +2557,14 @@ impl eframe::App for FrogApp {
             
             if !sources_with_schemas.is_empty() {
                 info!("Auto-opening ViewBuilderDialog after data load");
-                self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas));
+                self.view_builder = Some(ViewBuilderDialog::new_multi(sources_with_schemas, self.views.clone(), self.templates.clone()));
+            }
+        }
+        
+        // Apply the command-line workspace and position once its sources have loaded
+        if self.pending_launch.is_some() && self.status_bar.loading_count() == 0 {
+            if let Some(pending) = self.pending_launch.take() {
+                self.apply_launch(pending);
             }
         }
         
@@ -1890,6 +2600,51 @@ impl eframe::App for FrogApp {
             return;
         }
         
+        // Show join dialog
+        if let Some(ref mut dialog) = self.join_dialog {
+            let request = dialog.show_dialog(ctx);
+            if !dialog.show {
+                self.join_dialog = None;
+            }
+            if let Some(request) = request {
+                self.join_sources(request);
+            }
+        }
+        
+        // Show template naming dialog
+        if let Some(mut dialog) = self.save_template_dialog.take() {
+            if let Some((name, description, replace)) = dialog.show_dialog(ctx) {
+                if !self.save_template(&name, &description, replace) {
+                    dialog.ask_to_replace();
+                }
+            }
+            if dialog.show {
+                self.save_template_dialog = Some(dialog);
+            }
+        }
+        
+        // Show SQL query window
+        self.poll_sql_dialog();
+        if let Some(ref mut dialog) = self.sql_dialog {
+            let source = dialog.show_dialog(ctx);
+            if !dialog.show {
+                self.sql_dialog = None;
+            }
+            if let Some(source) = source {
+                self.add_derived_source(source);
+            }
+        }
+        
+        // Show parse error list
+        if let Some(ref mut window) = self.parse_errors_window {
+            let mut logs: Vec<_> = self.parse_errors.iter().collect();
+            logs.sort_by(|a, b| a.0.cmp(b.0));
+            window.show_window(ctx, &logs);
+            if !window.show {
+                self.parse_errors_window = None;
+            }
+        }
+        
         // Show SQLite table selection dialog
         if let Some((path, tables)) = &self.sqlite_table_selection.clone() {
             let mut close_dialog = false;
@@ -1950,7 +2705,7 @@ impl eframe::App for FrogApp {
         }
         
         // Show loading indicator if loading
-        if *self.is_loading.read() > 0 {
+        if self.status_bar.loading_count() > 0 {
             egui::Window::new("Loading...")
                 .collapsible(false)
                 .resizable(false)
@@ -2021,10 +2776,34 @@ impl eframe::App for FrogApp {
                 }
             });
         }
+        
+        self.record_navigation_change();
+        
+        for (view_id, export) in self.viewport.take_export_requests() {
+            match export {
+                ViewExport::Image => self.export_view(ctx, view_id),
+                ViewExport::Data => self.export_view_data(view_id),
+            }
+        }
     }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Subcommands run headless and exit with their status code
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    
+    // Anything else names files, a workspace and a position to open
+    let launch = match cli::parse_launch(&args) {
+        Ok(launch) => launch,
+        Err(message) => {
+            cli::launch_usage_error(&message);
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    };
+    
     // Initialize tracing
     tracing_subscriber::fmt::init();
     
@@ -2050,8 +2829,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     eframe::run_native(
         "F.R.O.G. - Fast, Responsive, Organized Graphics 🐸",
         options,
-        Box::new(|cc| {
-            Box::new(FrogApp::new(cc))
+        Box::new(move |cc| {
+            Box::new(FrogApp::new(cc, launch))
         }),
     ).map_err(|e| anyhow::anyhow!("Failed to run app: {}", e))?;
     
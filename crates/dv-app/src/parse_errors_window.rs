@@ -0,0 +1,96 @@
+//! Window listing values that failed to parse in loaded sources
+
+use egui::{Context, Grid, RichText, ScrollArea, Color32};
+use dv_data::ParseErrorLog;
+
+/// Failures shown for the selected column
+const SHOWN_FAILURES: usize = 200;
+
+/// Lists parse failures per source and column, with row numbers and raw text
+pub struct ParseErrorsWindow {
+    /// Source ID and column whose failures are listed
+    selected: Option<(String, String)>,
+
+    /// Show window
+    pub show: bool,
+}
+
+impl ParseErrorsWindow {
+    pub fn new() -> Self {
+        Self {
+            selected: None,
+            show: true,
+        }
+    }
+
+    /// Show the window for the logs of each source, sorted by source ID
+    pub fn show_window(&mut self, ctx: &Context, logs: &[(&String, &ParseErrorLog)]) {
+        let mut open = self.show;
+
+        egui::Window::new("⚠ Parse Errors")
+            .open(&mut open)
+            .default_size([520.0, 420.0])
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new("These values did not match their column type and were loaded as null. \
+                                   Rows are counted from the first row after the header; only rows read so far are checked.")
+                        .color(Color32::from_gray(170))
+                );
+                ui.add_space(4.0);
+
+                for (source_id, log) in logs {
+                    let counts = log.counts();
+                    if counts.is_empty() {
+                        continue;
+                    }
+                    ui.label(RichText::new(source_id.as_str()).strong());
+                    for (column, count) in counts {
+                        let key = ((*source_id).clone(), column.clone());
+                        let selected = self.selected.as_ref() == Some(&key);
+                        if ui.selectable_label(selected, format!("  {}: {} values", column, count)).clicked() {
+                            self.selected = if selected { None } else { Some(key) };
+                        }
+                    }
+                }
+
+                let Some((source_id, column)) = &self.selected else {
+                    return;
+                };
+                let Some((_, log)) = logs.iter().find(|(id, _)| *id == source_id) else {
+                    return;
+                };
+                let failures = log.failures(column);
+
+                ui.separator();
+                if let Some(first) = failures.first() {
+                    ui.label(format!("{} expects {}", column, first.expected));
+                }
+                ScrollArea::vertical()
+                    .id_source("parse_error_rows")
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        Grid::new("parse_error_grid")
+                            .striped(true)
+                            .spacing([16.0, 4.0])
+                            .show(ui, |ui| {
+                                ui.label(RichText::new("Row").strong());
+                                ui.label(RichText::new("Value").strong());
+                                ui.end_row();
+                                for failure in failures.iter().take(SHOWN_FAILURES) {
+                                    ui.monospace((failure.row + 1).to_string());
+                                    ui.monospace(format!("{:?}", failure.value));
+                                    ui.end_row();
+                                }
+                            });
+                        // Counting failures that weren't stored, too
+                        let shown = failures.len().min(SHOWN_FAILURES);
+                        let total = log.count(column);
+                        if total > shown {
+                            ui.label(format!("… and {} more", total - shown));
+                        }
+                    });
+            });
+
+        self.show = open;
+    }
+}
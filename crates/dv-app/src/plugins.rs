@@ -0,0 +1,38 @@
+//! View plugins installed by the user
+//!
+//! Plugins are WebAssembly modules (see `dv_plugins`) kept in the `plugins`
+//! folder of the configuration directory (`~/.config/frog-viz/plugins` on
+//! Linux) and offered alongside the built-in views.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+use dv_views::ViewRegistry;
+
+/// Folder of the user's plugins
+pub fn plugin_dir() -> Option<PathBuf> {
+    directories_next::ProjectDirs::from("", "", "frog-viz").map(|dirs| dirs.config_dir().join("plugins"))
+}
+
+/// The built-in views and those of the user's plugins. Plugins can't
+/// replace a view type that's already registered.
+pub fn registry() -> ViewRegistry {
+    let mut registry = ViewRegistry::builtin();
+    let Some(dir) = plugin_dir() else {
+        return registry;
+    };
+    let (plugins, errors) = dv_plugins::load_dir(&dir);
+    for e in errors {
+        warn!("Skipping plugin: {}", e);
+    }
+    for plugin in plugins {
+        let view_type = &plugin.manifest().view_type;
+        if registry.get(view_type).is_some() {
+            warn!("Skipping plugin {}: view type '{}' already exists", plugin.path().display(), view_type);
+            continue;
+        }
+        info!("Loaded view plugin '{}' from {}", view_type, plugin.path().display());
+        registry.register(Arc::new(plugin).descriptor());
+    }
+    registry
+}
@@ -0,0 +1,192 @@
+//! Headless rendering of dashboards and single views to PNG and SVG images
+//!
+//! The dashboard (or view) is laid out and painted by the regular UI code
+//! inside an egui context that has no window; the resulting shapes are drawn
+//! with a `dv_render` backend, so no GPU or display server is needed.
+//! `frog render` writes an HTML report instead when the output is `.html`.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use parking_lot::RwLock;
+use egui::epaint::ClippedShape;
+use dv_core::data::DataSource;
+use dv_core::events::EventBus;
+use dv_core::navigation::NavigationMode;
+use dv_data::DataError;
+use dv_render::{CpuRenderer, Renderer, SvgRenderer};
+use dv_views::{FrameTime, HoveredData, NavigationEngine, SpaceView, TimeControl, ViewFrame, ViewerContext, Viewport};
+
+use crate::cli::{self, Launch, EXIT_OK};
+use crate::launch;
+use crate::plugins;
+use crate::templates;
+use crate::workspace::Workspace;
+
+/// Frames run before capturing, so layout and lazily loaded data settle
+const SETTLE_FRAMES: usize = 3;
+
+/// Image format, from the output file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "png" => Some(ImageFormat::Png),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// Lay out and paint `viewport` in a headless egui context of `size` pixels
+pub fn capture(viewer_context: &ViewerContext, viewport: &mut Viewport, size: [u32; 2]) -> (Vec<ClippedShape>, egui::Color32) {
+    let (ctx, shapes) = lay_out(viewer_context, viewport, size);
+    (shapes, ctx.style().visuals.panel_fill)
+}
+
+fn lay_out(viewer_context: &ViewerContext, viewport: &mut Viewport, size: [u32; 2]) -> (egui::Context, Vec<ClippedShape>) {
+    let ctx = egui::Context::default();
+    dv_ui::apply_theme(&ctx, &dv_ui::Theme::default());
+    let shapes = run_frames(&ctx, egui::vec2(size[0] as f32, size[1] as f32), |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            viewport.ui(ui, viewer_context);
+        });
+    });
+    (ctx, shapes)
+}
+
+/// Paint one view on its own, at the size it was last drawn in the app.
+/// `memory` is a copy of the app's egui memory: with it and the view's own
+/// `Ui` ID, remembered state like plot zoom and the app's style carry over.
+pub fn capture_view(
+    viewer_context: &ViewerContext,
+    view: &mut dyn SpaceView,
+    frame: ViewFrame,
+    memory: egui::Memory,
+) -> (Vec<ClippedShape>, egui::Color32) {
+    let ctx = egui::Context::default();
+    ctx.memory_mut(|m| *m = memory);
+    let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, frame.rect.size());
+    let shapes = run_frames(&ctx, rect.size(), |ctx| {
+        let mut ui = egui::Ui::new(ctx.clone(), egui::LayerId::background(), frame.ui_id, rect, rect);
+        view.ui(viewer_context, &mut ui);
+    });
+    (shapes, ctx.style().visuals.panel_fill)
+}
+
+/// Run `add_contents` for `SETTLE_FRAMES` frames of `size` points, returning
+/// the shapes of the last
+fn run_frames(ctx: &egui::Context, size: egui::Vec2, mut add_contents: impl FnMut(&egui::Context)) -> Vec<ClippedShape> {
+    let screen_rect = egui::Rect::from_min_size(egui::Pos2::ZERO, size);
+    let mut shapes = Vec::new();
+    for frame in 0..SETTLE_FRAMES {
+        let input = egui::RawInput {
+            screen_rect: Some(screen_rect),
+            time: Some(frame as f64 / 60.0),
+            ..Default::default()
+        };
+        shapes = ctx.run(input, &mut add_contents).shapes;
+    }
+    shapes
+}
+
+/// Draw captured shapes to a PNG or SVG file
+pub fn save_image(shapes: &[ClippedShape], background: egui::Color32, size: [u32; 2], path: &Path) -> Result<(), DataError> {
+    let format = ImageFormat::from_path(path).ok_or_else(|| DataError::Other(format!(
+        "Unknown image format for {}; use .png or .svg",
+        path.display()
+    )))?;
+    let background = background.to_srgba_unmultiplied().map(|c| c as f32 / 255.0);
+    match format {
+        ImageFormat::Png => {
+            let mut renderer = CpuRenderer::new(size[0], size[1]).with_background(background);
+            draw(&mut renderer, shapes);
+            renderer.save_png(path)?;
+        }
+        ImageFormat::Svg => {
+            let mut renderer = SvgRenderer::new(size[0] as f32, size[1] as f32).with_background(background);
+            draw(&mut renderer, shapes);
+            renderer.save(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn draw(renderer: &mut dyn Renderer, shapes: &[ClippedShape]) {
+    renderer.begin_frame();
+    dv_render::egui_shapes::paint_shapes(renderer, shapes);
+    renderer.end_frame();
+}
+
+/// `frog render`: load the sources, build the workspace or default dashboard,
+/// navigate and write the image (or report)
+pub fn run(
+    runtime: &tokio::runtime::Runtime,
+    output: &Path,
+    launch: &Launch,
+    size: [u32; 2],
+    range: Option<&str>,
+) -> Result<i32, DataError> {
+    let report = output.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("html"));
+    if ImageFormat::from_path(output).is_none() && !report {
+        return Err(DataError::Other(format!("Unknown output format for {}; use .png, .svg or .html", output.display())));
+    }
+
+    let mut data_sources: HashMap<String, Arc<dyn DataSource>> = HashMap::new();
+    let mut source_ids = Vec::new();
+    for source in &launch.sources {
+        let file_name = source.path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string();
+        let (source_id, table) = if source.is_sqlite() {
+            let table = cli::sqlite_table(&source.path, source.table.as_deref())?;
+            (format!("{}:{}", file_name, table), Some(table))
+        } else {
+            (file_name, None)
+        };
+        let opened = runtime.block_on(cli::open_source(&source.path, table.as_deref()))?;
+        data_sources.insert(source_id.clone(), Arc::from(opened));
+        source_ids.push(source_id);
+    }
+    let workspace = launch.workspace.as_deref().map(Workspace::load).transpose().map_err(DataError::Other)?;
+
+    let viewer_context = ViewerContext {
+        data_sources: Arc::new(RwLock::new(data_sources)),
+        navigation: Arc::new(NavigationEngine::new(NavigationMode::Sequential)),
+        time_control: Arc::new(RwLock::new(TimeControl::default())),
+        hovered_data: Arc::new(RwLock::new(HoveredData::default())),
+        frame_time: Arc::new(RwLock::new(FrameTime::default())),
+        runtime_handle: runtime.handle().clone(),
+        time_axis_views: Arc::new(RwLock::new(Vec::new())),
+        events: EventBus::new(),
+    };
+
+    let views = launch::dashboard_views(
+        &viewer_context,
+        &plugins::registry(),
+        &templates::library(),
+        &source_ids,
+        workspace.as_ref(),
+        launch.nav_column.as_deref(),
+    );
+    if views.is_empty() {
+        return Err(DataError::Other("Nothing to render: give data files or a workspace".to_string()));
+    }
+    let mut viewport = Viewport::new();
+    views.show(&mut viewport);
+    launch::navigate(&viewer_context, &source_ids, launch, range).map_err(DataError::Other)?;
+
+    if report {
+        // Laid out once so views the report can't draw have a size for their snapshots
+        let (ctx, _) = lay_out(&viewer_context, &mut viewport, size);
+        let memory = ctx.memory(|m| m.clone());
+        crate::report::save(&viewer_context, &mut viewport, output, Some(&memory))?;
+        return Ok(EXIT_OK);
+    }
+    let (shapes, background) = capture(&viewer_context, &mut viewport, size);
+    save_image(&shapes, background, size, output)?;
+    Ok(EXIT_OK)
+}
@@ -0,0 +1,393 @@
+//! Interactive HTML reports
+//!
+//! A report is one HTML file holding the dock layout, the data behind each
+//! view as JSON (downsampled where it's large) and a small script that draws
+//! the charts in the browser, so it opens offline, e.g. from an email
+//! attachment. View types the script can't draw are embedded as SVG
+//! snapshots when they've been on screen.
+
+use std::collections::HashMap;
+use std::path::Path;
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{DataType, Float64Type, UInt64Type};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use serde_json::{json, Value};
+use dv_data::DataError;
+use dv_render::{Renderer, SvgRenderer};
+use dv_views::{SpaceView, ViewLayout, ViewerContext, Viewport};
+
+use crate::cli;
+
+const TEMPLATE: &str = include_str!("report.html");
+
+/// Points kept per time series line; larger series keep each bucket's
+/// extremes, so peaks survive
+const MAX_LINE_POINTS: usize = 2000;
+
+/// Points kept per scatter plot, sampled evenly
+const MAX_SCATTER_POINTS: usize = 5000;
+
+/// Rows kept per table
+const MAX_TABLE_ROWS: usize = 1000;
+
+/// Histogram bins when the view hasn't binned its data yet
+const DEFAULT_BINS: usize = 30;
+
+/// Build the report for the views of `viewport`, with the rows the views
+/// currently show. With the app's egui `memory`, views the report can't
+/// draw itself are included as snapshots of how they look on screen.
+pub fn build(
+    viewer_context: &ViewerContext,
+    viewport: &mut Viewport,
+    title: &str,
+    memory: Option<&egui::Memory>,
+) -> Result<String, DataError> {
+    let layout = viewport.layout()
+        .ok_or_else(|| DataError::Other("Nothing to report: there are no views".to_string()))?;
+    let navigation = viewer_context.navigation.get_context();
+    let range = navigation.view_range();
+
+    // Every view of a source shows the same rows, so each is read once
+    let mut batches: HashMap<String, Option<RecordBatch>> = HashMap::new();
+    let ids: Vec<_> = viewport.views().map(|view| view.id()).collect();
+    let mut views = serde_json::Map::new();
+    for id in ids {
+        let frame = viewport.view_frame(&id);
+        let Some(view) = viewport.view_mut(&id) else {
+            continue;
+        };
+        let source_id = {
+            let data_sources = viewer_context.data_sources.read();
+            view.data_source_id().map(str::to_string)
+                .or_else(|| data_sources.keys().min().cloned())
+        };
+        let batch = source_id.and_then(|source_id| {
+            batches.entry(source_id.clone())
+                .or_insert_with(|| {
+                    let data_sources = viewer_context.data_sources.read();
+                    let source = data_sources.get(&source_id)?;
+                    viewer_context.runtime_handle.block_on(source.query_range(&range)).ok()
+                })
+                .clone()
+        });
+
+        let mut data = view_data(view.as_ref(), batch.as_ref(), range.row_bounds().0);
+        if data.is_null() {
+            data = match (frame, memory) {
+                (Some(frame), Some(memory)) => {
+                    let (shapes, background) = crate::render::capture_view(viewer_context, view.as_mut(), frame, memory.clone());
+                    let mut renderer = SvgRenderer::new(frame.rect.width(), frame.rect.height())
+                        .with_background(background.to_srgba_unmultiplied().map(|c| c as f32 / 255.0));
+                    renderer.begin_frame();
+                    dv_render::egui_shapes::paint_shapes(&mut renderer, &shapes);
+                    renderer.end_frame();
+                    json!({ "kind": "image", "svg": renderer.to_svg() })
+                }
+                _ => json!({
+                    "kind": "message",
+                    "message": format!("{} views can't be shown in reports yet", view.view_type()),
+                }),
+            };
+        }
+        data["title"] = json!(view.display_name());
+        views.insert(id.to_string(), data);
+    }
+
+    let (start, end) = range.row_bounds();
+    let rows = if navigation.selection_rows().is_some() {
+        format!("Rows {}–{} of {}", start, end, navigation.total_rows)
+    } else {
+        format!("{} rows", navigation.total_rows)
+    };
+    let mut sources: Vec<String> = viewer_context.data_sources.read().keys().cloned().collect();
+    sources.sort();
+    let report = json!({
+        "title": title,
+        "subtitle": format!("{} · {} · generated {}", sources.join(", "), rows, chrono::Local::now().format("%Y-%m-%d %H:%M")),
+        "layout": layout_json(&layout),
+        "views": views,
+    });
+    Ok(render_html(title, &report))
+}
+
+/// Write the report to `path`
+pub fn save(
+    viewer_context: &ViewerContext,
+    viewport: &mut Viewport,
+    path: &Path,
+    memory: Option<&egui::Memory>,
+) -> Result<(), DataError> {
+    let title = path.file_stem().and_then(|s| s.to_str()).unwrap_or("Report");
+    let html = build(viewer_context, viewport, title, memory)?;
+    std::fs::write(path, html)?;
+    Ok(())
+}
+
+/// The report page for `report`, the JSON document the script draws
+pub fn render_html(title: &str, report: &Value) -> String {
+    // Markup like `</script>` in the data would end the script element
+    // early; `<` only occurs inside JSON strings, where `\u003c` is the same
+    let data = report.to_string().replace('<', "\\u003c");
+    TEMPLATE
+        .replace("{{title}}", &escape_html(title))
+        .replace("{{data}}", &data)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Layout as nested `{"tabs": [...]}` and `{"split": ..., "first": ..., "second": ...}` objects
+fn layout_json(layout: &ViewLayout) -> Value {
+    match layout {
+        ViewLayout::Tabs(ids) => json!({ "tabs": ids.iter().map(|id| id.to_string()).collect::<Vec<_>>() }),
+        ViewLayout::Split { horizontal, fraction, first, second } => json!({
+            "split": if *horizontal { "row" } else { "column" },
+            "fraction": fraction,
+            "first": layout_json(first),
+            "second": layout_json(second),
+        }),
+    }
+}
+
+/// Chart data for the view types the report script draws, or `Value::Null`
+fn view_data(view: &dyn SpaceView, batch: Option<&RecordBatch>, first_row: usize) -> Value {
+    let config = view.save_config();
+    let text = |key: &str| config.get(key).and_then(Value::as_str).filter(|s| !s.is_empty());
+    match view.view_type() {
+        "TimeSeriesView" => {
+            let Some(batch) = batch else { return Value::Null };
+            let y_columns: Vec<&str> = config.get("y_columns").and_then(Value::as_array)
+                .map(|columns| columns.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            line_data(batch, text("x_column"), &y_columns, first_row)
+        }
+        "ScatterPlotView" => match (batch, text("x_column"), text("y_column")) {
+            (Some(batch), Some(x), Some(y)) => scatter_data(batch, x, y),
+            _ => Value::Null,
+        },
+        "BarChartView" => match (batch, text("category_column"), text("value_column")) {
+            (Some(batch), Some(category), Some(value)) => bar_data(batch, category, value),
+            _ => Value::Null,
+        },
+        "HistogramView" => {
+            let column = text("column").unwrap_or_default();
+            let bins = view.result_table().and_then(|table| bins_from_table(&table)).or_else(|| {
+                let values = numeric_column(batch?, column)?.0;
+                let count = config.get("num_bins").and_then(Value::as_u64).map_or(DEFAULT_BINS, |n| n as usize);
+                Some(equal_width_bins(&values.into_iter().flatten().collect::<Vec<_>>(), count))
+            });
+            match bins {
+                Some(bins) => json!({ "kind": "histogram", "label": column, "bins": bins }),
+                None => Value::Null,
+            }
+        }
+        "TableView" => batch.map(table_data).unwrap_or(Value::Null),
+        _ => Value::Null,
+    }
+}
+
+/// A column as numbers, with whether they're milliseconds since the epoch
+fn numeric_column(batch: &RecordBatch, name: &str) -> Option<(Vec<Option<f64>>, bool)> {
+    cli::column_positions(batch.column_by_name(name)?.as_ref())
+}
+
+fn line_data(batch: &RecordBatch, x_column: Option<&str>, y_columns: &[&str], first_row: usize) -> Value {
+    let (x, x_time) = match x_column.and_then(|name| numeric_column(batch, name)) {
+        Some(positions) => positions,
+        None => ((0..batch.num_rows()).map(|i| Some((first_row + i) as f64)).collect(), false),
+    };
+    let series: Vec<Value> = y_columns.iter()
+        .filter_map(|name| {
+            let (y, _) = numeric_column(batch, name)?;
+            let points: Vec<(f64, f64)> = x.iter().zip(&y)
+                .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+                .collect();
+            let kept = downsample_min_max(&points, MAX_LINE_POINTS);
+            Some(json!({
+                "name": name,
+                "x": kept.iter().map(|&i| points[i].0).collect::<Vec<_>>(),
+                "y": kept.iter().map(|&i| points[i].1).collect::<Vec<_>>(),
+            }))
+        })
+        .collect();
+    if series.is_empty() {
+        return Value::Null;
+    }
+    json!({
+        "kind": "line",
+        "x_label": x_column.unwrap_or("Row"),
+        "x_time": x_time,
+        "series": series,
+        "note": downsampled_note(batch.num_rows(), MAX_LINE_POINTS),
+    })
+}
+
+fn scatter_data(batch: &RecordBatch, x_column: &str, y_column: &str) -> Value {
+    let (Some((x, x_time)), Some((y, y_time))) = (numeric_column(batch, x_column), numeric_column(batch, y_column)) else {
+        return Value::Null;
+    };
+    let points: Vec<(f64, f64)> = x.iter().zip(&y)
+        .filter_map(|(x, y)| Some(((*x)?, (*y)?)))
+        .collect();
+    let kept = stride_sample(points.len(), MAX_SCATTER_POINTS);
+    json!({
+        "kind": "scatter",
+        "x_label": x_column,
+        "y_label": y_column,
+        "x_time": x_time,
+        "y_time": y_time,
+        "x": kept.iter().map(|&i| points[i].0).collect::<Vec<_>>(),
+        "y": kept.iter().map(|&i| points[i].1).collect::<Vec<_>>(),
+        "note": downsampled_note(points.len(), MAX_SCATTER_POINTS),
+    })
+}
+
+/// Values summed per category, in category order, as the bar chart view does
+fn bar_data(batch: &RecordBatch, category_column: &str, value_column: &str) -> Value {
+    let (Some(categories), Some((values, _))) = (batch.column_by_name(category_column), numeric_column(batch, value_column)) else {
+        return Value::Null;
+    };
+    let Some(categories) = formatted(categories.as_ref()) else {
+        return Value::Null;
+    };
+    let mut sums: HashMap<String, f64> = HashMap::new();
+    for (category, value) in categories.into_iter().zip(values) {
+        if let (Some(category), Some(value)) = (category, value) {
+            *sums.entry(category).or_insert(0.0) += value;
+        }
+    }
+    let mut sums: Vec<(String, f64)> = sums.into_iter().collect();
+    sums.sort_by(|a, b| a.0.cmp(&b.0));
+    json!({
+        "kind": "bar",
+        "label": value_column,
+        "categories": sums.iter().map(|(c, _)| c).collect::<Vec<_>>(),
+        "values": sums.iter().map(|(_, v)| v).collect::<Vec<_>>(),
+    })
+}
+
+fn table_data(batch: &RecordBatch) -> Value {
+    let rows = batch.num_rows().min(MAX_TABLE_ROWS);
+    let columns: Vec<Vec<Option<String>>> = batch.columns().iter()
+        .map(|column| formatted(column.slice(0, rows).as_ref()).unwrap_or_else(|| vec![None; rows]))
+        .collect();
+    let numeric: Vec<bool> = batch.columns().iter().map(|column| column.data_type().is_numeric()).collect();
+    json!({
+        "kind": "table",
+        "columns": batch.schema().fields().iter().map(|f| f.name()).collect::<Vec<_>>(),
+        "numeric": numeric,
+        "rows": (0..rows).map(|row| columns.iter().map(|column| column[row].clone()).collect::<Vec<_>>()).collect::<Vec<_>>(),
+        "note": (batch.num_rows() > rows).then(|| format!("First {} of {} rows", rows, batch.num_rows())),
+    })
+}
+
+/// Values of an array as display text, `None` for nulls
+fn formatted(array: &dyn Array) -> Option<Vec<Option<String>>> {
+    let formatter = ArrayFormatter::try_new(array, &FormatOptions::default()).ok()?;
+    Some((0..array.len())
+        .map(|i| array.is_valid(i).then(|| formatter.value(i).to_string()))
+        .collect())
+}
+
+/// `[start, end, count]` bins from a histogram view's result table
+fn bins_from_table(table: &RecordBatch) -> Option<Vec<[f64; 3]>> {
+    let start = table.column_by_name("bin_start")?.as_primitive_opt::<Float64Type>()?;
+    let end = table.column_by_name("bin_end")?.as_primitive_opt::<Float64Type>()?;
+    let count = table.column_by_name("count")?;
+    let count = arrow::compute::cast(count, &DataType::UInt64).ok()?;
+    let count = count.as_primitive_opt::<UInt64Type>()?;
+    Some((0..table.num_rows())
+        .map(|i| [start.value(i), end.value(i), count.value(i) as f64])
+        .collect())
+}
+
+/// `count` equal-width `[start, end, count]` bins spanning `values`
+fn equal_width_bins(values: &[f64], count: usize) -> Vec<[f64; 3]> {
+    let finite = values.iter().copied().filter(|v| v.is_finite());
+    let Some((min, max)) = finite.clone().fold(None, |bounds: Option<(f64, f64)>, v| {
+        Some(bounds.map_or((v, v), |(lo, hi)| (lo.min(v), hi.max(v))))
+    }) else {
+        return Vec::new();
+    };
+    let count = count.max(1);
+    let width = if max > min { (max - min) / count as f64 } else { 1.0 };
+    let mut bins: Vec<[f64; 3]> = (0..count)
+        .map(|i| [min + i as f64 * width, min + (i + 1) as f64 * width, 0.0])
+        .collect();
+    for v in finite {
+        let i = (((v - min) / width) as usize).min(count - 1);
+        bins[i][2] += 1.0;
+    }
+    bins
+}
+
+/// Indices of the points to keep so at most about `max` remain: the first
+/// and last point of each bucket plus its lowest and highest, in order
+pub fn downsample_min_max(points: &[(f64, f64)], max: usize) -> Vec<usize> {
+    if points.len() <= max || max < 4 {
+        return (0..points.len()).collect();
+    }
+    let bucket = points.len().div_ceil(max / 4);
+    let mut kept = Vec::with_capacity(max);
+    for start in (0..points.len()).step_by(bucket) {
+        let end = (start + bucket).min(points.len());
+        let by_y = |a: &usize, b: &usize| points[*a].1.total_cmp(&points[*b].1);
+        let low = (start..end).min_by(by_y).unwrap_or(start);
+        let high = (start..end).max_by(by_y).unwrap_or(start);
+        let mut picks = [start, low, high, end - 1];
+        picks.sort_unstable();
+        for i in picks {
+            if kept.last() != Some(&i) {
+                kept.push(i);
+            }
+        }
+    }
+    kept
+}
+
+/// Evenly spaced indices keeping at most `max` of `len`
+fn stride_sample(len: usize, max: usize) -> Vec<usize> {
+    if len <= max {
+        return (0..len).collect();
+    }
+    (0..max).map(|i| i * len / max).collect()
+}
+
+fn downsampled_note(rows: usize, max: usize) -> Option<String> {
+    (rows > max).then(|| format!("Downsampled from {} points", rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsampling_keeps_extremes_in_order() {
+        let points: Vec<(f64, f64)> = (0..10_000)
+            .map(|i| (i as f64, if i == 4321 { 100.0 } else if i == 777 { -50.0 } else { (i % 7) as f64 }))
+            .collect();
+        let kept = downsample_min_max(&points, 400);
+        assert!(kept.len() <= 400);
+        assert!(kept.windows(2).all(|w| w[0] < w[1]));
+        assert!(kept.contains(&4321) && kept.contains(&777));
+        assert_eq!((kept[0], kept[kept.len() - 1]), (0, 9_999));
+
+        assert_eq!(downsample_min_max(&points[..10], 400), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn bins_span_the_values() {
+        let bins = equal_width_bins(&[0.0, 1.0, 2.0, 3.0, 4.0, f64::NAN], 2);
+        assert_eq!(bins, vec![[0.0, 2.0, 2.0], [2.0, 4.0, 3.0]]);
+        assert!(equal_width_bins(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn report_data_cannot_close_the_script() {
+        let html = render_html("Q3 <draft>", &json!({ "title": "</script><script>alert(1)" }));
+        assert!(html.contains("<title>Q3 &lt;draft&gt;</title>"));
+        assert!(!html.contains("</script><script>alert"));
+    }
+}
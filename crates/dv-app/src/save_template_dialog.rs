@@ -0,0 +1,95 @@
+//! Dialog for saving the dashboard as a template
+
+use egui::{Color32, Context, Grid, RichText};
+
+/// Dialog asking for a template's name and description
+pub struct SaveTemplateDialog {
+    name: String,
+    description: String,
+
+    /// A template of this name is already saved; ask before replacing it
+    existing: Option<String>,
+
+    /// Show dialog
+    pub show: bool,
+}
+
+impl SaveTemplateDialog {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            description: String::new(),
+            existing: None,
+            show: true,
+        }
+    }
+
+    /// Ask whether to replace the template saved under the name entered,
+    /// keeping the dialog open
+    pub fn ask_to_replace(&mut self) {
+        self.existing = Some(self.name.trim().to_string());
+        self.show = true;
+    }
+
+    /// Show the dialog and return the name and description if confirmed,
+    /// and whether a template of that name may be replaced
+    pub fn show_dialog(&mut self, ctx: &Context) -> Option<(String, String, bool)> {
+        let mut result = None;
+        let mut close = false;
+
+        egui::Window::new("Save as Template")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                Grid::new("save_template_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut self.name);
+                        ui.end_row();
+
+                        ui.label("Description:");
+                        ui.text_edit_singleline(&mut self.description);
+                        ui.end_row();
+                    });
+
+                ui.label(
+                    RichText::new("Columns are saved by role and position, so the template fits files of the same shape.")
+                        .small()
+                        .color(Color32::from_gray(160))
+                );
+
+                // Asking to replace only applies to the name it was asked for
+                let name = self.name.trim();
+                if self.existing.as_deref().is_some_and(|existing| existing != name) {
+                    self.existing = None;
+                }
+                if let Some(existing) = &self.existing {
+                    ui.label(
+                        RichText::new(format!("⚠ A template named '{}' already exists.", existing))
+                            .color(Color32::from_rgb(230, 180, 90))
+                    );
+                }
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    let replace = self.existing.is_some();
+                    let label = if replace { "Replace" } else { "Save" };
+                    if ui.add_enabled(!name.is_empty(), egui::Button::new(label)).clicked() {
+                        result = Some((name.to_string(), self.description.trim().to_string(), replace));
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if close || result.is_some() {
+            self.show = false;
+        }
+        result
+    }
+}
@@ -0,0 +1,166 @@
+//! SQL query window over the loaded data sources
+
+use arrow::array::Array;
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use egui::{Context, Grid, RichText, ScrollArea, Color32};
+use dv_data::{QuerySource, SqlEngine};
+
+/// Rows shown in the result preview
+const PREVIEW_ROWS: usize = 100;
+
+/// A registered table and the source it was copied from
+pub struct SqlTable {
+    pub table: String,
+    pub source_name: String,
+    pub columns: Vec<String>,
+}
+
+/// Window for running ad hoc SQL and keeping results as new data sources
+pub struct SqlDialog {
+    /// Engine holding copies of the loaded sources
+    engine: SqlEngine,
+
+    /// Registered tables
+    tables: Vec<SqlTable>,
+
+    /// Query text
+    query: String,
+
+    /// Name for the result source
+    result_name: String,
+
+    /// Result of the last run
+    preview: Option<RecordBatch>,
+
+    /// Error from the last run
+    error: Option<String>,
+
+    /// Show dialog
+    pub show: bool,
+}
+
+impl SqlDialog {
+    /// Create a dialog over an engine with `tables` registered
+    pub fn new(engine: SqlEngine, tables: Vec<SqlTable>) -> Self {
+        let query = tables.first()
+            .map(|t| format!("SELECT * FROM {} LIMIT 100", t.table))
+            .unwrap_or_default();
+
+        Self {
+            engine,
+            tables,
+            query,
+            result_name: "Query result".to_string(),
+            preview: None,
+            error: None,
+            show: true,
+        }
+    }
+
+    /// Show the window and return a new source when the user keeps a result
+    pub fn show_dialog(&mut self, ctx: &Context) -> Option<QuerySource> {
+        let mut result = None;
+        let mut open = self.show;
+
+        egui::Window::new("🗄 SQL Query")
+            .open(&mut open)
+            .default_size([720.0, 520.0])
+            .show(ctx, |ui| {
+                ui.collapsing("Tables", |ui| {
+                    for table in &self.tables {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.monospace(RichText::new(&table.table).strong());
+                            ui.label(RichText::new(format!("({})", table.source_name)).color(Color32::from_gray(150)));
+                            ui.label(table.columns.join(", "));
+                        });
+                    }
+                });
+
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.query)
+                        .code_editor()
+                        .desired_rows(6)
+                        .desired_width(f32::INFINITY)
+                );
+
+                ui.horizontal(|ui| {
+                    let run = ui.button("▶ Run").on_hover_text("Ctrl+Enter").clicked()
+                        || ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::Enter));
+                    if run {
+                        match self.engine.query(&self.query) {
+                            Ok(batch) => {
+                                self.preview = Some(batch);
+                                self.error = None;
+                            }
+                            Err(e) => {
+                                self.preview = None;
+                                self.error = Some(e.to_string());
+                            }
+                        }
+                    }
+
+                    ui.separator();
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.result_name);
+                    let can_add = self.preview.is_some() && !self.result_name.trim().is_empty();
+                    if ui.add_enabled(can_add, egui::Button::new("➕ Add as Data Source"))
+                        .on_hover_text("Keep the full result as a source that views can use")
+                        .clicked()
+                    {
+                        match QuerySource::new(self.result_name.trim().to_string(), &self.engine, &self.query) {
+                            Ok(source) => result = Some(source),
+                            Err(e) => self.error = Some(e.to_string()),
+                        }
+                    }
+                });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(Color32::from_rgb(255, 100, 100), error);
+                }
+
+                if let Some(batch) = &self.preview {
+                    ui.separator();
+                    ui.label(format!(
+                        "{} rows × {} columns{}",
+                        batch.num_rows(),
+                        batch.num_columns(),
+                        if batch.num_rows() > PREVIEW_ROWS { format!(" (showing first {})", PREVIEW_ROWS) } else { String::new() }
+                    ));
+                    show_preview(ui, batch);
+                }
+            });
+
+        self.show = open;
+        result
+    }
+}
+
+fn show_preview(ui: &mut egui::Ui, batch: &RecordBatch) {
+    ScrollArea::both()
+        .id_source("sql_preview")
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            Grid::new("sql_preview_grid")
+                .striped(true)
+                .spacing([12.0, 4.0])
+                .show(ui, |ui| {
+                    for field in batch.schema().fields() {
+                        ui.label(RichText::new(field.name()).strong());
+                    }
+                    ui.end_row();
+
+                    for row in 0..batch.num_rows().min(PREVIEW_ROWS) {
+                        for column in batch.columns() {
+                            let text = if column.is_null(row) {
+                                "null".to_string()
+                            } else {
+                                array_value_to_string(column, row).unwrap_or_default()
+                            };
+                            ui.monospace(text);
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
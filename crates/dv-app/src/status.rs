@@ -0,0 +1,134 @@
+//! Status bar state driven by the application event bus
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use parking_lot::RwLock;
+use eframe::egui::{self, Color32, Ui};
+
+use dv_core::events::{EventBus, Subscription};
+use dv_core::events::events::{
+    DataSourceLoading, DataSourceLoaded, DataSourceError, DataSourcesCleared, TaskFinished,
+};
+
+/// How long a status message stays visible
+const MESSAGE_LIFETIME: Duration = Duration::from_secs(8);
+
+/// Severity of a status message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusLevel {
+    Info,
+    Error,
+}
+
+/// A single status message
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub level: StatusLevel,
+    pub text: String,
+    pub timestamp: Instant,
+}
+
+/// Shared status state updated by event handlers
+#[derive(Default)]
+struct StatusState {
+    loading: usize,
+    message: Option<StatusMessage>,
+}
+
+/// Status bar that listens to data source events
+pub struct StatusBar {
+    state: Arc<RwLock<StatusState>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl StatusBar {
+    /// Create a status bar subscribed to `events`
+    pub fn new(events: &EventBus) -> Self {
+        let state = Arc::new(RwLock::new(StatusState::default()));
+        let mut subscriptions = Vec::new();
+
+        let s = state.clone();
+        subscriptions.push(events.subscribe::<DataSourceLoading, _>(move |_| {
+            s.write().loading += 1;
+        }));
+
+        let s = state.clone();
+        subscriptions.push(events.subscribe::<DataSourceLoaded, _>(move |e| {
+            let mut state = s.write();
+            state.loading = state.loading.saturating_sub(1);
+            state.message = Some(StatusMessage {
+                level: StatusLevel::Info,
+                text: format!("Loaded {} ({} rows × {} columns)", e.source_name, e.row_count, e.column_count),
+                timestamp: Instant::now(),
+            });
+        }));
+
+        let s = state.clone();
+        subscriptions.push(events.subscribe::<DataSourceError, _>(move |e| {
+            let mut state = s.write();
+            state.loading = state.loading.saturating_sub(1);
+            state.message = Some(StatusMessage {
+                level: StatusLevel::Error,
+                text: format!("Failed to load {}: {}", e.source_name, e.error),
+                timestamp: Instant::now(),
+            });
+        }));
+
+        let s = state.clone();
+        subscriptions.push(events.subscribe::<TaskFinished, _>(move |e| {
+            let (level, text) = match &e.error {
+                Some(error) => (StatusLevel::Error, format!("{}: {}", e.message, error)),
+                None => (StatusLevel::Info, e.message.clone()),
+            };
+            s.write().message = Some(StatusMessage {
+                level,
+                text,
+                timestamp: Instant::now(),
+            });
+        }));
+
+        let s = state.clone();
+        subscriptions.push(events.subscribe::<DataSourcesCleared, _>(move |_| {
+            s.write().message = None;
+        }));
+
+        Self {
+            state,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Show `text` until the next message replaces it or it expires
+    pub fn notify(&self, level: StatusLevel, text: impl Into<String>) {
+        self.state.write().message = Some(StatusMessage {
+            level,
+            text: text.into(),
+            timestamp: Instant::now(),
+        });
+    }
+
+    /// Number of sources currently loading
+    pub fn loading_count(&self) -> usize {
+        self.state.read().loading
+    }
+
+    /// Draw the latest status message, if it hasn't expired
+    pub fn ui(&self, ui: &mut Ui) {
+        let message = self.state.read().message.clone();
+        if let Some(message) = message {
+            let age = message.timestamp.elapsed();
+            if age > MESSAGE_LIFETIME {
+                return;
+            }
+
+            let (icon, color) = match message.level {
+                StatusLevel::Info => ("✔", Color32::from_gray(200)),
+                StatusLevel::Error => ("⚠", Color32::from_rgb(230, 110, 100)),
+            };
+            ui.label(egui::RichText::new(format!("{} {}", icon, message.text)).color(color).size(12.0));
+
+            // Keep repainting so the message disappears on time
+            ui.ctx().request_repaint_after(MESSAGE_LIFETIME - age);
+        }
+    }
+}
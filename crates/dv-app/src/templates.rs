@@ -0,0 +1,64 @@
+//! Dashboard templates saved by the user
+//!
+//! Templates are kept as JSON files in the `templates` folder of the
+//! configuration directory (`~/.config/frog-viz/templates` on Linux) and
+//! offered alongside the built-in ones.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::warn;
+use dv_templates::{SavedLayout, SavedTemplate, TemplateLibrary, ViewSpec};
+use dv_views::{ViewerContext, Viewport};
+
+/// Folder of the user's templates
+pub fn template_dir() -> Option<PathBuf> {
+    directories_next::ProjectDirs::from("", "", "frog-viz").map(|dirs| dirs.config_dir().join("templates"))
+}
+
+/// The built-in templates and those in the user's template folder
+pub fn library() -> TemplateLibrary {
+    let mut library = TemplateLibrary::builtin();
+    if let Some(dir) = template_dir() {
+        for e in library.load_dir(&dir) {
+            warn!("Skipping template: {}", e);
+        }
+    }
+    library
+}
+
+/// A template named `name` from the open views, generalized from the data
+/// source most of them show. Views of other sources are left out.
+pub fn capture(viewer_context: &ViewerContext, viewport: &Viewport, name: &str, description: &str) -> Result<SavedTemplate, String> {
+    let data_sources = viewer_context.data_sources.read();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for source_id in viewport.views().filter_map(|view| view.data_source_id()) {
+        *counts.entry(source_id).or_default() += 1;
+    }
+    let source_id = counts.into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        .map(|(id, _)| id.to_string())
+        .or_else(|| data_sources.keys().min().cloned())
+        .ok_or("Load a data file before saving a template")?;
+    let source = data_sources.get(&source_id)
+        .ok_or_else(|| format!("Data source '{}' isn't loaded", source_id))?;
+    let schema = viewer_context.runtime_handle.block_on(source.schema());
+
+    let mut names = HashMap::new();
+    let mut views = Vec::new();
+    for view in viewport.views() {
+        if view.data_source_id().is_some_and(|id| id != source_id) {
+            continue;
+        }
+        let id = format!("view{}", views.len() + 1);
+        let mut config = view.save_config();
+        // The template's views show whichever file it is applied to
+        if let Some(fields) = config.as_object_mut() {
+            fields.remove("data_source_id");
+        }
+        names.insert(view.id(), id.clone());
+        views.push(ViewSpec::new(&id, view.view_type(), view.title(), config));
+    }
+    let layout = viewport.layout()
+        .and_then(|layout| SavedLayout::from_view_layout(&layout, &|id| names.get(id).cloned()));
+    Ok(SavedTemplate::new(name, description, &schema, views, layout))
+}
@@ -6,7 +6,10 @@ use egui::{Context, Ui, Vec2, Color32, Rect, Pos2, Sense, Rounding, Stroke, Alig
 use dv_views::SpaceView;
 use dv_core::navigation::NavigationMode;
 use uuid;
-use dv_views::{TimeSeriesView, TableView, plots::{ScatterPlotView, BarChartView}};
+use dv_views::{ColumnCount, ColumnKind, ViewDescriptor, ViewRegistry};
+use dv_templates::{TemplateLibrary, ViewSpec};
+use egui_dock::{Node, NodeIndex};
+use serde_json::{Map, Value};
 
 /// Modern view builder dialog with visual layout editor
 pub struct ViewBuilderDialog {
@@ -19,7 +22,13 @@ pub struct ViewBuilderDialog {
     /// Column metadata for selected data source
     columns: ColumnMetadata,
     
-    /// Dashboard templates
+    /// View types that can be added
+    views: Arc<ViewRegistry>,
+    
+    /// Templates to start from
+    library: TemplateLibrary,
+
+    /// Templates applied to the selected data source
     templates: Vec<DashboardTemplate>,
     
     /// Current layout being edited
@@ -38,57 +47,13 @@ pub struct ViewBuilderDialog {
     selected_cell_id: Option<String>,
     
     /// Current plot being configured
-    plot_config_state: PlotConfigState,
-}
-
-/// State for configuring a plot before adding it
-#[derive(Clone)]
-struct PlotConfigState {
-    /// Selected plot type
-    selected_plot_type: Option<PlotType>,
-    
-    /// Configuration for the selected plot
-    config: ViewConfig,
-    
-    /// Whether the current config is valid
-    is_valid: bool,
-}
-
-#[derive(Clone, Debug, PartialEq)]
-enum PlotType {
-    TimeSeries,
-    Line,
-    Scatter,
-    BarChart,
-    Histogram,
-    Table,
-    BoxPlot,
-    ViolinPlot,
-    Heatmap,
-    AnomalyDetection,
-    CorrelationMatrix,
-    Scatter3D,
-    Surface3D,
-    ParallelCoordinates,
-    RadarChart,
-    PolarPlot,
-    Contour,
-    Sankey,
-    Treemap,
-    Sunburst,
-    NetworkGraph,
-    Distribution,
-    TimeAnalysis,
-    GeoPlot,
-    StreamGraph,
-    CandlestickChart,
+    plot_config: Option<ViewConfig>,
 }
 
 struct ColumnMetadata {
     numeric: Vec<ColumnInfo>,
     temporal: Vec<ColumnInfo>,
     categorical: Vec<ColumnInfo>,
-    all: Vec<ColumnInfo>,
 }
 
 #[derive(Clone)]
@@ -104,7 +69,8 @@ struct DashboardTemplate {
     description: String,
     icon: &'static str,
     layout: DashboardLayout,
-    required_columns: TemplateRequirements,
+    /// Whether the template fits the selected data source
+    compatible: bool,
 }
 
 #[derive(Clone)]
@@ -121,183 +87,15 @@ struct LayoutCell {
     view_config: ViewConfig,
 }
 
-/// View configuration
+/// View configuration: a registered view type and the settings it is
+/// loaded with
 #[derive(Clone, Debug)]
-pub enum ViewConfig {
-    TimeSeries {
-        title: String,
-        data_source_id: Option<String>,
-        x_column: Option<String>,
-        y_columns: Vec<String>,
-    },
-    Scatter {
-        title: String,
-        data_source_id: Option<String>,
-        x_column: String,
-        y_column: String,
-        color_column: Option<String>,
-    },
-    Table {
-        title: String,
-        data_source_id: Option<String>,
-        columns: Vec<String>,
-    },
-    BarChart {
-        title: String,
-        data_source_id: Option<String>,
-        category_column: String,
-        value_column: String,
-    },
-    SummaryStats {
-        title: String,
-        data_source_id: Option<String>,
-    },
-    Line {
-        title: String,
-        data_source_id: Option<String>,
-        x_column: Option<String>,
-        y_columns: Vec<String>,
-        category_column: Option<String>,
-    },
-    Histogram {
-        title: String,
-        data_source_id: Option<String>,
-        column: String,
-    },
-    BoxPlot {
-        title: String,
-        data_source_id: Option<String>,
-        value_column: String,
-        category_column: Option<String>,
-    },
-    ViolinPlot {
-        title: String,
-        data_source_id: Option<String>,
-        value_column: String,
-        category_column: Option<String>,
-    },
-    Heatmap {
-        title: String,
-        data_source_id: Option<String>,
-        x_column: String,
-        y_column: String,
-        value_column: String,
-    },
-    AnomalyDetection {
-        title: String,
-        data_source_id: Option<String>,
-        column: String,
-    },
-    CorrelationMatrix {
-        title: String,
-        data_source_id: Option<String>,
-        columns: Vec<String>,
-    },
-    Scatter3D {
-        title: String,
-        data_source_id: Option<String>,
-        x_column: String,
-        y_column: String,
-        z_column: String,
-    },
-    Surface3D {
-        title: String,
-        data_source_id: Option<String>,
-        x_column: String,
-        y_column: String,
-        z_column: String,
-    },
-    ParallelCoordinates {
-        title: String,
-        data_source_id: Option<String>,
-        columns: Vec<String>,
-    },
-    RadarChart {
-        title: String,
-        data_source_id: Option<String>,
-        value_columns: Vec<String>,
-        group_column: Option<String>,
-    },
-    PolarPlot {
-        title: String,
-        data_source_id: Option<String>,
-        angle_column: String,
-        radius_column: String,
-        category_column: Option<String>,
-    },
-    Contour {
-        title: String,
-        data_source_id: Option<String>,
-        x_column: String,
-        y_column: String,
-        z_column: String,
-    },
-    Sankey {
-        title: String,
-        data_source_id: Option<String>,
-        source_column: String,
-        target_column: String,
-        value_column: String,
-    },
-    Treemap {
-        title: String,
-        data_source_id: Option<String>,
-        category_column: String,
-        value_column: String,
-    },
-    Sunburst {
-        title: String,
-        data_source_id: Option<String>,
-        hierarchy_columns: Vec<String>,
-        value_column: Option<String>,
-    },
-    NetworkGraph {
-        title: String,
-        data_source_id: Option<String>,
-        source_column: String,
-        target_column: String,
-    },
-    Distribution {
-        title: String,
-        data_source_id: Option<String>,
-        column: String,
-    },
-    TimeAnalysis {
-        title: String,
-        data_source_id: Option<String>,
-        time_column: String,
-        value_columns: Vec<String>,
-    },
-    GeoPlot {
-        title: String,
-        data_source_id: Option<String>,
-        lat_column: String,
-        lon_column: String,
-        value_column: Option<String>,
-    },
-    StreamGraph {
-        title: String,
-        data_source_id: Option<String>,
-        time_column: String,
-        value_column: String,
-        category_column: Option<String>,
-    },
-    CandlestickChart {
-        title: String,
-        data_source_id: Option<String>,
-        time_column: String,
-        open_column: String,
-        high_column: String,
-        low_column: String,
-        close_column: String,
-    },
-    Empty,
-}
-
-struct TemplateRequirements {
-    min_numeric: usize,
-    min_temporal: usize,
-    min_categorical: usize,
+pub struct ViewConfig {
+    pub view_type: String,
+    pub title: String,
+    pub data_source_id: Option<String>,
+    /// Settings for `SpaceView::load_config`
+    pub settings: Value,
 }
 
 enum NavigationModeChoice {
@@ -320,7 +118,7 @@ fn plot_button(selected: bool) -> impl egui::Widget {
 
 impl ViewBuilderDialog {
     /// Create a new modern view builder with multiple data sources
-    pub fn new_multi(data_sources: Vec<(String, Arc<Schema>)>) -> Self {
+    pub fn new_multi(data_sources: Vec<(String, Arc<Schema>)>, views: Arc<ViewRegistry>, library: TemplateLibrary) -> Self {
         // Select first data source by default
         let selected_data_source = data_sources.first().map(|(id, _)| id.clone());
         
@@ -333,7 +131,6 @@ impl ViewBuilderDialog {
                     numeric: Vec::new(),
                     temporal: Vec::new(),
                     categorical: Vec::new(),
-                    all: Vec::new(),
                 }
             }
         } else {
@@ -341,11 +138,13 @@ impl ViewBuilderDialog {
                 numeric: Vec::new(),
                 temporal: Vec::new(),
                 categorical: Vec::new(),
-                all: Vec::new(),
             }
         };
         
-        let templates = Self::create_templates(&columns);
+        let schema = data_sources.iter()
+            .find(|(id, _)| Some(id) == selected_data_source.as_ref())
+            .map(|(_, schema)| schema.as_ref());
+        let templates = Self::create_templates(&views, &library, schema, selected_data_source.as_deref());
         
         // Default navigation mode
         let selected_nav_mode = if !columns.temporal.is_empty() {
@@ -364,23 +163,21 @@ impl ViewBuilderDialog {
             data_sources,
             selected_data_source,
             columns,
+            views,
+            library,
             templates,
             layout,
             selected_template: None,
             selected_nav_mode,
             show: true,
             selected_cell_id: None,
-            plot_config_state: PlotConfigState {
-                selected_plot_type: None,
-                config: ViewConfig::Empty,
-                is_valid: false,
-            },
+            plot_config: None,
         }
     }
     
     /// Create a new modern view builder (legacy single source)
     pub fn new(schema: Arc<Schema>) -> Self {
-        Self::new_multi(vec![("Default".to_string(), schema)])
+        Self::new_multi(vec![("Default".to_string(), schema)], Arc::new(ViewRegistry::builtin()), TemplateLibrary::builtin())
     }
     
     /// Analyze schema and categorize columns
@@ -388,7 +185,6 @@ impl ViewBuilderDialog {
         let mut numeric = Vec::new();
         let mut temporal = Vec::new();
         let mut categorical = Vec::new();
-        let mut all = Vec::new();
         
         for field in schema.fields() {
             let (icon, category) = match field.data_type() {
@@ -417,8 +213,6 @@ impl ViewBuilderDialog {
                 sample_values: vec![], // TODO: Could fetch actual samples
             };
             
-            all.push(col_info.clone());
-            
             match category {
                 "numeric" => numeric.push(col_info),
                 "temporal" => temporal.push(col_info),
@@ -431,66 +225,39 @@ impl ViewBuilderDialog {
             numeric,
             temporal,
             categorical,
-            all,
         }
     }
     
-    /// Create dashboard templates based on available columns
-    fn create_templates(columns: &ColumnMetadata) -> Vec<DashboardTemplate> {
+    /// Templates of `library` applied to the selected data source, those that
+    /// fit first
+    fn create_templates(views: &ViewRegistry, library: &TemplateLibrary, schema: Option<&Schema>, source_id: Option<&str>) -> Vec<DashboardTemplate> {
         let mut templates = Vec::new();
-        
-        // Time Series Dashboard
-        if !columns.numeric.is_empty() {
-            templates.push(DashboardTemplate {
-                name: "Time Series Dashboard".to_string(),
-                description: "Track metrics over time with multiple synchronized charts".to_string(),
-                icon: "📈",
-                layout: DashboardLayout {
-                    grid_size: (2, 2),
-                    cells: vec![
-                        LayoutCell {
-                            id: "main-trends".to_string(),
-                            grid_pos: (0, 0),
-                            grid_span: (2, 1),
-                            view_config: ViewConfig::TimeSeries {
-                                title: "Main Trends".to_string(),
-                                data_source_id: None,
-                                x_column: if !columns.temporal.is_empty() { 
-                                    Some(columns.temporal[0].name.clone()) 
-                                } else { None },
-                                y_columns: columns.numeric.iter().take(2).map(|c| c.name.clone()).collect(),
-                            },
-                        },
-                        LayoutCell {
-                            id: "detail-1".to_string(),
-                            grid_pos: (0, 1),
-                            grid_span: (1, 1),
-                            view_config: ViewConfig::Scatter {
-                                title: "Correlation".to_string(),
-                                data_source_id: None,
-                                x_column: columns.numeric.get(0).map(|c| c.name.clone()).unwrap_or_default(),
-                                y_column: columns.numeric.get(1).map(|c| c.name.clone()).unwrap_or_default(),
-                                color_column: None,
-                            },
-                        },
-                        LayoutCell {
-                            id: "data-table".to_string(),
-                            grid_pos: (1, 1),
-                            grid_span: (1, 1),
-                            view_config: ViewConfig::Table {
-                                title: "Data Inspector".to_string(),
-                                data_source_id: None,
-                                columns: vec![],
-                            },
-                        },
-                    ],
-                },
-                required_columns: TemplateRequirements {
-                    min_numeric: 1,
-                    min_temporal: 0,
-                    min_categorical: 0,
-                },
-            });
+        if let Some(schema) = schema {
+            for (template, _) in library.ranked(schema) {
+                if let Some(dashboard) = template.instantiate(schema) {
+                    templates.push(DashboardTemplate {
+                        name: template.name.clone(),
+                        description: template.description.clone(),
+                        icon: template_icon(&template.id),
+                        layout: dashboard_layout(views, &dashboard, source_id),
+                        compatible: true,
+                    });
+                }
+            }
+        }
+        for template in library.templates() {
+            if !templates.iter().any(|listed| listed.name == template.name) {
+                templates.push(DashboardTemplate {
+                    name: template.name.clone(),
+                    description: template.description.clone(),
+                    icon: template_icon(&template.id),
+                    layout: DashboardLayout {
+                        grid_size: (2, 2),
+                        cells: vec![],
+                    },
+                    compatible: false,
+                });
+            }
         }
         
         // Always add custom template
@@ -502,11 +269,7 @@ impl ViewBuilderDialog {
                 grid_size: (2, 2),
                 cells: vec![],
             },
-            required_columns: TemplateRequirements {
-                min_numeric: 0,
-                min_temporal: 0,
-                min_categorical: 0,
-            },
+            compatible: true,
         });
         
         templates
@@ -712,56 +475,18 @@ impl ViewBuilderDialog {
                 ui.heading("📊 Plot Types");
                 ui.add_space(4.0);
                 
-                // Basic plots
-                ui.label(egui::RichText::new("Basic").size(11.0).strong().color(Color32::from_gray(180)));
-                ui.add_space(2.0);
-                
-                self.show_plot_type_button_compact(ui, "📈", "Time Series", PlotType::TimeSeries);
-                self.show_plot_type_button_compact(ui, "📉", "Line", PlotType::Line);
-                self.show_plot_type_button_compact(ui, "🎯", "Scatter", PlotType::Scatter);
-                self.show_plot_type_button_compact(ui, "📊", "Bar Chart", PlotType::BarChart);
-                self.show_plot_type_button_compact(ui, "📊", "Histogram", PlotType::Histogram);
-                self.show_plot_type_button_compact(ui, "📋", "Table", PlotType::Table);
-                
-                ui.add_space(6.0);
-                ui.label(egui::RichText::new("Statistical").size(11.0).strong().color(Color32::from_gray(180)));
-                ui.add_space(2.0);
-                
-                self.show_plot_type_button_compact(ui, "📦", "Box Plot", PlotType::BoxPlot);
-                self.show_plot_type_button_compact(ui, "🎻", "Violin", PlotType::ViolinPlot);
-                self.show_plot_type_button_compact(ui, "🔥", "Heatmap", PlotType::Heatmap);
-                self.show_plot_type_button_compact(ui, "🎯", "Correlation", PlotType::CorrelationMatrix);
-                self.show_plot_type_button_compact(ui, "🔔", "Distribution", PlotType::Distribution);
-                self.show_plot_type_button_compact(ui, "⚠️", "Anomaly", PlotType::AnomalyDetection);
-                
-                ui.add_space(6.0);
-                ui.label(egui::RichText::new("Advanced").size(11.0).strong().color(Color32::from_gray(180)));
-                ui.add_space(2.0);
-                
-                self.show_plot_type_button_compact(ui, "🎲", "3D Scatter", PlotType::Scatter3D);
-                self.show_plot_type_button_compact(ui, "🏔️", "3D Surface", PlotType::Surface3D);
-                self.show_plot_type_button_compact(ui, "🧭", "Polar", PlotType::PolarPlot);
-                self.show_plot_type_button_compact(ui, "🗺️", "Contour", PlotType::Contour);
-                self.show_plot_type_button_compact(ui, "🌈", "Parallel Coords", PlotType::ParallelCoordinates);
-                self.show_plot_type_button_compact(ui, "🎯", "Radar", PlotType::RadarChart);
-                
-                ui.add_space(6.0);
-                ui.label(egui::RichText::new("Hierarchical").size(11.0).strong().color(Color32::from_gray(180)));
-                ui.add_space(2.0);
-                
-                self.show_plot_type_button_compact(ui, "🧵", "Sankey", PlotType::Sankey);
-                self.show_plot_type_button_compact(ui, "📊", "Treemap", PlotType::Treemap);
-                self.show_plot_type_button_compact(ui, "🌞", "Sunburst", PlotType::Sunburst);
-                self.show_plot_type_button_compact(ui, "🌐", "Network", PlotType::NetworkGraph);
-                
-                ui.add_space(6.0);
-                ui.label(egui::RichText::new("Specialized").size(11.0).strong().color(Color32::from_gray(180)));
-                ui.add_space(2.0);
-                
-                self.show_plot_type_button_compact(ui, "⏳", "Time Analysis", PlotType::TimeAnalysis);
-                self.show_plot_type_button_compact(ui, "🌍", "Geographic", PlotType::GeoPlot);
-                self.show_plot_type_button_compact(ui, "🌊", "Stream", PlotType::StreamGraph);
-                self.show_plot_type_button_compact(ui, "🕒", "Candlestick", PlotType::CandlestickChart);
+                let views = self.views.clone();
+                for (i, category) in views.categories().into_iter().enumerate() {
+                    if i > 0 {
+                        ui.add_space(6.0);
+                    }
+                    ui.label(egui::RichText::new(category).size(11.0).strong().color(Color32::from_gray(180)));
+                    ui.add_space(2.0);
+                    
+                    for view in views.views().iter().filter(|view| view.category == category) {
+                        self.show_plot_type_button_compact(ui, view);
+                    }
+                }
             });
             
             ui.separator();
@@ -770,41 +495,13 @@ impl ViewBuilderDialog {
             ui.vertical(|ui| {
                 ui.set_min_width(180.0);
                 
-                if let Some(plot_type) = &self.plot_config_state.selected_plot_type {
+                let views = self.views.clone();
+                let selected_view = self.plot_config.as_ref().and_then(|config| views.get(&config.view_type));
+                if let Some(view) = selected_view {
                     ui.heading("⚙️ Configuration");
                     ui.add_space(4.0);
                     
-                    // Show plot type name
-                    let plot_name = match plot_type {
-                        PlotType::TimeSeries => "Time Series",
-                        PlotType::Line => "Line Plot",
-                        PlotType::Scatter => "Scatter Plot",
-                        PlotType::BarChart => "Bar Chart",
-                        PlotType::Histogram => "Histogram",
-                        PlotType::Table => "Data Table",
-                        PlotType::BoxPlot => "Box Plot",
-                        PlotType::ViolinPlot => "Violin Plot",
-                        PlotType::Heatmap => "Heatmap",
-                        PlotType::AnomalyDetection => "Anomaly Detection",
-                        PlotType::CorrelationMatrix => "Correlation Matrix",
-                        PlotType::Scatter3D => "3D Scatter",
-                        PlotType::Surface3D => "3D Surface",
-                        PlotType::ParallelCoordinates => "Parallel Coordinates",
-                        PlotType::RadarChart => "Radar Chart",
-                        PlotType::PolarPlot => "Polar Plot",
-                        PlotType::Contour => "Contour Plot",
-                        PlotType::Sankey => "Sankey Diagram",
-                        PlotType::Treemap => "Treemap",
-                        PlotType::Sunburst => "Sunburst",
-                        PlotType::NetworkGraph => "Network Graph",
-                        PlotType::Distribution => "Distribution",
-                        PlotType::TimeAnalysis => "Time Analysis",
-                        PlotType::GeoPlot => "Geographic Plot",
-                        PlotType::StreamGraph => "Stream Graph",
-                        PlotType::CandlestickChart => "Candlestick Chart",
-                    };
-                    
-                    ui.label(egui::RichText::new(plot_name).size(13.0).strong().color(Color32::from_rgb(76, 175, 80)));
+                    ui.label(egui::RichText::new(&view.name).size(13.0).strong().color(Color32::from_rgb(76, 175, 80)));
                     ui.add_space(8.0);
                     
                     // Configuration form
@@ -812,31 +509,32 @@ impl ViewBuilderDialog {
                         .max_height(300.0)
                         .auto_shrink([false, false])
                         .show(ui, |ui| {
-                            self.show_plot_configuration_compact(ui);
+                            self.show_plot_configuration_compact(ui, view);
                         });
                     
                     ui.add_space(8.0);
                     
                     // Add button
+                    let is_valid = self.plot_config.as_ref().is_some_and(|config| self.is_config_valid(config));
                     let add_button = egui::Button::new(
                         egui::RichText::new("➕ Add to Dashboard")
                             .size(13.0)
                             .color(Color32::WHITE)
                     )
-                    .fill(if self.plot_config_state.is_valid { 
+                    .fill(if is_valid { 
                         Color32::from_rgb(76, 175, 80) 
                     } else { 
                         Color32::from_gray(60) 
                     })
                     .min_size(Vec2::new(ui.available_width(), 32.0));
                     
-                    let response = ui.add_enabled(self.plot_config_state.is_valid, add_button);
+                    let response = ui.add_enabled(is_valid, add_button);
                     
                     if response.clicked() {
                         self.add_configured_plot();
                     }
                     
-                    if !self.plot_config_state.is_valid {
+                    if !is_valid {
                         response.on_hover_text("Please configure all required columns");
                     }
                 } else {
@@ -877,10 +575,10 @@ impl ViewBuilderDialog {
     }
     
     /// Show a compact plot type selection button
-    fn show_plot_type_button_compact(&mut self, ui: &mut Ui, icon: &str, name: &str, plot_type: PlotType) {
-        let is_selected = self.plot_config_state.selected_plot_type.as_ref() == Some(&plot_type);
+    fn show_plot_type_button_compact(&mut self, ui: &mut Ui, view: &ViewDescriptor) {
+        let is_selected = self.plot_config.as_ref().is_some_and(|config| config.view_type == view.view_type);
         
-        let button_text = format!("{} {}", icon, name);
+        let button_text = format!("{} {}", view.icon, view.name);
         let button = egui::Button::new(button_text)
             .min_size(Vec2::new(ui.available_width(), 24.0))
             .selected(is_selected)
@@ -889,1070 +587,151 @@ impl ViewBuilderDialog {
         let response = ui.add(button);
         
         if response.clicked() {
-            self.plot_config_state.selected_plot_type = Some(plot_type);
-            self.plot_config_state.config = self.create_default_config(&self.plot_config_state.selected_plot_type.as_ref().unwrap());
-            self.plot_config_state.is_valid = false;
+            self.plot_config = Some(self.create_default_config(view));
         }
     }
     
-    /// Create default configuration for a plot type
-    fn create_default_config(&self, plot_type: &PlotType) -> ViewConfig {
-        // Get first columns of each type for defaults
-        let first_numeric = self.columns.numeric.first().map(|c| c.name.clone()).unwrap_or_default();
-        let second_numeric = self.columns.numeric.get(1).map(|c| c.name.clone()).unwrap_or_default();
-        let first_categorical = self.columns.categorical.first().map(|c| c.name.clone()).unwrap_or_default();
-        let first_temporal = self.columns.temporal.first().map(|c| c.name.clone());
-        
-        match plot_type {
-            PlotType::TimeSeries => ViewConfig::TimeSeries {
-                title: "Time Series".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                x_column: first_temporal.clone(),
-                y_columns: if !first_numeric.is_empty() { vec![first_numeric] } else { vec![] },
-            },
-            PlotType::Line => ViewConfig::Line {
-                title: "Line Plot".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                x_column: first_temporal.clone().or_else(|| Some(first_numeric.clone())),
-                y_columns: if !second_numeric.is_empty() { vec![second_numeric] } else if !first_numeric.is_empty() { vec![first_numeric] } else { vec![] },
-                category_column: None,
-            },
-            PlotType::Scatter => ViewConfig::Scatter {
-                title: "Scatter Plot".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                x_column: first_numeric.clone(),
-                y_column: if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() },
-                color_column: if !first_categorical.is_empty() { Some(first_categorical.clone()) } else { None },
-            },
-            PlotType::BarChart => ViewConfig::BarChart {
-                title: "Bar Chart".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                category_column: first_categorical.clone(),
-                value_column: first_numeric.clone(),
-            },
-            PlotType::Histogram => ViewConfig::Histogram {
-                title: "Histogram".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                column: first_numeric.clone(),
-            },
-            PlotType::Table => ViewConfig::Table {
-                title: "Data Table".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                columns: vec![],
-            },
-            PlotType::BoxPlot => ViewConfig::BoxPlot {
-                title: "Box Plot".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                value_column: first_numeric.clone(),
-                category_column: if !first_categorical.is_empty() { Some(first_categorical.clone()) } else { None },
-            },
-            PlotType::ViolinPlot => ViewConfig::ViolinPlot {
-                title: "Violin Plot".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                value_column: first_numeric.clone(),
-                category_column: if !first_categorical.is_empty() { Some(first_categorical.clone()) } else { None },
-            },
-            PlotType::Heatmap => ViewConfig::Heatmap {
-                title: "Heatmap".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                x_column: first_categorical.clone(),
-                y_column: if self.columns.categorical.len() > 1 { 
-                    self.columns.categorical[1].name.clone() 
-                } else { 
-                    first_categorical.clone() 
-                },
-                value_column: first_numeric.clone(),
-            },
-            PlotType::AnomalyDetection => ViewConfig::AnomalyDetection {
-                title: "Anomaly Detection".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                column: first_numeric.clone(),
-            },
-            PlotType::CorrelationMatrix => ViewConfig::CorrelationMatrix {
-                title: "Correlation Matrix".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                columns: self.columns.numeric.iter().take(5).map(|c| c.name.clone()).collect(),
-            },
-            PlotType::Scatter3D => ViewConfig::Scatter3D {
-                title: "3D Scatter".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                x_column: first_numeric.clone(),
-                y_column: if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() },
-                z_column: self.columns.numeric.get(2).map(|c| c.name.clone()).unwrap_or(first_numeric.clone()),
-            },
-            PlotType::Surface3D => ViewConfig::Surface3D {
-                title: "3D Surface".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                x_column: first_numeric.clone(),
-                y_column: if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() },
-                z_column: self.columns.numeric.get(2).map(|c| c.name.clone()).unwrap_or(first_numeric.clone()),
-            },
-            PlotType::ParallelCoordinates => ViewConfig::ParallelCoordinates {
-                title: "Parallel Coordinates".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                columns: self.columns.numeric.iter().take(5).map(|c| c.name.clone()).collect(),
-            },
-            PlotType::RadarChart => ViewConfig::RadarChart {
-                title: "Radar Chart".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                value_columns: self.columns.numeric.iter().take(5).map(|c| c.name.clone()).collect(),
-                group_column: if !first_categorical.is_empty() { Some(first_categorical.clone()) } else { None },
-            },
-            PlotType::PolarPlot => ViewConfig::PolarPlot {
-                title: "Polar Plot".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                angle_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("angle")) {
-                    col.name.clone()
-                } else {
-                    first_numeric.clone()
-                },
-                radius_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("radius")) {
-                    col.name.clone()
-                } else {
-                    if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() }
-                },
-                category_column: if !first_categorical.is_empty() { Some(first_categorical.clone()) } else { None },
-            },
-            PlotType::Contour => ViewConfig::Contour {
-                title: "Contour Plot".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                x_column: first_numeric.clone(),
-                y_column: if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() },
-                z_column: self.columns.numeric.get(2).map(|c| c.name.clone()).unwrap_or(first_numeric.clone()),
-            },
-            PlotType::Sankey => ViewConfig::Sankey {
-                title: "Sankey Diagram".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                source_column: first_categorical.clone(),
-                target_column: if self.columns.categorical.len() > 1 { 
-                    self.columns.categorical[1].name.clone() 
-                } else { 
-                    first_categorical.clone() 
-                },
-                value_column: first_numeric.clone(),
-            },
-            PlotType::Treemap => ViewConfig::Treemap {
-                title: "Treemap".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                category_column: first_categorical.clone(),
-                value_column: first_numeric.clone(),
-            },
-            PlotType::Sunburst => ViewConfig::Sunburst {
-                title: "Sunburst".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                hierarchy_columns: self.columns.categorical.iter().take(3).map(|c| c.name.clone()).collect(),
-                value_column: if !first_numeric.is_empty() { Some(first_numeric.clone()) } else { None },
-            },
-            PlotType::NetworkGraph => ViewConfig::NetworkGraph {
-                title: "Network Graph".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                source_column: first_categorical.clone(),
-                target_column: if self.columns.categorical.len() > 1 { 
-                    self.columns.categorical[1].name.clone() 
-                } else { 
-                    first_categorical.clone() 
-                },
-            },
-            PlotType::Distribution => ViewConfig::Distribution {
-                title: "Distribution".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                column: first_numeric.clone(),
-            },
-            PlotType::TimeAnalysis => ViewConfig::TimeAnalysis {
-                title: "Time Analysis".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                time_column: first_temporal.clone().unwrap_or(first_numeric.clone()),
-                value_columns: self.columns.numeric.iter().skip(1).take(3).map(|c| c.name.clone()).collect(),
-            },
-            PlotType::GeoPlot => ViewConfig::GeoPlot {
-                title: "Geographic Plot".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                lat_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("lat")) {
-                    col.name.clone()
-                } else {
-                    first_numeric.clone()
-                },
-                lon_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("lon")) {
-                    col.name.clone()
-                } else {
-                    if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() }
-                },
-                value_column: if self.columns.numeric.len() > 2 { 
-                    Some(self.columns.numeric[2].name.clone()) 
-                } else { 
-                    None 
-                },
-            },
-            PlotType::StreamGraph => ViewConfig::StreamGraph {
-                title: "Stream Graph".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                time_column: first_temporal.clone().unwrap_or(first_numeric.clone()),
-                value_column: if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() },
-                category_column: if !first_categorical.is_empty() { Some(first_categorical.clone()) } else { None },
-            },
-            PlotType::CandlestickChart => ViewConfig::CandlestickChart {
-                title: "Candlestick Chart".to_string(),
-                data_source_id: self.selected_data_source.clone(),
-                time_column: first_temporal.clone().unwrap_or(first_numeric.clone()),
-                open_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("open")) {
-                    col.name.clone()
-                } else {
-                    first_numeric.clone()
-                },
-                high_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("high")) {
-                    col.name.clone()
-                } else {
-                    if !second_numeric.is_empty() { second_numeric.clone() } else { first_numeric.clone() }
-                },
-                low_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("low")) {
-                    col.name.clone()
-                } else {
-                    self.columns.numeric.get(2).map(|c| c.name.clone()).unwrap_or(first_numeric.clone())
-                },
-                close_column: if let Some(col) = self.columns.numeric.iter().find(|c| c.name.to_lowercase().contains("close")) {
-                    col.name.clone()
-                } else {
-                    self.columns.numeric.get(3).map(|c| c.name.clone()).unwrap_or(first_numeric.clone())
-                },
-            },
+    /// Create default configuration for a view type, with columns of the
+    /// selected data source
+    fn create_default_config(&self, view: &ViewDescriptor) -> ViewConfig {
+        let schema = self.data_sources.iter()
+            .find(|(id, _)| Some(id) == self.selected_data_source.as_ref())
+            .map(|(_, schema)| schema);
+        ViewConfig {
+            view_type: view.view_type.clone(),
+            title: view.name.clone(),
+            data_source_id: self.selected_data_source.clone(),
+            settings: schema.map_or_else(|| Value::Object(Map::new()), |schema| view.default_config(schema)),
         }
     }
     
-    /// Show plot configuration UI (compact version)
-    fn show_plot_configuration_compact(&mut self, ui: &mut Ui) {
-        let mut config = self.plot_config_state.config.clone();
+    /// Show the configuration form of the plot being configured
+    fn show_plot_configuration_compact(&mut self, ui: &mut Ui, view: &ViewDescriptor) {
+        let Some(mut config) = self.plot_config.clone() else {
+            return;
+        };
         
         // Data source selector - shown for all plot types
+        let mut new_source = None;
         ui.horizontal(|ui| {
             ui.label("Data Source:");
             
-            let current_source = match &config {
-                ViewConfig::TimeSeries { data_source_id, .. } |
-                ViewConfig::Scatter { data_source_id, .. } |
-                ViewConfig::Table { data_source_id, .. } |
-                ViewConfig::BarChart { data_source_id, .. } |
-                ViewConfig::Histogram { data_source_id, .. } |
-                ViewConfig::Line { data_source_id, .. } |
-                ViewConfig::BoxPlot { data_source_id, .. } |
-                ViewConfig::ViolinPlot { data_source_id, .. } |
-                ViewConfig::Heatmap { data_source_id, .. } |
-                ViewConfig::AnomalyDetection { data_source_id, .. } |
-                ViewConfig::CorrelationMatrix { data_source_id, .. } |
-                ViewConfig::Scatter3D { data_source_id, .. } |
-                ViewConfig::Surface3D { data_source_id, .. } |
-                ViewConfig::ParallelCoordinates { data_source_id, .. } |
-                ViewConfig::RadarChart { data_source_id, .. } |
-                ViewConfig::PolarPlot { data_source_id, .. } |
-                ViewConfig::Distribution { data_source_id, .. } |
-                ViewConfig::SummaryStats { data_source_id, .. } => {
-                    data_source_id.as_deref().or(self.selected_data_source.as_deref()).unwrap_or("None")
-                }
-                _ => "None",
-            };
-            
+            let current_source = config.data_source_id.as_deref().or(self.selected_data_source.as_deref()).unwrap_or("None");
             egui::ComboBox::from_id_source("config_data_source")
                 .selected_text(current_source)
                 .width(ui.available_width() - 80.0)
                 .show_ui(ui, |ui| {
                     for (source_id, _) in &self.data_sources {
-                        let is_selected = match &config {
-                            ViewConfig::TimeSeries { data_source_id, .. } |
-                            ViewConfig::Scatter { data_source_id, .. } |
-                            ViewConfig::Table { data_source_id, .. } |
-                            ViewConfig::BarChart { data_source_id, .. } |
-                            ViewConfig::Histogram { data_source_id, .. } |
-                            ViewConfig::Line { data_source_id, .. } |
-                            ViewConfig::BoxPlot { data_source_id, .. } |
-                            ViewConfig::ViolinPlot { data_source_id, .. } |
-                            ViewConfig::Heatmap { data_source_id, .. } |
-                            ViewConfig::AnomalyDetection { data_source_id, .. } |
-                            ViewConfig::CorrelationMatrix { data_source_id, .. } |
-                            ViewConfig::Scatter3D { data_source_id, .. } |
-                            ViewConfig::Surface3D { data_source_id, .. } |
-                            ViewConfig::ParallelCoordinates { data_source_id, .. } |
-                            ViewConfig::RadarChart { data_source_id, .. } |
-                            ViewConfig::PolarPlot { data_source_id, .. } |
-                            ViewConfig::Distribution { data_source_id, .. } |
-                            ViewConfig::SummaryStats { data_source_id, .. } => {
-                                data_source_id.as_ref() == Some(source_id)
-                            }
-                            _ => false,
-                        };
-                        
+                        let is_selected = config.data_source_id.as_ref() == Some(source_id);
                         if ui.selectable_label(is_selected, source_id).clicked() {
-                            // Update data source in config
-                            match &mut config {
-                                ViewConfig::TimeSeries { data_source_id, .. } |
-                                ViewConfig::Scatter { data_source_id, .. } |
-                                ViewConfig::Table { data_source_id, .. } |
-                                ViewConfig::BarChart { data_source_id, .. } |
-                                ViewConfig::Histogram { data_source_id, .. } |
-                                ViewConfig::Line { data_source_id, .. } |
-                                ViewConfig::BoxPlot { data_source_id, .. } |
-                                ViewConfig::ViolinPlot { data_source_id, .. } |
-                                ViewConfig::Heatmap { data_source_id, .. } |
-                                ViewConfig::AnomalyDetection { data_source_id, .. } |
-                                ViewConfig::CorrelationMatrix { data_source_id, .. } |
-                                ViewConfig::Scatter3D { data_source_id, .. } |
-                                ViewConfig::Surface3D { data_source_id, .. } |
-                                ViewConfig::ParallelCoordinates { data_source_id, .. } |
-                                ViewConfig::RadarChart { data_source_id, .. } |
-                                ViewConfig::PolarPlot { data_source_id, .. } |
-                                ViewConfig::Distribution { data_source_id, .. } |
-                                ViewConfig::SummaryStats { data_source_id, .. } => {
-                                    *data_source_id = Some(source_id.clone());
-                                }
-                                _ => {}
-                            }
-                            
-                            // Update selected data source and refresh columns
-                            self.selected_data_source = Some(source_id.clone());
-                            if let Some((_, schema)) = self.data_sources.iter().find(|(id, _)| id == source_id) {
-                                self.columns = Self::analyze_schema(schema);
-                                
-                                // Reset configuration with new columns from the new data source
-                                if let Some(plot_type) = &self.plot_config_state.selected_plot_type {
-                                    config = self.create_default_config(plot_type);
-                                    // Update the data source id in the new config
-                                    match &mut config {
-                                        ViewConfig::TimeSeries { data_source_id, .. } |
-                                        ViewConfig::Scatter { data_source_id, .. } |
-                                        ViewConfig::Table { data_source_id, .. } |
-                                        ViewConfig::BarChart { data_source_id, .. } |
-                                        ViewConfig::Histogram { data_source_id, .. } |
-                                        ViewConfig::Line { data_source_id, .. } |
-                                        ViewConfig::BoxPlot { data_source_id, .. } |
-                                        ViewConfig::ViolinPlot { data_source_id, .. } |
-                                        ViewConfig::Heatmap { data_source_id, .. } |
-                                        ViewConfig::AnomalyDetection { data_source_id, .. } |
-                                        ViewConfig::CorrelationMatrix { data_source_id, .. } |
-                                        ViewConfig::Scatter3D { data_source_id, .. } |
-                                        ViewConfig::Surface3D { data_source_id, .. } |
-                                        ViewConfig::ParallelCoordinates { data_source_id, .. } |
-                                        ViewConfig::RadarChart { data_source_id, .. } |
-                                        ViewConfig::PolarPlot { data_source_id, .. } |
-                                        ViewConfig::Distribution { data_source_id, .. } |
-                                        ViewConfig::SummaryStats { data_source_id, .. } => {
-                                            *data_source_id = Some(source_id.clone());
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
+                            new_source = Some(source_id.clone());
                         }
                     }
                 });
         });
         
+        if let Some(source_id) = new_source {
+            // Update selected data source and refresh columns
+            self.selected_data_source = Some(source_id.clone());
+            if let Some((_, schema)) = self.data_sources.iter().find(|(id, _)| *id == source_id) {
+                self.columns = Self::analyze_schema(schema);
+                self.templates = Self::create_templates(&self.views, &self.library, Some(schema), Some(&source_id));
+                self.selected_template = None;
+            }
+            // Reset configuration with new columns from the new data source
+            config = ViewConfig {
+                title: config.title,
+                ..self.create_default_config(view)
+            };
+        }
+        
         ui.separator();
         
-        match &mut config {
-            ViewConfig::TimeSeries { title, data_source_id: _, x_column, y_columns } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                ui.label("X-Axis (optional):");
-                let current_x = x_column.as_ref().map(|s| s.as_str()).unwrap_or("Auto (Row Index)");
-                egui::ComboBox::from_id_source("config_ts_x")
-                    .selected_text(current_x)
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(x_column, None, "Auto (Row Index)");
-                        for col in &self.columns.temporal {
-                            ui.selectable_value(x_column, Some(col.name.clone()), &col.name);
-                        }
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(x_column, Some(col.name.clone()), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                ui.label("Y-Axis (select one or more):");
-                
-                let mut any_selected = false;
-                for col in &self.columns.numeric {
-                    let mut selected = y_columns.contains(&col.name);
-                    if ui.checkbox(&mut selected, &col.name).changed() {
-                        if selected {
-                            if !y_columns.contains(&col.name) {
-                                y_columns.push(col.name.clone());
-                            }
-                        } else {
-                            y_columns.retain(|c| c != &col.name);
-                        }
-                    }
-                    if selected {
-                        any_selected = true;
-                    }
-                }
-                
-                self.plot_config_state.is_valid = any_selected;
-            }
-            
-            ViewConfig::Scatter { title, data_source_id: _, x_column, y_column, color_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("X-Axis:");
-                egui::ComboBox::from_id_source("config_scatter_x")
-                    .selected_text(if x_column.is_empty() { "Select..." } else { x_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(x_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Y-Axis:");
-                egui::ComboBox::from_id_source("config_scatter_y")
-                    .selected_text(if y_column.is_empty() { "Select..." } else { y_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(y_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Color By (optional):");
-                let current_color = color_column.as_ref().map(|s| s.as_str()).unwrap_or("None");
-                egui::ComboBox::from_id_source("config_scatter_color")
-                    .selected_text(current_color)
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(color_column, None, "None");
-                        for col in &self.columns.categorical {
-                            ui.selectable_value(color_column, Some(col.name.clone()), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !x_column.is_empty() && !y_column.is_empty();
-            }
-            
-            ViewConfig::BarChart { title, data_source_id: _, category_column, value_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Category:");
-                egui::ComboBox::from_id_source("config_bar_cat")
-                    .selected_text(if category_column.is_empty() { "Select..." } else { category_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.categorical {
-                            ui.selectable_value(category_column, col.name.clone(), &col.name);
-                        }
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(category_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Value:");
-                egui::ComboBox::from_id_source("config_bar_val")
-                    .selected_text(if value_column.is_empty() { "Select..." } else { value_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(value_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !category_column.is_empty() && !value_column.is_empty();
-            }
-            
-            ViewConfig::Histogram { title, data_source_id: _, column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Column:");
-                egui::ComboBox::from_id_source("config_hist")
-                    .selected_text(if column.is_empty() { "Select..." } else { column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !column.is_empty();
-            }
-            
-            ViewConfig::Table { title, data_source_id: _, columns } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Columns (optional, all if none):");
-                ui.horizontal(|ui| {
-                    if ui.small_button("All").clicked() {
-                        columns.clear();
-                        columns.extend(self.columns.all.iter().map(|c| c.name.clone()));
-                    }
-                    if ui.small_button("Clear").clicked() {
-                        columns.clear();
-                    }
-                });
-                
-                for col in &self.columns.all {
-                    let mut selected = columns.contains(&col.name);
-                    if ui.checkbox(&mut selected, &col.name).changed() {
-                        if selected {
-                            if !columns.contains(&col.name) {
-                                columns.push(col.name.clone());
-                            }
-                        } else {
-                            columns.retain(|c| c != &col.name);
-                        }
-                    }
-                }
-                
-                self.plot_config_state.is_valid = true; // Table is always valid
-            }
-            
-            ViewConfig::BoxPlot { title, data_source_id: _, value_column, category_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Value:");
-                egui::ComboBox::from_id_source("config_box_val")
-                    .selected_text(if value_column.is_empty() { "Select..." } else { value_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(value_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Category (optional):");
-                let current_category = category_column.as_ref().map(|s| s.as_str()).unwrap_or("None");
-                egui::ComboBox::from_id_source("config_box_cat")
-                    .selected_text(current_category)
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(category_column, None, "None");
-                        for col in &self.columns.categorical {
-                            ui.selectable_value(category_column, Some(col.name.clone()), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !value_column.is_empty();
-            }
-            
-            ViewConfig::ViolinPlot { title, data_source_id: _, value_column, category_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Value:");
-                egui::ComboBox::from_id_source("config_violin_val")
-                    .selected_text(if value_column.is_empty() { "Select..." } else { value_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(value_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Category (optional):");
-                let current_category = category_column.as_ref().map(|s| s.as_str()).unwrap_or("None");
-                egui::ComboBox::from_id_source("config_violin_cat")
-                    .selected_text(current_category)
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(category_column, None, "None");
-                        for col in &self.columns.categorical {
-                            ui.selectable_value(category_column, Some(col.name.clone()), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !value_column.is_empty();
-            }
-            
-            ViewConfig::Heatmap { title, data_source_id: _, x_column, y_column, value_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("X-Axis:");
-                egui::ComboBox::from_id_source("config_heat_x")
-                    .selected_text(if x_column.is_empty() { "Select..." } else { x_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(x_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Y-Axis:");
-                egui::ComboBox::from_id_source("config_heat_y")
-                    .selected_text(if y_column.is_empty() { "Select..." } else { y_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(y_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Value:");
-                egui::ComboBox::from_id_source("config_heat_val")
-                    .selected_text(if value_column.is_empty() { "Select..." } else { value_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(value_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !x_column.is_empty() && !y_column.is_empty() && !value_column.is_empty();
-            }
-            
-            ViewConfig::AnomalyDetection { title, data_source_id: _, column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Column:");
-                egui::ComboBox::from_id_source("config_anomaly")
-                    .selected_text(if column.is_empty() { "Select..." } else { column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !column.is_empty();
-            }
+        ui.horizontal(|ui| {
+            ui.label("Title:");
+            ui.text_edit_singleline(&mut config.title);
+        });
+        
+        if view.columns.is_empty() {
+            ui.add_space(4.0);
+            ui.label(egui::RichText::new("Shows all columns").size(11.0).color(Color32::from_gray(160)));
+        }
+        
+        let schema = self.data_sources.iter()
+            .find(|(id, _)| Some(id) == config.data_source_id.as_ref())
+            .map(|(_, schema)| schema.clone());
+        if !config.settings.is_object() {
+            config.settings = Value::Object(Map::new());
+        }
+        let Some(settings) = config.settings.as_object_mut() else {
+            return;
+        };
+        
+        for field in &view.columns {
+            let columns = schema.as_deref().map(|schema| field.kind.columns(schema)).unwrap_or_default();
+            ui.add_space(4.0);
             
-            ViewConfig::CorrelationMatrix { title, data_source_id: _, columns } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Columns (select at least 2):");
-                ui.horizontal(|ui| {
-                    if ui.small_button("All").clicked() {
-                        columns.clear();
-                        columns.extend(self.columns.numeric.iter().map(|c| c.name.clone()));
-                    }
-                    if ui.small_button("Clear").clicked() {
-                        columns.clear();
-                    }
-                });
-                
-                for col in &self.columns.numeric {
-                    let mut selected = columns.contains(&col.name);
-                    if ui.checkbox(&mut selected, &col.name).changed() {
-                        if selected {
-                            if !columns.contains(&col.name) {
-                                columns.push(col.name.clone());
+            match field.count {
+                ColumnCount::One | ColumnCount::Optional => {
+                    let optional = field.count == ColumnCount::Optional;
+                    let none_text = if field.kind == ColumnKind::Ordered { "Auto (Row Index)" } else { "None" };
+                    ui.label(if optional { format!("{} (optional):", field.label) } else { format!("{}:", field.label) });
+                    
+                    let current = settings.get(&field.key).and_then(Value::as_str).map(str::to_string);
+                    let mut selected = current.clone();
+                    egui::ComboBox::from_id_source(("config_column", &field.key))
+                        .selected_text(selected.as_deref().unwrap_or(if optional { none_text } else { "Select..." }))
+                        .width(ui.available_width())
+                        .show_ui(ui, |ui| {
+                            if optional {
+                                ui.selectable_value(&mut selected, None, none_text);
                             }
-                        } else {
-                            columns.retain(|c| c != &col.name);
-                        }
-                    }
-                }
-                
-                self.plot_config_state.is_valid = columns.len() >= 2;
-            }
-            
-            ViewConfig::Scatter3D { title, data_source_id: _, x_column, y_column, z_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("X-Axis:");
-                egui::ComboBox::from_id_source("config_scatter3d_x")
-                    .selected_text(if x_column.is_empty() { "Select..." } else { x_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(x_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Y-Axis:");
-                egui::ComboBox::from_id_source("config_scatter3d_y")
-                    .selected_text(if y_column.is_empty() { "Select..." } else { y_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(y_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Z-Axis:");
-                egui::ComboBox::from_id_source("config_scatter3d_z")
-                    .selected_text(if z_column.is_empty() { "Select..." } else { z_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(z_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !x_column.is_empty() && !y_column.is_empty() && !z_column.is_empty();
-            }
-            
-            ViewConfig::Surface3D { title, data_source_id: _, x_column, y_column, z_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("X-Axis:");
-                egui::ComboBox::from_id_source("config_surface3d_x")
-                    .selected_text(if x_column.is_empty() { "Select..." } else { x_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(x_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Y-Axis:");
-                egui::ComboBox::from_id_source("config_surface3d_y")
-                    .selected_text(if y_column.is_empty() { "Select..." } else { y_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(y_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Z-Axis:");
-                egui::ComboBox::from_id_source("config_surface3d_z")
-                    .selected_text(if z_column.is_empty() { "Select..." } else { z_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(z_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !x_column.is_empty() && !y_column.is_empty() && !z_column.is_empty();
-            }
-            
-            ViewConfig::ParallelCoordinates { title, data_source_id: _, columns } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Columns (select at least 2):");
-                ui.horizontal(|ui| {
-                    if ui.small_button("All").clicked() {
-                        columns.clear();
-                        columns.extend(self.columns.numeric.iter().map(|c| c.name.clone()));
-                    }
-                    if ui.small_button("Clear").clicked() {
-                        columns.clear();
-                    }
-                });
-                
-                for col in &self.columns.numeric {
-                    let mut selected = columns.contains(&col.name);
-                    if ui.checkbox(&mut selected, &col.name).changed() {
-                        if selected {
-                            if !columns.contains(&col.name) {
-                                columns.push(col.name.clone());
+                            for column in &columns {
+                                ui.selectable_value(&mut selected, Some(column.clone()), column);
                             }
-                        } else {
-                            columns.retain(|c| c != &col.name);
-                        }
+                        });
+                    if selected != current {
+                        settings.insert(field.key.clone(), selected.map_or(Value::Null, Value::String));
                     }
                 }
-                
-                self.plot_config_state.is_valid = columns.len() >= 2;
-            }
-            
-            ViewConfig::RadarChart { title, data_source_id: _, value_columns, group_column: _ } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Value Columns (select at least 3):");
-                ui.horizontal(|ui| {
-                    if ui.small_button("All").clicked() {
-                        value_columns.clear();
-                        value_columns.extend(self.columns.numeric.iter().map(|c| c.name.clone()));
-                    }
-                    if ui.small_button("Clear").clicked() {
-                        value_columns.clear();
-                    }
-                });
-                
-                for col in &self.columns.numeric {
-                    let mut selected = value_columns.contains(&col.name);
-                    if ui.checkbox(&mut selected, &col.name).changed() {
-                        if selected {
-                            if !value_columns.contains(&col.name) {
-                                value_columns.push(col.name.clone());
-                            }
-                        } else {
-                            value_columns.retain(|c| c != &col.name);
-                        }
+                ColumnCount::Many { min, .. } => {
+                    if min > 1 {
+                        ui.label(format!("{} (select at least {}):", field.label, min));
+                    } else {
+                        ui.label(format!("{} (select one or more):", field.label));
                     }
-                }
-                
-                self.plot_config_state.is_valid = value_columns.len() >= 3;
-            }
-            
-            ViewConfig::PolarPlot { title, data_source_id: _, angle_column, radius_column, category_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Angle Column (0-360° or radians):");
-                egui::ComboBox::from_id_source("config_polar_angle")
-                    .selected_text(if angle_column.is_empty() { "Select..." } else { angle_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(angle_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Radius Column:");
-                egui::ComboBox::from_id_source("config_polar_radius")
-                    .selected_text(if radius_column.is_empty() { "Select..." } else { radius_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(radius_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Category/Color (optional):");
-                let current_category = category_column.as_ref().map(|s| s.as_str()).unwrap_or("None");
-                egui::ComboBox::from_id_source("config_polar_category")
-                    .selected_text(current_category)
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(category_column, None, "None");
-                        for col in &self.columns.categorical {
-                            ui.selectable_value(category_column, Some(col.name.clone()), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !angle_column.is_empty() && !radius_column.is_empty();
-            }
-            
-            ViewConfig::Contour { title, data_source_id: _, x_column, y_column, z_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("X Column:");
-                egui::ComboBox::from_id_source("config_contour_x")
-                    .selected_text(if x_column.is_empty() { "Select..." } else { x_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(x_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Y Column:");
-                egui::ComboBox::from_id_source("config_contour_y")
-                    .selected_text(if y_column.is_empty() { "Select..." } else { y_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(y_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Z Column (values):");
-                egui::ComboBox::from_id_source("config_contour_z")
-                    .selected_text(if z_column.is_empty() { "Select..." } else { z_column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(z_column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !x_column.is_empty() && !y_column.is_empty() && !z_column.is_empty();
-            }
-            
-            ViewConfig::Distribution { title, data_source_id: _, column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                
-                ui.label("Column to Analyze:");
-                egui::ComboBox::from_id_source("config_dist_column")
-                    .selected_text(if column.is_empty() { "Select..." } else { column.as_str() })
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(column, col.name.clone(), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = !column.is_empty();
-            }
-            
-            ViewConfig::Line { title, data_source_id: _, x_column, y_columns, category_column } => {
-                ui.horizontal(|ui| {
-                    ui.label("Title:");
-                    ui.text_edit_singleline(title);
-                });
-                
-                ui.add_space(4.0);
-                ui.label("X-Axis (optional):");
-                let current_x = x_column.as_ref().map(|s| s.as_str()).unwrap_or("Auto (Row Index)");
-                egui::ComboBox::from_id_source("config_line_x")
-                    .selected_text(current_x)
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(x_column, None, "Auto (Row Index)");
-                        for col in &self.columns.temporal {
-                            ui.selectable_value(x_column, Some(col.name.clone()), &col.name);
-                        }
-                        for col in &self.columns.numeric {
-                            ui.selectable_value(x_column, Some(col.name.clone()), &col.name);
-                        }
-                    });
-                
-                ui.add_space(4.0);
-                ui.label("Y-Axis (select one or more):");
-                
-                let mut any_selected = false;
-                for col in &self.columns.numeric {
-                    let mut selected = y_columns.contains(&col.name);
-                    if ui.checkbox(&mut selected, &col.name).changed() {
-                        if selected {
-                            if !y_columns.contains(&col.name) {
-                                y_columns.push(col.name.clone());
+                    
+                    let mut chosen: Vec<String> = settings.get(&field.key).and_then(Value::as_array)
+                        .map(|names| names.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                        .unwrap_or_default();
+                    let mut changed = false;
+                    for column in &columns {
+                        let mut checked = chosen.contains(column);
+                        if ui.checkbox(&mut checked, column).changed() {
+                            if checked {
+                                chosen.push(column.clone());
+                            } else {
+                                chosen.retain(|name| name != column);
                             }
-                        } else {
-                            y_columns.retain(|c| c != &col.name);
+                            changed = true;
                         }
                     }
-                    if selected {
-                        any_selected = true;
+                    if changed {
+                        settings.insert(field.key.clone(), Value::Array(chosen.into_iter().map(Value::String).collect()));
                     }
                 }
-                
-                ui.add_space(4.0);
-                ui.label("Category/Color (optional):");
-                let current_category = category_column.as_ref().map(|s| s.as_str()).unwrap_or("None");
-                egui::ComboBox::from_id_source("config_line_category")
-                    .selected_text(current_category)
-                    .width(ui.available_width())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(category_column, None, "None");
-                        for col in &self.columns.categorical {
-                            ui.selectable_value(category_column, Some(col.name.clone()), &col.name);
-                        }
-                    });
-                
-                self.plot_config_state.is_valid = any_selected;
-            }
-            
-            _ => {
-                ui.label("Configuration for this plot type coming soon...");
-                self.plot_config_state.is_valid = false;
             }
         }
         
-        self.plot_config_state.config = config;
+        self.plot_config = Some(config);
     }
     
     /// Add the configured plot to the dashboard
     fn add_configured_plot(&mut self) {
-        if self.plot_config_state.is_valid {
-            self.add_view(self.plot_config_state.config.clone());
-            
-            // Reset configuration state
-            self.plot_config_state = PlotConfigState {
-                selected_plot_type: None,
-                config: ViewConfig::Empty,
-                is_valid: false,
-            };
+        if self.plot_config.as_ref().is_some_and(|config| self.is_config_valid(config)) {
+            if let Some(config) = self.plot_config.take() {
+                self.add_view(config);
+            }
         }
     }
     
@@ -2205,35 +984,8 @@ impl ViewBuilderDialog {
         );
         
         // Icon and title
-        let (icon, title) = match &cell.view_config {
-            ViewConfig::TimeSeries { title, .. } => ("📈", title.as_str()),
-            ViewConfig::Line { title, .. } => ("📉", title.as_str()),
-            ViewConfig::Scatter { title, .. } => ("🎯", title.as_str()),
-            ViewConfig::BarChart { title, .. } => ("📊", title.as_str()),
-            ViewConfig::Histogram { title, .. } => ("📊", title.as_str()),
-            ViewConfig::Table { title, .. } => ("📋", title.as_str()),
-            ViewConfig::BoxPlot { title, .. } => ("📦", title.as_str()),
-            ViewConfig::ViolinPlot { title, .. } => ("🎻", title.as_str()),
-            ViewConfig::Heatmap { title, .. } => ("🔥", title.as_str()),
-            ViewConfig::AnomalyDetection { title, .. } => ("⚠️", title.as_str()),
-            ViewConfig::CorrelationMatrix { title, .. } => ("🎯", title.as_str()),
-            ViewConfig::Scatter3D { title, .. } => ("🎲", title.as_str()),
-            ViewConfig::Surface3D { title, .. } => ("🏔️", title.as_str()),
-            ViewConfig::ParallelCoordinates { title, .. } => ("🌈", title.as_str()),
-            ViewConfig::RadarChart { title, .. } => ("🎯", title.as_str()),
-            ViewConfig::PolarPlot { .. } => ("🧭", "Polar Plot"),
-            ViewConfig::Contour { .. } => ("🗺️", "Contour Plot"),
-            ViewConfig::Sankey { .. } => ("🧵", "Sankey Diagram"),
-            ViewConfig::Treemap { .. } => ("📊", "Treemap"),
-            ViewConfig::Sunburst { .. } => ("🌞", "Sunburst"),
-            ViewConfig::NetworkGraph { .. } => ("🌐", "Network Graph"),
-            ViewConfig::Distribution { .. } => ("📊", "Distribution"),
-            ViewConfig::TimeAnalysis { .. } => ("⏳", "Time Analysis"),
-            ViewConfig::GeoPlot { .. } => ("🌍", "Geographic Plot"),
-            ViewConfig::StreamGraph { .. } => ("🌊", "Stream Graph"),
-            ViewConfig::CandlestickChart { .. } => ("🕒", "Candlestick Chart"),
-            _ => ("📊", "Plot"),
-        };
+        let icon = self.views.get(&cell.view_config.view_type).map_or("📊", |view| view.icon.as_str());
+        let title = cell.view_config.title.as_str();
         
         // Draw content
         let text_pos = rect.min + Vec2::new(8.0, 8.0);
@@ -2271,72 +1023,15 @@ impl ViewBuilderDialog {
     
     /// Check if a view config is valid
     fn is_config_valid(&self, config: &ViewConfig) -> bool {
-        match config {
-            ViewConfig::TimeSeries { y_columns, .. } => !y_columns.is_empty(),
-            ViewConfig::Line { y_columns, .. } => !y_columns.is_empty(),
-            ViewConfig::Scatter { x_column, y_column, .. } => !x_column.is_empty() && !y_column.is_empty(),
-            ViewConfig::BarChart { category_column, value_column, .. } => !category_column.is_empty() && !value_column.is_empty(),
-            ViewConfig::Histogram { column, .. } => !column.is_empty(),
-            ViewConfig::Table { .. } => true,
-            ViewConfig::BoxPlot { value_column, .. } => !value_column.is_empty(),
-            ViewConfig::ViolinPlot { value_column, .. } => !value_column.is_empty(),
-            ViewConfig::Heatmap { x_column, y_column, value_column, .. } => 
-                !x_column.is_empty() && !y_column.is_empty() && !value_column.is_empty(),
-            ViewConfig::AnomalyDetection { column, .. } => !column.is_empty(),
-            ViewConfig::CorrelationMatrix { columns, .. } => columns.len() >= 2,
-            ViewConfig::Scatter3D { x_column, y_column, z_column, .. } => 
-                !x_column.is_empty() && !y_column.is_empty() && !z_column.is_empty(),
-            ViewConfig::Surface3D { x_column, y_column, z_column, .. } => 
-                !x_column.is_empty() && !y_column.is_empty() && !z_column.is_empty(),
-            ViewConfig::ParallelCoordinates { columns, .. } => columns.len() >= 2,
-            ViewConfig::RadarChart { value_columns, .. } => value_columns.len() >= 3,
-            ViewConfig::PolarPlot { angle_column, radius_column, .. } => 
-                !angle_column.is_empty() && !radius_column.is_empty(),
-            ViewConfig::Contour { x_column, y_column, z_column, .. } => 
-                !x_column.is_empty() && !y_column.is_empty() && !z_column.is_empty(),
-            ViewConfig::Distribution { column, .. } => !column.is_empty(),
-            _ => false,
-        }
+        self.views.get(&config.view_type).is_some_and(|view| view.is_complete(&config.settings))
     }
     
     /// Get column info text for a view config
     fn get_column_info_text(&self, config: &ViewConfig) -> String {
-        match config {
-            ViewConfig::TimeSeries { x_column, y_columns, .. } => {
-                let x = x_column.as_ref().map(|s| s.as_str()).unwrap_or("Row Index");
-                if y_columns.is_empty() {
-                    String::new()
-                } else if y_columns.len() == 1 {
-                    format!("{} → {}", x, y_columns[0])
-                } else {
-                    format!("{} → {} series", x, y_columns.len())
-                }
-            }
-            ViewConfig::Line { x_column, y_columns, category_column, .. } => {
-                let x = x_column.as_ref().map(|s| s.as_str()).unwrap_or("Row Index");
-                let mut text = if y_columns.is_empty() {
-                    String::new()
-                } else if y_columns.len() == 1 {
-                    format!("{} → {}", x, y_columns[0])
-                } else {
-                    format!("{} → {} series", x, y_columns.len())
-                };
-                if let Some(cat) = category_column {
-                    text.push_str(&format!(" (by {})", cat));
-                }
-                text
-            }
-            ViewConfig::Scatter { x_column, y_column, .. } => {
-                format!("{} vs {}", x_column, y_column)
-            }
-            ViewConfig::BarChart { category_column, value_column, .. } => {
-                format!("{}: {}", category_column, value_column)
-            }
-            ViewConfig::Histogram { column, .. } => column.clone(),
-            ViewConfig::Table { .. } => {
-                "All columns".to_string()
-            }
-            _ => String::new(),
+        match self.views.get(&config.view_type) {
+            Some(view) if view.columns.is_empty() => "All columns".to_string(),
+            Some(view) => view.summary(&config.settings),
+            None => String::new(),
         }
     }
     
@@ -2355,9 +1050,7 @@ impl ViewBuilderDialog {
     
     /// Check if template is compatible with available columns
     fn is_template_compatible(&self, template: &DashboardTemplate) -> bool {
-        self.columns.numeric.len() >= template.required_columns.min_numeric &&
-        self.columns.temporal.len() >= template.required_columns.min_temporal &&
-        self.columns.categorical.len() >= template.required_columns.min_categorical
+        template.compatible
     }
     
     /// Apply a template to the layout
@@ -2431,306 +1124,18 @@ impl ViewBuilderDialog {
     
     /// Build the actual views from the layout
     fn build_views(&self) -> (Vec<Box<dyn SpaceView>>, NavigationMode) {
-        let mut views: Vec<Box<dyn SpaceView>> = Vec::new();
-        
-        for cell in &self.layout.cells {
-            match &cell.view_config {
-                ViewConfig::TimeSeries { title, data_source_id, x_column, y_columns } => {
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = TimeSeriesView::new(id, title.clone());
-                    view.config.data_source_id = data_source_id.clone().or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone())
-                    });
-                    view.config.x_column = x_column.clone();
-                    view.config.y_columns = y_columns.clone();
-                    view.config.show_legend = true;
-                    view.config.show_grid = true;
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Scatter { title, data_source_id, x_column, y_column, color_column } => {
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = ScatterPlotView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.x_column = x_column.clone();
-                    view.config.y_column = y_column.clone();
-                    view.config.color_column = color_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Table { title, data_source_id, columns } => {
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = TableView::new(id, title.clone());
-                    view.config.data_source_id = data_source_id.clone().or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone())
-                    });
-                    views.push(Box::new(view));
-                }
-                ViewConfig::BarChart { title, data_source_id, category_column, value_column } => {
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = BarChartView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.category_column = category_column.clone();
-                    view.config.value_column = value_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Line { title, data_source_id, x_column, y_columns, category_column } => {
-                    use dv_views::plots::LinePlotView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = LinePlotView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.x_column = x_column.clone();
-                    view.config.y_columns = y_columns.clone();
-                    view.config.category_column = category_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Histogram { title, data_source_id, column } => {
-                    use dv_views::plots::HistogramView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = HistogramView::new(id, title.clone());
-                    view.config.data_source_id = data_source_id.clone().or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone())
-                    });
-                    view.config.column = column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::BoxPlot { title, data_source_id, value_column, category_column } => {
-                    use dv_views::plots::BoxPlotView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = BoxPlotView::new(id, title.clone());
-                    view.config.data_source_id = data_source_id.clone().or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone())
-                    });
-                    view.config.value_column = value_column.clone();
-                    view.config.category_column = category_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::ViolinPlot { title, data_source_id, value_column, category_column } => {
-                    use dv_views::plots::ViolinPlotView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = ViolinPlotView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.value_column = value_column.clone();
-                    view.config.category_column = category_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Heatmap { title, data_source_id, x_column, y_column, value_column } => {
-                    use dv_views::plots::HeatmapView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = HeatmapView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.x_column = x_column.clone();
-                    view.config.y_column = y_column.clone();
-                    view.config.value_column = value_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::AnomalyDetection { title, data_source_id, column } => {
-                    use dv_views::plots::AnomalyDetectionView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = AnomalyDetectionView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.column = column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::CorrelationMatrix { title, data_source_id, columns } => {
-                    use dv_views::plots::CorrelationMatrixView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = CorrelationMatrixView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.columns = columns.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Scatter3D { title, data_source_id, x_column, y_column, z_column } => {
-                    use dv_views::plots::Scatter3DView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = Scatter3DView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.x_column = x_column.clone();
-                    view.config.y_column = y_column.clone();
-                    view.config.z_column = z_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Surface3D { title, data_source_id, x_column, y_column, z_column } => {
-                    use dv_views::plots::Surface3DPlot;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = Surface3DPlot::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.x_column = x_column.clone();
-                    view.config.y_column = y_column.clone();
-                    view.config.z_column = z_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::ParallelCoordinates { title, data_source_id, columns } => {
-                    use dv_views::plots::ParallelCoordinatesView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = ParallelCoordinatesView::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.columns = columns.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::RadarChart { title, data_source_id, value_columns, group_column } => {
-                    use dv_views::plots::RadarChart;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = RadarChart::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.value_columns = value_columns.clone();
-                    view.config.group_column = group_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Distribution { title, data_source_id, column } => {
-                    use dv_views::plots::DistributionPlot;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = DistributionPlot::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.column = column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::PolarPlot { title, data_source_id, angle_column, radius_column, category_column } => {
-                    use dv_views::PolarPlotView;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = PolarPlotView::new(id, title.clone());
-                    {
-                        let config = view.config_mut();
-                        config.data_source_id = data_source_id.clone().or_else(|| {
-                            self.data_sources.first().map(|(id, _)| id.clone())
-                        });
-                        config.angle_column = angle_column.clone();
-                        config.radius_column = radius_column.clone();
-                        config.category_column = category_column.clone();
-                    }
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Contour { title, data_source_id, x_column, y_column, z_column } => {
-                    use dv_views::plots::ContourPlot;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = ContourPlot::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.x_column = x_column.clone();
-                    view.config.y_column = y_column.clone();
-                    view.config.z_column = z_column.clone();
-                    views.push(Box::new(view));
+        let default_source = self.data_sources.first().map(|(id, _)| id.clone());
+        let views = self.layout.cells.iter()
+            .filter_map(|cell| {
+                let config = &cell.view_config;
+                let mut view = self.views.create(&config.view_type, uuid::Uuid::new_v4(), config.title.clone())?;
+                view.load_config(config.settings.clone());
+                if let Some(source_id) = config.data_source_id.clone().or_else(|| default_source.clone()) {
+                    view.set_data_source(source_id);
                 }
-                ViewConfig::Sankey { title, data_source_id, source_column, target_column, value_column } => {
-                    use dv_views::plots::SankeyDiagram;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = SankeyDiagram::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.source_column = source_column.clone();
-                    view.config.target_column = target_column.clone();
-                    view.config.value_column = value_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Treemap { title, data_source_id, category_column, value_column } => {
-                    use dv_views::plots::Treemap;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = Treemap::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.path_column = category_column.clone();  // Changed from category_column to path_column
-                    view.config.value_column = value_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::Sunburst { title, data_source_id, hierarchy_columns, value_column } => {
-                    use dv_views::plots::SunburstChart;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = SunburstChart::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.hierarchy_columns = hierarchy_columns.clone();
-                    view.config.value_column = value_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::NetworkGraph { title, data_source_id, source_column, target_column } => {
-                    use dv_views::plots::NetworkGraph;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = NetworkGraph::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.source_column = source_column.clone();
-                    view.config.target_column = target_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::TimeAnalysis { title, data_source_id, time_column, value_columns } => {
-                    use dv_views::plots::TimeAnalysisPlot;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = TimeAnalysisPlot::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.time_column = time_column.clone();
-                    view.config.value_columns = value_columns.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::GeoPlot { title, data_source_id, lat_column, lon_column, value_column } => {
-                    use dv_views::plots::GeoPlot;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = GeoPlot::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.lat_column = lat_column.clone();
-                    view.config.lon_column = lon_column.clone();
-                    view.config.value_column = value_column.clone();
-                    views.push(Box::new(view));
-                }
-                ViewConfig::StreamGraph { title, data_source_id, time_column, value_column, category_column } => {
-                    use dv_views::plots::StreamGraph;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = StreamGraph::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.x_column = time_column.clone();  // Changed from time_column to x_column
-                    view.config.value_column = value_column.clone();
-                    view.config.category_column = category_column.clone().unwrap_or_default();  // Handle Option<String> to String
-                    views.push(Box::new(view));
-                }
-                ViewConfig::CandlestickChart { title, data_source_id, time_column, open_column, high_column, low_column, close_column } => {
-                    use dv_views::plots::CandlestickChart;
-                    let id = uuid::Uuid::new_v4();
-                    let mut view = CandlestickChart::new(id, title.clone());
-                    view.config.data_source_id = Some(data_source_id.clone().unwrap_or_else(|| {
-                        self.data_sources.first().map(|(id, _)| id.clone()).unwrap_or_default()
-                    }));
-                    view.config.time_column = Some(time_column.clone());
-                    view.config.open_column = Some(open_column.clone());
-                    view.config.high_column = Some(high_column.clone());
-                    view.config.low_column = Some(low_column.clone());
-                    view.config.close_column = Some(close_column.clone());
-                    views.push(Box::new(view));
-                }
-                _ => {}
-            }
-        }
+                Some(view)
+            })
+            .collect();
         
         // Convert navigation mode choice to actual mode
         let nav_mode = match &self.selected_nav_mode {
@@ -2743,4 +1148,82 @@ impl ViewBuilderDialog {
         
         (views, nav_mode)
     }
-} 
\ No newline at end of file
+}
+
+fn template_icon(template_id: &str) -> &'static str {
+    match template_id {
+        "time_series" => "📈",
+        "event_log" => "📜",
+        "metrics" => "📊",
+        "table" => "📋",
+        _ => "🧩",
+    }
+}
+
+/// The builder's grid for a template dashboard. Each pane of the dock layout
+/// becomes a cell spanning the grid lines its edges fall on; cells hold one
+/// view, so a pane with several keeps the first the builder can configure.
+fn dashboard_layout(views: &ViewRegistry, dashboard: &dv_templates::Dashboard, source_id: Option<&str>) -> DashboardLayout {
+    let mut panes = Vec::new();
+    pane_rects(dashboard.layout.main_surface(), NodeIndex::root(), [0.0, 0.0, 1.0, 1.0], &mut panes);
+    let edges = |axis: usize| {
+        let mut edges: Vec<f32> = panes.iter().flat_map(|(_, rect)| [rect[axis], rect[axis + 2]]).collect();
+        edges.sort_by(f32::total_cmp);
+        edges.dedup_by(|a, b| (*a - *b).abs() < 1e-3);
+        edges
+    };
+    let (xs, ys) = (edges(0), edges(1));
+    let line = |edges: &[f32], value: f32| edges.iter().position(|edge| (edge - value).abs() < 1e-3).unwrap_or(0);
+    let cells = panes.iter()
+        .filter_map(|(tabs, rect)| {
+            let view_config = tabs.iter()
+                .filter_map(|id| dashboard.views.iter().find(|view| &view.id == id))
+                .find_map(|view| view_config(views, view, source_id))?;
+            let pos = (line(&xs, rect[0]), line(&ys, rect[1]));
+            Some(LayoutCell {
+                id: uuid::Uuid::new_v4().to_string(),
+                grid_pos: pos,
+                grid_span: (line(&xs, rect[2]) - pos.0, line(&ys, rect[3]) - pos.1),
+                view_config,
+            })
+        })
+        .collect();
+    DashboardLayout {
+        grid_size: (xs.len().saturating_sub(1).max(1), ys.len().saturating_sub(1).max(1)),
+        cells,
+    }
+}
+
+/// Tabs and `[left, top, right, bottom]` fraction of the area of each pane
+/// under `index`
+fn pane_rects<'a>(tree: &'a egui_dock::Tree<String>, index: NodeIndex, rect: [f32; 4], panes: &mut Vec<(&'a [String], [f32; 4])>) {
+    if index.0 >= tree.len() {
+        return;
+    }
+    match &tree[index] {
+        Node::Leaf { tabs, .. } => panes.push((tabs, rect)),
+        Node::Horizontal { fraction, .. } => {
+            let split = rect[0] + (rect[2] - rect[0]) * fraction;
+            pane_rects(tree, index.left(), [rect[0], rect[1], split, rect[3]], panes);
+            pane_rects(tree, index.right(), [split, rect[1], rect[2], rect[3]], panes);
+        }
+        Node::Vertical { fraction, .. } => {
+            let split = rect[1] + (rect[3] - rect[1]) * fraction;
+            pane_rects(tree, index.left(), [rect[0], rect[1], rect[2], split], panes);
+            pane_rects(tree, index.right(), [rect[0], split, rect[2], rect[3]], panes);
+        }
+        Node::Empty => {}
+    }
+}
+
+/// The builder's configuration for a template view, if its type is
+/// registered and its columns are set
+fn view_config(views: &ViewRegistry, view: &ViewSpec, source_id: Option<&str>) -> Option<ViewConfig> {
+    views.get(&view.view_type).filter(|descriptor| descriptor.is_complete(&view.config))?;
+    Some(ViewConfig {
+        view_type: view.view_type.clone(),
+        title: view.title.clone(),
+        data_source_id: source_id.map(str::to_string),
+        settings: view.config.clone(),
+    })
+}
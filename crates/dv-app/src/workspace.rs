@@ -0,0 +1,214 @@
+//! Saving and reopening dashboards
+//!
+//! A workspace file lists the open views in dock order with their type,
+//! title, data source ID and settings, and how they are docked. Data sources
+//! are referenced by ID (the file name, or `file:table` for SQLite), so a
+//! workspace applies to any session that loaded files with the same names.
+
+use std::path::Path;
+use egui::Rect;
+use egui_dock::{DockState, Node};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+use dv_views::{SpaceViewId, ViewRegistry, Viewport};
+
+use crate::launch::DashboardViews;
+
+/// Current workspace file version
+const VERSION: u32 = 1;
+
+/// A saved dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub version: u32,
+    pub views: Vec<SavedView>,
+    /// Dock arrangement with tabs as indices into `views`, or `None` for a
+    /// grid (workspaces saved before layouts were kept)
+    #[serde(default)]
+    pub layout: Option<DockState<usize>>,
+}
+
+/// One view of a saved dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedView {
+    pub view_type: String,
+    pub title: String,
+    #[serde(default)]
+    pub data_source_id: Option<String>,
+    #[serde(default)]
+    pub config: Value,
+}
+
+impl Workspace {
+    /// Capture the views of `viewport`
+    pub fn from_viewport(viewport: &Viewport) -> Self {
+        let ids: Vec<SpaceViewId> = viewport.views().map(|view| view.id()).collect();
+        // A tab without a view gets an index past the end, which
+        // `build_views` drops
+        let mut layout = viewport.dock_state().map_tabs(|id| {
+            ids.iter().position(|view| view == id).unwrap_or(ids.len())
+        });
+        // Where panes were drawn is worked out again on the next frame, and
+        // panes not drawn yet have infinite rects, which JSON can't hold
+        for surface in layout.iter_surfaces_mut() {
+            for node in surface.node_tree_mut().into_iter().flat_map(|tree| tree.iter_mut()) {
+                node.set_rect(Rect::ZERO);
+                if let Node::Leaf { viewport, .. } = node {
+                    *viewport = Rect::ZERO;
+                }
+            }
+        }
+        Self {
+            version: VERSION,
+            views: viewport.views()
+                .map(|view| SavedView {
+                    view_type: view.view_type().to_string(),
+                    title: view.title().to_string(),
+                    data_source_id: view.data_source_id().map(str::to_string),
+                    config: view.save_config(),
+                })
+                .collect(),
+            layout: Some(layout),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let workspace: Workspace = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid workspace {}: {}", path.display(), e))?;
+        if workspace.version > VERSION {
+            return Err(format!(
+                "{} was saved by a newer version (workspace version {})",
+                path.display(),
+                workspace.version
+            ));
+        }
+        Ok(workspace)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Recreate the saved views as they were docked. Returns the views and
+    /// the types that aren't registered.
+    pub fn build_views(&self, registry: &ViewRegistry) -> (DashboardViews, Vec<String>) {
+        let mut views = Vec::new();
+        let mut ids = Vec::with_capacity(self.views.len());
+        let mut unknown = Vec::new();
+        for saved in &self.views {
+            let Some(mut view) = registry.create(&saved.view_type, Uuid::new_v4(), saved.title.clone()) else {
+                unknown.push(saved.view_type.clone());
+                ids.push(None);
+                continue;
+            };
+            ids.push(Some(view.id()));
+            if !saved.config.is_null() {
+                view.load_config(saved.config.clone());
+            }
+            if let Some(source_id) = &saved.data_source_id {
+                view.set_data_source(source_id.clone());
+            }
+            views.push(view);
+        }
+
+        // Tabs of views that couldn't be recreated are dropped from the arrangement
+        let layout = self.layout.as_ref().map(|layout| {
+            let mut layout = layout.clone();
+            let missing: Vec<usize> = layout.iter_all_tabs()
+                .filter(|(_, &index)| ids.get(index).copied().flatten().is_none())
+                .map(|(_, &index)| index)
+                .collect();
+            for index in missing {
+                if let Some(location) = layout.find_tab(&index) {
+                    layout.remove_tab(location);
+                }
+            }
+            layout.map_tabs(|&index| ids[index].unwrap())
+        });
+        (DashboardViews { views, layout }, unknown)
+    }
+
+    /// Data sources the views read from
+    pub fn data_source_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.views.iter().filter_map(|view| view.data_source_id.as_deref()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dv_views::ViewLayout;
+    use egui_dock::NodeIndex;
+    use serde_json::json;
+
+    #[test]
+    fn views_and_layout_survive_a_round_trip() {
+        let registry = ViewRegistry::builtin();
+        let mut views = Vec::new();
+        for (view_type, title) in [("TableView", "Readings"), ("HistogramView", "Spread"), ("TableView", "Totals")] {
+            let mut view = registry.create(view_type, Uuid::new_v4(), title.to_string()).unwrap();
+            view.set_data_source("sensors.csv".to_string());
+            views.push(view);
+        }
+        views[0].load_config(json!({ "striped_rows": false }));
+        let ids: Vec<SpaceViewId> = views.iter().map(|view| view.id()).collect();
+        let mut dock = DockState::new(vec![ids[0]]);
+        dock.main_surface_mut().split_right(NodeIndex::root(), 0.3, vec![ids[1], ids[2]]);
+        let mut viewport = Viewport::new();
+        viewport.create_docked_layout(views, dock);
+
+        let saved = Workspace::from_viewport(&viewport);
+        assert_eq!(saved.data_source_ids(), vec!["sensors.csv"]);
+
+        // A view type this build doesn't know, docked with the others
+        let mut edited = saved.clone();
+        edited.views.push(SavedView {
+            view_type: "RetiredView".to_string(),
+            title: "Old".to_string(),
+            data_source_id: None,
+            config: Value::Null,
+        });
+        edited.layout.as_mut().unwrap().push_to_first_leaf(3);
+
+        let text = serde_json::to_string(&edited).unwrap();
+        let loaded: Workspace = serde_json::from_str(&text).unwrap();
+        let (rebuilt, unknown) = loaded.build_views(&registry);
+        assert_eq!(unknown, vec!["RetiredView"]);
+        assert_eq!(rebuilt.views.len(), 3);
+
+        let mut reopened = Viewport::new();
+        rebuilt.show(&mut reopened);
+        let resaved = Workspace::from_viewport(&reopened);
+        assert_eq!(serde_json::to_value(&resaved.views).unwrap(), serde_json::to_value(&saved.views).unwrap());
+        assert_eq!(resaved.views[0].config["striped_rows"], json!(false));
+
+        let new_ids: Vec<SpaceViewId> = reopened.views().map(|view| view.id()).collect();
+        assert_eq!(reopened.layout(), Some(ViewLayout::Split {
+            horizontal: true,
+            fraction: 0.3,
+            first: Box::new(ViewLayout::Tabs(vec![new_ids[0]])),
+            second: Box::new(ViewLayout::Tabs(vec![new_ids[1], new_ids[2]])),
+        }));
+    }
+
+    #[test]
+    fn workspaces_without_a_layout_still_load() {
+        let loaded: Workspace = serde_json::from_value(json!({
+            "version": 1,
+            "views": [{ "view_type": "TableView", "title": "Readings" }],
+        })).unwrap();
+        assert!(loaded.layout.is_none());
+
+        let (rebuilt, unknown) = loaded.build_views(&ViewRegistry::builtin());
+        assert!(unknown.is_empty());
+        assert!(rebuilt.layout.is_none());
+        assert_eq!(rebuilt.views[0].title(), "Readings");
+    }
+}
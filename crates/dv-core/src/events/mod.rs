@@ -1,26 +1,93 @@
-use std::sync::Arc;
+//! System-wide event bus
+//!
+//! Events can be published from any thread (e.g. background loading tasks),
+//! but they are only queued there. Handlers run when the owner of the bus
+//! calls [`EventBus::dispatch`], which the application does once per frame on
+//! the UI thread. Handlers never run while the bus's internal locks are held,
+//! so they are free to publish further events, (un)subscribe, or even call
+//! `dispatch` themselves.
+
+use std::any::TypeId;
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
 use parking_lot::Mutex;
 use ahash::AHashMap;
 
-/// System-wide event bus
-pub struct EventBus {
-    handlers: Arc<Mutex<AHashMap<std::any::TypeId, Vec<Box<dyn EventHandler>>>>>,
-}
+use crate::navigation::{NavigationContext, NavigationRange, NavigationSubscriber};
 
 /// Event trait that all events must implement
 pub trait Event: Send + Sync + 'static {
     fn as_any(&self) -> &dyn std::any::Any;
 }
 
-/// Handler trait for event handlers
-pub trait EventHandler: Send + Sync {
-    fn handle(&mut self, event: &dyn Event);
+/// Type-erased handler stored by the bus
+type DynHandler = Arc<Mutex<dyn FnMut(&dyn Event) + Send>>;
+
+/// Identifier of a single subscription
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Shared state of the bus
+struct EventBusInner {
+    handlers: Mutex<AHashMap<TypeId, Vec<(SubscriptionId, DynHandler)>>>,
+    queue: Mutex<Vec<Box<dyn Event>>>,
+    next_id: Mutex<u64>,
+    waker: Mutex<Option<Arc<dyn Fn() + Send + Sync>>>,
+    /// Whether a `dispatch` is delivering events
+    dispatching: AtomicBool,
+}
+
+/// System-wide event bus
+#[derive(Clone)]
+pub struct EventBus {
+    inner: Arc<EventBusInner>,
+}
+
+/// Handle to a subscription; the handler is removed when this is dropped
+/// (or when [`Subscription::unsubscribe`] is called).
+#[must_use = "dropping a Subscription immediately unsubscribes the handler"]
+pub struct Subscription {
+    id: SubscriptionId,
+    type_id: TypeId,
+    bus: Weak<EventBusInner>,
+}
+
+impl Subscription {
+    /// Get the subscription ID
+    pub fn id(&self) -> SubscriptionId {
+        self.id
+    }
+
+    /// Remove the handler from the bus
+    pub fn unsubscribe(self) {
+        // Removal happens in Drop
+    }
+
+    /// Keep the handler registered for the lifetime of the bus
+    pub fn detach(mut self) {
+        self.bus = Weak::new();
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(inner) = self.bus.upgrade() {
+            EventBus::remove_handler(&inner, self.type_id, self.id);
+        }
+    }
 }
 
 /// Common system events
 pub mod events {
     use super::Event;
-    
+    use crate::navigation::{NavigationPosition, NavigationRange};
+
+    /// Data source started loading
+    #[derive(Debug, Clone)]
+    pub struct DataSourceLoading {
+        pub source_name: String,
+    }
+
     /// Data source loaded event
     #[derive(Debug, Clone)]
     pub struct DataSourceLoaded {
@@ -28,34 +95,58 @@ pub mod events {
         pub row_count: usize,
         pub column_count: usize,
     }
-    
+
     /// Data source error event
     #[derive(Debug, Clone)]
     pub struct DataSourceError {
         pub source_name: String,
         pub error: String,
     }
-    
+
+    /// All data sources were removed (e.g. returning to the welcome screen)
+    #[derive(Debug, Clone)]
+    pub struct DataSourcesCleared;
+
     /// View created event
     #[derive(Debug, Clone)]
     pub struct ViewCreated {
         pub view_id: String,
         pub view_type: String,
     }
-    
+
     /// View closed event
     #[derive(Debug, Clone)]
     pub struct ViewClosed {
         pub view_id: String,
     }
-    
+
+    /// Navigation position changed
+    #[derive(Debug, Clone)]
+    pub struct NavigationChanged {
+        pub position: NavigationPosition,
+        pub total_rows: usize,
+    }
+
+    /// Selection range changed (`None` when the selection was cleared)
+    #[derive(Debug, Clone)]
+    pub struct SelectionChanged {
+        pub range: Option<NavigationRange>,
+    }
+
     /// Template changed event
     #[derive(Debug, Clone)]
     pub struct TemplateChanged {
         pub template_id: String,
         pub template_name: String,
     }
-    
+
+    /// Work started on the runtime finished, with `error` set if it failed
+    #[derive(Debug, Clone)]
+    pub struct TaskFinished {
+        pub message: String,
+        pub error: Option<String>,
+    }
+
     // Implement Event trait for all event types
     macro_rules! impl_event {
         ($($t:ty),*) => {
@@ -68,13 +159,18 @@ pub mod events {
             )*
         }
     }
-    
+
     impl_event!(
+        DataSourceLoading,
         DataSourceLoaded,
         DataSourceError,
+        DataSourcesCleared,
         ViewCreated,
         ViewClosed,
-        TemplateChanged
+        NavigationChanged,
+        SelectionChanged,
+        TemplateChanged,
+        TaskFinished
     );
 }
 
@@ -82,25 +178,120 @@ impl EventBus {
     /// Create a new event bus
     pub fn new() -> Self {
         Self {
-            handlers: Arc::new(Mutex::new(AHashMap::new())),
+            inner: Arc::new(EventBusInner {
+                handlers: Mutex::new(AHashMap::new()),
+                queue: Mutex::new(Vec::new()),
+                next_id: Mutex::new(0),
+                waker: Mutex::new(None),
+                dispatching: AtomicBool::new(false),
+            }),
         }
     }
-    
+
+    /// Set a callback invoked whenever an event is queued, e.g. to request a
+    /// repaint so the UI thread dispatches it promptly.
+    pub fn set_waker(&self, waker: impl Fn() + Send + Sync + 'static) {
+        *self.inner.waker.lock() = Some(Arc::new(waker));
+    }
+
     /// Subscribe to events of a specific type
-    pub fn subscribe<E: Event>(&self, handler: Box<dyn EventHandler>) {
-        let type_id = std::any::TypeId::of::<E>();
-        let mut handlers = self.handlers.lock();
-        handlers.entry(type_id).or_insert_with(Vec::new).push(handler);
+    pub fn subscribe<E, F>(&self, mut handler: F) -> Subscription
+    where
+        E: Event,
+        F: FnMut(&E) + Send + 'static,
+    {
+        let type_id = TypeId::of::<E>();
+        let id = {
+            let mut next_id = self.inner.next_id.lock();
+            *next_id += 1;
+            SubscriptionId(*next_id)
+        };
+
+        let erased: DynHandler = Arc::new(Mutex::new(move |event: &dyn Event| {
+            if let Some(event) = event.as_any().downcast_ref::<E>() {
+                handler(event);
+            }
+        }));
+
+        self.inner.handlers.lock()
+            .entry(type_id)
+            .or_default()
+            .push((id, erased));
+
+        Subscription {
+            id,
+            type_id,
+            bus: Arc::downgrade(&self.inner),
+        }
     }
-    
-    /// Publish an event
+
+    /// Queue an event for delivery on the next [`dispatch`](Self::dispatch)
     pub fn publish<E: Event>(&self, event: E) {
-        let type_id = std::any::TypeId::of::<E>();
-        let mut handlers = self.handlers.lock();
-        
-        if let Some(event_handlers) = handlers.get_mut(&type_id) {
-            for handler in event_handlers.iter_mut() {
-                handler.handle(&event);
+        self.inner.queue.lock().push(Box::new(event));
+
+        let waker = self.inner.waker.lock().clone();
+        if let Some(waker) = waker {
+            waker();
+        }
+    }
+
+    /// Deliver all queued events to their handlers.
+    ///
+    /// Events published by handlers during dispatch are delivered in the same
+    /// call. A `dispatch` made while another is delivering (e.g. from inside a
+    /// handler, whose lock is held while it runs) returns at once and leaves
+    /// the events to it. Returns the number of events delivered.
+    pub fn dispatch(&self) -> usize {
+        if self.inner.dispatching.swap(true, Ordering::Acquire) {
+            return 0;
+        }
+        let mut delivered = 0;
+
+        loop {
+            let pending = std::mem::take(&mut *self.inner.queue.lock());
+            if pending.is_empty() {
+                break;
+            }
+
+            for event in pending {
+                let type_id = event.as_any().type_id();
+
+                // Snapshot handlers so none of them run under the map lock
+                let handlers: Vec<DynHandler> = self.inner.handlers.lock()
+                    .get(&type_id)
+                    .map(|list| list.iter().map(|(_, h)| h.clone()).collect())
+                    .unwrap_or_default();
+
+                for handler in handlers {
+                    (handler.lock())(event.as_ref());
+                }
+                delivered += 1;
+            }
+        }
+
+        self.inner.dispatching.store(false, Ordering::Release);
+        delivered
+    }
+
+    /// Number of events waiting for dispatch
+    pub fn pending_count(&self) -> usize {
+        self.inner.queue.lock().len()
+    }
+
+    /// Number of handlers subscribed to events of type `E`
+    pub fn subscriber_count<E: Event>(&self) -> usize {
+        self.inner.handlers.lock()
+            .get(&TypeId::of::<E>())
+            .map(|list| list.len())
+            .unwrap_or(0)
+    }
+
+    fn remove_handler(inner: &EventBusInner, type_id: TypeId, id: SubscriptionId) {
+        let mut handlers = inner.handlers.lock();
+        if let Some(list) = handlers.get_mut(&type_id) {
+            list.retain(|(handler_id, _)| *handler_id != id);
+            if list.is_empty() {
+                handlers.remove(&type_id);
             }
         }
     }
@@ -112,28 +303,119 @@ impl Default for EventBus {
     }
 }
 
-/// Helper struct for creating event handlers from closures
-pub struct ClosureEventHandler<F> {
-    handler: F,
-    _phantom: std::marker::PhantomData<()>,
+/// Forwards navigation engine changes onto an [`EventBus`] as
+/// [`events::NavigationChanged`] and [`events::SelectionChanged`].
+///
+/// The navigation engine only holds weak references to its subscribers, so
+/// the caller must keep the returned `Arc` alive.
+pub struct NavigationEventBridge {
+    bus: EventBus,
+    last_range: Mutex<Option<NavigationRange>>,
+}
+
+impl NavigationEventBridge {
+    /// Create a bridge publishing to `bus`
+    pub fn new(bus: EventBus) -> Arc<Self> {
+        Arc::new(Self {
+            bus,
+            last_range: Mutex::new(None),
+        })
+    }
 }
 
-impl<F> EventHandler for ClosureEventHandler<F>
-where
-    F: FnMut(&dyn Event) + Send + Sync,
-{
-    fn handle(&mut self, event: &dyn Event) {
-        (self.handler)(event);
+impl NavigationSubscriber for NavigationEventBridge {
+    fn on_navigation_change(&self, context: &NavigationContext) {
+        self.bus.publish(events::NavigationChanged {
+            position: context.position.clone(),
+            total_rows: context.total_rows,
+        });
+
+        let mut last = self.last_range.lock();
+        if *last != context.selection_range {
+            *last = context.selection_range.clone();
+            self.bus.publish(events::SelectionChanged {
+                range: context.selection_range.clone(),
+            });
+        }
     }
 }
 
-/// Create an event handler from a closure
-pub fn handler_from_fn<F>(f: F) -> Box<dyn EventHandler>
-where
-    F: FnMut(&dyn Event) + Send + Sync + 'static,
-{
-    Box::new(ClosureEventHandler { 
-        handler: f,
-        _phantom: std::marker::PhantomData,
-    })
-} 
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::events::*;
+
+    #[test]
+    fn events_are_queued_until_dispatch() {
+        let bus = EventBus::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let _sub = bus.subscribe::<DataSourceLoaded, _>(move |e| sink.lock().push(e.row_count));
+
+        bus.publish(DataSourceLoaded { source_name: "a".into(), row_count: 3, column_count: 1 });
+        assert!(seen.lock().is_empty());
+        assert_eq!(bus.pending_count(), 1);
+
+        assert_eq!(bus.dispatch(), 1);
+        assert_eq!(*seen.lock(), vec![3]);
+    }
+
+    #[test]
+    fn dropping_subscription_unsubscribes() {
+        let bus = EventBus::new();
+        let count = Arc::new(Mutex::new(0));
+        let sink = count.clone();
+        let sub = bus.subscribe::<ViewClosed, _>(move |_| *sink.lock() += 1);
+
+        bus.publish(ViewClosed { view_id: "v".into() });
+        bus.dispatch();
+        sub.unsubscribe();
+        bus.publish(ViewClosed { view_id: "v".into() });
+        bus.dispatch();
+
+        assert_eq!(*count.lock(), 1);
+        assert_eq!(bus.subscriber_count::<ViewClosed>(), 0);
+    }
+
+    #[test]
+    fn handlers_can_publish_during_dispatch() {
+        let bus = EventBus::new();
+        let relay = bus.clone();
+        bus.subscribe::<ViewCreated, _>(move |e| {
+            relay.publish(ViewClosed { view_id: e.view_id.clone() });
+        }).detach();
+
+        let closed = Arc::new(Mutex::new(None));
+        let sink = closed.clone();
+        let _sub = bus.subscribe::<ViewClosed, _>(move |e| *sink.lock() = Some(e.view_id.clone()));
+
+        bus.publish(ViewCreated { view_id: "x".into(), view_type: "TableView".into() });
+        assert_eq!(bus.dispatch(), 2);
+        assert_eq!(closed.lock().as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn handlers_can_dispatch_during_dispatch() {
+        let bus = EventBus::new();
+        let relay = bus.clone();
+        let nested = Arc::new(Mutex::new(None));
+        let sink = nested.clone();
+        bus.subscribe::<ViewCreated, _>(move |e| {
+            relay.publish(ViewClosed { view_id: e.view_id.clone() });
+            *sink.lock() = Some(relay.dispatch());
+        }).detach();
+
+        let closed = Arc::new(Mutex::new(0));
+        let count = closed.clone();
+        let _sub = bus.subscribe::<ViewClosed, _>(move |_| *count.lock() += 1);
+
+        bus.publish(ViewCreated { view_id: "x".into(), view_type: "TableView".into() });
+        assert_eq!(bus.dispatch(), 2);
+        assert_eq!(*nested.lock(), Some(0));
+        assert_eq!(*closed.lock(), 1);
+
+        // The bus is usable again afterwards
+        bus.publish(ViewClosed { view_id: "y".into() });
+        assert_eq!(bus.dispatch(), 1);
+    }
+}
@@ -7,12 +7,15 @@ pub mod navigation;
 pub mod state;
 pub mod sync;
 pub mod events;
+pub mod playback;
 
 // Re-export commonly used types
 pub use navigation::{
     NavigationEngine, NavigationMode, NavigationPosition, 
     NavigationContext, NavigationSubscriber,
 };
+pub use events::{EventBus, Event, Subscription, SubscriptionId, NavigationEventBridge};
+pub use playback::{PlaybackEngine, PlaybackMode, PlaybackStep, LoopMode};
 pub use state::{AppState, AppSettings, SpaceViewId, HoveredData, ViewerContext, TimeControl, FrameTime};
 
 // Placeholder modules that will be implemented in other crates
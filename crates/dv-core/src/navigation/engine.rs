@@ -41,7 +41,8 @@ impl NavigationEngine {
         state.mode = spec.mode;
         state.total_rows = spec.total_rows;
         
-        // Reset position to beginning
+        // Reset position to beginning and clear the selection
+        state.selection_range = None;
         state.position = match &state.mode {
             NavigationMode::Temporal => NavigationPosition::Temporal(0),
             NavigationMode::Sequential => NavigationPosition::Sequential(0),
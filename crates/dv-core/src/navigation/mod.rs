@@ -37,4 +37,49 @@ pub struct NavigationContext {
     pub position: NavigationPosition,
     pub selection_range: Option<NavigationRange>,
     pub total_rows: usize,
-} 
\ No newline at end of file
+}
+
+impl NavigationContext {
+    /// The selection range as ordered row bounds clamped to the data, or
+    /// `None` if nothing (or only an empty range) is selected
+    pub fn selection_rows(&self) -> Option<NavigationRange> {
+        let (start, end) = self.selection_range.as_ref()?.row_bounds();
+        let end = end.min(self.total_rows);
+        if start >= end {
+            return None;
+        }
+        Some(NavigationRange::rows(start, end))
+    }
+
+    /// Rows views should query: the selection range if one is set,
+    /// otherwise the full dataset
+    pub fn view_range(&self) -> NavigationRange {
+        self.selection_rows()
+            .unwrap_or_else(|| NavigationRange::rows(0, self.total_rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_restricts_view_range() {
+        let engine = NavigationEngine::new(NavigationMode::Sequential);
+        engine.update_spec(NavigationSpec {
+            mode: NavigationMode::Sequential,
+            total_rows: 100,
+            temporal_bounds: None,
+            categories: None,
+        });
+        assert_eq!(engine.get_context().view_range(), NavigationRange::rows(0, 100));
+
+        // Reversed and out-of-bounds ranges are normalized
+        engine.set_range(Some(NavigationRange::rows(150, 40)));
+        assert_eq!(engine.get_context().view_range(), NavigationRange::rows(40, 100));
+
+        engine.set_range(None);
+        assert_eq!(engine.get_context().selection_rows(), None);
+        assert_eq!(engine.get_context().view_range(), NavigationRange::rows(0, 100));
+    }
+}
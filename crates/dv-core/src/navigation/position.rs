@@ -30,8 +30,25 @@ pub struct NavigationBounds<T> {
 }
 
 /// A range in the navigation space
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NavigationRange {
     pub start: NavigationPosition,
     pub end: NavigationPosition,
-} 
\ No newline at end of file
+}
+
+impl NavigationRange {
+    /// Row range `[start, end)`
+    pub fn rows(start: usize, end: usize) -> Self {
+        Self {
+            start: NavigationPosition::Sequential(start),
+            end: NavigationPosition::Sequential(end),
+        }
+    }
+
+    /// Row bounds `(start, end)` of the range, ordered so that `start <= end`
+    pub fn row_bounds(&self) -> (usize, usize) {
+        let a = self.start.frame_nr();
+        let b = self.end.frame_nr();
+        (a.min(b), a.max(b))
+    }
+}
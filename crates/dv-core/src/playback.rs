@@ -0,0 +1,369 @@
+//! Playback engine
+//!
+//! Advances the navigation position over time. The engine holds no UI state:
+//! the caller feeds it the elapsed wall-clock time each frame and applies the
+//! returned [`PlaybackStep`], so playback is deterministic and can be tested
+//! without a running application.
+
+use arrow::array::{Array, Int64Array};
+use arrow::datatypes::{DataType, TimeUnit};
+use serde::{Serialize, Deserialize};
+
+use crate::state::TimeControl;
+
+/// Default playback rate in fixed-rate mode
+pub const DEFAULT_ROWS_PER_SECOND: f64 = 0.5;
+
+/// How playback advances
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PlaybackMode {
+    /// Advance a fixed number of rows per second (× speed)
+    RowsPerSecond(f64),
+    /// Advance through the row timestamps at wall-clock rate (× speed)
+    RealTime,
+}
+
+impl Default for PlaybackMode {
+    fn default() -> Self {
+        PlaybackMode::RowsPerSecond(DEFAULT_ROWS_PER_SECOND)
+    }
+}
+
+/// What happens when playback reaches the end of its range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LoopMode {
+    /// Stop at the end
+    #[default]
+    Off,
+    /// Jump back to the start
+    Repeat,
+    /// Reverse direction at either end
+    PingPong,
+}
+
+impl LoopMode {
+    /// The next mode when cycling through them, e.g. with a toggle button
+    pub fn next(self) -> Self {
+        match self {
+            LoopMode::Off => LoopMode::Repeat,
+            LoopMode::Repeat => LoopMode::PingPong,
+            LoopMode::PingPong => LoopMode::Off,
+        }
+    }
+}
+
+/// Result of a playback tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackStep {
+    /// Stay on the current row
+    Hold,
+    /// Seek to the given row
+    Seek(usize),
+    /// Reached the end of the range: seek to the given row and stop playing
+    Stop(usize),
+}
+
+/// Direction of travel (only ping-pong looping plays backwards)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Forward,
+    Backward,
+}
+
+/// Playback state machine
+///
+/// Playback is confined to a row range `[start, end)`: the selection range if
+/// one is set, otherwise the whole dataset. Looping therefore loops within
+/// the selection.
+#[derive(Debug, Clone)]
+pub struct PlaybackEngine {
+    /// Fractional rows carried over between ticks in fixed-rate mode
+    accumulator: f64,
+    /// Playback clock (ms) in real-time mode
+    clock: Option<f64>,
+    /// Row the engine last moved to, to detect seeks made by the user
+    last_row: Option<usize>,
+    direction: Direction,
+}
+
+impl PlaybackEngine {
+    /// Create a stopped engine
+    pub fn new() -> Self {
+        Self {
+            accumulator: 0.0,
+            clock: None,
+            last_row: None,
+            direction: Direction::Forward,
+        }
+    }
+
+    /// Forget the carried-over time, e.g. when playback is paused
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Advance playback by `dt` seconds of wall-clock time.
+    ///
+    /// `row` is the current navigation row and `bounds` the row range
+    /// `[start, end)` to play within. `timestamps` holds one timestamp in
+    /// milliseconds per row, sorted ascending; real-time mode falls back to
+    /// the default fixed rate if they don't cover `bounds`.
+    pub fn tick(
+        &mut self,
+        control: &TimeControl,
+        row: usize,
+        bounds: (usize, usize),
+        timestamps: Option<&[i64]>,
+        dt: f64,
+    ) -> PlaybackStep {
+        let (start, end) = bounds;
+        if !control.playing || start >= end {
+            return PlaybackStep::Hold;
+        }
+
+        // The user seeked (or this is the first tick): restart from there
+        if self.last_row != Some(row) {
+            self.accumulator = 0.0;
+            self.clock = None;
+            self.direction = Direction::Forward;
+        }
+
+        // Outside the range (e.g. a selection was just made): jump to its start
+        if row < start || row >= end {
+            return self.finish(PlaybackStep::Seek(start));
+        }
+
+        let timestamps = timestamps.filter(|ts| ts.len() >= end);
+        let step = match (control.mode, timestamps) {
+            (PlaybackMode::RealTime, Some(timestamps)) => {
+                self.tick_real_time(control, row, bounds, timestamps, dt)
+            }
+            (PlaybackMode::RowsPerSecond(rate), _) => self.tick_fixed_rate(control, rate, row, bounds, dt),
+            (PlaybackMode::RealTime, None) => {
+                self.tick_fixed_rate(control, DEFAULT_ROWS_PER_SECOND, row, bounds, dt)
+            }
+        };
+        self.finish(step)
+    }
+
+    fn finish(&mut self, step: PlaybackStep) -> PlaybackStep {
+        match step {
+            PlaybackStep::Seek(row) => self.last_row = Some(row),
+            PlaybackStep::Stop(_) => self.reset(),
+            PlaybackStep::Hold => {}
+        }
+        step
+    }
+
+    fn tick_fixed_rate(
+        &mut self,
+        control: &TimeControl,
+        rows_per_second: f64,
+        row: usize,
+        bounds: (usize, usize),
+        dt: f64,
+    ) -> PlaybackStep {
+        self.last_row = Some(row);
+        self.accumulator += rows_per_second.max(0.0) * control.speed.max(0.0) * dt;
+
+        // Only advance when we've accumulated at least one row
+        let steps = self.accumulator as usize;
+        if steps == 0 {
+            return PlaybackStep::Hold;
+        }
+        self.accumulator -= steps as f64;
+
+        self.advance_rows(control.loop_mode, row, bounds, steps)
+    }
+
+    /// Move `steps` rows from `row` in the current direction, applying the loop mode
+    fn advance_rows(&mut self, loop_mode: LoopMode, row: usize, (start, end): (usize, usize), steps: usize) -> PlaybackStep {
+        let len = end - start;
+        let last = end - 1;
+        let offset = row - start;
+
+        match loop_mode {
+            LoopMode::Off => {
+                if offset + steps >= len {
+                    PlaybackStep::Stop(last)
+                } else {
+                    PlaybackStep::Seek(row + steps)
+                }
+            }
+            LoopMode::Repeat => PlaybackStep::Seek(start + (offset + steps) % len),
+            LoopMode::PingPong => {
+                if len == 1 {
+                    return PlaybackStep::Seek(start);
+                }
+                // Position within one forward-and-back cycle of 2 * (len - 1) steps;
+                // phases up to len - 1 move forward, the rest move backward
+                let period = 2 * (len - 1);
+                let phase = match self.direction {
+                    Direction::Forward => offset,
+                    Direction::Backward => (period - offset) % period,
+                };
+                let phase = (phase + steps) % period;
+                let (direction, offset) = if phase < len - 1 {
+                    (Direction::Forward, phase)
+                } else {
+                    (Direction::Backward, period - phase)
+                };
+                self.direction = direction;
+                PlaybackStep::Seek(start + offset)
+            }
+        }
+    }
+
+    fn tick_real_time(
+        &mut self,
+        control: &TimeControl,
+        row: usize,
+        (start, end): (usize, usize),
+        timestamps: &[i64],
+        dt: f64,
+    ) -> PlaybackStep {
+        let range = &timestamps[start..end];
+        let first = range[0] as f64;
+        let last = range[range.len() - 1] as f64;
+
+        let delta = dt * 1000.0 * control.speed.max(0.0);
+        let mut clock = self.clock.unwrap_or(timestamps[row] as f64);
+        clock += match self.direction {
+            Direction::Forward => delta,
+            Direction::Backward => -delta,
+        };
+
+        // Handle running off either end of the range
+        if clock > last {
+            match control.loop_mode {
+                LoopMode::Off => return PlaybackStep::Stop(end - 1),
+                LoopMode::Repeat => clock = first + (clock - last) % (last - first).max(1.0),
+                LoopMode::PingPong => {
+                    clock = (last - (clock - last)).max(first);
+                    self.direction = Direction::Backward;
+                }
+            }
+        } else if clock < first {
+            clock = (first + (first - clock)).min(last);
+            self.direction = Direction::Forward;
+        }
+        self.clock = Some(clock);
+
+        // Forward: the last row at or before the clock. Backward: the first row at or after it.
+        let offset = match self.direction {
+            Direction::Forward => range.partition_point(|&t| (t as f64) <= clock).saturating_sub(1),
+            Direction::Backward => range.partition_point(|&t| (t as f64) < clock).min(range.len() - 1),
+        };
+        let target = start + offset;
+
+        self.last_row = Some(target);
+        if target == row {
+            PlaybackStep::Hold
+        } else {
+            PlaybackStep::Seek(target)
+        }
+    }
+}
+
+impl Default for PlaybackEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Convert a timestamp-like column to milliseconds since the epoch.
+///
+/// Supports timestamp and date columns of any unit; other types (and nulls)
+/// return `None`.
+pub fn timestamps_ms(array: &dyn Array) -> Option<Vec<i64>> {
+    match array.data_type() {
+        DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {}
+        _ => return None,
+    }
+    if array.null_count() > 0 {
+        return None;
+    }
+
+    let millis = arrow::compute::cast(array, &DataType::Timestamp(TimeUnit::Millisecond, None)).ok()?;
+    let millis = arrow::compute::cast(&millis, &DataType::Int64).ok()?;
+    let millis = millis.as_any().downcast_ref::<Int64Array>()?;
+    Some(millis.values().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control(mode: PlaybackMode, loop_mode: LoopMode) -> TimeControl {
+        TimeControl {
+            playing: true,
+            speed: 1.0,
+            loop_mode,
+            mode,
+        }
+    }
+
+    /// Run `ticks` ticks of `dt` seconds, applying each step, and return the rows visited
+    fn run(engine: &mut PlaybackEngine, control: &mut TimeControl, mut row: usize, bounds: (usize, usize), timestamps: Option<&[i64]>, dt: f64, ticks: usize) -> Vec<usize> {
+        let mut rows = Vec::new();
+        for _ in 0..ticks {
+            match engine.tick(control, row, bounds, timestamps, dt) {
+                PlaybackStep::Hold => {}
+                PlaybackStep::Seek(r) => row = r,
+                PlaybackStep::Stop(r) => {
+                    row = r;
+                    control.playing = false;
+                }
+            }
+            rows.push(row);
+        }
+        rows
+    }
+
+    #[test]
+    fn fixed_rate_accumulates_fractional_rows() {
+        let mut engine = PlaybackEngine::new();
+        let mut control = control(PlaybackMode::RowsPerSecond(4.0), LoopMode::Off);
+        control.speed = 0.5;
+        // 2 rows/s at 0.25s per tick: one row every other tick, stopping at the end
+        let rows = run(&mut engine, &mut control, 0, (0, 3), None, 0.25, 6);
+        assert_eq!(rows, vec![0, 1, 1, 2, 2, 2]);
+        assert!(!control.playing);
+    }
+
+    #[test]
+    fn repeat_and_ping_pong_stay_in_selection() {
+        let mut engine = PlaybackEngine::new();
+        let mut control = control(PlaybackMode::RowsPerSecond(1.0), LoopMode::Repeat);
+        // Starts outside the selection, so jumps to its start first
+        let rows = run(&mut engine, &mut control, 0, (10, 13), None, 1.0, 5);
+        assert_eq!(rows, vec![10, 11, 12, 10, 11]);
+
+        let mut engine = PlaybackEngine::new();
+        control.loop_mode = LoopMode::PingPong;
+        let rows = run(&mut engine, &mut control, 10, (10, 13), None, 1.0, 6);
+        assert_eq!(rows, vec![11, 12, 11, 10, 11, 12]);
+    }
+
+    #[test]
+    fn real_time_follows_timestamps() {
+        let timestamps = [0, 100, 200, 1000, 1100];
+        let mut engine = PlaybackEngine::new();
+        let mut control = control(PlaybackMode::RealTime, LoopMode::Off);
+        control.speed = 2.0;
+        // 100ms wall-clock per tick at 2x = 200ms of data time per tick
+        let rows = run(&mut engine, &mut control, 0, (0, 5), Some(&timestamps), 0.1, 7);
+        assert_eq!(rows, vec![2, 2, 2, 2, 3, 4, 4]);
+        assert!(!control.playing);
+    }
+
+    #[test]
+    fn real_time_without_timestamps_falls_back_to_fixed_rate() {
+        let mut engine = PlaybackEngine::new();
+        let mut control = control(PlaybackMode::RealTime, LoopMode::Off);
+        let step = engine.tick(&control, 0, (0, 10), None, 1.0 / DEFAULT_ROWS_PER_SECOND);
+        assert_eq!(step, PlaybackStep::Seek(1));
+        control.playing = false;
+        assert_eq!(engine.tick(&control, 1, (0, 10), None, 10.0), PlaybackStep::Hold);
+    }
+}
@@ -46,7 +46,8 @@ pub struct HoveredData {
 pub struct TimeControl {
     pub playing: bool,
     pub speed: f64,
-    pub looping: bool,
+    pub loop_mode: crate::playback::LoopMode,
+    pub mode: crate::playback::PlaybackMode,
 }
 
 impl Default for TimeControl {
@@ -54,7 +55,8 @@ impl Default for TimeControl {
         Self {
             playing: false,
             speed: 1.0,
-            looping: false,
+            loop_mode: crate::playback::LoopMode::Off,
+            mode: crate::playback::PlaybackMode::default(),
         }
     }
 }
@@ -70,7 +72,7 @@ pub struct FrameTime {
 #[derive(Clone)]
 pub struct ViewerContext {
     /// Map of data sources by their unique ID (filename)
-    pub data_sources: Arc<RwLock<HashMap<String, Box<dyn crate::data::DataSource>>>>,
+    pub data_sources: Arc<RwLock<HashMap<String, Arc<dyn crate::data::DataSource>>>>,
     
     /// Navigation engine
     pub navigation: Arc<crate::navigation::NavigationEngine>,
@@ -89,4 +91,21 @@ pub struct ViewerContext {
     
     /// Views that share time axis
     pub time_axis_views: Arc<RwLock<Vec<SpaceViewId>>>,
-} 
\ No newline at end of file
+    
+    /// Application event bus
+    pub events: crate::events::EventBus,
+}
+
+impl ViewerContext {
+    /// Query the rows a view should display from `source`: the selected
+    /// rows when a selection range is set, otherwise the whole dataset, as
+    /// [`NavigationContext::view_range`](crate::navigation::NavigationContext::view_range)
+    /// gives them.
+    pub fn query_view_data(
+        &self,
+        source: &dyn crate::data::DataSource,
+    ) -> anyhow::Result<arrow::record_batch::RecordBatch> {
+        let range = self.navigation.get_context().view_range();
+        self.runtime_handle.block_on(source.query_range(&range))
+    }
+}
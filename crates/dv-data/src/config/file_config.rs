@@ -54,6 +54,96 @@ impl From<DataType> for SerializableDataType {
     }
 }
 
+/// User-defined column computed from other columns, e.g. `power = voltage * current`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComputedColumn {
+    /// Name of the new column
+    pub name: String,
+    
+    /// Expression in the `crate::expr` language
+    pub expression: String,
+}
+
+/// Reshaping applied after loading (after computed columns and resampling)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReshapeSpec {
+    /// Wide to long: each row becomes one row per value column, holding the
+    /// column name in `key_name` and its value in `value_name`
+    Melt {
+        value_columns: Vec<String>,
+        key_name: String,
+        value_name: String,
+    },
+    
+    /// Long to wide: one row per distinct combination of `index_columns`,
+    /// with a column per distinct value of `key_column`
+    Pivot {
+        index_columns: Vec<String>,
+        key_column: String,
+        value_column: String,
+    },
+}
+
+/// How values falling in one resampling interval are combined
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Aggregation {
+    #[default]
+    Mean,
+    Last,
+    Max,
+    Sum,
+}
+
+/// How intervals without values are filled
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum GapFill {
+    /// Leave them null
+    #[default]
+    None,
+    /// Repeat the previous value
+    ForwardFill,
+    /// Interpolate between the neighbouring values
+    Linear,
+}
+
+/// Resampling of a temporal source onto a fixed time grid
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResampleSpec {
+    /// Time column; the first timestamp column when `None`
+    pub time_column: Option<String>,
+    
+    /// Grid spacing in milliseconds
+    pub interval_ms: i64,
+    
+    /// Aggregation for columns without an override
+    pub aggregation: Aggregation,
+    
+    /// Per-column aggregation overrides
+    #[serde(default)]
+    pub column_aggregations: HashMap<String, Aggregation>,
+    
+    /// Gap filling for empty intervals
+    pub gap_fill: GapFill,
+}
+
+impl ResampleSpec {
+    /// Resample to `interval_ms` with mean aggregation and no gap filling
+    pub fn new(interval_ms: i64) -> Self {
+        Self {
+            time_column: None,
+            interval_ms,
+            aggregation: Aggregation::default(),
+            column_aggregations: HashMap::new(),
+            gap_fill: GapFill::default(),
+        }
+    }
+    
+    /// Aggregation used for `column`
+    pub fn aggregation_for(&self, column: &str) -> Aggregation {
+        self.column_aggregations.get(column).copied().unwrap_or(self.aggregation)
+    }
+}
+
 /// Configuration for a single file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileConfig {
@@ -86,6 +176,23 @@ pub struct FileConfig {
     
     /// Detected column names
     pub detected_columns: Vec<String>,
+    
+    /// Computed columns appended after the selected columns
+    #[serde(default)]
+    pub computed_columns: Vec<ComputedColumn>,
+    
+    /// Optional resampling onto a fixed time grid (after computed columns)
+    #[serde(default)]
+    pub resample: Option<ResampleSpec>,
+    
+    /// Optional wide/long reshaping
+    #[serde(default)]
+    pub reshape: Option<ReshapeSpec>,
+    
+    /// Fail loading on values that don't parse as their column type,
+    /// instead of loading them as null
+    #[serde(default)]
+    pub strict_parsing: bool,
 }
 
 /// File type
@@ -108,6 +215,10 @@ impl Default for FileConfig {
             is_loaded: false,
             preview_lines: None,
             detected_columns: Vec::new(),
+            computed_columns: Vec::new(),
+            resample: None,
+            reshape: None,
+            strict_parsing: false,
         }
     }
 }
@@ -126,6 +237,10 @@ impl FileConfig {
             is_loaded: false,
             preview_lines: None,
             detected_columns: Vec::new(),
+            computed_columns: Vec::new(),
+            resample: None,
+            reshape: None,
+            strict_parsing: false,
         }
     }
     
@@ -143,6 +258,10 @@ impl FileConfig {
         self.header_line != other.header_line
             || self.selected_columns != other.selected_columns
             || self.column_types != other.column_types
+            || self.computed_columns != other.computed_columns
+            || self.resample != other.resample
+            || self.reshape != other.reshape
+            || self.strict_parsing != other.strict_parsing
     }
     
     /// Get column type with override
@@ -158,6 +277,22 @@ impl FileConfig {
     pub fn is_null_value(&self, value: &str) -> bool {
         self.null_config.patterns.iter().any(|pattern| value == pattern)
     }
+    
+    /// Read the header row of a CSV file and select every column in it
+    pub fn select_all_columns(&mut self) -> Result<(), crate::DataError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_path(&self.path)?;
+        let mut record = csv::StringRecord::new();
+        for _ in 0..=self.header_line {
+            if !reader.read_record(&mut record)? {
+                return Err(crate::DataError::Other(format!("{} has no header row", self.file_name())));
+            }
+        }
+        self.detected_columns = record.iter().map(|s| s.to_string()).collect();
+        self.selected_columns = self.detected_columns.iter().cloned().collect();
+        Ok(())
+    }
 }
 
 /// File configuration manager
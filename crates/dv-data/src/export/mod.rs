@@ -0,0 +1,135 @@
+//! Writing batches to files
+//!
+//! The output format follows the file extension: CSV, Parquet, Arrow IPC or a
+//! SQLite database holding a single table.
+
+use std::fs::File;
+use std::path::Path;
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+use crate::DataError;
+use crate::sql::SqlEngine;
+
+/// File format to write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+    ArrowIpc,
+    Sqlite,
+}
+
+impl ExportFormat {
+    /// Every format, for file dialogs
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Csv,
+        ExportFormat::Parquet,
+        ExportFormat::ArrowIpc,
+        ExportFormat::Sqlite,
+    ];
+
+    /// Format implied by the extension of `path`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        Some(match extension.as_str() {
+            "csv" => ExportFormat::Csv,
+            "parquet" => ExportFormat::Parquet,
+            "arrow" | "ipc" | "feather" => ExportFormat::ArrowIpc,
+            "db" | "sqlite" | "sqlite3" => ExportFormat::Sqlite,
+            _ => return None,
+        })
+    }
+
+    /// Default file extension
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::ArrowIpc => "arrow",
+            ExportFormat::Sqlite => "db",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Parquet => "Parquet",
+            ExportFormat::ArrowIpc => "Arrow IPC",
+            ExportFormat::Sqlite => "SQLite",
+        }
+    }
+}
+
+/// Write `batch` to `path` in the format implied by its extension. SQLite
+/// output gets one table named `table`.
+pub fn write_batch(batch: &RecordBatch, path: &Path, table: &str) -> Result<(), DataError> {
+    let format = ExportFormat::from_path(path).ok_or_else(|| DataError::Other(format!(
+        "Unknown output format for {}; use .csv, .parquet, .arrow or .db",
+        path.display()
+    )))?;
+    write_batch_as(batch, path, format, table)
+}
+
+/// Write `batch` to `path` as `format`
+pub fn write_batch_as(batch: &RecordBatch, path: &Path, format: ExportFormat, table: &str) -> Result<(), DataError> {
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = arrow::csv::WriterBuilder::new()
+                .with_header(true)
+                .build(File::create(path)?);
+            writer.write(batch)?;
+        }
+        ExportFormat::Parquet => {
+            let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)
+                .map_err(|e| DataError::Other(e.to_string()))?;
+            writer.write(batch).map_err(|e| DataError::Other(e.to_string()))?;
+            writer.close().map_err(|e| DataError::Other(e.to_string()))?;
+        }
+        ExportFormat::ArrowIpc => {
+            let mut writer = FileWriter::try_new(File::create(path)?, &batch.schema())?;
+            writer.write(batch)?;
+            writer.finish()?;
+        }
+        ExportFormat::Sqlite => {
+            let mut engine = SqlEngine::new()?;
+            engine.register_batch(table, batch)?;
+            if path.exists() {
+                std::fs::remove_file(path)?;
+            }
+            engine.save(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use arrow::array::{ArrayRef, Float64Array, StringArray};
+
+    #[test]
+    fn writes_every_format() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("name", Arc::new(StringArray::from(vec![Some("a"), None])) as ArrayRef),
+            ("value", Arc::new(Float64Array::from(vec![1.5, 2.5])) as ArrayRef),
+        ]).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("dv_export_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for format in ExportFormat::ALL {
+            let path = dir.join(format!("out.{}", format.extension()));
+            assert_eq!(ExportFormat::from_path(&path), Some(format));
+            write_batch(&batch, &path, "out").unwrap();
+            assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        }
+
+        let csv = std::fs::read_to_string(dir.join("out.csv")).unwrap();
+        assert_eq!(csv, "name,value\na,1.5\n,2.5\n");
+        let conn = rusqlite::Connection::open(dir.join("out.db")).unwrap();
+        let sum: f64 = conn.query_row("SELECT SUM(value) FROM out", [], |row| row.get(0)).unwrap();
+        assert_eq!(sum, 4.0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,293 @@
+//! Expression evaluation with arrow compute kernels
+
+use std::sync::Arc;
+use arrow::array::{new_null_array, Array, ArrayRef, BooleanArray, Float64Array, Scalar};
+use arrow::compute::kernels::{cmp, numeric, zip::zip};
+use arrow::compute::kernels::temporal::{date_part, DatePart};
+use arrow::compute::{cast, concat, unary};
+use arrow::datatypes::{DataType, Float64Type, TimeUnit};
+use arrow::record_batch::RecordBatch;
+
+use super::{BinaryOp, Expr, Function};
+use crate::DataError;
+
+/// Intermediate result: a column, or a constant not yet broadcast to one
+enum Value {
+    Array(ArrayRef),
+    Scalar(f64),
+}
+
+fn error(message: impl Into<String>) -> DataError {
+    DataError::Expression(message.into())
+}
+
+/// Evaluate `expr` against `batch`, producing one value per row
+pub fn evaluate(expr: &Expr, batch: &RecordBatch) -> Result<ArrayRef, DataError> {
+    Ok(match eval(expr, batch)? {
+        Value::Array(array) => array,
+        Value::Scalar(value) => Arc::new(Float64Array::from(vec![value; batch.num_rows()])),
+    })
+}
+
+fn eval(expr: &Expr, batch: &RecordBatch) -> Result<Value, DataError> {
+    match expr {
+        Expr::Number(value) => Ok(Value::Scalar(*value)),
+        Expr::Column(name) => batch.column_by_name(name)
+            .cloned()
+            .map(Value::Array)
+            .ok_or_else(|| error(format!("Unknown column '{}'", name))),
+        Expr::Neg(inner) => match eval(inner, batch)? {
+            Value::Scalar(value) => Ok(Value::Scalar(-value)),
+            Value::Array(array) => Ok(Value::Array(numeric::neg(&to_f64(&array)?)?)),
+        },
+        Expr::Binary { op, left, right } => binary(*op, eval(left, batch)?, eval(right, batch)?),
+        Expr::Call { function, args } => call(*function, args, batch),
+    }
+}
+
+fn to_f64(array: &ArrayRef) -> Result<ArrayRef, DataError> {
+    if array.data_type() == &DataType::Float64 {
+        return Ok(array.clone());
+    }
+    cast(array, &DataType::Float64)
+        .map_err(|_| error(format!("Cannot use {:?} values in arithmetic", array.data_type())))
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, DataError> {
+    // Fold constants without going through arrays
+    if let (Value::Scalar(l), Value::Scalar(r)) = (&left, &right) {
+        let (l, r) = (*l, *r);
+        let truth = |b: bool| if b { 1.0 } else { 0.0 };
+        return Ok(Value::Scalar(match op {
+            BinaryOp::Add => l + r,
+            BinaryOp::Sub => l - r,
+            BinaryOp::Mul => l * r,
+            BinaryOp::Div => l / r,
+            BinaryOp::Rem => l % r,
+            BinaryOp::Eq => truth(l == r),
+            BinaryOp::NotEq => truth(l != r),
+            BinaryOp::Lt => truth(l < r),
+            BinaryOp::LtEq => truth(l <= r),
+            BinaryOp::Gt => truth(l > r),
+            BinaryOp::GtEq => truth(l >= r),
+        }));
+    }
+
+    let left = Operand::new(left)?;
+    let right = Operand::new(right)?;
+    let (l, r) = (left.datum(), right.datum());
+
+    let result: ArrayRef = match op {
+        BinaryOp::Add => numeric::add(l, r)?,
+        BinaryOp::Sub => numeric::sub(l, r)?,
+        BinaryOp::Mul => numeric::mul(l, r)?,
+        BinaryOp::Div => numeric::div(l, r)?,
+        BinaryOp::Rem => numeric::rem(l, r)?,
+        BinaryOp::Eq => Arc::new(cmp::eq(l, r)?),
+        BinaryOp::NotEq => Arc::new(cmp::neq(l, r)?),
+        BinaryOp::Lt => Arc::new(cmp::lt(l, r)?),
+        BinaryOp::LtEq => Arc::new(cmp::lt_eq(l, r)?),
+        BinaryOp::Gt => Arc::new(cmp::gt(l, r)?),
+        BinaryOp::GtEq => Arc::new(cmp::gt_eq(l, r)?),
+    };
+    Ok(Value::Array(result))
+}
+
+/// A value prepared as a Float64 kernel argument
+enum Operand {
+    Array(ArrayRef),
+    Scalar(Scalar<Float64Array>),
+}
+
+impl Operand {
+    fn new(value: Value) -> Result<Self, DataError> {
+        Ok(match value {
+            Value::Array(array) => Operand::Array(to_f64(&array)?),
+            Value::Scalar(value) => Operand::Scalar(Float64Array::new_scalar(value)),
+        })
+    }
+
+    fn datum(&self) -> &dyn arrow::array::Datum {
+        match self {
+            Operand::Array(array) => array,
+            Operand::Scalar(scalar) => scalar,
+        }
+    }
+}
+
+fn call(function: Function, args: &[Expr], batch: &RecordBatch) -> Result<Value, DataError> {
+    let arg = eval(&args[0], batch)?;
+    let count = || args.get(1).and_then(Expr::as_count).unwrap_or(1);
+
+    match function {
+        Function::Abs => map_f64(arg, f64::abs),
+        Function::Sqrt => map_f64(arg, f64::sqrt),
+        Function::Ln => map_f64(arg, f64::ln),
+        Function::Exp => map_f64(arg, f64::exp),
+        Function::If => {
+            let mask = match arg {
+                Value::Array(array) => cast(&array, &DataType::Boolean)?,
+                Value::Scalar(value) => Arc::new(BooleanArray::from(vec![value != 0.0; batch.num_rows()])),
+            };
+            let mask = mask.as_any().downcast_ref::<BooleanArray>()
+                .ok_or_else(|| error("if() needs a true/false condition"))?;
+            let then = Operand::new(eval(&args[1], batch)?)?;
+            let otherwise = Operand::new(eval(&args[2], batch)?)?;
+            Ok(Value::Array(zip(mask, then.datum(), otherwise.datum())?))
+        }
+        Function::Lag => match arg {
+            Value::Scalar(value) => Ok(Value::Scalar(value)),
+            Value::Array(array) => Ok(Value::Array(lag(&array, count())?)),
+        },
+        Function::RollingMean | Function::RollingSum => match arg {
+            Value::Scalar(value) if function == Function::RollingSum => Ok(Value::Scalar(value * count() as f64)),
+            Value::Scalar(value) => Ok(Value::Scalar(value)),
+            Value::Array(array) => Ok(Value::Array(rolling(&array, count(), function == Function::RollingMean)?)),
+        },
+        Function::Year => temporal(arg, DatePart::Year),
+        Function::Month => temporal(arg, DatePart::Month),
+        Function::Day => temporal(arg, DatePart::Day),
+        Function::Weekday => temporal(arg, DatePart::DayOfWeekMonday0),
+        Function::Hour => temporal(arg, DatePart::Hour),
+        Function::Minute => temporal(arg, DatePart::Minute),
+        Function::Second => temporal(arg, DatePart::Second),
+    }
+}
+
+fn map_f64(value: Value, f: fn(f64) -> f64) -> Result<Value, DataError> {
+    Ok(match value {
+        Value::Scalar(value) => Value::Scalar(f(value)),
+        Value::Array(array) => {
+            let array = to_f64(&array)?;
+            let array = array.as_any().downcast_ref::<Float64Array>()
+                .ok_or_else(|| error("Expected numeric values"))?;
+            Value::Array(Arc::new(unary::<Float64Type, _, Float64Type>(array, f)))
+        }
+    })
+}
+
+/// Shift values down by `n` rows, filling the start with nulls
+fn lag(array: &ArrayRef, n: usize) -> Result<ArrayRef, DataError> {
+    let len = array.len();
+    if n >= len {
+        return Ok(new_null_array(array.data_type(), len));
+    }
+    let padding = new_null_array(array.data_type(), n);
+    Ok(concat(&[padding.as_ref(), array.slice(0, len - n).as_ref()])?)
+}
+
+/// Sum or mean over the current row and up to `window - 1` previous rows,
+/// skipping nulls
+fn rolling(array: &ArrayRef, window: usize, mean: bool) -> Result<ArrayRef, DataError> {
+    let array = to_f64(array)?;
+    let array = array.as_any().downcast_ref::<Float64Array>()
+        .ok_or_else(|| error("Expected numeric values"))?;
+
+    let mut sum = 0.0;
+    let mut count = 0usize;
+    let mut result = Vec::with_capacity(array.len());
+    for i in 0..array.len() {
+        if array.is_valid(i) {
+            sum += array.value(i);
+            count += 1;
+        }
+        if i >= window && array.is_valid(i - window) {
+            sum -= array.value(i - window);
+            count -= 1;
+        }
+        result.push(match count {
+            0 => None,
+            _ if mean => Some(sum / count as f64),
+            _ => Some(sum),
+        });
+    }
+    Ok(Arc::new(Float64Array::from(result)))
+}
+
+/// Extract a calendar or clock field from dates and timestamps
+fn temporal(value: Value, part: DatePart) -> Result<Value, DataError> {
+    let Value::Array(array) = value else {
+        return Err(error("Date functions need a date or timestamp column"));
+    };
+    let array = match array.data_type() {
+        DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => array,
+        // Timestamps that were loaded as text
+        DataType::Utf8 | DataType::LargeUtf8 => cast(&array, &DataType::Timestamp(TimeUnit::Millisecond, None))?,
+        other => return Err(error(format!("Date functions need a date or timestamp column, not {:?}", other))),
+    };
+    Ok(Value::Array(date_part(array.as_ref(), part)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+    use crate::expr::parse;
+
+    fn batch() -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            ("v", Arc::new(Int64Array::from(vec![Some(1), None, Some(3), Some(5)])) as ArrayRef),
+            ("name", Arc::new(StringArray::from(vec!["a", "b", "c", "d"])) as ArrayRef),
+            ("time", Arc::new(StringArray::from(vec![
+                "2024-03-01T06:15:00", "2024-03-02T18:30:00", "2024-12-31T23:59:59", "2025-01-01T00:00:00",
+            ])) as ArrayRef),
+        ]).unwrap()
+    }
+
+    fn run(expression: &str) -> Result<ArrayRef, DataError> {
+        evaluate(&parse(expression).unwrap(), &batch())
+    }
+
+    fn values(array: &ArrayRef) -> Vec<Option<f64>> {
+        let array = cast(array, &DataType::Float64).unwrap();
+        array.as_any().downcast_ref::<Float64Array>().unwrap().iter().collect()
+    }
+
+    #[test]
+    fn nulls_propagate_through_arithmetic() {
+        assert_eq!(values(&run("v * 2 + 1").unwrap()), vec![Some(3.0), None, Some(7.0), Some(11.0)]);
+        assert_eq!(values(&run("-v").unwrap()), vec![Some(-1.0), None, Some(-3.0), Some(-5.0)]);
+        assert_eq!(values(&run("abs(0 - v)").unwrap()), vec![Some(1.0), None, Some(3.0), Some(5.0)]);
+        // Text that isn't a number counts as null
+        assert_eq!(values(&run("sqrt(name)").unwrap()), vec![None; 4]);
+    }
+
+    #[test]
+    fn comparisons_are_true_or_false() {
+        let result = run("v >= 3").unwrap();
+        assert_eq!(result.data_type(), &DataType::Boolean);
+        assert_eq!(values(&result), vec![Some(0.0), None, Some(1.0), Some(1.0)]);
+        assert_eq!(values(&run("if(1 < 2, v, 0)").unwrap()), vec![Some(1.0), None, Some(3.0), Some(5.0)]);
+    }
+
+    #[test]
+    fn lags_past_the_batch_are_null() {
+        assert_eq!(values(&run("lag(v, 2)").unwrap()), vec![None, None, Some(1.0), None]);
+        assert_eq!(values(&run("lag(v, 4)").unwrap()), vec![None; 4]);
+        assert_eq!(values(&run("lag(v, 1e300)").unwrap()), vec![None; 4]);
+        // A constant has the same value in every row
+        assert_eq!(values(&run("lag(7, 3)").unwrap()), vec![Some(7.0); 4]);
+    }
+
+    #[test]
+    fn rolling_windows_skip_nulls() {
+        assert_eq!(values(&run("rolling_sum(v, 2)").unwrap()), vec![Some(1.0), Some(1.0), Some(3.0), Some(8.0)]);
+        assert_eq!(values(&run("rolling_mean(v, 3)").unwrap()), vec![Some(1.0), Some(1.0), Some(2.0), Some(4.0)]);
+        assert_eq!(values(&run("rolling_sum(2, 3)").unwrap()), vec![Some(6.0); 4]);
+    }
+
+    #[test]
+    fn date_parts_of_text_timestamps() {
+        assert_eq!(values(&run("year(time)").unwrap()), vec![Some(2024.0), Some(2024.0), Some(2024.0), Some(2025.0)]);
+        assert_eq!(values(&run("hour(time)").unwrap()), vec![Some(6.0), Some(18.0), Some(23.0), Some(0.0)]);
+        assert_eq!(values(&run("weekday(time)").unwrap()), vec![Some(4.0), Some(5.0), Some(1.0), Some(2.0)]);
+        assert!(run("month(v)").is_err());
+        assert!(run("day(3)").is_err());
+    }
+
+    #[test]
+    fn unknown_columns_are_errors() {
+        assert!(run("missing + 1").is_err());
+        assert!(run("if(missing, 1, 0)").is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Expression language for computed columns
+//!
+//! Expressions combine columns, numbers and functions, e.g.
+//! `voltage * current`, `value - lag(value)`, `rolling_mean(temp, 20)` or
+//! `hour(timestamp)`. Column names that are not plain identifiers can be
+//! written in backticks: `` `Temp (C)` * 1.8 + 32 ``.
+
+pub mod parser;
+pub mod eval;
+
+pub use parser::parse;
+pub use eval::evaluate;
+
+/// Binary operators, loosest binding first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// Built-in functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    Abs,
+    Sqrt,
+    Ln,
+    Exp,
+    /// `if(condition, then, else)`
+    If,
+    /// `lag(x)` or `lag(x, n)`: the value `n` rows earlier
+    Lag,
+    /// `rolling_mean(x, window)` over the current and previous rows
+    RollingMean,
+    /// `rolling_sum(x, window)` over the current and previous rows
+    RollingSum,
+    Year,
+    Month,
+    Day,
+    Weekday,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Function {
+    /// Look up a function by its name in expressions
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "abs" => Function::Abs,
+            "sqrt" => Function::Sqrt,
+            "ln" => Function::Ln,
+            "exp" => Function::Exp,
+            "if" => Function::If,
+            "lag" => Function::Lag,
+            "rolling_mean" => Function::RollingMean,
+            "rolling_sum" => Function::RollingSum,
+            "year" => Function::Year,
+            "month" => Function::Month,
+            "day" => Function::Day,
+            "weekday" => Function::Weekday,
+            "hour" => Function::Hour,
+            "minute" => Function::Minute,
+            "second" => Function::Second,
+            _ => return None,
+        })
+    }
+
+    /// Accepted number of arguments
+    fn arity(&self) -> std::ops::RangeInclusive<usize> {
+        match self {
+            Function::If => 3..=3,
+            Function::Lag => 1..=2,
+            Function::RollingMean | Function::RollingSum => 2..=2,
+            _ => 1..=1,
+        }
+    }
+}
+
+/// Parsed expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Column(String),
+    Neg(Box<Expr>),
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Call {
+        function: Function,
+        args: Vec<Expr>,
+    },
+}
+
+impl Expr {
+    /// Columns referenced by this expression
+    pub fn columns(&self) -> Vec<&str> {
+        let mut columns = Vec::new();
+        self.visit_columns(&mut columns);
+        columns
+    }
+
+    fn visit_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Column(name) => {
+                if !columns.contains(&name.as_str()) {
+                    columns.push(name);
+                }
+            }
+            Expr::Neg(inner) => inner.visit_columns(columns),
+            Expr::Binary { left, right, .. } => {
+                left.visit_columns(columns);
+                right.visit_columns(columns);
+            }
+            Expr::Call { args, .. } => args.iter().for_each(|arg| arg.visit_columns(columns)),
+        }
+    }
+
+    /// Number of preceding rows needed to evaluate the first row of a range
+    /// exactly (for `lag` and rolling windows)
+    pub fn lookback(&self) -> usize {
+        match self {
+            Expr::Number(_) | Expr::Column(_) => 0,
+            Expr::Neg(inner) => inner.lookback(),
+            Expr::Binary { left, right, .. } => left.lookback().max(right.lookback()),
+            Expr::Call { function, args } => {
+                let inner = args.iter().map(Expr::lookback).max().unwrap_or(0);
+                let own = match function {
+                    Function::Lag => args.get(1).and_then(Expr::as_count).unwrap_or(1),
+                    Function::RollingMean | Function::RollingSum => {
+                        args.get(1).and_then(Expr::as_count).unwrap_or(1).saturating_sub(1)
+                    }
+                    _ => 0,
+                };
+                // Offsets are unbounded literals, e.g. lag(x, 1e300)
+                inner.saturating_add(own)
+            }
+        }
+    }
+
+    /// Value of a non-negative integer literal (lag offsets, window sizes)
+    fn as_count(&self) -> Option<usize> {
+        match self {
+            Expr::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as usize),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use arrow::array::{Array, ArrayRef, Float64Array, Int64Array};
+    use arrow::record_batch::RecordBatch;
+
+    fn values(array: &ArrayRef) -> Vec<Option<f64>> {
+        array.as_any().downcast_ref::<Float64Array>().unwrap().iter().collect()
+    }
+
+    #[test]
+    fn parses_with_precedence_and_lookback() {
+        let expr = parse("a - lag(a, 2) * 3 > 1").unwrap();
+        let Expr::Binary { op: BinaryOp::Gt, left, .. } = &expr else { panic!("{:?}", expr) };
+        assert!(matches!(**left, Expr::Binary { op: BinaryOp::Sub, .. }));
+        assert_eq!(expr.columns(), vec!["a"]);
+        assert_eq!(expr.lookback(), 2);
+        assert_eq!(parse("rolling_mean(lag(`x y`), 4)").unwrap().lookback(), 4);
+
+        assert!(parse("a +").is_err());
+        assert!(parse("nope(a)").is_err());
+        assert!(parse("lag(a, 1.5)").is_err());
+    }
+
+    #[test]
+    fn huge_offsets_saturate_the_lookback() {
+        let expr = parse("lag(lag(a, 1e300), 5)").unwrap();
+        assert_eq!(expr.lookback(), usize::MAX);
+    }
+
+    #[test]
+    fn evaluates_arithmetic_lag_and_rolling_windows() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("v", Arc::new(Int64Array::from(vec![1, 2, 4, 8])) as ArrayRef),
+            ("i", Arc::new(Float64Array::from(vec![0.5, 0.5, 0.5, 0.5])) as ArrayRef),
+        ]).unwrap();
+
+        let power = evaluate(&parse("v * i").unwrap(), &batch).unwrap();
+        assert_eq!(values(&power), vec![Some(0.5), Some(1.0), Some(2.0), Some(4.0)]);
+
+        let delta = evaluate(&parse("v - lag(v)").unwrap(), &batch).unwrap();
+        assert_eq!(values(&delta), vec![None, Some(1.0), Some(2.0), Some(4.0)]);
+
+        let mean = evaluate(&parse("rolling_mean(v, 2)").unwrap(), &batch).unwrap();
+        assert_eq!(values(&mean), vec![Some(1.0), Some(1.5), Some(3.0), Some(6.0)]);
+
+        let picked = evaluate(&parse("if(v > 2, v, -1)").unwrap(), &batch).unwrap();
+        assert_eq!(values(&picked), vec![Some(-1.0), Some(-1.0), Some(4.0), Some(8.0)]);
+
+        let constant = evaluate(&parse("2 * 3").unwrap(), &batch).unwrap();
+        assert_eq!(values(&constant), vec![Some(6.0); 4]);
+    }
+}
@@ -0,0 +1,309 @@
+//! Tokenizer and recursive-descent parser for expressions
+
+use super::{BinaryOp, Expr, Function};
+use crate::DataError;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    /// Backtick-quoted column name; never treated as a function
+    Quoted(String),
+    Op(BinaryOp),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn error(message: impl Into<String>) -> DataError {
+    DataError::Expression(message.into())
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, DataError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '0'..='9' | '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // Exponent, e.g. 1e-3
+                if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                    let mut j = i + 1;
+                    if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                        j += 1;
+                    }
+                    if j < chars.len() && chars[j].is_ascii_digit() {
+                        i = j;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                let value = text.parse().map_err(|_| error(format!("Invalid number '{}'", text)))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            '`' => {
+                let end = chars[i + 1..].iter().position(|&c| c == '`')
+                    .ok_or_else(|| error("Unterminated `quoted` column name"))?;
+                tokens.push(Token::Quoted(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            '(' => { tokens.push(Token::LParen); i += 1; }
+            ')' => { tokens.push(Token::RParen); i += 1; }
+            ',' => { tokens.push(Token::Comma); i += 1; }
+            '+' => { tokens.push(Token::Op(BinaryOp::Add)); i += 1; }
+            '-' => { tokens.push(Token::Op(BinaryOp::Sub)); i += 1; }
+            '*' => { tokens.push(Token::Op(BinaryOp::Mul)); i += 1; }
+            '/' => { tokens.push(Token::Op(BinaryOp::Div)); i += 1; }
+            '%' => { tokens.push(Token::Op(BinaryOp::Rem)); i += 1; }
+            '<' | '>' | '=' | '!' => {
+                let op = match (c, next) {
+                    ('<', Some('=')) => BinaryOp::LtEq,
+                    ('>', Some('=')) => BinaryOp::GtEq,
+                    ('=', Some('=')) => BinaryOp::Eq,
+                    ('!', Some('=')) => BinaryOp::NotEq,
+                    ('<', _) => BinaryOp::Lt,
+                    ('>', _) => BinaryOp::Gt,
+                    _ => return Err(error(format!("Unexpected '{}'", c))),
+                };
+                i += if next == Some('=') { 2 } else { 1 };
+                tokens.push(Token::Op(op));
+            }
+            _ => return Err(error(format!("Unexpected '{}'", c))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse an expression
+pub fn parse(input: &str) -> Result<Expr, DataError> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err(error("Expression is empty"));
+    }
+
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.comparison()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(error(format!("Unexpected {:?} after expression", token))),
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Consume the next token if it is one of `ops`
+    fn operator(&mut self, ops: &[BinaryOp]) -> Option<BinaryOp> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn binary_level(
+        &mut self,
+        ops: &[BinaryOp],
+        operand: fn(&mut Self) -> Result<Expr, DataError>,
+    ) -> Result<Expr, DataError> {
+        let mut left = operand(self)?;
+        while let Some(op) = self.operator(ops) {
+            let right = operand(self)?;
+            left = Expr::Binary { op, left: Box::new(left), right: Box::new(right) };
+        }
+        Ok(left)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, DataError> {
+        use BinaryOp::*;
+        self.binary_level(&[Eq, NotEq, Lt, LtEq, Gt, GtEq], Self::additive)
+    }
+
+    fn additive(&mut self) -> Result<Expr, DataError> {
+        self.binary_level(&[BinaryOp::Add, BinaryOp::Sub], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, DataError> {
+        self.binary_level(&[BinaryOp::Mul, BinaryOp::Div, BinaryOp::Rem], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, DataError> {
+        if self.operator(&[BinaryOp::Sub]).is_some() {
+            return Ok(match self.unary()? {
+                Expr::Number(n) => Expr::Number(-n),
+                inner => Expr::Neg(Box::new(inner)),
+            });
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, DataError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Quoted(name)) => Ok(Expr::Column(name)),
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    self.call(&name)
+                } else {
+                    Ok(Expr::Column(name))
+                }
+            }
+            Some(Token::LParen) => {
+                let expr = self.comparison()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            Some(token) => Err(error(format!("Unexpected {:?}", token))),
+            None => Err(error("Unexpected end of expression")),
+        }
+    }
+
+    fn call(&mut self, name: &str) -> Result<Expr, DataError> {
+        let function = Function::from_name(name)
+            .ok_or_else(|| error(format!("Unknown function '{}'", name)))?;
+
+        let mut args = Vec::new();
+        if self.peek() != Some(&Token::RParen) {
+            loop {
+                args.push(self.comparison()?);
+                if self.peek() == Some(&Token::Comma) {
+                    self.pos += 1;
+                } else {
+                    break;
+                }
+            }
+        }
+        self.expect(Token::RParen)?;
+
+        if !function.arity().contains(&args.len()) {
+            return Err(error(format!("Wrong number of arguments to '{}'", name)));
+        }
+        // Offsets and window sizes decide how many rows to look back, so they must be constants
+        if matches!(function, Function::Lag | Function::RollingMean | Function::RollingSum) {
+            if let Some(count) = args.get(1) {
+                match count.as_count() {
+                    Some(n) if n > 0 => {}
+                    _ => return Err(error(format!("The second argument to '{}' must be a positive whole number", name))),
+                }
+            }
+        }
+
+        Ok(Expr::Call { function, args })
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), DataError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(error(format!("Expected {:?}, found {:?}", expected, token))),
+            None => Err(error(format!("Expected {:?} before end of expression", expected))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> Expr {
+        Expr::Column(name.to_string())
+    }
+
+    #[test]
+    fn tokenizes_numbers_names_and_operators() {
+        let tokens = tokenize("1.5e-3 >= `a b`*x_1 != 2E2").unwrap();
+        assert_eq!(tokens, vec![
+            Token::Number(0.0015),
+            Token::Op(BinaryOp::GtEq),
+            Token::Quoted("a b".to_string()),
+            Token::Op(BinaryOp::Mul),
+            Token::Ident("x_1".to_string()),
+            Token::Op(BinaryOp::NotEq),
+            Token::Number(200.0),
+        ]);
+
+        // An `e` not followed by digits starts a name
+        assert_eq!(tokenize("2e").unwrap(), vec![Token::Number(2.0), Token::Ident("e".to_string())]);
+
+        assert!(tokenize("`open").is_err());
+        assert!(tokenize("a = b").is_err());
+        assert!(tokenize("1.2.3").is_err());
+        assert!(tokenize("a & b").is_err());
+    }
+
+    #[test]
+    fn folds_negative_numbers_and_groups_left_to_right() {
+        assert_eq!(parse("-2").unwrap(), Expr::Number(-2.0));
+        assert_eq!(parse("--a").unwrap(), Expr::Neg(Box::new(Expr::Neg(Box::new(column("a"))))));
+
+        // a - b - c is (a - b) - c; parentheses override
+        let Expr::Binary { op: BinaryOp::Sub, left, right } = parse("a - b - c").unwrap() else { panic!() };
+        assert!(matches!(*left, Expr::Binary { op: BinaryOp::Sub, .. }));
+        assert_eq!(*right, column("c"));
+        let Expr::Binary { op: BinaryOp::Mul, right, .. } = parse("a * (b + c)").unwrap() else { panic!() };
+        assert!(matches!(*right, Expr::Binary { op: BinaryOp::Add, .. }));
+    }
+
+    #[test]
+    fn quoted_names_are_never_functions() {
+        let Expr::Binary { left, .. } = parse("`abs` * 2").unwrap() else { panic!() };
+        assert_eq!(*left, column("abs"));
+        assert!(parse("`abs`(x)").is_err());
+    }
+
+    #[test]
+    fn checks_calls() {
+        assert_eq!(parse("ABS(x)").unwrap(), Expr::Call { function: Function::Abs, args: vec![column("x")] });
+        assert!(parse("abs()").is_err());
+        assert!(parse("if(a, b)").is_err());
+        assert!(parse("rolling_sum(a)").is_err());
+        assert!(parse("lag(a, 0)").is_err());
+        assert!(parse("lag(a, b)").is_err());
+        assert!(parse("rolling_mean(a, -2)").is_err());
+        assert!(parse("abs(a").is_err());
+    }
+
+    #[test]
+    fn rejects_incomplete_input() {
+        assert!(parse("").is_err());
+        assert!(parse("   ").is_err());
+        assert!(parse("(a").is_err());
+        assert!(parse("a b").is_err());
+        assert!(parse("a )").is_err());
+        assert!(parse("a, b").is_err());
+    }
+}
@@ -6,6 +6,10 @@ pub mod schema;
 pub mod sources;
 pub mod config;
 pub mod memory;
+pub mod expr;
+pub mod sql;
+pub mod profile;
+pub mod export;
 
 use arrow::error::ArrowError;
 use tokio::task::JoinError;
@@ -14,7 +18,9 @@ use thiserror::Error;
 // Re-exports
 pub use cache::DataCache;
 pub use index::DataIndex;
-pub use sources::{CsvSource, SqliteSource, ConfiguredCsvSource};
+pub use sources::{CsvSource, SqliteSource, ConfiguredCsvSource, BatchSource, JoinedSource, ComputedSource, QuerySource, ReshapeSource, ResampleSource, ParseErrorLog, ParseFailure};
+pub use sql::SqlEngine;
+pub use profile::{DataProfile, ColumnProfile};
 
 /// Errors that can occur in data operations
 #[derive(Error, Debug)]
@@ -37,6 +43,12 @@ pub enum DataError {
     #[error("Invalid navigation position")]
     InvalidPosition,
     
+    #[error("Expression error: {0}")]
+    Expression(String),
+    
+    #[error("Parse error in {0}")]
+    Parse(ParseFailure),
+    
     #[error("Join error: {0}")]
     Join(#[from] JoinError),
     
@@ -0,0 +1,527 @@
+//! Data-quality profiling
+//!
+//! [`SchemaDetector`] only looks at a sample when choosing column types. A
+//! profile reads every row and reports what loading actually did to it: which
+//! null patterns matched, how many values failed to parse for the chosen type
+//! and silently became null, duplicates, ordering, gaps in timestamps and
+//! outliers. Profiles serialize to JSON for sharing alongside a dashboard.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use ahash::{AHashSet, RandomState};
+use arrow::array::{Array, Float64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema, TimeUnit};
+use arrow::util::display::array_value_to_string;
+use csv::ReaderBuilder;
+use serde::Serialize;
+use dv_core::data::DataSource;
+use crate::DataError;
+use crate::config::FileConfig;
+use crate::schema::{ColumnStats, SchemaDetector};
+use crate::sources::{read_all, ConfiguredCsvSource};
+use crate::sources::configured_csv_source::ParsedCell;
+
+/// Failing values kept per column as examples
+const MAX_FAILED_EXAMPLES: usize = 5;
+
+/// A step between consecutive timestamps counts as a gap when it is this many
+/// times the median step
+const GAP_FACTOR: f64 = 3.0;
+
+/// Values further than this many interquartile ranges outside the quartiles
+/// count as outliers
+const OUTLIER_IQR_FACTOR: f64 = 1.5;
+
+/// Data-quality report for one source
+#[derive(Debug, Clone, Serialize)]
+pub struct DataProfile {
+    /// Source name
+    pub source: String,
+
+    /// Rows read
+    pub row_count: usize,
+
+    /// Rows identical to an earlier row in every profiled column
+    pub duplicate_rows: usize,
+
+    /// One entry per column, in schema order
+    pub columns: Vec<ColumnProfile>,
+}
+
+/// Data-quality report for one column
+#[derive(Debug, Clone, Serialize)]
+pub struct ColumnProfile {
+    pub name: String,
+
+    /// Type the column is loaded as
+    pub data_type: String,
+
+    /// Null values, including parse failures and missing fields
+    pub null_count: usize,
+
+    /// Values matching each configured null pattern
+    pub nulls_by_pattern: BTreeMap<String, usize>,
+
+    /// Rows too short to have this field
+    pub missing_fields: usize,
+
+    /// Non-null values that could not be parsed as `data_type`
+    pub parse_failures: usize,
+
+    /// Share of non-null values that parsed as `data_type` (1.0 when there are none)
+    pub conformance_rate: f64,
+
+    /// A few distinct values that failed to parse
+    pub failed_examples: Vec<String>,
+
+    /// Distinct non-null values
+    pub distinct_count: usize,
+
+    /// Non-null values equal to an earlier value
+    pub duplicate_count: usize,
+
+    /// Ordering of the parsed values; `None` for text columns
+    pub monotonicity: Option<Monotonicity>,
+
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+
+    /// Values outside the 1.5 × IQR fences; only for numeric columns
+    pub outlier_count: usize,
+
+    /// Spacing of consecutive values; only for timestamp columns
+    pub time_gaps: Option<TimeGaps>,
+
+    /// Statistics from schema detection on the loading sample
+    pub sample_stats: Option<ColumnStats>,
+}
+
+/// Ordering of a column's values in row order, ignoring nulls
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Monotonicity {
+    Constant,
+    StrictlyIncreasing,
+    Increasing,
+    StrictlyDecreasing,
+    Decreasing,
+    Unordered,
+}
+
+/// Spacing of a timestamp column, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeGaps {
+    /// Median forward step between consecutive timestamps
+    pub median_interval_ms: f64,
+
+    /// Forward steps longer than three times the median
+    pub gap_count: usize,
+
+    /// Longest forward step
+    pub largest_gap_ms: i64,
+
+    /// Timestamps earlier than the one before them
+    pub backward_jumps: usize,
+}
+
+impl DataProfile {
+    /// Pretty-printed JSON report
+    pub fn to_json(&self) -> Result<String, DataError> {
+        serde_json::to_string_pretty(self).map_err(|e| DataError::Other(e.to_string()))
+    }
+
+    /// Look up a column by name
+    pub fn column(&self, name: &str) -> Option<&ColumnProfile> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+}
+
+impl ColumnProfile {
+    /// Share of non-null values that failed to parse
+    pub fn failure_rate(&self) -> f64 {
+        1.0 - self.conformance_rate
+    }
+}
+
+/// Running counts for one column
+struct ColumnProfiler {
+    name: String,
+    data_type: DataType,
+    null_count: usize,
+    nulls_by_pattern: BTreeMap<String, usize>,
+    missing_fields: usize,
+    parse_failures: usize,
+    failed_examples: Vec<String>,
+    non_null: usize,
+    distinct: AHashSet<u64>,
+    values: Vec<f64>,
+}
+
+impl ColumnProfiler {
+    fn new(name: &str, data_type: &DataType) -> Self {
+        Self {
+            name: name.to_string(),
+            data_type: data_type.clone(),
+            null_count: 0,
+            nulls_by_pattern: BTreeMap::new(),
+            missing_fields: 0,
+            parse_failures: 0,
+            failed_examples: Vec::new(),
+            non_null: 0,
+            distinct: AHashSet::new(),
+            values: Vec::new(),
+        }
+    }
+
+    fn null(&mut self, pattern: Option<&str>) {
+        self.null_count += 1;
+        if let Some(pattern) = pattern {
+            *self.nulls_by_pattern.entry(pattern.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    fn missing(&mut self) {
+        self.null_count += 1;
+        self.missing_fields += 1;
+    }
+
+    fn value(&mut self, text: &str, parsed: ParsedCell, hasher: &RandomState) {
+        self.non_null += 1;
+        self.distinct.insert(hasher.hash_one(text));
+        match parsed {
+            ParsedCell::Value(v) => self.values.push(v),
+            ParsedCell::Text => {}
+            ParsedCell::Failed => {
+                // Loading turns these into nulls
+                self.null_count += 1;
+                self.parse_failures += 1;
+                if self.failed_examples.len() < MAX_FAILED_EXAMPLES && !self.failed_examples.iter().any(|e| e == text) {
+                    self.failed_examples.push(text.to_string());
+                }
+            }
+        }
+    }
+
+    fn finish(self, sample_stats: Option<ColumnStats>) -> ColumnProfile {
+        let is_time = matches!(self.data_type, DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64);
+        let is_text = !is_time && self.data_type != DataType::Boolean && !self.data_type.is_numeric();
+
+        let (min, max, mean) = if self.values.is_empty() {
+            (None, None, None)
+        } else {
+            let (min, max) = self.values.iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+            let mean = self.values.iter().sum::<f64>() / self.values.len() as f64;
+            (Some(min), Some(max), Some(mean))
+        };
+
+        let outlier_count = if self.data_type.is_numeric() && !is_time {
+            count_outliers(&self.values)
+        } else {
+            0
+        };
+
+        ColumnProfile {
+            data_type: format!("{:?}", self.data_type),
+            null_count: self.null_count,
+            nulls_by_pattern: self.nulls_by_pattern,
+            missing_fields: self.missing_fields,
+            parse_failures: self.parse_failures,
+            conformance_rate: if self.non_null == 0 {
+                1.0
+            } else {
+                1.0 - self.parse_failures as f64 / self.non_null as f64
+            },
+            failed_examples: self.failed_examples,
+            distinct_count: self.distinct.len(),
+            duplicate_count: self.non_null - self.distinct.len(),
+            monotonicity: (!is_text).then(|| monotonicity(&self.values)),
+            min,
+            max,
+            mean,
+            outlier_count,
+            time_gaps: if is_time { time_gaps(&self.values) } else { None },
+            sample_stats,
+            name: self.name,
+        }
+    }
+}
+
+fn monotonicity(values: &[f64]) -> Monotonicity {
+    let (mut up, mut down, mut equal) = (false, false, false);
+    for pair in values.windows(2) {
+        match pair[1].partial_cmp(&pair[0]) {
+            Some(std::cmp::Ordering::Greater) => up = true,
+            Some(std::cmp::Ordering::Less) => down = true,
+            _ => equal = true,
+        }
+    }
+    match (up, down, equal) {
+        (false, false, _) => Monotonicity::Constant,
+        (true, false, false) => Monotonicity::StrictlyIncreasing,
+        (true, false, true) => Monotonicity::Increasing,
+        (false, true, false) => Monotonicity::StrictlyDecreasing,
+        (false, true, true) => Monotonicity::Decreasing,
+        (true, true, _) => Monotonicity::Unordered,
+    }
+}
+
+/// Linearly interpolated quantile of sorted values
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = (sorted.len() - 1) as f64 * q;
+    let below = position.floor() as usize;
+    let above = position.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+fn count_outliers(values: &[f64]) -> usize {
+    let mut sorted: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    if sorted.len() < 4 {
+        return 0;
+    }
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let (q1, q3) = (quantile(&sorted, 0.25), quantile(&sorted, 0.75));
+    let fence = OUTLIER_IQR_FACTOR * (q3 - q1);
+    sorted.iter().filter(|&&v| v < q1 - fence || v > q3 + fence).count()
+}
+
+fn time_gaps(values: &[f64]) -> Option<TimeGaps> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mut steps = Vec::with_capacity(values.len() - 1);
+    let mut backward_jumps = 0;
+    for pair in values.windows(2) {
+        let step = pair[1] - pair[0];
+        if step < 0.0 {
+            backward_jumps += 1;
+        } else if step > 0.0 {
+            steps.push(step);
+        }
+    }
+
+    let mut sorted = steps.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median_interval_ms = if sorted.is_empty() { 0.0 } else { quantile(&sorted, 0.5) };
+    Some(TimeGaps {
+        median_interval_ms,
+        gap_count: steps.iter().filter(|&&s| s > GAP_FACTOR * median_interval_ms).count(),
+        largest_gap_ms: sorted.last().copied().unwrap_or(0.0) as i64,
+        backward_jumps,
+    })
+}
+
+/// Profile the raw text of a configured CSV file, parsing every cell the
+/// way loading does, so parse failures and null patterns are exact
+pub async fn profile_csv(config: &FileConfig) -> Result<DataProfile, DataError> {
+    let (schema, _) = ConfiguredCsvSource::analyze_file(config).await?;
+    let config = config.clone();
+    tokio::task::spawn_blocking(move || profile_csv_blocking(&config, &schema)).await?
+}
+
+fn profile_csv_blocking(config: &FileConfig, schema: &Schema) -> Result<DataProfile, DataError> {
+    let file = File::open(&config.path)?;
+    // Short rows are counted as missing fields rather than failing the profile
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(BufReader::new(file));
+
+    let mut record = csv::StringRecord::new();
+    for _ in 0..config.header_line {
+        reader.read_record(&mut record)?;
+    }
+    reader.read_record(&mut record)?;
+    let headers: Vec<String> = record.iter().map(|s| s.to_string()).collect();
+
+    let mut columns = Vec::new();
+    for field in schema.fields() {
+        let index = headers.iter().position(|h| h == field.name()).ok_or_else(|| {
+            DataError::SchemaDetection(format!("Column '{}' not found in CSV", field.name()))
+        })?;
+        columns.push((index, ColumnProfiler::new(field.name(), field.data_type())));
+    }
+
+    let hasher = RandomState::new();
+    let mut rows = AHashSet::new();
+    let mut row_count = 0;
+    let mut duplicate_rows = 0;
+    let sample_size = config.sample_size;
+    let mut samples: Vec<Vec<String>> = Vec::new();
+
+    while reader.read_record(&mut record)? {
+        row_count += 1;
+        let cells: Vec<Option<&str>> = columns.iter().map(|(index, _)| record.get(*index)).collect();
+        if !rows.insert(hasher.hash_one(&cells)) {
+            duplicate_rows += 1;
+        }
+        if samples.len() < sample_size {
+            samples.push(cells.iter().map(|c| c.unwrap_or_default().to_string()).collect());
+        }
+
+        for ((_, profiler), cell) in columns.iter_mut().zip(cells) {
+            match cell {
+                None => profiler.missing(),
+                Some(value) if config.is_null_value(value) => profiler.null(Some(value)),
+                Some(value) => {
+                    let parsed = ConfiguredCsvSource::parse_cell(value, &profiler.data_type);
+                    profiler.value(value, parsed, &hasher);
+                }
+            }
+        }
+    }
+
+    let names: Vec<String> = columns.iter().map(|(_, p)| p.name.clone()).collect();
+    let mut sample_stats = SchemaDetector::new()
+        .with_sample_size(sample_size)
+        .detect_from_samples(&names, &samples)
+        .column_stats;
+
+    Ok(DataProfile {
+        source: config.file_name(),
+        row_count,
+        duplicate_rows,
+        columns: columns.into_iter()
+            .map(|(_, profiler)| {
+                let stats = sample_stats.remove(&profiler.name);
+                profiler.finish(stats)
+            })
+            .collect(),
+    })
+}
+
+/// Profile any source from its loaded values. Without the raw text there are
+/// no parse failures or null patterns to report.
+pub async fn profile_source(source: &dyn DataSource) -> Result<DataProfile, DataError> {
+    let batch = read_all(source).await?;
+    let hasher = RandomState::new();
+
+    let mut columns = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let mut profiler = ColumnProfiler::new(field.name(), field.data_type());
+        let numeric = match field.data_type() {
+            DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 => {
+                let millis = cast(column, &DataType::Timestamp(TimeUnit::Millisecond, None))?;
+                Some(cast(&cast(&millis, &DataType::Int64)?, &DataType::Float64)?)
+            }
+            DataType::Boolean => Some(cast(column, &DataType::Float64)?),
+            data_type if data_type.is_numeric() => Some(cast(column, &DataType::Float64)?),
+            _ => None,
+        };
+        let numeric = numeric.as_ref().and_then(|a| a.as_any().downcast_ref::<Float64Array>());
+
+        for row in 0..batch.num_rows() {
+            if column.is_null(row) {
+                profiler.null(None);
+                continue;
+            }
+            let text = array_value_to_string(column, row)?;
+            let parsed = match numeric {
+                Some(values) => ParsedCell::Value(values.value(row)),
+                None => ParsedCell::Text,
+            };
+            profiler.value(&text, parsed, &hasher);
+        }
+        columns.push(profiler);
+    }
+
+    let mut rows = AHashSet::new();
+    let mut duplicate_rows = 0;
+    for row in 0..batch.num_rows() {
+        let cells = batch.columns().iter()
+            .map(|c| if c.is_null(row) { Ok(None) } else { array_value_to_string(c, row).map(Some) })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !rows.insert(hasher.hash_one(&cells)) {
+            duplicate_rows += 1;
+        }
+    }
+
+    Ok(DataProfile {
+        source: source.source_name().to_string(),
+        row_count: batch.num_rows(),
+        duplicate_rows,
+        columns: columns.into_iter().map(|p| p.finish(None)).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FileType;
+
+    #[tokio::test]
+    async fn profiles_parse_failures_nulls_and_time_gaps() {
+        let path = std::env::temp_dir().join(format!("dv_profile_{}.csv", std::process::id()));
+        std::fs::write(&path, "\
+time,temperature,site
+2024-01-01 00:00:00,20.5,a
+2024-01-01 00:01:00,N/A,a
+2024-01-01 00:02:00,err,b
+2024-01-01 00:10:00,21.0,b
+2024-01-01 00:11:00,,b
+2024-01-01 00:05:00,500,c
+2024-01-01 00:05:00,500,c
+").unwrap();
+
+        let mut config = FileConfig::new(path.clone(), FileType::Csv);
+        for column in ["time", "temperature", "site"] {
+            config.selected_columns.insert(column.to_string());
+        }
+        config.column_types.insert("temperature".to_string(), DataType::Float64.into());
+        let profile = profile_csv(&config).await.unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(profile.row_count, 7);
+        assert_eq!(profile.duplicate_rows, 1);
+
+        let temperature = profile.column("temperature").unwrap();
+        assert_eq!(temperature.nulls_by_pattern.get("N/A"), Some(&1));
+        assert_eq!(temperature.nulls_by_pattern.get(""), Some(&1));
+        assert_eq!(temperature.parse_failures, 1);
+        assert_eq!(temperature.null_count, 3);
+        assert_eq!(temperature.failed_examples, vec!["err"]);
+        assert!((temperature.failure_rate() - 0.2).abs() < 1e-9);
+        assert_eq!(temperature.duplicate_count, 1);
+
+        let time = profile.column("time").unwrap();
+        assert!(time.data_type.starts_with("Timestamp"));
+        assert_eq!(time.monotonicity, Some(Monotonicity::Unordered));
+        let gaps = time.time_gaps.as_ref().unwrap();
+        assert_eq!(gaps.median_interval_ms, 60_000.0);
+        assert_eq!(gaps.gap_count, 1);
+        assert_eq!(gaps.largest_gap_ms, 480_000);
+        assert_eq!(gaps.backward_jumps, 1);
+
+        let site = profile.column("site").unwrap();
+        assert_eq!(site.distinct_count, 3);
+        assert_eq!(site.monotonicity, None);
+        assert!(profile.to_json().unwrap().contains("\"parse_failures\": 1"));
+    }
+
+    #[tokio::test]
+    async fn short_rows_count_as_missing_fields() {
+        let path = std::env::temp_dir().join(format!("dv_profile_short_{}.csv", std::process::id()));
+        std::fs::write(&path, "a,b\n1,true\n2\n3,maybe\n").unwrap();
+
+        let mut config = FileConfig::new(path.clone(), FileType::Csv);
+        config.selected_columns.extend(["a".to_string(), "b".to_string()]);
+        config.column_types.insert("b".to_string(), DataType::Boolean.into());
+        let profile = profile_csv(&config).await;
+        std::fs::remove_file(&path).ok();
+
+        let profile = profile.unwrap();
+        assert_eq!(profile.row_count, 3);
+        let b = profile.column("b").unwrap();
+        assert_eq!(b.missing_fields, 1);
+        assert_eq!(b.parse_failures, 1);
+        assert_eq!(b.failed_examples, vec!["maybe"]);
+    }
+
+    #[test]
+    fn counts_iqr_outliers() {
+        assert_eq!(count_outliers(&[1.0, 2.0, 3.0, 4.0, 5.0, 100.0]), 1);
+        assert_eq!(monotonicity(&[1.0, 1.0, 2.0]), Monotonicity::Increasing);
+    }
+}
@@ -1,5 +1,6 @@
 use arrow::datatypes::{Schema, Field, DataType};
 use std::collections::HashMap;
+use serde::Serialize;
 
 /// Schema detector for analyzing data and determining column types
 pub struct SchemaDetector {
@@ -11,11 +12,13 @@ pub struct SchemaDetector {
 pub struct SchemaInfo {
     pub schema: Schema,
     pub column_stats: HashMap<String, ColumnStats>,
+    /// Statistics in header order, kept apart for columns sharing a name
+    pub columns: Vec<ColumnStats>,
     pub suggested_navigation_column: Option<String>,
 }
 
 /// Statistics about a column
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ColumnStats {
     pub null_count: usize,
     pub distinct_count: usize,
@@ -43,6 +46,7 @@ impl SchemaDetector {
     pub fn detect_from_samples(&self, headers: &[String], samples: &[Vec<String>]) -> SchemaInfo {
         let mut fields = Vec::new();
         let mut column_stats = HashMap::new();
+        let mut columns = Vec::with_capacity(headers.len());
         let mut suggested_navigation_column = None;
         
         for (col_idx, header) in headers.iter().enumerate() {
@@ -56,12 +60,14 @@ impl SchemaDetector {
             }
             
             fields.push(Field::new(header, data_type, stats.null_count > 0));
-            column_stats.insert(header.clone(), stats);
+            column_stats.insert(header.clone(), stats.clone());
+            columns.push(stats);
         }
         
         SchemaInfo {
             schema: Schema::new(fields),
             column_stats,
+            columns,
             suggested_navigation_column,
         }
     }
@@ -191,4 +197,22 @@ impl Default for SchemaDetector {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_headers_keep_their_own_stats() {
+        let headers = vec!["value".to_string(), "value".to_string()];
+        let samples: Vec<Vec<String>> = [["1", "a"], ["2", ""]].iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect();
+        let info = SchemaDetector::new().detect_from_samples(&headers, &samples);
+        assert_eq!(info.columns.len(), 2);
+        assert_eq!(info.columns[0].null_count, 0);
+        assert_eq!(info.columns[1].null_count, 1);
+        assert_eq!(info.columns[0].max_value.as_deref(), Some("2"));
+    }
+}
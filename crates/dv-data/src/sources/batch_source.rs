@@ -0,0 +1,125 @@
+//! Data source over a single in-memory batch
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+use dv_core::navigation::{NavigationPosition, NavigationSpec, NavigationMode, NavigationRange};
+use dv_core::data::DataSource;
+use crate::DataError;
+
+/// Rows returned by `query_at`, centered on the position
+const WINDOW_SIZE: usize = 1000;
+
+/// Named rows held in memory, navigated by row. Sources that compute their
+/// whole result up front (joins, queries, resampling) keep it in one.
+pub struct BatchSource {
+    /// Display name
+    name: String,
+
+    /// All rows of the source
+    batch: RecordBatch,
+}
+
+impl BatchSource {
+    pub fn new(name: impl Into<String>, batch: RecordBatch) -> Self {
+        Self {
+            name: name.into(),
+            batch,
+        }
+    }
+
+    /// All rows of the source
+    pub fn batch(&self) -> &RecordBatch {
+        &self.batch
+    }
+}
+
+#[async_trait]
+impl DataSource for BatchSource {
+    async fn schema(&self) -> Arc<Schema> {
+        self.batch.schema()
+    }
+
+    async fn navigation_spec(&self) -> anyhow::Result<NavigationSpec> {
+        Ok(NavigationSpec {
+            mode: NavigationMode::Sequential,
+            total_rows: self.batch.num_rows(),
+            temporal_bounds: None,
+            categories: None,
+        })
+    }
+
+    async fn query_at(&self, position: &NavigationPosition) -> anyhow::Result<RecordBatch> {
+        let row_idx = match position {
+            NavigationPosition::Sequential(idx) => *idx,
+            _ => return Err(DataError::InvalidPosition.into()),
+        };
+
+        // Query a window around the position
+        let start = row_idx.saturating_sub(WINDOW_SIZE / 2);
+        let end = (start + WINDOW_SIZE).min(self.batch.num_rows());
+
+        self.query_range(&NavigationRange::rows(start, end)).await
+    }
+
+    async fn query_range(&self, range: &NavigationRange) -> anyhow::Result<RecordBatch> {
+        let (start, end) = match (&range.start, &range.end) {
+            (NavigationPosition::Sequential(s), NavigationPosition::Sequential(e)) => (*s, *e),
+            _ => return Err(DataError::InvalidPosition.into()),
+        };
+
+        let total = self.batch.num_rows();
+        let start = start.min(total);
+        let end = end.clamp(start, total);
+        Ok(self.batch.slice(start, end - start))
+    }
+
+    async fn row_count(&self) -> anyhow::Result<usize> {
+        Ok(self.batch.num_rows())
+    }
+
+    fn source_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, ArrayRef, Int64Array};
+
+    fn source(rows: i64) -> BatchSource {
+        let values = Arc::new(Int64Array::from((0..rows).collect::<Vec<_>>())) as ArrayRef;
+        BatchSource::new("numbers", RecordBatch::try_from_iter(vec![("n", values)]).unwrap())
+    }
+
+    fn first_value(batch: &RecordBatch) -> i64 {
+        batch.column(0).as_any().downcast_ref::<Int64Array>().unwrap().value(0)
+    }
+
+    #[tokio::test]
+    async fn ranges_are_clamped_to_the_rows() {
+        let source = source(10);
+        assert_eq!(source.row_count().await.unwrap(), 10);
+        assert_eq!(source.navigation_spec().await.unwrap().total_rows, 10);
+
+        let rows = source.query_range(&NavigationRange::rows(3, 6)).await.unwrap();
+        assert_eq!((rows.num_rows(), first_value(&rows)), (3, 3));
+        assert_eq!(source.query_range(&NavigationRange::rows(8, 50)).await.unwrap().num_rows(), 2);
+        assert_eq!(source.query_range(&NavigationRange::rows(20, 30)).await.unwrap().num_rows(), 0);
+        assert_eq!(source.query_range(&NavigationRange::rows(6, 3)).await.unwrap().num_rows(), 0);
+    }
+
+    #[tokio::test]
+    async fn positions_query_a_window_around_them() {
+        let source = source(5000);
+        let window = source.query_at(&NavigationPosition::Sequential(2000)).await.unwrap();
+        assert_eq!((window.num_rows(), first_value(&window)), (WINDOW_SIZE, 1500));
+
+        let start = source.query_at(&NavigationPosition::Sequential(10)).await.unwrap();
+        assert_eq!((start.num_rows(), first_value(&start)), (WINDOW_SIZE, 0));
+
+        assert!(source.query_at(&NavigationPosition::Temporal(0)).await.is_err());
+    }
+}
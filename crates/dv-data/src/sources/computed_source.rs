@@ -0,0 +1,204 @@
+//! Data source wrapper adding computed columns
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use arrow::datatypes::{Schema, Field};
+use arrow::record_batch::RecordBatch;
+use dv_core::navigation::{NavigationPosition, NavigationSpec, NavigationRange};
+use dv_core::data::DataSource;
+use crate::DataError;
+use crate::config::ComputedColumn;
+use crate::expr::{self, Expr};
+
+/// Data source that appends computed columns to another source
+pub struct ComputedSource {
+    /// Wrapped source
+    inner: Box<dyn DataSource>,
+
+    /// Parsed expressions, evaluated in order so later ones can use earlier ones
+    columns: Vec<(String, Expr)>,
+
+    /// Inner schema followed by the computed fields
+    schema: Arc<Schema>,
+
+    /// Extra rows queried before a range so `lag` and rolling windows are exact
+    lookback: usize,
+}
+
+impl ComputedSource {
+    /// Wrap `inner`, failing if an expression does not parse or refers to unknown columns
+    pub async fn new(inner: Box<dyn DataSource>, definitions: &[ComputedColumn]) -> Result<Self, DataError> {
+        let inner_schema = inner.schema().await;
+        let mut columns = Vec::new();
+        // Lookback of each computed column, including that of computed columns it uses
+        let mut lookbacks: Vec<(String, usize)> = Vec::new();
+
+        for definition in definitions {
+            let name = definition.name.trim().to_string();
+            if name.is_empty() {
+                return Err(DataError::Expression("Computed column needs a name".to_string()));
+            }
+            if inner_schema.field_with_name(&name).is_ok() || lookbacks.iter().any(|(n, _)| n == &name) {
+                return Err(DataError::Expression(format!("Column '{}' already exists", name)));
+            }
+
+            let parsed = expr::parse(&definition.expression)
+                .map_err(|e| DataError::Expression(format!("{}: {}", name, e)))?;
+            let inherited = parsed.columns().iter()
+                .filter_map(|column| lookbacks.iter().find(|(n, _)| n == column).map(|(_, l)| *l))
+                .max()
+                .unwrap_or(0);
+            lookbacks.push((name.clone(), parsed.lookback().saturating_add(inherited)));
+            columns.push((name, parsed));
+        }
+
+        let mut source = Self {
+            lookback: lookbacks.iter().map(|(_, l)| *l).max().unwrap_or(0),
+            inner,
+            columns,
+            schema: inner_schema.clone(),
+        };
+
+        // Evaluating on an empty batch checks column references and yields the output types
+        let empty = source.compute(RecordBatch::new_empty(inner_schema))?;
+        source.schema = empty.schema();
+        Ok(source)
+    }
+
+    /// Append the computed columns to a batch of the inner source
+    fn compute(&self, mut batch: RecordBatch) -> Result<RecordBatch, DataError> {
+        for (name, parsed) in &self.columns {
+            let values = expr::evaluate(parsed, &batch)
+                .map_err(|e| DataError::Expression(format!("{}: {}", name, e)))?;
+
+            let mut fields: Vec<Field> = batch.schema().fields().iter().map(|f| f.as_ref().clone()).collect();
+            fields.push(Field::new(name, values.data_type().clone(), true));
+            let mut arrays = batch.columns().to_vec();
+            arrays.push(values);
+
+            batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?;
+        }
+        Ok(batch)
+    }
+}
+
+#[async_trait]
+impl DataSource for ComputedSource {
+    async fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    async fn navigation_spec(&self) -> anyhow::Result<NavigationSpec> {
+        self.inner.navigation_spec().await
+    }
+
+    async fn query_at(&self, position: &NavigationPosition) -> anyhow::Result<RecordBatch> {
+        let row_idx = match position {
+            NavigationPosition::Sequential(idx) => *idx,
+            // The inner source resolves times and categories to its own
+            // window, whose preceding rows aren't known, so `lag` and rolling
+            // windows start over at its first row
+            _ => return Ok(self.compute(self.inner.query_at(position).await?)?),
+        };
+
+        // Query a window around the position
+        let window_size = 1000;
+        let total = self.inner.row_count().await?;
+        let start = row_idx.saturating_sub(window_size / 2);
+        let end = (start + window_size).min(total);
+
+        self.query_range(&NavigationRange::rows(start, end)).await
+    }
+
+    async fn query_range(&self, range: &NavigationRange) -> anyhow::Result<RecordBatch> {
+        let (start, end) = match (&range.start, &range.end) {
+            (NavigationPosition::Sequential(s), NavigationPosition::Sequential(e)) => (*s, *e),
+            _ => return Ok(self.compute(self.inner.query_range(range).await?)?),
+        };
+
+        let fetch_start = start.saturating_sub(self.lookback);
+        let batch = self.inner.query_range(&NavigationRange::rows(fetch_start, end)).await?;
+        let batch = self.compute(batch)?;
+
+        // Drop the lookback rows again
+        let skip = (start - fetch_start).min(batch.num_rows());
+        Ok(batch.slice(skip, batch.num_rows() - skip))
+    }
+
+    async fn row_count(&self) -> anyhow::Result<usize> {
+        self.inner.row_count().await
+    }
+
+    fn source_name(&self) -> &str {
+        self.inner.source_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, Float64Array, TimestampMillisecondArray};
+    use dv_core::navigation::NavigationMode;
+
+    /// Rows navigated by their timestamps, resolving a time to the rows from it on
+    struct TimedSource {
+        batch: RecordBatch,
+    }
+
+    #[async_trait]
+    impl DataSource for TimedSource {
+        async fn schema(&self) -> Arc<Schema> {
+            self.batch.schema()
+        }
+
+        async fn navigation_spec(&self) -> anyhow::Result<NavigationSpec> {
+            Ok(NavigationSpec {
+                mode: NavigationMode::Temporal,
+                total_rows: self.batch.num_rows(),
+                temporal_bounds: Some((1_000, 3_000)),
+                categories: None,
+            })
+        }
+
+        async fn query_at(&self, position: &NavigationPosition) -> anyhow::Result<RecordBatch> {
+            let NavigationPosition::Temporal(time) = position else {
+                return self.query_range(&NavigationRange::rows(0, self.batch.num_rows())).await;
+            };
+            let times = self.batch.column(0).as_any().downcast_ref::<TimestampMillisecondArray>().unwrap();
+            let first = times.values().iter().position(|t| t >= time).unwrap_or(times.len());
+            Ok(self.batch.slice(first, self.batch.num_rows() - first))
+        }
+
+        async fn query_range(&self, range: &NavigationRange) -> anyhow::Result<RecordBatch> {
+            let (start, end) = (range.start.frame_nr(), range.end.frame_nr().min(self.batch.num_rows()));
+            Ok(self.batch.slice(start, end - start))
+        }
+
+        async fn row_count(&self) -> anyhow::Result<usize> {
+            Ok(self.batch.num_rows())
+        }
+
+        fn source_name(&self) -> &str {
+            "timed"
+        }
+    }
+
+    #[tokio::test]
+    async fn computed_columns_follow_temporal_navigation() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("t", Arc::new(TimestampMillisecondArray::from(vec![1_000, 2_000, 3_000])) as ArrayRef),
+            ("v", Arc::new(Float64Array::from(vec![1.0, 2.0, 4.0])) as ArrayRef),
+        ]).unwrap();
+        let definitions = [ComputedColumn { name: "double".to_string(), expression: "v * 2".to_string() }];
+        let source = ComputedSource::new(Box::new(TimedSource { batch }), &definitions).await.unwrap();
+        assert!(matches!(source.navigation_spec().await.unwrap().mode, NavigationMode::Temporal));
+
+        let at = source.query_at(&NavigationPosition::Temporal(2_000)).await.unwrap();
+        let double = at.column_by_name("double").unwrap().as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(double.values().to_vec(), vec![4.0, 8.0]);
+
+        let rows = source.query_range(&NavigationRange::rows(1, 3)).await.unwrap();
+        assert_eq!(rows.num_rows(), 2);
+        assert_eq!(rows.schema().field(2).name(), "double");
+    }
+}
@@ -16,12 +16,23 @@ use dv_core::navigation::{NavigationSpec, NavigationPosition, NavigationRange, N
 use chrono::{DateTime, NaiveDateTime};
 
 use crate::{DataError, config::FileConfig};
+use super::parse_errors::{ParseErrorLog, ParseFailure};
 
 /// Performance tuning constants (same as csv_source.rs)
 const MAX_SAMPLE_ROWS: usize = 5000;
 const CHUNK_SIZE: usize = 10000;
 const MAX_CACHED_CHUNKS: usize = 50;
 
+/// A CSV cell as loading reads it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ParsedCell {
+    /// Parsed to a number (booleans as 0/1, timestamps as milliseconds)
+    Value(f64),
+    /// Text column: nothing to parse
+    Text,
+    Failed,
+}
+
 /// CSV data source with configuration support
 pub struct ConfiguredCsvSource {
     /// File configuration
@@ -41,6 +52,9 @@ pub struct ConfiguredCsvSource {
     
     /// Source name
     source_name: String,
+    
+    /// Values that failed to parse in the chunks read so far
+    parse_errors: ParseErrorLog,
 }
 
 /// Data cache for chunk-based loading
@@ -66,6 +80,11 @@ impl ConfiguredCsvSource {
         // Analyze the file with configuration
         let (schema, row_count) = Self::analyze_file(&config).await?;
         
+        // In strict mode any value that doesn't parse fails loading
+        if config.strict_parsing {
+            Self::validate_file(&config, &schema).await?;
+        }
+        
         // Determine navigation spec
         let navigation_spec = Self::determine_navigation(&schema, &config).await?;
         
@@ -80,20 +99,117 @@ impl ConfiguredCsvSource {
                 max_chunks: MAX_CACHED_CHUNKS,
                 access_order: Vec::new(),
             })),
+            parse_errors: ParseErrorLog::new(),
         })
     }
     
+    /// Record parse failures in `log` instead of a log of this source's own
+    pub fn with_parse_errors(mut self, log: ParseErrorLog) -> Self {
+        self.parse_errors = log;
+        self
+    }
+    
+    /// Values that failed to parse in the chunks read so far
+    pub fn parse_errors(&self) -> &ParseErrorLog {
+        &self.parse_errors
+    }
+    
+    /// Parse `value` the way a column of `data_type` loads it
+    pub(crate) fn parse_cell(value: &str, data_type: &DataType) -> ParsedCell {
+        let parsed = match data_type {
+            DataType::Boolean => match value.to_lowercase().as_str() {
+                "true" | "1" => Some(1.0),
+                "false" | "0" => Some(0.0),
+                _ => None,
+            },
+            DataType::Int64 => value.parse::<i64>().ok().map(|v| v as f64),
+            DataType::Float64 => value.parse::<f64>().ok(),
+            DataType::Timestamp(_, _) => Self::timestamp_millis(value).map(|v| v as f64),
+            _ => return ParsedCell::Text,
+        };
+        parsed.map_or(ParsedCell::Failed, ParsedCell::Value)
+    }
+    
+    /// Parse failures in the selected columns of `row_data`, whose first row is `start_row`
+    fn find_parse_failures(
+        row_data: &[Vec<Option<String>>],
+        start_row: usize,
+        header: &[String],
+        schema: &Schema,
+    ) -> Vec<ParseFailure> {
+        let mut failures = Vec::new();
+        for field in schema.fields() {
+            let Some(col_idx) = header.iter().position(|h| h == field.name()) else {
+                continue;
+            };
+            for (i, row) in row_data.iter().enumerate() {
+                if let Some(Some(value)) = row.get(col_idx) {
+                    if Self::parse_cell(value, field.data_type()) == ParsedCell::Failed {
+                        failures.push(ParseFailure {
+                            column: field.name().clone(),
+                            row: start_row + i,
+                            value: value.clone(),
+                            expected: field.data_type().clone(),
+                        });
+                    }
+                }
+            }
+        }
+        failures.sort_by_key(|f| f.row);
+        failures
+    }
+    
+    /// Read every row and fail on the first value that doesn't parse
+    async fn validate_file(config: &FileConfig, schema: &Schema) -> Result<(), DataError> {
+        tokio::task::spawn_blocking({
+            let config = config.clone();
+            let schema = schema.clone();
+            move || {
+                let file = File::open(&config.path)?;
+                let mut csv_reader = ReaderBuilder::new()
+                    .has_headers(false)
+                    .from_reader(BufReader::new(file));
+                
+                let mut record = csv::StringRecord::new();
+                for _ in 0..config.header_line {
+                    csv_reader.read_record(&mut record)?;
+                }
+                csv_reader.read_record(&mut record)?;
+                let header: Vec<String> = record.iter().map(|s| s.to_string()).collect();
+                
+                let mut start_row = 0;
+                loop {
+                    let mut row_data = Vec::with_capacity(CHUNK_SIZE);
+                    while row_data.len() < CHUNK_SIZE && csv_reader.read_record(&mut record)? {
+                        row_data.push(record.iter()
+                            .map(|s| (!config.is_null_value(s)).then(|| s.to_string()))
+                            .collect::<Vec<_>>());
+                    }
+                    if let Some(failure) = Self::find_parse_failures(&row_data, start_row, &header, &schema).into_iter().next() {
+                        return Err(DataError::Parse(failure));
+                    }
+                    if row_data.len() < CHUNK_SIZE {
+                        return Ok(());
+                    }
+                    start_row += row_data.len();
+                }
+            }
+        }).await?
+    }
+    
     /// Analyze the CSV file with configuration
-    async fn analyze_file(config: &FileConfig) -> Result<(Schema, usize), DataError> {
+    pub(crate) async fn analyze_file(config: &FileConfig) -> Result<(Schema, usize), DataError> {
         tokio::task::spawn_blocking({
             let config = config.clone();
             move || {
                 let file = File::open(&config.path)?;
                 let mut reader = BufReader::new(file);
                 
-                // Skip to header line
+                // Skip to header line. Short rows are sampled as they are;
+                // they only fail once loaded, so profiling can report them.
                 let mut csv_reader = ReaderBuilder::new()
                     .has_headers(false)
+                    .flexible(true)
                     .from_reader(&mut reader);
                 
                 // Skip lines before header
@@ -233,6 +349,7 @@ impl ConfiguredCsvSource {
     async fn read_chunk(&self, start_row: usize, num_rows: usize) -> Result<RecordBatch, DataError> {
         let config = self.config.clone();
         let schema = self.schema.clone();
+        let parse_errors = self.parse_errors.clone();
         
         tokio::task::spawn_blocking(move || {
             let file = File::open(&config.path)?;
@@ -287,6 +404,19 @@ impl ConfiguredCsvSource {
                 header.iter().map(|s| s.to_string()).collect::<Vec<_>>()
             };
             
+            // Values that don't parse load as null; keep a record of them,
+            // or refuse the chunk in strict mode
+            let failures = Self::find_parse_failures(&row_data, start_row, &header_record, &schema);
+            if config.strict_parsing {
+                if let Some(failure) = failures.into_iter().next() {
+                    return Err(DataError::Parse(failure));
+                }
+            } else {
+                for failure in failures {
+                    parse_errors.record(failure);
+                }
+            }
+            
             // Build arrow arrays for each selected column
             let mut columns: Vec<ArrayRef> = Vec::new();
             
@@ -356,22 +486,9 @@ impl ConfiguredCsvSource {
                             let mut builder = TimestampMillisecondBuilder::new();
                             for row in &row_data {
                                 if let Some(Some(value)) = row.get(col_idx) {
-                                    if let Ok(v) = value.parse::<i64>() {
-                                        // Unix timestamp in seconds or milliseconds
-                                        if v > 1_000_000_000_000 {
-                                            // Milliseconds
-                                            builder.append_value(v);
-                                        } else {
-                                            // Seconds - convert to milliseconds
-                                            builder.append_value(v * 1000);
-                                        }
-                                    } else {
-                                        // Try parsing as date string
-                                        let parsed = Self::parse_timestamp(value);
-                                        match parsed {
-                                            Some(ts) => builder.append_value(ts),
-                                            None => builder.append_null(),
-                                        }
+                                    match Self::timestamp_millis(value) {
+                                        Some(ts) => builder.append_value(ts),
+                                        None => builder.append_null(),
                                     }
                                 } else {
                                     builder.append_null();
@@ -405,6 +522,17 @@ impl ConfiguredCsvSource {
         }).await.map_err(|e| DataError::SchemaDetection(e.to_string()))?
     }
     
+    /// Parse a timestamp cell into milliseconds since epoch: Unix seconds or
+    /// milliseconds, or a date string
+    pub(crate) fn timestamp_millis(value: &str) -> Option<i64> {
+        match value.parse::<i64>() {
+            // Milliseconds, or seconds converted to milliseconds
+            Ok(v) if v > 1_000_000_000_000 => Some(v),
+            Ok(v) => Some(v * 1000),
+            Err(_) => Self::parse_timestamp(value),
+        }
+    }
+    
     /// Parse a timestamp string into milliseconds since epoch
     fn parse_timestamp(value: &str) -> Option<i64> {
         // Try common date formats
@@ -491,4 +619,37 @@ impl dv_core::data::DataSource for ConfiguredCsvSource {
     fn source_name(&self) -> &str {
         &self.source_name
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dv_core::data::DataSource;
+    use crate::config::FileType;
+
+    #[tokio::test]
+    async fn records_parse_failures_and_fails_in_strict_mode() {
+        let path = std::env::temp_dir().join(format!("dv_parse_errors_{}.csv", std::process::id()));
+        std::fs::write(&path, "id,temperature\n1,20.5\n2,\"12,5\"\n3,N/A\n4,21\n").unwrap();
+
+        let mut config = FileConfig::new(path.clone(), FileType::Csv);
+        config.selected_columns.insert("id".to_string());
+        config.selected_columns.insert("temperature".to_string());
+        config.column_types.insert("temperature".to_string(), DataType::Float64.into());
+
+        let source = ConfiguredCsvSource::new(config.clone()).await.unwrap();
+        let batch = source.query_range(&NavigationRange::rows(0, 4)).await.unwrap();
+        assert_eq!(batch.column(1).null_count(), 2);
+        source.query_range(&NavigationRange::rows(0, 4)).await.unwrap();
+
+        let log = source.parse_errors();
+        assert_eq!(log.total(), 1);
+        let failures = log.failures("temperature");
+        assert_eq!((failures[0].row, failures[0].value.as_str()), (1, "12,5"));
+
+        config.strict_parsing = true;
+        let error = ConfiguredCsvSource::new(config).await.err().unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(matches!(error, DataError::Parse(ref f) if f.row == 1));
+        assert_eq!(error.to_string(), "Parse error in column 'temperature', row 2: \"12,5\" is not a valid Float64");
+    }
+}
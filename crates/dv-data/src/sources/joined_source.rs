@@ -0,0 +1,306 @@
+//! Joined data source combining two sources horizontally
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use arrow::array::{Array, ArrayRef, Float64Array, Int64Array, UInt64Array};
+use arrow::compute::{cast, take};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
+use dv_core::navigation::{NavigationPosition, NavigationSpec, NavigationRange};
+use dv_core::data::DataSource;
+use crate::DataError;
+use super::{read_all, BatchSource};
+
+/// How unmatched left rows are handled by a key join
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// Keep only left rows with at least one match
+    Inner,
+    /// Keep every left row, with nulls where nothing matched
+    Left,
+}
+
+/// How rows of the two sources are matched
+#[derive(Debug, Clone, PartialEq)]
+pub enum JoinSpec {
+    /// Match rows whose key columns are equal
+    Key {
+        kind: JoinKind,
+        left_on: Vec<String>,
+        right_on: Vec<String>,
+    },
+
+    /// Match each left row to the latest right row at or before its timestamp.
+    /// The tolerance is in milliseconds for temporal columns and in column
+    /// units for numeric ones; `None` accepts any earlier row.
+    AsOf {
+        left_on: String,
+        right_on: String,
+        tolerance: Option<f64>,
+    },
+}
+
+/// Data source joining the columns of two sources row by row
+pub struct JoinedSource {
+    /// Materialized join result: left columns followed by right columns
+    rows: BatchSource,
+}
+
+impl JoinedSource {
+    /// Join two sources. Both are read in full and the result is kept in memory.
+    pub async fn new(left: &dyn DataSource, right: &dyn DataSource, spec: JoinSpec) -> Result<Self, DataError> {
+        let left_batch = read_all(left).await?;
+        let right_batch = read_all(right).await?;
+        let batch = join_batches(&left_batch, &right_batch, &spec)?;
+        let name = format!("{} ⋈ {}", left.source_name(), right.source_name());
+
+        Ok(Self {
+            rows: BatchSource::new(name, batch),
+        })
+    }
+}
+
+/// Join two batches according to `spec`
+pub fn join_batches(left: &RecordBatch, right: &RecordBatch, spec: &JoinSpec) -> Result<RecordBatch, DataError> {
+    let (left_rows, right_rows, skip_right) = match spec {
+        JoinSpec::Key { kind, left_on, right_on } => {
+            let (l, r) = key_matches(left, right, left_on, right_on, *kind)?;
+            (l, r, right_on.as_slice())
+        }
+        JoinSpec::AsOf { left_on, right_on, tolerance } => {
+            let (l, r) = asof_matches(left, right, left_on, right_on, *tolerance)?;
+            (l, r, &[][..])
+        }
+    };
+
+    let left_indices = UInt64Array::from(left_rows);
+    let right_indices = UInt64Array::from(right_rows);
+
+    let mut fields: Vec<Field> = Vec::new();
+    let mut columns: Vec<ArrayRef> = Vec::new();
+
+    for (field, column) in left.schema().fields().iter().zip(left.columns()) {
+        fields.push(field.as_ref().clone());
+        columns.push(take(column.as_ref(), &left_indices, None)?);
+    }
+
+    // Right key columns duplicate the left keys, so they are dropped
+    for (field, column) in right.schema().fields().iter().zip(right.columns()) {
+        if skip_right.iter().any(|name| name == field.name()) {
+            continue;
+        }
+        let mut name = field.name().clone();
+        while fields.iter().any(|f| f.name() == &name) {
+            name.push_str("_right");
+        }
+        fields.push(field.as_ref().clone().with_name(name).with_nullable(true));
+        columns.push(take(column.as_ref(), &right_indices, None)?);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Look up a column by name, reporting which side it was expected on
+fn column<'a>(batch: &'a RecordBatch, name: &str, side: &str) -> Result<&'a ArrayRef, DataError> {
+    batch.column_by_name(name)
+        .ok_or_else(|| DataError::Other(format!("Join column '{}' not found in {} source", name, side)))
+}
+
+/// Row pairs for an equality join on key columns
+fn key_matches(
+    left: &RecordBatch,
+    right: &RecordBatch,
+    left_on: &[String],
+    right_on: &[String],
+    kind: JoinKind,
+) -> Result<(Vec<u64>, Vec<Option<u64>>), DataError> {
+    if left_on.is_empty() || left_on.len() != right_on.len() {
+        return Err(DataError::Other("Key join needs the same number of key columns on both sides".to_string()));
+    }
+
+    let left_keys = left_on.iter()
+        .map(|name| column(left, name, "left").cloned())
+        .collect::<Result<Vec<_>, _>>()?;
+    // Compare right keys in the left key types so e.g. Int32 and Int64 keys match
+    let right_keys = right_on.iter().zip(&left_keys)
+        .map(|(name, left_key)| Ok(cast(column(right, name, "right")?, left_key.data_type())?))
+        .collect::<Result<Vec<_>, DataError>>()?;
+
+    let converter = RowConverter::new(
+        left_keys.iter().map(|key| SortField::new(key.data_type().clone())).collect()
+    )?;
+    let left_encoded = converter.convert_columns(&left_keys)?;
+    let right_encoded = converter.convert_columns(&right_keys)?;
+
+    // Null keys never match, as in SQL
+    let has_null = |keys: &[ArrayRef], row: usize| keys.iter().any(|key| key.is_null(row));
+
+    let mut lookup: HashMap<_, Vec<u64>> = HashMap::new();
+    for row in 0..right.num_rows() {
+        if !has_null(&right_keys, row) {
+            lookup.entry(right_encoded.row(row)).or_default().push(row as u64);
+        }
+    }
+
+    let mut left_rows = Vec::new();
+    let mut right_rows = Vec::new();
+    for row in 0..left.num_rows() {
+        let matches = if has_null(&left_keys, row) {
+            None
+        } else {
+            lookup.get(&left_encoded.row(row))
+        };
+        match matches {
+            Some(matches) => {
+                for &matched in matches {
+                    left_rows.push(row as u64);
+                    right_rows.push(Some(matched));
+                }
+            }
+            None if kind == JoinKind::Left => {
+                left_rows.push(row as u64);
+                right_rows.push(None);
+            }
+            None => {}
+        }
+    }
+
+    Ok((left_rows, right_rows))
+}
+
+/// Row pairs for a backward as-of join; every left row is kept
+fn asof_matches(
+    left: &RecordBatch,
+    right: &RecordBatch,
+    left_on: &str,
+    right_on: &str,
+    tolerance: Option<f64>,
+) -> Result<(Vec<u64>, Vec<Option<u64>>), DataError> {
+    let left_times = time_values(column(left, left_on, "left")?)?;
+    let right_times = time_values(column(right, right_on, "right")?)?;
+
+    // Right rows ordered by time; the stable sort keeps the later row last on ties
+    let mut order: Vec<usize> = (0..right_times.len()).filter(|&row| right_times[row].is_some()).collect();
+    order.sort_by(|&a, &b| right_times[a].partial_cmp(&right_times[b]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let left_rows = (0..left.num_rows() as u64).collect();
+    let right_rows = left_times.iter()
+        .map(|time| {
+            let time = (*time)?;
+            let after = order.partition_point(|&row| right_times[row].is_some_and(|t| t <= time));
+            let candidate = order[..after].last().copied()?;
+            let gap = time - right_times[candidate]?;
+            match tolerance {
+                Some(tolerance) if gap > tolerance => None,
+                _ => Some(candidate as u64),
+            }
+        })
+        .collect();
+
+    Ok((left_rows, right_rows))
+}
+
+/// Comparable values of a time column: milliseconds for temporal types,
+/// plain values for numeric ones
+fn time_values(array: &ArrayRef) -> Result<Vec<Option<f64>>, DataError> {
+    match array.data_type() {
+        DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64 | DataType::Utf8 | DataType::LargeUtf8 => {
+            let millis = cast(array, &DataType::Timestamp(TimeUnit::Millisecond, None))?;
+            let millis = cast(&millis, &DataType::Int64)?;
+            let millis = millis.as_any().downcast_ref::<Int64Array>()
+                .ok_or_else(|| DataError::Other("Failed to read timestamps".to_string()))?;
+            Ok(millis.iter().map(|v| v.map(|v| v as f64)).collect())
+        }
+        data_type if data_type.is_numeric() => {
+            let values = cast(array, &DataType::Float64)?;
+            let values = values.as_any().downcast_ref::<Float64Array>()
+                .ok_or_else(|| DataError::Other("Failed to read numeric values".to_string()))?;
+            Ok(values.iter().collect())
+        }
+        data_type => Err(DataError::Other(format!("Cannot align on column of type {:?}", data_type))),
+    }
+}
+
+#[async_trait]
+impl DataSource for JoinedSource {
+    async fn schema(&self) -> Arc<Schema> {
+        self.rows.schema().await
+    }
+
+    async fn navigation_spec(&self) -> anyhow::Result<NavigationSpec> {
+        self.rows.navigation_spec().await
+    }
+
+    async fn query_at(&self, position: &NavigationPosition) -> anyhow::Result<RecordBatch> {
+        self.rows.query_at(position).await
+    }
+
+    async fn query_range(&self, range: &NavigationRange) -> anyhow::Result<RecordBatch> {
+        self.rows.query_range(range).await
+    }
+
+    async fn row_count(&self) -> anyhow::Result<usize> {
+        self.rows.row_count().await
+    }
+
+    fn source_name(&self) -> &str {
+        self.rows.source_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+
+    fn batch(columns: Vec<(&str, ArrayRef)>) -> RecordBatch {
+        RecordBatch::try_from_iter(columns).unwrap()
+    }
+
+    #[test]
+    fn key_join_matches_and_keeps_unmatched_left_rows() {
+        let left = batch(vec![
+            ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            ("value", Arc::new(StringArray::from(vec!["a", "b", "c"])) as ArrayRef),
+        ]);
+        let right = batch(vec![
+            ("id", Arc::new(Int64Array::from(vec![3, 1])) as ArrayRef),
+            ("value", Arc::new(StringArray::from(vec!["z", "x"])) as ArrayRef),
+        ]);
+
+        let spec = |kind| JoinSpec::Key { kind, left_on: vec!["id".into()], right_on: vec!["id".into()] };
+
+        let inner = join_batches(&left, &right, &spec(JoinKind::Inner)).unwrap();
+        assert_eq!(inner.num_rows(), 2);
+        let names: Vec<_> = inner.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, vec!["id", "value", "value_right"]);
+        let matched = inner.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(matched.value(0), "x");
+        assert_eq!(matched.value(1), "z");
+
+        let outer = join_batches(&left, &right, &spec(JoinKind::Left)).unwrap();
+        assert_eq!(outer.num_rows(), 3);
+        assert!(outer.column(2).is_null(1));
+    }
+
+    #[test]
+    fn asof_join_takes_latest_earlier_row_within_tolerance() {
+        let left = batch(vec![("t", Arc::new(Int64Array::from(vec![5, 10, 100])) as ArrayRef)]);
+        let right = batch(vec![
+            ("t", Arc::new(Int64Array::from(vec![9, 0, 4])) as ArrayRef),
+            ("reading", Arc::new(Int32Array::from(vec![90, 0, 40])) as ArrayRef),
+        ]);
+
+        let spec = JoinSpec::AsOf { left_on: "t".into(), right_on: "t".into(), tolerance: Some(5.0) };
+        let joined = join_batches(&left, &right, &spec).unwrap();
+
+        assert_eq!(joined.num_rows(), 3);
+        let readings = joined.column_by_name("reading").unwrap().as_any().downcast_ref::<Int32Array>().unwrap();
+        assert_eq!(readings.value(0), 40);
+        assert_eq!(readings.value(1), 90);
+        assert!(readings.is_null(2));
+    }
+}
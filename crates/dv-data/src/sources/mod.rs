@@ -5,9 +5,57 @@ pub mod sqlite_source;
 pub mod combined_csv_source;
 pub mod configured_csv_source;
 pub mod configured_combined_csv_source;
+pub mod batch_source;
+pub mod joined_source;
+pub mod computed_source;
+pub mod query_source;
+pub mod reshape_source;
+pub mod resample_source;
+pub mod parse_errors;
 
 pub use csv_source::CsvSource;
 pub use sqlite_source::SqliteSource;
 pub use combined_csv_source::CombinedCsvSource;
 pub use configured_csv_source::ConfiguredCsvSource;
-pub use configured_combined_csv_source::ConfiguredCombinedCsvSource; 
\ No newline at end of file
+pub use configured_combined_csv_source::ConfiguredCombinedCsvSource;
+pub use batch_source::BatchSource;
+pub use joined_source::{JoinedSource, JoinSpec, JoinKind};
+pub use computed_source::ComputedSource;
+pub use query_source::QuerySource;
+pub use reshape_source::ReshapeSource;
+pub use resample_source::ResampleSource;
+pub use parse_errors::{ParseErrorLog, ParseFailure};
+
+use arrow::compute::concat_batches;
+use arrow::record_batch::RecordBatch;
+use dv_core::data::DataSource;
+use dv_core::navigation::NavigationRange;
+use crate::DataError;
+
+/// Rows requested per query when reading a source in full
+const READ_CHUNK: usize = 10_000;
+
+/// Read every row of a source into a single batch
+pub async fn read_all(source: &dyn DataSource) -> Result<RecordBatch, DataError> {
+    let total = source.row_count().await.map_err(|e| DataError::Other(e.to_string()))?;
+    let mut batches = Vec::new();
+    let mut start = 0;
+
+    // Sources may cap the rows returned per query, so advance by what came back
+    while start < total {
+        let end = (start + READ_CHUNK).min(total);
+        let batch = source.query_range(&NavigationRange::rows(start, end)).await
+            .map_err(|e| DataError::Other(e.to_string()))?;
+        if batch.num_rows() == 0 {
+            break;
+        }
+        start += batch.num_rows();
+        batches.push(batch);
+    }
+
+    let schema = match batches.first() {
+        Some(batch) => batch.schema(),
+        None => source.schema().await,
+    };
+    Ok(concat_batches(&schema, &batches)?)
+}
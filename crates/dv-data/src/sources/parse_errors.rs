@@ -0,0 +1,174 @@
+//! Record of values that failed to parse while loading
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use arrow::datatypes::DataType;
+use parking_lot::RwLock;
+
+/// Failures kept per column; later ones are counted but not stored
+const MAX_RECORDED_PER_COLUMN: usize = 1000;
+
+/// A value that did not match its column's type
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseFailure {
+    pub column: String,
+
+    /// Data row, counting from 0 after the header
+    pub row: usize,
+
+    /// Raw text of the cell
+    pub value: String,
+
+    /// Type the column is loaded as
+    pub expected: DataType,
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "column '{}', row {}: {:?} is not a valid {}",
+            self.column, self.row + 1, self.value, self.expected
+        )
+    }
+}
+
+/// Failures of one column
+#[derive(Default)]
+struct ColumnFailures {
+    /// Raw text by row, up to `MAX_RECORDED_PER_COLUMN` rows
+    rows: BTreeMap<usize, String>,
+
+    /// Distinct failing rows seen beyond the recorded ones
+    dropped: usize,
+
+    /// Furthest row a dropped failure was seen at, so re-reads aren't counted twice
+    dropped_up_to: Option<usize>,
+
+    expected: Option<DataType>,
+}
+
+/// Shared log of parse failures for one source. Failures are recorded as
+/// chunks are read, so the log grows as more of the file is viewed; reading
+/// the same rows again does not count them twice.
+#[derive(Clone, Default)]
+pub struct ParseErrorLog {
+    columns: Arc<RwLock<BTreeMap<String, ColumnFailures>>>,
+}
+
+impl ParseErrorLog {
+    /// Create an empty log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a failure, ignoring rows already recorded
+    pub fn record(&self, failure: ParseFailure) {
+        let mut columns = self.columns.write();
+        let column = columns.entry(failure.column).or_default();
+        column.expected.get_or_insert(failure.expected);
+        if column.rows.contains_key(&failure.row) {
+            return;
+        }
+        if column.rows.len() < MAX_RECORDED_PER_COLUMN {
+            column.rows.insert(failure.row, failure.value);
+        } else if column.dropped_up_to.is_none_or(|last| failure.row > last) {
+            column.dropped += 1;
+            column.dropped_up_to = Some(failure.row);
+        }
+    }
+
+    /// Failures recorded across all columns
+    pub fn total(&self) -> usize {
+        self.columns.read().values().map(|c| c.rows.len() + c.dropped).sum()
+    }
+
+    /// Failure count per column, in column name order
+    pub fn counts(&self) -> Vec<(String, usize)> {
+        self.columns.read().iter()
+            .map(|(name, c)| (name.clone(), c.rows.len() + c.dropped))
+            .collect()
+    }
+
+    /// Failures of `column`, including those seen beyond the stored ones
+    pub fn count(&self, column: &str) -> usize {
+        self.columns.read().get(column).map_or(0, |c| c.rows.len() + c.dropped)
+    }
+
+    /// Stored failures of `column`, in row order (at most 1000)
+    pub fn failures(&self, column: &str) -> Vec<ParseFailure> {
+        let columns = self.columns.read();
+        let Some(failures) = columns.get(column) else {
+            return Vec::new();
+        };
+        let expected = failures.expected.clone().unwrap_or(DataType::Utf8);
+        failures.rows.iter()
+            .map(|(&row, value)| ParseFailure {
+                column: column.to_string(),
+                row,
+                value: value.clone(),
+                expected: expected.clone(),
+            })
+            .collect()
+    }
+
+    /// Forget all failures
+    pub fn clear(&self) {
+        self.columns.write().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(column: &str, row: usize) -> ParseFailure {
+        ParseFailure {
+            column: column.to_string(),
+            row,
+            value: format!("bad{}", row),
+            expected: DataType::Int64,
+        }
+    }
+
+    #[test]
+    fn rereading_rows_does_not_count_them_twice() {
+        let log = ParseErrorLog::new();
+        for row in [5, 2, 5, 9, 2] {
+            log.record(failure("a", row));
+        }
+        log.record(failure("b", 0));
+
+        assert_eq!(log.count("a"), 3);
+        assert_eq!(log.counts(), vec![("a".to_string(), 3), ("b".to_string(), 1)]);
+        assert_eq!(log.total(), 4);
+        let rows: Vec<usize> = log.failures("a").iter().map(|f| f.row).collect();
+        assert_eq!(rows, vec![2, 5, 9]);
+        assert_eq!(log.failures("a")[0], failure("a", 2));
+        assert_eq!(log.count("missing"), 0);
+        assert!(log.failures("missing").is_empty());
+
+        log.clear();
+        assert_eq!(log.total(), 0);
+    }
+
+    #[test]
+    fn failures_beyond_the_stored_ones_are_counted() {
+        let log = ParseErrorLog::new();
+        for row in 0..MAX_RECORDED_PER_COLUMN + 10 {
+            log.record(failure("a", row));
+        }
+        // Reading the dropped rows again
+        for row in MAX_RECORDED_PER_COLUMN..MAX_RECORDED_PER_COLUMN + 10 {
+            log.record(failure("a", row));
+        }
+        // A stored row again, then new rows further on
+        log.record(failure("a", 3));
+        log.record(failure("a", MAX_RECORDED_PER_COLUMN + 20));
+
+        assert_eq!(log.failures("a").len(), MAX_RECORDED_PER_COLUMN);
+        assert_eq!(log.count("a"), MAX_RECORDED_PER_COLUMN + 11);
+        assert_eq!(log.total(), MAX_RECORDED_PER_COLUMN + 11);
+    }
+}
@@ -0,0 +1,109 @@
+//! Data source holding the result of a SQL query
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+use dv_core::navigation::{NavigationPosition, NavigationSpec, NavigationRange};
+use dv_core::data::DataSource;
+use crate::DataError;
+use crate::sql::SqlEngine;
+use super::BatchSource;
+
+/// Named, in-memory result of a SQL query
+pub struct QuerySource {
+    /// Query result
+    rows: BatchSource,
+
+    /// Query that produced the rows
+    sql: String,
+}
+
+impl QuerySource {
+    /// Run `sql` on `engine` and keep the result under `name`
+    pub fn new(name: String, engine: &SqlEngine, sql: &str) -> Result<Self, DataError> {
+        Ok(Self {
+            rows: BatchSource::new(name, engine.query(sql)?),
+            sql: sql.to_string(),
+        })
+    }
+
+    /// The query that produced this source
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+}
+
+#[async_trait]
+impl DataSource for QuerySource {
+    async fn schema(&self) -> Arc<Schema> {
+        self.rows.schema().await
+    }
+
+    async fn navigation_spec(&self) -> anyhow::Result<NavigationSpec> {
+        self.rows.navigation_spec().await
+    }
+
+    async fn query_at(&self, position: &NavigationPosition) -> anyhow::Result<RecordBatch> {
+        self.rows.query_at(position).await
+    }
+
+    async fn query_range(&self, range: &NavigationRange) -> anyhow::Result<RecordBatch> {
+        self.rows.query_range(range).await
+    }
+
+    async fn row_count(&self) -> anyhow::Result<usize> {
+        self.rows.row_count().await
+    }
+
+    fn source_name(&self) -> &str {
+        self.rows.source_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, ArrayRef, Float64Array, StringArray};
+
+    fn engine() -> SqlEngine {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("sensor", Arc::new(StringArray::from(vec!["a", "b", "a", "c"])) as ArrayRef),
+            ("value", Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0, 4.0])) as ArrayRef),
+        ]).unwrap();
+        let mut engine = SqlEngine::new().unwrap();
+        engine.register_batch("readings", &batch).unwrap();
+        engine
+    }
+
+    #[tokio::test]
+    async fn keeps_the_query_and_its_rows() {
+        let sql = "SELECT sensor, SUM(value) AS total FROM readings GROUP BY sensor ORDER BY sensor";
+        let source = QuerySource::new("Totals".to_string(), &engine(), sql).unwrap();
+        assert_eq!(source.sql(), sql);
+        assert_eq!(source.source_name(), "Totals");
+        assert_eq!(source.row_count().await.unwrap(), 3);
+
+        let schema = source.schema().await;
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["sensor", "total"]);
+
+        let rows = source.query_range(&NavigationRange::rows(1, 3)).await.unwrap();
+        let totals = rows.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(totals.values().to_vec(), vec![2.0, 4.0]);
+    }
+
+    #[tokio::test]
+    async fn empty_results_have_no_rows() {
+        let source = QuerySource::new("None".to_string(), &engine(), "SELECT * FROM readings WHERE value > 10").unwrap();
+        assert_eq!(source.row_count().await.unwrap(), 0);
+        assert_eq!(source.navigation_spec().await.unwrap().total_rows, 0);
+        assert_eq!(source.query_at(&NavigationPosition::Sequential(0)).await.unwrap().num_rows(), 0);
+    }
+
+    #[test]
+    fn invalid_queries_are_errors() {
+        assert!(QuerySource::new("Bad".to_string(), &engine(), "SELECT nope FROM readings").is_err());
+        assert!(QuerySource::new("Bad".to_string(), &engine(), "SELEC 1").is_err());
+    }
+}
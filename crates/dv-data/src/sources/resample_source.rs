@@ -0,0 +1,254 @@
+//! Data source wrapper resampling onto a fixed time grid
+
+use std::sync::Arc;
+use async_trait::async_trait;
+use arrow::array::{Array, ArrayRef, Float64Array, Int64Array, TimestampMillisecondArray, UInt64Array};
+use arrow::compute::{cast, take};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use dv_core::navigation::{NavigationPosition, NavigationSpec, NavigationRange};
+use dv_core::data::DataSource;
+use crate::DataError;
+use crate::config::{Aggregation, GapFill, ResampleSpec};
+use super::{read_all, BatchSource};
+
+/// Upper bound on grid rows, to catch intervals far too small for the data
+const MAX_INTERVALS: i64 = 5_000_000;
+
+/// Data source holding another source resampled to a fixed interval
+pub struct ResampleSource {
+    /// Resampled rows, one per interval, under the source's name
+    rows: BatchSource,
+}
+
+impl ResampleSource {
+    /// Resample every row of `inner`
+    pub async fn new(inner: Box<dyn DataSource>, spec: &ResampleSpec) -> Result<Self, DataError> {
+        let batch = resample(&read_all(inner.as_ref()).await?, spec)?;
+        Ok(Self {
+            rows: BatchSource::new(inner.source_name(), batch),
+        })
+    }
+}
+
+/// Time column named in `spec`, or the first timestamp column
+fn time_column(schema: &Schema, spec: &ResampleSpec) -> Result<String, DataError> {
+    match &spec.time_column {
+        Some(name) => schema.field_with_name(name)
+            .map(|f| f.name().clone())
+            .map_err(|_| DataError::Other(format!("Time column '{}' not found", name))),
+        None => schema.fields().iter()
+            .find(|f| matches!(f.data_type(), DataType::Timestamp(_, _) | DataType::Date32 | DataType::Date64))
+            .map(|f| f.name().clone())
+            .ok_or_else(|| DataError::Other("Resampling needs a timestamp column".to_string())),
+    }
+}
+
+/// Resample a batch: one row per interval from the first to the last timestamp
+pub fn resample(batch: &RecordBatch, spec: &ResampleSpec) -> Result<RecordBatch, DataError> {
+    if spec.interval_ms <= 0 {
+        return Err(DataError::Other("Resampling interval must be positive".to_string()));
+    }
+    let interval = spec.interval_ms;
+    let schema = batch.schema();
+    let time_name = time_column(&schema, spec)?;
+
+    let times = batch.column_by_name(&time_name)
+        .ok_or_else(|| DataError::Other(format!("Time column '{}' not found", time_name)))?;
+    let times = cast(&cast(times, &DataType::Timestamp(TimeUnit::Millisecond, None))?, &DataType::Int64)?;
+    let times = times.as_any().downcast_ref::<Int64Array>()
+        .ok_or_else(|| DataError::Other("Failed to read timestamps".to_string()))?;
+
+    // Grid from the interval holding the earliest time to the one holding the latest
+    let (min, max) = times.iter().flatten()
+        .fold((i64::MAX, i64::MIN), |(lo, hi), t| (lo.min(t), hi.max(t)));
+    let (origin, count) = if min > max {
+        (0, 0)
+    } else {
+        let origin = min.div_euclid(interval) * interval;
+        let count = (max - origin) / interval + 1;
+        if count > MAX_INTERVALS {
+            return Err(DataError::Other(format!(
+                "Resampling to {} ms would produce {} rows; choose a longer interval", interval, count
+            )));
+        }
+        (origin, count as usize)
+    };
+    let bucket_of = |row: usize| -> Option<usize> {
+        times.is_valid(row).then(|| ((times.value(row) - origin) / interval) as usize)
+    };
+
+    let mut fields = Vec::new();
+    let mut arrays: Vec<ArrayRef> = Vec::new();
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        if field.name() == &time_name {
+            let grid: Vec<i64> = (0..count as i64).map(|i| origin + i * interval).collect();
+            fields.push(Field::new(field.name(), DataType::Timestamp(TimeUnit::Millisecond, None), false));
+            arrays.push(Arc::new(TimestampMillisecondArray::from(grid)));
+            continue;
+        }
+
+        if field.data_type().is_numeric() {
+            let values = aggregate(column, count, &bucket_of, times, spec.aggregation_for(field.name()))?;
+            let values = fill_values(values, spec.gap_fill);
+            fields.push(Field::new(field.name(), DataType::Float64, true));
+            arrays.push(Arc::new(Float64Array::from(values)));
+        } else {
+            // Non-numeric columns keep the latest value in each interval
+            let mut latest: Vec<Option<(i64, usize)>> = vec![None; count];
+            for row in 0..batch.num_rows() {
+                if let (Some(bucket), true) = (bucket_of(row), column.is_valid(row)) {
+                    let t = times.value(row);
+                    if latest[bucket].is_none_or(|(last, _)| t >= last) {
+                        latest[bucket] = Some((t, row));
+                    }
+                }
+            }
+            let mut rows: Vec<Option<u64>> = latest.iter().map(|l| l.map(|(_, row)| row as u64)).collect();
+            if spec.gap_fill != GapFill::None {
+                forward_fill(&mut rows);
+            }
+            fields.push(field.as_ref().clone().with_nullable(true));
+            arrays.push(take(column.as_ref(), &UInt64Array::from(rows), None)?);
+        }
+    }
+
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+/// Combine the values of each interval
+fn aggregate(
+    column: &ArrayRef,
+    count: usize,
+    bucket_of: &dyn Fn(usize) -> Option<usize>,
+    times: &Int64Array,
+    aggregation: Aggregation,
+) -> Result<Vec<Option<f64>>, DataError> {
+    let values = cast(column, &DataType::Float64)?;
+    let values = values.as_any().downcast_ref::<Float64Array>()
+        .ok_or_else(|| DataError::Other("Failed to read numeric values".to_string()))?;
+
+    let mut sums = vec![0.0; count];
+    let mut counts = vec![0usize; count];
+    let mut maxima = vec![f64::NEG_INFINITY; count];
+    let mut latest: Vec<Option<(i64, f64)>> = vec![None; count];
+
+    for row in 0..values.len() {
+        let Some(bucket) = bucket_of(row) else { continue };
+        if values.is_null(row) {
+            continue;
+        }
+        let value = values.value(row);
+        sums[bucket] += value;
+        counts[bucket] += 1;
+        maxima[bucket] = maxima[bucket].max(value);
+        let t = times.value(row);
+        if latest[bucket].is_none_or(|(last, _)| t >= last) {
+            latest[bucket] = Some((t, value));
+        }
+    }
+
+    Ok((0..count)
+        .map(|bucket| {
+            if counts[bucket] == 0 {
+                return None;
+            }
+            Some(match aggregation {
+                Aggregation::Mean => sums[bucket] / counts[bucket] as f64,
+                Aggregation::Sum => sums[bucket],
+                Aggregation::Max => maxima[bucket],
+                Aggregation::Last => latest[bucket].map(|(_, v)| v).unwrap_or(f64::NAN),
+            })
+        })
+        .collect())
+}
+
+fn forward_fill<T: Copy>(values: &mut [Option<T>]) {
+    let mut previous = None;
+    for value in values.iter_mut() {
+        match value {
+            Some(v) => previous = Some(*v),
+            None => *value = previous,
+        }
+    }
+}
+
+/// Fill empty intervals; leading and trailing gaps stay null for linear filling
+fn fill_values(mut values: Vec<Option<f64>>, gap_fill: GapFill) -> Vec<Option<f64>> {
+    match gap_fill {
+        GapFill::None => {}
+        GapFill::ForwardFill => forward_fill(&mut values),
+        GapFill::Linear => {
+            let mut previous: Option<(usize, f64)> = None;
+            for i in 0..values.len() {
+                if let Some(value) = values[i] {
+                    if let Some((j, start)) = previous {
+                        let span = (i - j) as f64;
+                        for (k, slot) in values[j + 1..i].iter_mut().enumerate() {
+                            *slot = Some(start + (value - start) * (k + 1) as f64 / span);
+                        }
+                    }
+                    previous = Some((i, value));
+                }
+            }
+        }
+    }
+    values
+}
+
+#[async_trait]
+impl DataSource for ResampleSource {
+    async fn schema(&self) -> Arc<Schema> {
+        self.rows.schema().await
+    }
+
+    async fn navigation_spec(&self) -> anyhow::Result<NavigationSpec> {
+        self.rows.navigation_spec().await
+    }
+
+    async fn query_at(&self, position: &NavigationPosition) -> anyhow::Result<RecordBatch> {
+        self.rows.query_at(position).await
+    }
+
+    async fn query_range(&self, range: &NavigationRange) -> anyhow::Result<RecordBatch> {
+        self.rows.query_range(range).await
+    }
+
+    async fn row_count(&self) -> anyhow::Result<usize> {
+        self.rows.row_count().await
+    }
+
+    fn source_name(&self) -> &str {
+        self.rows.source_name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resamples_to_a_grid_with_aggregation_and_gap_filling() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("t", Arc::new(TimestampMillisecondArray::from(vec![1_000, 1_500, 3_200])) as ArrayRef),
+            ("v", Arc::new(Float64Array::from(vec![1.0, 3.0, 8.0])) as ArrayRef),
+        ]).unwrap();
+
+        let mut spec = ResampleSpec::new(1_000);
+        let mean = resample(&batch, &spec).unwrap();
+        assert_eq!(mean.num_rows(), 3);
+        let values = mean.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(values.iter().collect::<Vec<_>>(), vec![Some(2.0), None, Some(8.0)]);
+
+        spec.column_aggregations.insert("v".to_string(), Aggregation::Max);
+        spec.gap_fill = GapFill::Linear;
+        let filled = resample(&batch, &spec).unwrap();
+        let values = filled.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(values.iter().collect::<Vec<_>>(), vec![Some(3.0), Some(5.5), Some(8.0)]);
+
+        spec.gap_fill = GapFill::ForwardFill;
+        let filled = resample(&batch, &spec).unwrap();
+        let values = filled.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(values.value(1), 3.0);
+    }
+}
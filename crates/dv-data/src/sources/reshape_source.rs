@@ -0,0 +1,384 @@
+//! Data source wrapper reshaping between wide and long layouts
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use arrow::array::{Array, ArrayRef, StringArray, UInt32Array, UInt64Array};
+use arrow::compute::{cast, interleave, take};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
+use arrow::util::display::array_value_to_string;
+use dv_core::navigation::{NavigationPosition, NavigationSpec, NavigationMode, NavigationRange};
+use dv_core::data::DataSource;
+use crate::DataError;
+use crate::config::ReshapeSpec;
+use super::read_all;
+
+/// How the wrapped source is reshaped
+enum Layout {
+    /// Rows are melted on the fly; each inner row yields one row per value column
+    Melt {
+        inner: Box<dyn DataSource>,
+        id_columns: Vec<String>,
+        value_columns: Vec<String>,
+        value_type: DataType,
+    },
+
+    /// Pivoted result, computed once
+    Pivot { batch: RecordBatch },
+}
+
+/// Data source that melts wide data to long format or pivots long data to wide
+pub struct ReshapeSource {
+    /// Reshaping
+    layout: Layout,
+
+    /// Output schema, known up front
+    schema: Arc<Schema>,
+
+    /// Output row count
+    row_count: usize,
+
+    /// Source name
+    name: String,
+}
+
+impl ReshapeSource {
+    /// Wrap `inner` with the given reshaping
+    pub async fn new(inner: Box<dyn DataSource>, spec: &ReshapeSpec) -> Result<Self, DataError> {
+        let inner_schema = inner.schema().await;
+        let name = inner.source_name().to_string();
+        let missing = |column: &String| {
+            DataError::Other(format!("Reshape column '{}' not found", column))
+        };
+
+        match spec {
+            ReshapeSpec::Melt { value_columns, key_name, value_name } => {
+                if value_columns.is_empty() {
+                    return Err(DataError::Other("Select at least one column to melt".to_string()));
+                }
+                let mut value_fields = Vec::new();
+                for column in value_columns {
+                    value_fields.push(inner_schema.field_with_name(column).map_err(|_| missing(column))?);
+                }
+
+                // Numeric columns melt into Float64; anything else into text
+                let value_type = if value_fields.iter().all(|f| f.data_type().is_numeric()) {
+                    DataType::Float64
+                } else {
+                    DataType::Utf8
+                };
+
+                let id_fields: Vec<Field> = inner_schema.fields().iter()
+                    .filter(|f| !value_columns.contains(f.name()))
+                    .map(|f| f.as_ref().clone())
+                    .collect();
+                let id_columns: Vec<String> = id_fields.iter().map(|f| f.name().clone()).collect();
+                if key_name == value_name {
+                    return Err(DataError::Other(format!("Melted key and value columns are both named '{}'", key_name)));
+                }
+                if let Some(taken) = [key_name, value_name].into_iter().find(|name| id_columns.contains(name)) {
+                    return Err(DataError::Other(format!("Melted column '{}' is already a column of the source", taken)));
+                }
+
+                let mut fields = id_fields;
+                fields.push(Field::new(key_name, DataType::Utf8, false));
+                fields.push(Field::new(value_name, value_type.clone(), true));
+
+                let row_count = inner.row_count().await.map_err(|e| DataError::Other(e.to_string()))?
+                    * value_columns.len();
+
+                Ok(Self {
+                    layout: Layout::Melt {
+                        inner,
+                        id_columns,
+                        value_columns: value_columns.clone(),
+                        value_type,
+                    },
+                    schema: Arc::new(Schema::new(fields)),
+                    row_count,
+                    name,
+                })
+            }
+            ReshapeSpec::Pivot { index_columns, key_column, value_column } => {
+                for column in index_columns.iter().chain([key_column, value_column]) {
+                    inner_schema.field_with_name(column).map_err(|_| missing(column))?;
+                }
+                if let Some(column) = index_columns.iter().find(|c| *c == key_column || *c == value_column) {
+                    return Err(DataError::Other(format!("Column '{}' can't both index and fill the pivot", column)));
+                }
+                let batch = pivot(&read_all(inner.as_ref()).await?, index_columns, key_column, value_column)?;
+
+                Ok(Self {
+                    schema: batch.schema(),
+                    row_count: batch.num_rows(),
+                    layout: Layout::Pivot { batch },
+                    name,
+                })
+            }
+        }
+    }
+}
+
+/// Melt a batch: every row becomes one row per value column
+fn melt(
+    batch: &RecordBatch,
+    schema: &Arc<Schema>,
+    id_columns: &[String],
+    value_columns: &[String],
+    value_type: &DataType,
+) -> Result<RecordBatch, DataError> {
+    let width = value_columns.len();
+    let rows = batch.num_rows();
+    let column = |name: &String| batch.column_by_name(name)
+        .ok_or_else(|| DataError::Other(format!("Reshape column '{}' not found", name)));
+
+    let repeat = UInt32Array::from_iter_values((0..rows as u32).flat_map(|row| std::iter::repeat_n(row, width)));
+    let mut arrays: Vec<ArrayRef> = Vec::new();
+    for name in id_columns {
+        arrays.push(take(column(name)?.as_ref(), &repeat, None)?);
+    }
+
+    let keys: StringArray = (0..rows).flat_map(|_| value_columns.iter().map(Some)).collect();
+    arrays.push(Arc::new(keys));
+
+    let values = value_columns.iter()
+        .map(|name| Ok(cast(column(name)?, value_type)?))
+        .collect::<Result<Vec<ArrayRef>, DataError>>()?;
+    let sources: Vec<&dyn Array> = values.iter().map(|a| a.as_ref()).collect();
+    let order: Vec<(usize, usize)> = (0..rows).flat_map(|row| (0..width).map(move |k| (k, row))).collect();
+    arrays.push(interleave(&sources, &order)?);
+
+    Ok(RecordBatch::try_new(schema.clone(), arrays)?)
+}
+
+/// Pivot long data: one row per index combination (in order of first
+/// appearance) and one column per distinct key; the last value wins. Keys
+/// named like an index column get a numeric suffix.
+pub fn pivot(
+    batch: &RecordBatch,
+    index_columns: &[String],
+    key_column: &str,
+    value_column: &str,
+) -> Result<RecordBatch, DataError> {
+    let column = |name: &str| batch.column_by_name(name)
+        .cloned()
+        .ok_or_else(|| DataError::Other(format!("Reshape column '{}' not found", name)));
+
+    let index = index_columns.iter().map(|name| column(name)).collect::<Result<Vec<_>, _>>()?;
+    let keys = column(key_column)?;
+    let values = column(value_column)?;
+
+    // Group rows by their index values
+    let mut group_of_row = Vec::with_capacity(batch.num_rows());
+    let mut first_rows: Vec<u64> = Vec::new();
+    if index.is_empty() {
+        if batch.num_rows() > 0 {
+            first_rows.push(0);
+        }
+        group_of_row.resize(batch.num_rows(), 0);
+    } else {
+        let converter = RowConverter::new(
+            index.iter().map(|a| SortField::new(a.data_type().clone())).collect()
+        )?;
+        let encoded = converter.convert_columns(&index)?;
+        let mut groups = HashMap::new();
+        for row in 0..batch.num_rows() {
+            let group = *groups.entry(encoded.row(row)).or_insert_with(|| {
+                first_rows.push(row as u64);
+                first_rows.len() - 1
+            });
+            group_of_row.push(group);
+        }
+    }
+
+    // One output column per distinct key
+    let mut key_names: Vec<String> = Vec::new();
+    let mut cells: Vec<Vec<Option<u64>>> = Vec::new();
+    for (row, &group) in group_of_row.iter().enumerate() {
+        if keys.is_null(row) {
+            continue;
+        }
+        let key = array_value_to_string(&keys, row)?;
+        let k = match key_names.iter().position(|name| name == &key) {
+            Some(k) => k,
+            None => {
+                key_names.push(key);
+                cells.push(vec![None; first_rows.len()]);
+                key_names.len() - 1
+            }
+        };
+        cells[k][group] = Some(row as u64);
+    }
+
+    let first_rows = UInt64Array::from(first_rows);
+    let mut fields = Vec::new();
+    let mut arrays = Vec::new();
+    for (name, array) in index_columns.iter().zip(&index) {
+        fields.push(batch.schema().field_with_name(name)?.clone());
+        arrays.push(take(array.as_ref(), &first_rows, None)?);
+    }
+    for (name, rows) in key_names.iter().zip(cells) {
+        // Clear of the columns so far and of the other keys
+        let taken = |candidate: &String, fields: &[Field]| {
+            fields.iter().any(|f| f.name() == candidate) || (candidate != name && key_names.contains(candidate))
+        };
+        let mut unique = name.clone();
+        let mut suffix = 1;
+        while taken(&unique, &fields) {
+            suffix += 1;
+            unique = format!("{}_{}", name, suffix);
+        }
+        fields.push(Field::new(unique, values.data_type().clone(), true));
+        arrays.push(take(values.as_ref(), &UInt64Array::from(rows), None)?);
+    }
+
+    let schema = Arc::new(Schema::new(fields));
+    if arrays.is_empty() {
+        return Ok(RecordBatch::new_empty(schema));
+    }
+    Ok(RecordBatch::try_new(schema, arrays)?)
+}
+
+#[async_trait]
+impl DataSource for ReshapeSource {
+    async fn schema(&self) -> Arc<Schema> {
+        self.schema.clone()
+    }
+
+    async fn navigation_spec(&self) -> anyhow::Result<NavigationSpec> {
+        Ok(NavigationSpec {
+            mode: NavigationMode::Sequential,
+            total_rows: self.row_count,
+            temporal_bounds: None,
+            categories: None,
+        })
+    }
+
+    async fn query_at(&self, position: &NavigationPosition) -> anyhow::Result<RecordBatch> {
+        let row_idx = match position {
+            NavigationPosition::Sequential(idx) => *idx,
+            _ => return Err(DataError::InvalidPosition.into()),
+        };
+
+        // Query a window around the position
+        let window_size = 1000;
+        let start = row_idx.saturating_sub(window_size / 2);
+        let end = (start + window_size).min(self.row_count);
+
+        self.query_range(&NavigationRange::rows(start, end)).await
+    }
+
+    async fn query_range(&self, range: &NavigationRange) -> anyhow::Result<RecordBatch> {
+        let (start, end) = match (&range.start, &range.end) {
+            (NavigationPosition::Sequential(s), NavigationPosition::Sequential(e)) => (*s, *e),
+            _ => return Err(DataError::InvalidPosition.into()),
+        };
+        let start = start.min(self.row_count);
+        let end = end.clamp(start, self.row_count);
+
+        match &self.layout {
+            Layout::Pivot { batch } => Ok(batch.slice(start, end - start)),
+            Layout::Melt { inner, id_columns, value_columns, value_type } => {
+                let width = value_columns.len();
+                let inner_start = start / width;
+                let inner_end = end.div_ceil(width);
+                let batch = inner.query_range(&NavigationRange::rows(inner_start, inner_end)).await?;
+                let melted = melt(&batch, &self.schema, id_columns, value_columns, value_type)?;
+
+                let skip = (start - inner_start * width).min(melted.num_rows());
+                let len = (end - start).min(melted.num_rows() - skip);
+                Ok(melted.slice(skip, len))
+            }
+        }
+    }
+
+    async fn row_count(&self) -> anyhow::Result<usize> {
+        Ok(self.row_count)
+    }
+
+    fn source_name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, Int64Array};
+    use crate::sources::BatchSource;
+
+    #[test]
+    fn melt_then_pivot_round_trips() {
+        let wide = RecordBatch::try_from_iter(vec![
+            ("t", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+            ("a", Arc::new(Float64Array::from(vec![10.0, 20.0])) as ArrayRef),
+            ("b", Arc::new(Int64Array::from(vec![30, 40])) as ArrayRef),
+        ]).unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("t", DataType::Int64, true),
+            Field::new("sensor", DataType::Utf8, false),
+            Field::new("value", DataType::Float64, true),
+        ]));
+        let columns = vec!["a".to_string(), "b".to_string()];
+        let long = melt(&wide, &schema, &["t".to_string()], &columns, &DataType::Float64).unwrap();
+
+        assert_eq!(long.num_rows(), 4);
+        let sensors = long.column(1).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(sensors.iter().flatten().collect::<Vec<_>>(), vec!["a", "b", "a", "b"]);
+        let values = long.column(2).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(values.values().to_vec(), vec![10.0, 30.0, 20.0, 40.0]);
+
+        let back = pivot(&long, &["t".to_string()], "sensor", "value").unwrap();
+        assert_eq!(back.num_rows(), 2);
+        let names: Vec<_> = back.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, vec!["t", "a", "b"]);
+        let b = back.column(2).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(b.values().to_vec(), vec![30.0, 40.0]);
+    }
+
+    #[test]
+    fn pivoted_keys_never_shadow_index_columns() {
+        let long = RecordBatch::try_from_iter(vec![
+            ("t", Arc::new(Int64Array::from(vec![1, 1, 1])) as ArrayRef),
+            ("key", Arc::new(StringArray::from(vec!["t", "t_2", "x"])) as ArrayRef),
+            ("value", Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0])) as ArrayRef),
+        ]).unwrap();
+
+        let wide = pivot(&long, &["t".to_string()], "key", "value").unwrap();
+        let names: Vec<_> = wide.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, vec!["t", "t_3", "t_2", "x"]);
+        let renamed = wide.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(renamed.value(0), 1.0);
+    }
+
+    #[tokio::test]
+    async fn colliding_reshape_names_are_rejected() {
+        let batch = RecordBatch::try_from_iter(vec![
+            ("t", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+            ("a", Arc::new(Float64Array::from(vec![10.0, 20.0])) as ArrayRef),
+        ]).unwrap();
+        let source = || Box::new(BatchSource::new("wide", batch.clone())) as Box<dyn DataSource>;
+        let melt = |key: &str, value: &str| ReshapeSpec::Melt {
+            value_columns: vec!["a".to_string()],
+            key_name: key.to_string(),
+            value_name: value.to_string(),
+        };
+
+        assert!(ReshapeSource::new(source(), &melt("t", "value")).await.is_err());
+        assert!(ReshapeSource::new(source(), &melt("sensor", "t")).await.is_err());
+        assert!(ReshapeSource::new(source(), &melt("same", "same")).await.is_err());
+        // A value column's name is free once it's melted
+        let melted = ReshapeSource::new(source(), &melt("sensor", "a")).await.unwrap();
+        assert_eq!(melted.row_count().await.unwrap(), 2);
+
+        let pivot = ReshapeSpec::Pivot {
+            index_columns: vec!["t".to_string()],
+            key_column: "t".to_string(),
+            value_column: "a".to_string(),
+        };
+        assert!(ReshapeSource::new(source(), &pivot).await.is_err());
+    }
+}
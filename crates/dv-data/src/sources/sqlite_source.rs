@@ -52,6 +52,18 @@ impl SqliteSource {
         })
     }
     
+    /// Names of the user tables in a database
+    pub fn list_tables<P: AsRef<Path>>(path: P) -> Result<Vec<String>, DataError> {
+        let conn = Connection::open(path)
+            .map_err(|e| DataError::Other(format!("Failed to open SQLite database: {}", e)))?;
+        let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type='table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .map_err(|e| DataError::Other(format!("Failed to list tables: {}", e)))?;
+        let tables = stmt.query_map([], |row| row.get::<_, String>(0))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| DataError::Other(format!("Failed to list tables: {}", e)))?;
+        Ok(tables)
+    }
+
     /// Detect schema from SQLite table
     fn detect_schema(conn: &Connection, table_name: &str) -> Result<Schema, DataError> {
         let query = format!("PRAGMA table_info({})", table_name);
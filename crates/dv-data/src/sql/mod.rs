@@ -0,0 +1,278 @@
+//! Ad hoc SQL over loaded data sources
+//!
+//! Registered sources are copied into an in-memory SQLite database, so any
+//! source (CSV included) can be filtered, grouped and joined with plain SQL.
+//! Query results come back as Arrow batches and can be wrapped in a
+//! [`QuerySource`](crate::sources::QuerySource) for views to bind to.
+
+use std::sync::Arc;
+use arrow::array::{Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+use rusqlite::{Connection, types::Value};
+use dv_core::data::DataSource;
+use crate::DataError;
+use crate::sources::read_all;
+
+/// In-memory SQL engine holding copies of registered sources as tables
+pub struct SqlEngine {
+    conn: Connection,
+    tables: Vec<String>,
+}
+
+fn sql_error(e: rusqlite::Error) -> DataError {
+    DataError::Sqlite(e.to_string())
+}
+
+/// Quote an identifier for use in SQL
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+impl SqlEngine {
+    /// Create an empty engine
+    pub fn new() -> Result<Self, DataError> {
+        Ok(Self {
+            conn: Connection::open_in_memory().map_err(sql_error)?,
+            tables: Vec::new(),
+        })
+    }
+
+    /// Names of the registered tables
+    pub fn tables(&self) -> &[String] {
+        &self.tables
+    }
+
+    /// Turn a source name such as `sensor data.csv` into a table name
+    /// (`sensor_data`) that is not yet registered
+    pub fn table_name_for(&self, source_name: &str) -> String {
+        let stem = std::path::Path::new(source_name)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(source_name);
+        let mut base: String = stem.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        if base.is_empty() || base.starts_with(|c: char| c.is_ascii_digit()) {
+            base.insert(0, 't');
+        }
+
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while self.tables.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        name
+    }
+
+    /// Copy every row of `source` into a table, replacing any table of that name
+    pub async fn register(&mut self, table: &str, source: &dyn DataSource) -> Result<(), DataError> {
+        let batch = read_all(source).await?;
+        self.register_batch(table, &batch)
+    }
+
+    /// Copy a batch into a table, replacing any table of that name
+    pub fn register_batch(&mut self, table: &str, batch: &RecordBatch) -> Result<(), DataError> {
+        let schema = batch.schema();
+        let columns: Vec<String> = schema.fields().iter()
+            .map(|field| format!("{} {}", quote(field.name()), declared_type(field.data_type())))
+            .collect();
+
+        let tx = self.conn.transaction().map_err(sql_error)?;
+        tx.execute(&format!("DROP TABLE IF EXISTS {}", quote(table)), []).map_err(sql_error)?;
+        tx.execute(&format!("CREATE TABLE {} ({})", quote(table), columns.join(", ")), []).map_err(sql_error)?;
+        {
+            let placeholders = vec!["?"; schema.fields().len()].join(", ");
+            let mut insert = tx.prepare(&format!("INSERT INTO {} VALUES ({})", quote(table), placeholders))
+                .map_err(sql_error)?;
+            let prepared = batch.columns().iter().map(SqlColumn::new).collect::<Result<Vec<_>, _>>()?;
+            let mut row = Vec::with_capacity(prepared.len());
+            for i in 0..batch.num_rows() {
+                row.clear();
+                for column in &prepared {
+                    row.push(column.value(i)?);
+                }
+                insert.execute(rusqlite::params_from_iter(row.iter())).map_err(sql_error)?;
+            }
+        }
+        tx.commit().map_err(sql_error)?;
+
+        if !self.tables.iter().any(|t| t == table) {
+            self.tables.push(table.to_string());
+        }
+        Ok(())
+    }
+
+    /// Write every registered table to a SQLite database file
+    pub fn save(&self, path: &std::path::Path) -> Result<(), DataError> {
+        self.conn.backup(rusqlite::DatabaseName::Main, path, None).map_err(sql_error)
+    }
+
+    /// Run a query and collect its rows
+    pub fn query(&self, sql: &str) -> Result<RecordBatch, DataError> {
+        let mut stmt = self.conn.prepare(sql).map_err(sql_error)?;
+        let names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
+        let declared: Vec<Option<String>> = stmt.columns().iter()
+            .map(|c| c.decl_type().map(|t| t.to_uppercase()))
+            .collect();
+
+        let mut values: Vec<Vec<Value>> = vec![Vec::new(); names.len()];
+        let mut rows = stmt.query([]).map_err(sql_error)?;
+        while let Some(row) = rows.next().map_err(sql_error)? {
+            for (i, column) in values.iter_mut().enumerate() {
+                column.push(row.get::<_, Value>(i).map_err(sql_error)?);
+            }
+        }
+
+        let mut fields = Vec::with_capacity(names.len());
+        let mut arrays = Vec::with_capacity(names.len());
+        for ((name, declared), column) in names.iter().zip(&declared).zip(&values) {
+            let array = to_arrow(column, declared.as_deref())?;
+            fields.push(Field::new(name, array.data_type().clone(), true));
+            arrays.push(array);
+        }
+
+        let schema = Arc::new(Schema::new(fields));
+        if arrays.is_empty() {
+            return Ok(RecordBatch::new_empty(schema));
+        }
+        Ok(RecordBatch::try_new(schema, arrays)?)
+    }
+}
+
+/// SQLite column type used for an Arrow type; temporal types are stored as
+/// ISO text so SQLite's date functions work on them
+fn declared_type(data_type: &DataType) -> &'static str {
+    match data_type {
+        DataType::Boolean => "BOOLEAN",
+        DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64
+        | DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => "INTEGER",
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => "REAL",
+        DataType::Date32 | DataType::Date64 => "DATE",
+        DataType::Timestamp(_, _) => "TIMESTAMP",
+        _ => "TEXT",
+    }
+}
+
+/// A column prepared for insertion, cast once rather than per value
+enum SqlColumn {
+    Boolean(BooleanArray),
+    Integer(Int64Array),
+    Real(Float64Array),
+    Text(ArrayRef),
+}
+
+impl SqlColumn {
+    fn new(column: &ArrayRef) -> Result<Self, DataError> {
+        let cast_as = |data_type: &DataType| cast(column, data_type);
+        Ok(match declared_type(column.data_type()) {
+            "BOOLEAN" => SqlColumn::Boolean(cast_as(&DataType::Boolean)?.as_any().downcast_ref::<BooleanArray>().cloned()
+                .ok_or_else(|| DataError::Other("Expected a boolean column".to_string()))?),
+            "INTEGER" => SqlColumn::Integer(cast_as(&DataType::Int64)?.as_any().downcast_ref::<Int64Array>().cloned()
+                .ok_or_else(|| DataError::Other("Expected an integer column".to_string()))?),
+            "REAL" => SqlColumn::Real(cast_as(&DataType::Float64)?.as_any().downcast_ref::<Float64Array>().cloned()
+                .ok_or_else(|| DataError::Other("Expected a numeric column".to_string()))?),
+            _ => SqlColumn::Text(column.clone()),
+        })
+    }
+
+    fn value(&self, row: usize) -> Result<Value, DataError> {
+        Ok(match self {
+            SqlColumn::Boolean(array) if array.is_valid(row) => Value::Integer(array.value(row) as i64),
+            SqlColumn::Integer(array) if array.is_valid(row) => Value::Integer(array.value(row)),
+            SqlColumn::Real(array) if array.is_valid(row) => Value::Real(array.value(row)),
+            SqlColumn::Text(array) if array.is_valid(row) => Value::Text(array_value_to_string(array, row)?),
+            _ => Value::Null,
+        })
+    }
+}
+
+/// Build an Arrow array from SQLite values. Declared types decide temporal
+/// and boolean columns; otherwise the values do (all integers → Int64,
+/// any reals → Float64, anything else → Utf8).
+fn to_arrow(values: &[Value], declared: Option<&str>) -> Result<ArrayRef, DataError> {
+    let all = |f: fn(&Value) -> bool| values.iter().all(|v| matches!(v, Value::Null) || f(v));
+
+    if declared == Some("BOOLEAN") && all(|v| matches!(v, Value::Integer(_))) {
+        return Ok(Arc::new(values.iter()
+            .map(|v| match v { Value::Integer(i) => Some(*i != 0), _ => None })
+            .collect::<BooleanArray>()));
+    }
+    if all(|v| matches!(v, Value::Integer(_))) && !matches!(declared, Some("DATE" | "TIMESTAMP")) {
+        return Ok(Arc::new(values.iter()
+            .map(|v| match v { Value::Integer(i) => Some(*i), _ => None })
+            .collect::<Int64Array>()));
+    }
+    if all(|v| matches!(v, Value::Integer(_) | Value::Real(_))) && !matches!(declared, Some("DATE" | "TIMESTAMP")) {
+        return Ok(Arc::new(values.iter()
+            .map(|v| match v {
+                Value::Integer(i) => Some(*i as f64),
+                Value::Real(f) => Some(*f),
+                _ => None,
+            })
+            .collect::<Float64Array>()));
+    }
+
+    let text: ArrayRef = Arc::new(values.iter()
+        .map(|v| match v {
+            Value::Null => None,
+            Value::Integer(i) => Some(i.to_string()),
+            Value::Real(f) => Some(f.to_string()),
+            Value::Text(s) => Some(s.clone()),
+            Value::Blob(b) => Some(format!("<{} bytes>", b.len())),
+        })
+        .collect::<StringArray>());
+
+    // Restore columns that were temporal before the round trip through text
+    let temporal = match declared {
+        Some("TIMESTAMP") => Some(DataType::Timestamp(TimeUnit::Millisecond, None)),
+        Some("DATE") => Some(DataType::Date32),
+        _ => None,
+    };
+    if let Some(data_type) = temporal {
+        if let Ok(parsed) = cast(&text, &data_type) {
+            if parsed.null_count() == text.null_count() {
+                return Ok(parsed);
+            }
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_registered_batches_with_group_by_and_join() {
+        let machines = RecordBatch::try_from_iter(vec![
+            ("id", Arc::new(Int64Array::from(vec![1, 2])) as ArrayRef),
+            ("line", Arc::new(StringArray::from(vec!["A", "B"])) as ArrayRef),
+        ]).unwrap();
+        let readings = RecordBatch::try_from_iter(vec![
+            ("machine", Arc::new(Int64Array::from(vec![1, 1, 2])) as ArrayRef),
+            ("temp", Arc::new(Float64Array::from(vec![10.0, 20.0, 5.0])) as ArrayRef),
+        ]).unwrap();
+
+        let mut engine = SqlEngine::new().unwrap();
+        engine.register_batch("machines", &machines).unwrap();
+        engine.register_batch("readings", &readings).unwrap();
+        assert_eq!(engine.table_name_for("Machines.csv"), "machines_2");
+
+        let result = engine.query(
+            "SELECT m.line, AVG(r.temp) AS mean_temp, COUNT(*) AS n \
+             FROM readings r JOIN machines m ON m.id = r.machine \
+             GROUP BY m.line ORDER BY m.line"
+        ).unwrap();
+
+        assert_eq!(result.num_rows(), 2);
+        let mean = result.column(1).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(mean.value(0), 15.0);
+        let count = result.column(2).as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(count.value(1), 1);
+        assert!(engine.query("SELECT nope FROM machines").is_err());
+    }
+}
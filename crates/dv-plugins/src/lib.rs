@@ -0,0 +1,84 @@
+//! View plugins loaded at runtime
+//!
+//! A plugin is a WebAssembly module adding one view type. It runs in an
+//! embedded interpreter, sandboxed from the application: it sees only the
+//! rows of the data source its view shows, as Arrow IPC, and the navigation
+//! state, and it answers with drawing commands ([`dv_render::DrawCommand`])
+//! that the view replays. Plugins are registered in the
+//! [`dv_views::ViewRegistry`] like the built-in views, so the Dashboard
+//! Builder, workspaces and templates can use them.
+//!
+//! # Module interface (version 1)
+//!
+//! Strings and buffers are passed in the module's memory; a buffer returned
+//! to the host is an `i64` holding its address in the upper and its length in
+//! the lower 32 bits.
+//!
+//! | Export | Signature | |
+//! |---|---|---|
+//! | `memory` | memory | |
+//! | `frog_abi_version` | `() -> i32` | `1` |
+//! | `frog_alloc` | `(len: i32) -> i32` | A buffer for the host to write `len` bytes to |
+//! | `frog_free` | `(ptr: i32, len: i32)` | Optional; called for buffers the host is done with |
+//! | `frog_describe` | `() -> i64` | The manifest, as JSON (see [`PluginManifest`]) |
+//! | `frog_render` | `(context_ptr, context_len, data_ptr, data_len: i32) -> i64` | JSON array of drawing commands |
+//!
+//! The render context is JSON ([`RenderContext`]): the view's size and
+//! settings, and the navigation position. The data is an Arrow IPC stream
+//! of the rows the view shows (empty without a data source), holding the
+//! columns its settings name, or all columns for plugins without column
+//! settings. Drawing coordinates are pixels from the view's top left.
+//!
+//! Modules may import `frog.log(ptr: i32, len: i32)` to log a UTF-8 message.
+//! Each call is limited in time, and memory to [`MEMORY_LIMIT`] bytes.
+
+mod manifest;
+mod view;
+mod wasm;
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+pub use manifest::{ColumnSpec, PluginManifest};
+pub use view::PluginView;
+pub use wasm::{NavigationState, PluginInstance, RenderContext, WasmPlugin, ABI_VERSION, MEMORY_LIMIT};
+
+/// Errors loading or running plugins
+#[derive(Error, Debug)]
+pub enum PluginError {
+    #[error("Failed to read {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+
+    #[error("Invalid plugin {0}: {1}")]
+    Invalid(PathBuf, String),
+
+    #[error("Plugin '{0}' failed: {1}")]
+    Call(String, String),
+
+    #[error("Plugin '{0}' returned invalid drawing commands: {1}")]
+    Output(String, serde_json::Error),
+}
+
+/// Plugins in the `.wasm` files of `dir`, and the files that couldn't be
+/// loaded. A missing directory has none.
+pub fn load_dir(dir: &Path) -> (Vec<WasmPlugin>, Vec<PluginError>) {
+    let mut plugins = Vec::new();
+    let mut errors = Vec::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (plugins, errors),
+        Err(e) => return (plugins, vec![PluginError::Read(dir.to_path_buf(), e)]),
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "wasm"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        match WasmPlugin::load(&path) {
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => errors.push(e),
+        }
+    }
+    (plugins, errors)
+}
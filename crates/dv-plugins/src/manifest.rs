@@ -0,0 +1,179 @@
+//! What a plugin tells the host about its view type
+
+use serde::{Deserialize, Serialize};
+
+use dv_views::{ColumnField, ColumnKind};
+
+/// The view type a plugin adds, returned by `frog_describe`, e.g.
+///
+/// ```json
+/// {
+///   "view_type": "WaferMap",
+///   "name": "Wafer Map",
+///   "icon": "🟢",
+///   "columns": [
+///     {"key": "x_column", "label": "Die X", "kind": "numeric", "hint": "x"},
+///     {"key": "y_column", "label": "Die Y", "kind": "numeric", "hint": "y"},
+///     {"key": "bin_column", "label": "Bin", "kind": "categorical", "count": "optional"}
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// Type name, unique among view types
+    pub view_type: String,
+    /// Name shown to users
+    pub name: String,
+    #[serde(default = "default_icon")]
+    pub icon: String,
+    /// Dashboard Builder heading
+    #[serde(default = "default_category")]
+    pub category: String,
+    /// Column settings, as in `dv_views::ColumnField`
+    #[serde(default)]
+    pub columns: Vec<ColumnSpec>,
+}
+
+/// A column setting of a plugin view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSpec {
+    pub key: String,
+    pub label: String,
+    /// `numeric`, `temporal`, `categorical`, `ordered` or `any`
+    #[serde(default = "default_kind")]
+    pub kind: String,
+    /// `one`, `optional` or `many`
+    #[serde(default = "default_count")]
+    pub count: String,
+    /// Least and default number of columns for `many`
+    #[serde(default)]
+    pub min: Option<usize>,
+    #[serde(default)]
+    pub default: Option<usize>,
+    #[serde(default)]
+    pub hint: Option<String>,
+}
+
+fn default_icon() -> String {
+    "🧩".to_string()
+}
+
+fn default_category() -> String {
+    "Plugins".to_string()
+}
+
+fn default_kind() -> String {
+    "any".to_string()
+}
+
+fn default_count() -> String {
+    "one".to_string()
+}
+
+impl ColumnSpec {
+    /// The setting as the registry describes it
+    pub fn to_field(&self) -> Result<ColumnField, String> {
+        let kind = match self.kind.as_str() {
+            "numeric" => ColumnKind::Numeric,
+            "temporal" => ColumnKind::Temporal,
+            "categorical" => ColumnKind::Categorical,
+            "ordered" => ColumnKind::Ordered,
+            "any" => ColumnKind::Any,
+            other => return Err(format!("column '{}' has an unknown kind '{}'", self.key, other)),
+        };
+        let field = match self.count.as_str() {
+            "one" => ColumnField::one(&self.key, &self.label, kind),
+            "optional" => ColumnField::optional(&self.key, &self.label, kind),
+            "many" => {
+                let min = self.min.unwrap_or(1);
+                ColumnField::many(&self.key, &self.label, kind, min, self.default.unwrap_or(min).max(min))
+            }
+            other => return Err(format!("column '{}' has an unknown count '{}'", self.key, other)),
+        };
+        Ok(match &self.hint {
+            Some(hint) => field.hint(&hint.to_lowercase()),
+            None => field,
+        })
+    }
+}
+
+impl PluginManifest {
+    /// Column settings as the registry describes them
+    pub fn fields(&self) -> Result<Vec<ColumnField>, String> {
+        self.columns.iter().map(ColumnSpec::to_field).collect()
+    }
+
+    /// Whether the view reads the columns of its settings rather than all
+    pub fn names_columns(&self) -> bool {
+        !self.columns.is_empty()
+    }
+
+    /// Column names set in `settings`
+    pub fn columns_in(&self, settings: &serde_json::Value) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for column in &self.columns {
+            let set: Vec<&str> = match settings.get(&column.key) {
+                Some(serde_json::Value::String(name)) => vec![name.as_str()],
+                Some(serde_json::Value::Array(values)) => values.iter().filter_map(|value| value.as_str()).collect(),
+                _ => Vec::new(),
+            };
+            for name in set {
+                if !names.iter().any(|known| known == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use dv_views::ColumnCount;
+
+    use super::*;
+
+    fn spec(kind: &str, count: &str) -> ColumnSpec {
+        ColumnSpec {
+            key: "x_column".to_string(),
+            label: "X".to_string(),
+            kind: kind.to_string(),
+            count: count.to_string(),
+            min: Some(2),
+            default: None,
+            hint: Some("X".to_string()),
+        }
+    }
+
+    #[test]
+    fn column_specs_name_their_problems() {
+        assert_eq!(spec("angle", "one").to_field().unwrap_err(), "column 'x_column' has an unknown kind 'angle'");
+        assert_eq!(spec("numeric", "some").to_field().unwrap_err(), "column 'x_column' has an unknown count 'some'");
+
+        let field = spec("numeric", "many").to_field().unwrap();
+        assert_eq!(field.key, "x_column");
+        assert_eq!(field.kind, ColumnKind::Numeric);
+        assert_eq!(field.count, ColumnCount::Many { min: 2, default: 2 });
+        assert_eq!(field.hint.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn settings_name_each_column_once() {
+        let manifest: PluginManifest = serde_json::from_value(json!({
+            "view_type": "Pairs",
+            "name": "Pairs",
+            "columns": [
+                {"key": "x", "label": "X"},
+                {"key": "series", "label": "Series", "count": "many"},
+                {"key": "color", "label": "Color", "count": "optional"}
+            ]
+        })).unwrap();
+        assert_eq!(manifest.icon, "🧩");
+        assert_eq!(manifest.fields().unwrap().len(), 3);
+
+        let settings = json!({ "x": "time", "series": ["a", "time", "b", 3], "color": null });
+        assert_eq!(manifest.columns_in(&settings), vec!["time", "a", "b"]);
+    }
+}
@@ -0,0 +1,280 @@
+//! The view showing a plugin's drawing
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use arrow::record_batch::RecordBatch;
+use egui::{vec2, Align2, Button, Color32, FontId, Rect, Sense, Ui};
+use serde_json::{Map, Value};
+use tracing::warn;
+
+use dv_core::events::events::{DataSourceLoaded, DataSourcesCleared};
+use dv_core::events::{EventBus, Subscription};
+use dv_render::{replay, DrawCommand, PainterRenderer};
+use dv_views::{SelectionState, SpaceView, SpaceViewId, ViewerContext};
+
+use crate::{NavigationState, PluginError, PluginInstance, RenderContext, WasmPlugin};
+
+/// A view drawn by a plugin
+pub struct PluginView {
+    id: SpaceViewId,
+    title: String,
+    plugin: Arc<WasmPlugin>,
+    data_source_id: Option<String>,
+    settings: Value,
+    /// Instance drawing the view, started on first use and again after it fails
+    instance: Option<PluginInstance>,
+    drawn: Option<Drawing>,
+    /// Bumped when a data source is loaded or the sources are cleared
+    revision: Arc<AtomicU64>,
+    reloads: Vec<Subscription>,
+}
+
+/// What a view was last drawn for, and the plugin's drawing or error
+struct Drawing {
+    context: RenderContext,
+    source_id: Option<String>,
+    /// Source revision, so loading or reloading a source draws again
+    revision: u64,
+    commands: Result<Vec<DrawCommand>, String>,
+}
+
+impl PluginView {
+    pub fn new(id: SpaceViewId, title: String, plugin: Arc<WasmPlugin>) -> Self {
+        Self {
+            id,
+            title,
+            plugin,
+            data_source_id: None,
+            settings: Value::Object(Map::new()),
+            instance: None,
+            drawn: None,
+            revision: Arc::new(AtomicU64::new(0)),
+            reloads: Vec::new(),
+        }
+    }
+
+    /// Count source loads on `events`, on first use
+    fn watch_sources(&mut self, events: &EventBus) {
+        if !self.reloads.is_empty() {
+            return;
+        }
+        let revision = self.revision.clone();
+        self.reloads.push(events.subscribe::<DataSourceLoaded, _>(move |_| {
+            revision.fetch_add(1, Ordering::Relaxed);
+        }));
+        let revision = self.revision.clone();
+        self.reloads.push(events.subscribe::<DataSourcesCleared, _>(move |_| {
+            revision.fetch_add(1, Ordering::Relaxed);
+        }));
+    }
+
+    /// Rows of the view's source for the plugin, with the columns its
+    /// settings name
+    fn data(&self, ctx: &ViewerContext) -> Option<RecordBatch> {
+        let data_sources = ctx.data_sources.read();
+        let source = match &self.data_source_id {
+            Some(source_id) => data_sources.get(source_id),
+            None => data_sources.values().next(),
+        }?;
+        let batch = ctx.query_view_data(source.as_ref())
+            .map_err(|e| warn!("{} couldn't read its data: {}", self.title, e))
+            .ok()?;
+        let manifest = self.plugin.manifest();
+        if !manifest.names_columns() {
+            return Some(batch);
+        }
+        let schema = batch.schema();
+        let indices: Vec<usize> = manifest.columns_in(&self.settings).iter()
+            .filter_map(|name| schema.index_of(name).ok())
+            .collect();
+        batch.project(&indices).ok()
+    }
+
+    /// Have the plugin draw for `context` unless it already has. A failure
+    /// is kept like a drawing, so a plugin that runs out of fuel stalls the
+    /// window once rather than every frame; it's tried again when what it
+    /// draws for changes, or on "Retry". Returns whether the plugin ran.
+    fn draw(&mut self, ctx: &ViewerContext, context: RenderContext) -> bool {
+        let revision = self.revision.load(Ordering::Relaxed);
+        let stale = self.drawn.as_ref().is_none_or(|drawn| {
+            drawn.context != context || drawn.source_id != self.data_source_id || drawn.revision != revision
+        });
+        if !stale {
+            return false;
+        }
+        let commands = self.render(ctx, &context).map_err(|e| {
+            warn!("{}", e);
+            // The instance may be left in any state
+            self.instance = None;
+            e.to_string()
+        });
+        self.drawn = Some(Drawing { context, source_id: self.data_source_id.clone(), revision, commands });
+        true
+    }
+
+    fn render(&mut self, ctx: &ViewerContext, context: &RenderContext) -> Result<Vec<DrawCommand>, PluginError> {
+        let data = self.data(ctx);
+        let instance = match &mut self.instance {
+            Some(instance) => instance,
+            None => self.instance.insert(self.plugin.instantiate()?),
+        };
+        instance.render(context, data.as_ref())
+    }
+}
+
+impl SpaceView for PluginView {
+    fn id(&self) -> SpaceViewId {
+        self.id
+    }
+
+    fn display_name(&self) -> &str {
+        &self.title
+    }
+
+    fn view_type(&self) -> &str {
+        &self.plugin.manifest().view_type
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn set_data_source(&mut self, source_id: String) {
+        self.data_source_id = Some(source_id);
+        self.drawn = None;
+    }
+
+    fn data_source_id(&self) -> Option<&str> {
+        self.data_source_id.as_deref()
+    }
+
+    fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
+        let (rect, _) = ui.allocate_exact_size(ui.available_size(), Sense::hover());
+        let context = RenderContext {
+            width: rect.width(),
+            height: rect.height(),
+            settings: self.settings.clone(),
+            navigation: NavigationState::from(&ctx.navigation.get_context()),
+        };
+
+        self.watch_sources(&ctx.events);
+        self.draw(ctx, context);
+
+        let painter = ui.painter_at(rect);
+        match self.drawn.as_ref().map(|drawn| &drawn.commands) {
+            Some(Ok(commands)) => replay(commands, &mut PainterRenderer::new(painter, rect.min)),
+            Some(Err(e)) => {
+                painter.text(rect.center(), Align2::CENTER_CENTER, format!("⚠ {}", e), FontId::proportional(13.0), Color32::from_rgb(255, 152, 0));
+                let button = Rect::from_center_size(rect.center() + vec2(0.0, 28.0), vec2(80.0, 22.0));
+                if ui.put(button, Button::new("Retry")).clicked() {
+                    self.drawn = None;
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn save_config(&self) -> Value {
+        let mut config = self.settings.clone();
+        if let (Some(fields), Some(source_id)) = (config.as_object_mut(), &self.data_source_id) {
+            fields.insert("data_source_id".to_string(), Value::String(source_id.clone()));
+        }
+        config
+    }
+
+    fn load_config(&mut self, config: Value) {
+        let Value::Object(mut fields) = config else {
+            return;
+        };
+        if let Some(Value::String(source_id)) = fields.remove("data_source_id") {
+            self.data_source_id = Some(source_id);
+        }
+        self.settings = Value::Object(fields);
+        self.drawn = None;
+    }
+
+    fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
+
+    fn on_frame_update(&mut self, _ctx: &ViewerContext, _dt: f32) {}
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use parking_lot::RwLock;
+    use serde_json::json;
+
+    use dv_core::navigation::{NavigationEngine, NavigationMode, NavigationPosition};
+    use dv_core::state::{FrameTime, HoveredData, TimeControl};
+
+    use super::*;
+
+    /// Describes itself, then traps on every render
+    const TRAP: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (data (i32.const 0) "{\"view_type\":\"Trap\",\"name\":\"Trap\"}")
+          (func (export "frog_abi_version") (result i32) (i32.const 1))
+          (func (export "frog_alloc") (param i32) (result i32) (i32.const 1024))
+          (func (export "frog_describe") (result i64) (i64.const 34))
+          (func (export "frog_render") (param i32 i32 i32 i32) (result i64) (unreachable)))
+    "#;
+
+    fn context(width: f32) -> RenderContext {
+        RenderContext {
+            width,
+            height: 50.0,
+            settings: json!({}),
+            navigation: NavigationState {
+                row: 0,
+                position: NavigationPosition::Sequential(0),
+                total_rows: 0,
+                selection: None,
+            },
+        }
+    }
+
+    #[test]
+    fn failures_are_kept_until_what_is_drawn_changes() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let ctx = ViewerContext {
+            data_sources: Arc::new(RwLock::new(HashMap::new())),
+            navigation: Arc::new(NavigationEngine::new(NavigationMode::Sequential)),
+            time_control: Arc::new(RwLock::new(TimeControl::default())),
+            hovered_data: Arc::new(RwLock::new(HoveredData::default())),
+            frame_time: Arc::new(RwLock::new(FrameTime::default())),
+            runtime_handle: runtime.handle().clone(),
+            time_axis_views: Arc::new(RwLock::new(Vec::new())),
+            events: EventBus::new(),
+        };
+        let plugin = Arc::new(WasmPlugin::new(Path::new("trap.wasm"), &wat::parse_str(TRAP).unwrap()).unwrap());
+        let mut view = PluginView::new(SpaceViewId::nil(), "Trap".to_string(), plugin);
+
+        view.watch_sources(&ctx.events);
+
+        // The plugin runs, and warns, once for a context however many frames ask
+        assert!(view.draw(&ctx, context(100.0)));
+        assert!(matches!(view.drawn.as_ref().map(|drawn| &drawn.commands), Some(Err(_))));
+        assert!(!view.draw(&ctx, context(100.0)));
+        assert!(!view.draw(&ctx, context(100.0)));
+
+        // A new size, or a loaded source, is worth another try
+        assert!(view.draw(&ctx, context(120.0)));
+        ctx.events.publish(DataSourceLoaded { source_name: "data.csv".to_string(), row_count: 3, column_count: 1 });
+        assert!(!view.draw(&ctx, context(120.0)), "events wait for dispatch");
+        ctx.events.dispatch();
+        assert!(view.draw(&ctx, context(120.0)));
+        assert!(!view.draw(&ctx, context(120.0)));
+    }
+}
@@ -0,0 +1,313 @@
+//! Running plugin modules
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+use wasmi::{
+    Caller, Config, Engine, Extern, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder,
+    WasmParams, WasmResults,
+};
+
+use dv_core::navigation::{NavigationContext, NavigationPosition};
+use dv_render::DrawCommand;
+use dv_views::{ColumnField, ViewDescriptor};
+
+use crate::{PluginError, PluginManifest, PluginView};
+
+/// Version of the module interface the host implements
+pub const ABI_VERSION: i32 = 1;
+
+/// Most memory a plugin instance may use, in bytes
+pub const MEMORY_LIMIT: usize = 256 << 20;
+
+/// Interpreter fuel for each call into a plugin, about one per instruction,
+/// so a plugin that never returns stalls the window for a few seconds at most
+const FUEL_PER_CALL: u64 = 500_000_000;
+
+/// What a view asks its plugin to draw
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenderContext {
+    /// Size of the view in pixels
+    pub width: f32,
+    pub height: f32,
+    /// The view's settings, with its column settings by key
+    pub settings: Value,
+    pub navigation: NavigationState,
+}
+
+/// Navigation as a plugin sees it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NavigationState {
+    /// Current row
+    pub row: usize,
+    /// Current position, e.g. `{"Sequential": 12}` or `{"Temporal": 1700000000000}`
+    pub position: NavigationPosition,
+    pub total_rows: usize,
+    /// Selected rows, `[start, end)`, if any; the data holds only these
+    pub selection: Option<[usize; 2]>,
+}
+
+impl From<&NavigationContext> for NavigationState {
+    fn from(context: &NavigationContext) -> Self {
+        Self {
+            row: context.position.frame_nr(),
+            position: context.position.clone(),
+            total_rows: context.total_rows,
+            selection: context.selection_rows().map(|range| {
+                let (start, end) = range.row_bounds();
+                [start, end]
+            }),
+        }
+    }
+}
+
+/// A loaded plugin module
+pub struct WasmPlugin {
+    path: PathBuf,
+    engine: Engine,
+    module: Module,
+    manifest: PluginManifest,
+    fields: Vec<ColumnField>,
+}
+
+impl std::fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPlugin")
+            .field("path", &self.path)
+            .field("manifest", &self.manifest)
+            .finish()
+    }
+}
+
+impl WasmPlugin {
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        let wasm = std::fs::read(path).map_err(|e| PluginError::Read(path.to_path_buf(), e))?;
+        Self::new(path, &wasm)
+    }
+
+    /// Compile `wasm`, loaded from `path`, and ask it for its manifest
+    pub fn new(path: &Path, wasm: &[u8]) -> Result<Self, PluginError> {
+        let invalid = |message: String| PluginError::Invalid(path.to_path_buf(), message);
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, wasm).map_err(|e| invalid(e.to_string()))?;
+
+        let name = path.file_stem().map_or_else(|| "plugin".to_string(), |stem| stem.to_string_lossy().into_owned());
+        let mut instance = PluginInstance::new(&engine, &module, name).map_err(|e| invalid(e.to_string()))?;
+        let version: i32 = instance.call("frog_abi_version", ()).map_err(|e| invalid(e.to_string()))?;
+        if version != ABI_VERSION {
+            return Err(invalid(format!("interface version {} isn't supported (expected {})", version, ABI_VERSION)));
+        }
+        let manifest = instance.describe().map_err(|e| invalid(e.to_string()))?;
+        let fields = manifest.fields().map_err(invalid)?;
+        Ok(Self { path: path.to_path_buf(), engine, module, manifest, fields })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    /// A fresh instance of the module
+    pub fn instantiate(&self) -> Result<PluginInstance, PluginError> {
+        PluginInstance::new(&self.engine, &self.module, self.manifest.name.clone())
+    }
+
+    /// The plugin's view type, for registering in a `ViewRegistry`
+    pub fn descriptor(self: &Arc<Self>) -> ViewDescriptor {
+        let plugin = self.clone();
+        let mut descriptor = ViewDescriptor::new(
+            &self.manifest.view_type,
+            &self.manifest.name,
+            &self.manifest.icon,
+            &self.manifest.category,
+            move |id, title| Box::new(PluginView::new(id, title, plugin.clone())),
+        );
+        for field in &self.fields {
+            descriptor = descriptor.column(field.clone());
+        }
+        descriptor
+    }
+}
+
+/// A running plugin module
+pub struct PluginInstance {
+    name: String,
+    store: Store<StoreLimits>,
+    instance: Instance,
+    memory: Memory,
+}
+
+impl PluginInstance {
+    fn new(engine: &Engine, module: &Module, name: String) -> Result<Self, PluginError> {
+        let fail = |message: String| PluginError::Call(name.clone(), message);
+        let mut store = Store::new(engine, StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build());
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL_PER_CALL).map_err(|e| fail(e.to_string()))?;
+
+        let mut linker = Linker::<StoreLimits>::new(engine);
+        let log_name = name.clone();
+        linker.func_wrap("frog", "log", move |caller: Caller<'_, StoreLimits>, ptr: i32, len: i32| {
+            let Some(memory) = caller.get_export("memory").and_then(Extern::into_memory) else {
+                return;
+            };
+            let start = ptr as u32 as usize;
+            if let Some(bytes) = memory.data(&caller).get(start..start.saturating_add(len as u32 as usize)) {
+                info!("[{}] {}", log_name, String::from_utf8_lossy(bytes));
+            }
+        }).map_err(|e| fail(e.to_string()))?;
+
+        let instance = linker.instantiate(&mut store, module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|e| fail(e.to_string()))?;
+        let memory = instance.get_memory(&store, "memory").ok_or_else(|| fail("exports no memory".to_string()))?;
+        Ok(Self { name, store, instance, memory })
+    }
+
+    /// The plugin's manifest
+    pub fn describe(&mut self) -> Result<PluginManifest, PluginError> {
+        let output: i64 = self.call("frog_describe", ())?;
+        let json = self.read(output)?;
+        serde_json::from_slice(&json).map_err(|e| PluginError::Call(self.name.clone(), format!("invalid manifest: {}", e)))
+    }
+
+    /// The plugin's drawing of `data` for `context`
+    pub fn render(&mut self, context: &RenderContext, data: Option<&RecordBatch>) -> Result<Vec<DrawCommand>, PluginError> {
+        let context = serde_json::to_vec(context).expect("render contexts serialize to JSON");
+        let data = match data {
+            Some(batch) => ipc_stream(batch).map_err(|e| PluginError::Call(self.name.clone(), format!("failed to encode data: {}", e)))?,
+            None => Vec::new(),
+        };
+        let context_ptr = self.write(&context)?;
+        let data_ptr = match self.write(&data) {
+            Ok(ptr) => ptr,
+            Err(e) => {
+                self.free(context_ptr, context.len());
+                return Err(e);
+            }
+        };
+        let output = self.call::<_, i64>("frog_render", (context_ptr, context.len() as i32, data_ptr, data.len() as i32));
+        // The inputs are handed back whether or not the call succeeded, so
+        // failures don't fill the plugin's memory
+        self.free(context_ptr, context.len());
+        self.free(data_ptr, data.len());
+        let output = output?;
+        let json = self.read(output)?;
+        self.free((output as u64 >> 32) as i32, json.len());
+        serde_json::from_slice(&json).map_err(|e| PluginError::Output(self.name.clone(), e))
+    }
+
+    fn call<P: WasmParams, R: WasmResults>(&mut self, function: &str, params: P) -> Result<R, PluginError> {
+        let fail = |message: String| PluginError::Call(self.name.clone(), format!("{}: {}", function, message));
+        let func = self.instance.get_typed_func::<P, R>(&self.store, function).map_err(|e| fail(e.to_string()))?;
+        self.store.set_fuel(FUEL_PER_CALL).map_err(|e| fail(e.to_string()))?;
+        func.call(&mut self.store, params).map_err(|e| fail(e.to_string()))
+    }
+
+    /// Copy `bytes` into a buffer allocated by the plugin
+    fn write(&mut self, bytes: &[u8]) -> Result<i32, PluginError> {
+        if bytes.is_empty() {
+            return Ok(0);
+        }
+        let len = i32::try_from(bytes.len())
+            .map_err(|_| PluginError::Call(self.name.clone(), "input too large".to_string()))?;
+        let ptr: i32 = self.call("frog_alloc", len)?;
+        self.memory.write(&mut self.store, ptr as u32 as usize, bytes)
+            .map_err(|e| PluginError::Call(self.name.clone(), format!("frog_alloc returned an invalid buffer: {}", e)))?;
+        Ok(ptr)
+    }
+
+    /// The buffer `packed` points to
+    fn read(&self, packed: i64) -> Result<Vec<u8>, PluginError> {
+        let start = (packed as u64 >> 32) as usize;
+        let len = packed as u32 as usize;
+        self.memory.data(&self.store).get(start..start + len)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| PluginError::Call(self.name.clone(), "returned a buffer outside its memory".to_string()))
+    }
+
+    /// Hand a buffer back to the plugin, if it frees them
+    fn free(&mut self, ptr: i32, len: usize) {
+        if len > 0 && self.instance.get_export(&self.store, "frog_free").is_some() {
+            let _ = self.call::<(i32, i32), ()>("frog_free", (ptr, len as i32));
+        }
+    }
+}
+
+/// `batch` as an Arrow IPC stream
+fn ipc_stream(batch: &RecordBatch) -> Result<Vec<u8>, arrow::error::ArrowError> {
+    let mut writer = StreamWriter::try_new(Vec::new(), &batch.schema())?;
+    writer.write(batch)?;
+    writer.into_inner()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Float64Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use serde_json::json;
+
+    /// Draws a red square, or nothing without data
+    const SQUARE: &str = r#"
+        (module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 4096))
+          (data (i32.const 0) "{\"view_type\":\"Square\",\"name\":\"Square\",\"columns\":[{\"key\":\"column\",\"label\":\"Column\",\"kind\":\"numeric\"}]}")
+          (data (i32.const 1024) "[{\"op\":\"rect\",\"min\":[0,0],\"max\":[10,10],\"color\":[1,0,0,1],\"filled\":true}]")
+          (data (i32.const 2048) "[]")
+          (func (export "frog_abi_version") (result i32) (i32.const 1))
+          (func (export "frog_alloc") (param $len i32) (result i32)
+            (global.get $next)
+            (global.set $next (i32.add (global.get $next) (local.get $len))))
+          (func (export "frog_describe") (result i64) (i64.const 101))
+          (func (export "frog_render") (param i32 i32 i32 i32) (result i64)
+            (if (result i64) (i32.gt_u (local.get 3) (i32.const 0))
+              (then (i64.const 4398046511177))
+              (else (i64.const 8796093022210)))))
+    "#;
+
+    #[test]
+    fn plugins_describe_themselves_and_draw_their_data() {
+        let path = Path::new("square.wasm");
+        let plugin = Arc::new(WasmPlugin::new(path, &wat::parse_str(SQUARE).unwrap()).unwrap());
+        assert_eq!(plugin.manifest().view_type, "Square");
+        assert_eq!(plugin.manifest().category, "Plugins");
+        let descriptor = plugin.descriptor();
+        assert_eq!(descriptor.columns[0].key, "column");
+        assert!(!descriptor.is_complete(&json!({})));
+
+        let context = RenderContext {
+            width: 100.0,
+            height: 50.0,
+            settings: json!({ "column": "value" }),
+            navigation: NavigationState {
+                row: 0,
+                position: NavigationPosition::Sequential(0),
+                total_rows: 3,
+                selection: None,
+            },
+        };
+        let schema = Arc::new(Schema::new(vec![Field::new("value", DataType::Float64, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Float64Array::from(vec![1.0, 2.0, 3.0]))]).unwrap();
+        let mut instance = plugin.instantiate().unwrap();
+        assert_eq!(instance.render(&context, Some(&batch)).unwrap(), vec![DrawCommand::Rect {
+            min: [0.0, 0.0],
+            max: [10.0, 10.0],
+            color: [1.0, 0.0, 0.0, 1.0],
+            filled: true,
+        }]);
+        assert!(instance.render(&context, None).unwrap().is_empty());
+
+        assert!(matches!(WasmPlugin::new(path, b"not wasm"), Err(PluginError::Invalid(..))));
+    }
+}
@@ -0,0 +1,96 @@
+//! Recorded drawing
+//!
+//! A [`DrawCommand`] is one [`Renderer`] call as data, so drawing produced
+//! elsewhere (such as by a view plugin, as JSON) can be replayed into any
+//! renderer. Commands serialize tagged by `op`, e.g.
+//! `{"op": "line", "start": [0, 0], "end": [10, 10], "color": [1, 0, 0, 1], "width": 2}`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::Renderer;
+
+/// A `Renderer` call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum DrawCommand {
+    Line { start: [f32; 2], end: [f32; 2], color: [f32; 4], width: f32 },
+    Lines { segments: Vec<([f32; 2], [f32; 2])>, color: [f32; 4], width: f32 },
+    Point { position: [f32; 2], color: [f32; 4], size: f32 },
+    Points { positions: Vec<[f32; 2]>, color: [f32; 4], size: f32 },
+    Rect { min: [f32; 2], max: [f32; 2], color: [f32; 4], filled: bool },
+    Text { text: String, position: [f32; 2], color: [f32; 4], size: f32 },
+    Polygon { points: Vec<[f32; 2]>, color: [f32; 4], filled: bool },
+    Triangles { triangles: Vec<[[f32; 2]; 3]>, color: [f32; 4] },
+    /// Restrict the following commands to `[min, max]`, or lift the
+    /// restriction without a rectangle
+    Clip { rect: Option<([f32; 2], [f32; 2])> },
+}
+
+impl DrawCommand {
+    /// Make the call on `renderer`
+    pub fn draw(&self, renderer: &mut dyn Renderer) {
+        match self {
+            DrawCommand::Line { start, end, color, width } => renderer.draw_line(*start, *end, *color, *width),
+            DrawCommand::Lines { segments, color, width } => renderer.draw_lines(segments, *color, *width),
+            DrawCommand::Point { position, color, size } => renderer.draw_point(*position, *color, *size),
+            DrawCommand::Points { positions, color, size } => renderer.draw_points(positions, *color, *size),
+            DrawCommand::Rect { min, max, color, filled } => renderer.draw_rect(*min, *max, *color, *filled),
+            DrawCommand::Text { text, position, color, size } => renderer.draw_text(text, *position, *color, *size),
+            DrawCommand::Polygon { points, color, filled } => {
+                if !points.is_empty() {
+                    renderer.draw_polygon(points, *color, *filled);
+                }
+            }
+            DrawCommand::Triangles { triangles, color } => renderer.draw_triangles(triangles, *color),
+            DrawCommand::Clip { rect } => renderer.set_clip_rect(*rect),
+        }
+    }
+}
+
+/// Draw `commands` in order. A clip rectangle they leave set is lifted.
+pub fn replay(commands: &[DrawCommand], renderer: &mut dyn Renderer) {
+    for command in commands {
+        command.draw(renderer);
+    }
+    renderer.set_clip_rect(None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_command_round_trips_through_json() {
+        let color = [1.0, 0.5, 0.0, 1.0];
+        let commands = vec![
+            DrawCommand::Line { start: [0.0, 0.0], end: [10.0, 10.0], color, width: 2.0 },
+            DrawCommand::Lines { segments: vec![([0.0, 0.0], [1.0, 1.0])], color, width: 1.0 },
+            DrawCommand::Point { position: [3.0, 4.0], color, size: 5.0 },
+            DrawCommand::Points { positions: vec![[1.0, 2.0], [3.0, 4.0]], color, size: 2.0 },
+            DrawCommand::Rect { min: [0.0, 0.0], max: [5.0, 5.0], color, filled: true },
+            DrawCommand::Text { text: "label".to_string(), position: [1.0, 1.0], color, size: 12.0 },
+            DrawCommand::Polygon { points: vec![[0.0, 0.0], [4.0, 0.0], [0.0, 4.0]], color, filled: false },
+            DrawCommand::Triangles { triangles: vec![[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]], color },
+            DrawCommand::Clip { rect: Some(([0.0, 0.0], [8.0, 8.0])) },
+            DrawCommand::Clip { rect: None },
+        ];
+        let ops = ["line", "lines", "point", "points", "rect", "text", "polygon", "triangles", "clip", "clip"];
+        for (command, op) in commands.iter().zip(ops) {
+            let json = serde_json::to_value(command).unwrap();
+            assert_eq!(json["op"], op);
+            assert_eq!(&serde_json::from_value::<DrawCommand>(json).unwrap(), command);
+        }
+    }
+
+    #[test]
+    fn commands_read_as_documented() {
+        let json = r#"{"op": "line", "start": [0, 0], "end": [10, 10], "color": [1, 0, 0, 1], "width": 2}"#;
+        assert_eq!(serde_json::from_str::<DrawCommand>(json).unwrap(), DrawCommand::Line {
+            start: [0.0, 0.0],
+            end: [10.0, 10.0],
+            color: [1.0, 0.0, 0.0, 1.0],
+            width: 2.0,
+        });
+        assert!(serde_json::from_str::<DrawCommand>(r#"{"op": "circle"}"#).is_err());
+    }
+}
@@ -0,0 +1,597 @@
+//! CPU renderer drawing into an RGBA image
+//!
+//! Shapes are turned into polygons, clipped to the clip rectangle and
+//! rasterized with exact area coverage, which anti-aliases every edge.
+//! Batched calls rasterize all their shapes into one coverage mask, so the
+//! joints of a polyline or the shared edges of a mesh aren't blended twice.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+use ab_glyph::{Font, FontArc, GlyphId, PxScale, ScaleFont};
+use ab_glyph_rasterizer::Rasterizer;
+
+use crate::{Renderer, RendererCapabilities};
+
+/// Glyph bitmaps kept before the cache is cleared
+const GLYPH_CACHE_SIZE: usize = 4096;
+
+/// Subpixel positions a glyph is rasterized at, per axis
+const SUBPIXEL_STEPS: f32 = 4.0;
+
+/// Renderer that draws into an RGBA8 pixel buffer, for image export and
+/// machines without a GPU
+pub struct CpuRenderer {
+    width: u32,
+    height: u32,
+    /// Non-premultiplied RGBA8, row by row from the top
+    pixels: Vec<u8>,
+    background: [f32; 4],
+    /// Area `[x0, y0, x1, y1]` drawing is restricted to, within the image
+    clip: [f32; 4],
+    /// Coverage buffer, reused between shapes
+    rasterizer: Rasterizer,
+    glyphs: HashMap<GlyphKey, GlyphBitmap>,
+}
+
+/// A glyph of one font at one size and subpixel offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: usize,
+    glyph: GlyphId,
+    size: u32,
+    offset: [u8; 2],
+}
+
+/// Coverage of a rasterized glyph, relative to its pen position
+struct GlyphBitmap {
+    left: i32,
+    top: i32,
+    width: usize,
+    coverage: Vec<f32>,
+}
+
+impl CpuRenderer {
+    /// Create a `width` × `height` image cleared to opaque black
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut renderer = Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+            background: [0.0, 0.0, 0.0, 1.0],
+            clip: [0.0, 0.0, width as f32, height as f32],
+            rasterizer: Rasterizer::new(0, 0),
+            glyphs: HashMap::new(),
+        };
+        renderer.begin_frame();
+        renderer
+    }
+
+    /// Color `begin_frame` clears to
+    pub fn with_background(mut self, color: [f32; 4]) -> Self {
+        self.background = color;
+        self.begin_frame();
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// RGBA8 pixels, row by row from the top
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Color of one pixel
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    /// Encode the image as PNG
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)
+    }
+
+    /// Save the image as a PNG file
+    pub fn save_png(&self, path: &Path) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_png(file).map_err(std::io::Error::other)
+    }
+
+    /// Width of `text` drawn at `size` by `draw_text`
+    pub fn text_width(&self, text: &str, size: f32) -> f32 {
+        let fonts = fonts();
+        let mut width = 0.0;
+        let mut previous: Option<(usize, GlyphId)> = None;
+        for c in text.chars() {
+            let (index, font) = font_for(fonts, c);
+            let scaled = font.font.as_scaled(font.scale(size));
+            let id = scaled.glyph_id(c);
+            if let Some((_, previous_id)) = previous.filter(|(i, _)| *i == index) {
+                width += scaled.kern(previous_id, id);
+            }
+            width += scaled.h_advance(id);
+            previous = Some((index, id));
+        }
+        width
+    }
+
+    /// Blend `color` over pixel `(x, y)`, scaled by `coverage`
+    fn blend(&mut self, x: i32, y: i32, color: [f32; 4], coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let alpha = color[3] * coverage.min(1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        let dst = &mut self.pixels[i..i + 4];
+        let dst_alpha = dst[3] as f32 / 255.0;
+        let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+        for c in 0..3 {
+            let d = dst[c] as f32 / 255.0;
+            let blended = (color[c] * alpha + d * dst_alpha * (1.0 - alpha)) / out_alpha.max(f32::EPSILON);
+            dst[c] = (blended * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+        dst[3] = (out_alpha * 255.0).round() as u8;
+    }
+
+    /// Fill the union of `polygons` with anti-aliased edges
+    fn fill(&mut self, polygons: &[Vec<[f32; 2]>], color: [f32; 4]) {
+        if color[3] <= 0.0 {
+            return;
+        }
+        let clipped: Vec<Vec<[f32; 2]>> = polygons.iter()
+            .map(|polygon| clip_polygon(polygon, self.clip))
+            .filter(|polygon| polygon.len() >= 3)
+            .collect();
+        let mut bounds = [f32::INFINITY, f32::INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY];
+        for p in clipped.iter().flatten() {
+            bounds = [bounds[0].min(p[0]), bounds[1].min(p[1]), bounds[2].max(p[0]), bounds[3].max(p[1])];
+        }
+        if !bounds[0].is_finite() {
+            return;
+        }
+        let (x0, y0) = (bounds[0].floor(), bounds[1].floor());
+        let width = (bounds[2].ceil() - x0) as usize;
+        let height = (bounds[3].ceil() - y0) as usize;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let mut rasterizer = std::mem::replace(&mut self.rasterizer, Rasterizer::new(0, 0));
+        rasterizer.reset(width, height);
+        for polygon in &clipped {
+            for (i, a) in polygon.iter().enumerate() {
+                let b = polygon[(i + 1) % polygon.len()];
+                rasterizer.draw_line(
+                    ab_glyph_rasterizer::point(a[0] - x0, a[1] - y0),
+                    ab_glyph_rasterizer::point(b[0] - x0, b[1] - y0),
+                );
+            }
+        }
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        rasterizer.for_each_pixel_2d(|x, y, coverage| {
+            if coverage > 0.0 {
+                self.blend(x0 + x as i32, y0 + y as i32, color, coverage);
+            }
+        });
+        self.rasterizer = rasterizer;
+    }
+
+    /// Fill an axis-aligned rectangle, with partial coverage on fractional edges
+    fn fill_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4]) {
+        let [cx0, cy0, cx1, cy1] = self.clip;
+        let (x0, y0) = (min[0].max(cx0), min[1].max(cy0));
+        let (x1, y1) = (max[0].min(cx1), max[1].min(cy1));
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+        // Coverage of pixel `i` by the span `[a, b)`
+        let span = |i: i32, a: f32, b: f32| (b.min(i as f32 + 1.0) - a.max(i as f32)).clamp(0.0, 1.0);
+        for y in y0.floor() as i32..y1.ceil() as i32 {
+            let row = span(y, y0, y1);
+            for x in x0.floor() as i32..x1.ceil() as i32 {
+                self.blend(x, y, color, row * span(x, x0, x1));
+            }
+        }
+    }
+
+    /// Draw a glyph with its origin at `pen`, rasterizing it on first use
+    fn draw_glyph(&mut self, key: GlyphKey, font: &FontData, pen: [f32; 2], color: [f32; 4]) {
+        if self.glyphs.len() >= GLYPH_CACHE_SIZE && !self.glyphs.contains_key(&key) {
+            self.glyphs.clear();
+        }
+        let bitmap = self.glyphs.remove(&key).unwrap_or_else(|| rasterize_glyph(key, font));
+
+        let (origin_x, origin_y) = (pen[0].floor() as i32, pen[1].floor() as i32);
+        let [x0, y0, x1, y1] = self.clip;
+        for (i, &coverage) in bitmap.coverage.iter().enumerate() {
+            let x = origin_x + bitmap.left + (i % bitmap.width) as i32;
+            let y = origin_y + bitmap.top + (i / bitmap.width) as i32;
+            let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+            if cx >= x0 && cx < x1 && cy >= y0 && cy < y1 {
+                self.blend(x, y, color, coverage);
+            }
+        }
+        self.glyphs.insert(key, bitmap);
+    }
+}
+
+/// Coverage of a glyph at the size and subpixel offset of `key`
+fn rasterize_glyph(key: GlyphKey, font: &FontData) -> GlyphBitmap {
+    let offset = key.offset.map(|o| o as f32 / SUBPIXEL_STEPS);
+    let glyph = key.glyph.with_scale_and_position(f32::from_bits(key.size), ab_glyph::point(offset[0], offset[1]));
+    let Some(outline) = font.font.outline_glyph(glyph) else {
+        return GlyphBitmap { left: 0, top: 0, width: 1, coverage: Vec::new() };
+    };
+    let bounds = outline.px_bounds();
+    let width = (bounds.width() as usize).max(1);
+    let mut coverage = vec![0.0; width * bounds.height() as usize];
+    outline.draw(|x, y, c| {
+        if let Some(cell) = coverage.get_mut(y as usize * width + x as usize) {
+            *cell = c;
+        }
+    });
+    GlyphBitmap { left: bounds.min.x as i32, top: bounds.min.y as i32, width, coverage }
+}
+
+/// Outline of a line as a rectangle, extended by half its width at both ends
+/// so that consecutive segments meet without gaps
+fn line_polygon(start: [f32; 2], end: [f32; 2], width: f32) -> Option<Vec<[f32; 2]>> {
+    let (dx, dy) = (end[0] - start[0], end[1] - start[1]);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 || !length.is_finite() {
+        return None;
+    }
+    let half = width / 2.0;
+    let (ux, uy) = (dx / length * half, dy / length * half);
+    let (nx, ny) = (-uy, ux);
+    Some(counter_clockwise(&[
+        [start[0] - ux + nx, start[1] - uy + ny],
+        [end[0] + ux + nx, end[1] + uy + ny],
+        [end[0] + ux - nx, end[1] + uy - ny],
+        [start[0] - ux - nx, start[1] - uy - ny],
+    ]))
+}
+
+/// Circle as a polygon fine enough to look round at its size
+fn circle_polygon(center: [f32; 2], radius: f32) -> Vec<[f32; 2]> {
+    let segments = (radius * 2.5).ceil().clamp(8.0, 64.0) as usize;
+    (0..segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+        })
+        .collect()
+}
+
+/// Copy of `points` wound counter-clockwise, so that overlapping polygons
+/// add up instead of cancelling out
+fn counter_clockwise(points: &[[f32; 2]]) -> Vec<[f32; 2]> {
+    let area: f32 = points.iter().enumerate()
+        .map(|(i, a)| {
+            let b = points[(i + 1) % points.len()];
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum();
+    let mut points = points.to_vec();
+    if area < 0.0 {
+        points.reverse();
+    }
+    points
+}
+
+/// Sutherland–Hodgman clipping of a polygon to the rectangle `[x0, y0, x1, y1]`
+fn clip_polygon(points: &[[f32; 2]], [x0, y0, x1, y1]: [f32; 4]) -> Vec<[f32; 2]> {
+    let mut polygon = points.to_vec();
+    // Each edge as an axis, a bound, and whether points inside are below it
+    for (axis, bound, below) in [(0, x0, false), (0, x1, true), (1, y0, false), (1, y1, true)] {
+        if polygon.is_empty() {
+            break;
+        }
+        let inside = |p: &[f32; 2]| if below { p[axis] <= bound } else { p[axis] >= bound };
+        let input = std::mem::take(&mut polygon);
+        for (i, current) in input.iter().enumerate() {
+            let previous = input[(i + input.len() - 1) % input.len()];
+            if inside(current) != inside(&previous) {
+                let t = (bound - previous[axis]) / (current[axis] - previous[axis]);
+                let mut crossing = [
+                    previous[0] + t * (current[0] - previous[0]),
+                    previous[1] + t * (current[1] - previous[1]),
+                ];
+                crossing[axis] = bound;
+                polygon.push(crossing);
+            }
+            if inside(current) {
+                polygon.push(*current);
+            }
+        }
+    }
+    polygon
+}
+
+/// A font of the fallback chain
+struct FontData {
+    font: FontArc,
+    /// Size multiplier from egui's font tweaks
+    scale: f32,
+}
+
+impl FontData {
+    fn scale(&self, size: f32) -> PxScale {
+        PxScale::from(size * self.scale)
+    }
+}
+
+/// egui's bundled proportional fonts, in fallback order
+fn fonts() -> &'static [FontData] {
+    static FONTS: OnceLock<Vec<FontData>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let definitions = egui::FontDefinitions::default();
+        definitions.families.get(&egui::FontFamily::Proportional)
+            .into_iter()
+            .flatten()
+            .filter_map(|name| {
+                let data = definitions.font_data.get(name)?;
+                Some(FontData {
+                    font: FontArc::try_from_vec(data.font.to_vec()).ok()?,
+                    scale: data.tweak.scale,
+                })
+            })
+            .collect()
+    })
+}
+
+/// The first font that has a glyph for `c`, or the primary font
+fn font_for(fonts: &[FontData], c: char) -> (usize, &FontData) {
+    fonts.iter().enumerate()
+        .find(|(_, font)| font.font.glyph_id(c).0 != 0)
+        .unwrap_or((0, &fonts[0]))
+}
+
+impl Renderer for CpuRenderer {
+    fn begin_frame(&mut self) {
+        let rgba = self.background.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    fn end_frame(&mut self) {}
+
+    fn draw_line(&mut self, start: [f32; 2], end: [f32; 2], color: [f32; 4], width: f32) {
+        self.draw_lines(&[(start, end)], color, width);
+    }
+
+    fn draw_lines(&mut self, segments: &[([f32; 2], [f32; 2])], color: [f32; 4], width: f32) {
+        // Hairlines are drawn a pixel wide and faded in proportion
+        let (width, color) = if width < 1.0 {
+            (1.0, [color[0], color[1], color[2], color[3] * width.max(0.0)])
+        } else {
+            (width, color)
+        };
+        let polygons: Vec<Vec<[f32; 2]>> = segments.iter()
+            .filter_map(|&(start, end)| line_polygon(start, end, width))
+            .collect();
+        self.fill(&polygons, color);
+    }
+
+    fn draw_point(&mut self, position: [f32; 2], color: [f32; 4], size: f32) {
+        self.draw_points(&[position], color, size);
+    }
+
+    fn draw_points(&mut self, positions: &[[f32; 2]], color: [f32; 4], size: f32) {
+        let polygons: Vec<Vec<[f32; 2]>> = positions.iter()
+            .map(|&position| circle_polygon(position, size.max(0.0) / 2.0))
+            .collect();
+        self.fill(&polygons, color);
+    }
+
+    fn draw_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4], filled: bool) {
+        if filled {
+            self.fill_rect(min, max, color);
+        } else {
+            let corners = [min, [max[0], min[1]], max, [min[0], max[1]]];
+            self.draw_polygon(&corners, color, false);
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, position: [f32; 2], color: [f32; 4], size: f32) {
+        let fonts = fonts();
+        if fonts.is_empty() || size <= 0.0 {
+            return;
+        }
+        let baseline = position[1] + fonts[0].font.as_scaled(fonts[0].scale(size)).ascent();
+        let fraction = |v: f32| ((v - v.floor()) * SUBPIXEL_STEPS).floor() as u8;
+        let mut x = position[0];
+        let mut previous: Option<(usize, GlyphId)> = None;
+        for c in text.chars() {
+            let (index, font) = font_for(fonts, c);
+            let scale = font.scale(size);
+            let scaled = font.font.as_scaled(scale);
+            let id = scaled.glyph_id(c);
+            if let Some((_, previous_id)) = previous.filter(|(i, _)| *i == index) {
+                x += scaled.kern(previous_id, id);
+            }
+            if !c.is_whitespace() {
+                let key = GlyphKey {
+                    font: index,
+                    glyph: id,
+                    size: scale.y.to_bits(),
+                    offset: [fraction(x), fraction(baseline)],
+                };
+                self.draw_glyph(key, font, [x, baseline], color);
+            }
+            x += scaled.h_advance(id);
+            previous = Some((index, id));
+        }
+    }
+
+    fn draw_polygon(&mut self, points: &[[f32; 2]], color: [f32; 4], filled: bool) {
+        if filled {
+            self.fill(&[points.to_vec()], color);
+        } else {
+            let segments: Vec<_> = (0..points.len())
+                .map(|i| (points[i], points[(i + 1) % points.len()]))
+                .collect();
+            self.draw_lines(&segments, color, 1.0);
+        }
+    }
+
+    fn draw_triangles(&mut self, triangles: &[[[f32; 2]; 3]], color: [f32; 4]) {
+        let polygons: Vec<Vec<[f32; 2]>> = triangles.iter().map(|t| counter_clockwise(t)).collect();
+        self.fill(&polygons, color);
+    }
+
+    fn set_clip_rect(&mut self, rect: Option<([f32; 2], [f32; 2])>) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        self.clip = match rect {
+            Some((min, max)) => [min[0].clamp(0.0, w), min[1].clamp(0.0, h), max[0].clamp(0.0, w), max[1].clamp(0.0, h)],
+            None => [0.0, 0.0, w, h],
+        };
+    }
+
+    fn capabilities(&self) -> RendererCapabilities {
+        RendererCapabilities {
+            max_texture_size: self.width.max(self.height),
+            max_vertices: usize::MAX,
+            supports_instancing: false,
+            supports_compute: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+    const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+    fn canvas(width: u32, height: u32) -> CpuRenderer {
+        CpuRenderer::new(width, height).with_background(WHITE)
+    }
+
+    /// How much black was drawn over the white background, 0 to 255
+    fn ink(renderer: &CpuRenderer, x: u32, y: u32) -> u8 {
+        255 - renderer.pixel(x, y)[0]
+    }
+
+    #[test]
+    fn draws_and_encodes_png() {
+        let mut renderer = canvas(40, 20);
+        renderer.draw_rect([0.0, 0.0], [10.0, 10.0], [1.0, 0.0, 0.0, 1.0], true);
+        renderer.draw_line([0.0, 15.5], [40.0, 15.5], [0.0, 0.0, 1.0, 0.5], 1.0);
+        renderer.draw_text("Hi", [20.0, 0.0], BLACK, 14.0);
+
+        assert_eq!(renderer.pixel(5, 5), [255, 0, 0, 255]);
+        assert_eq!(renderer.pixel(20, 15), [128, 128, 255, 255]);
+        assert_eq!(renderer.pixel(38, 5)[..3], [255, 255, 255], "right of the text");
+        let text_pixels = (20..40).flat_map(|x| (0..14).map(move |y| (x, y)))
+            .filter(|&(x, y)| renderer.pixel(x, y)[0] < 128)
+            .count();
+        assert!(text_pixels > 10);
+
+        let mut png = Vec::new();
+        renderer.write_png(&mut png).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+    }
+
+    #[test]
+    fn anti_aliases_edges_by_coverage() {
+        let mut renderer = canvas(10, 10);
+        // Covers column 2 fully and half of columns 1 and 3
+        renderer.draw_rect([1.5, 0.0], [3.5, 10.0], BLACK, true);
+        assert_eq!(ink(&renderer, 2, 5), 255);
+        assert_eq!(ink(&renderer, 1, 5), 127);
+        assert_eq!(ink(&renderer, 3, 5), 127);
+        assert_eq!(ink(&renderer, 4, 5), 0);
+
+        // A diagonal line leaves partially covered pixels along its edges
+        let mut renderer = canvas(20, 20);
+        renderer.draw_line([2.0, 2.0], [18.0, 12.0], BLACK, 2.0);
+        let partial = (0..20).flat_map(|x| (0..20).map(move |y| (x, y)))
+            .filter(|&(x, y)| (1..255).contains(&ink(&renderer, x, y)))
+            .count();
+        assert!(partial > 20, "only {} partially covered pixels", partial);
+    }
+
+    #[test]
+    fn clips_geometry_and_text() {
+        let mut renderer = canvas(20, 20);
+        renderer.set_clip_rect(Some(([5.0, 5.0], [10.0, 10.0])));
+        renderer.draw_rect([-1000.0, -1000.0], [1000.0, 1000.0], BLACK, true);
+        renderer.draw_line([-1e6, 15.0], [1e6, 15.0], BLACK, 3.0);
+        renderer.draw_text("Clipped", [0.0, 0.0], BLACK, 20.0);
+        assert_eq!(ink(&renderer, 7, 7), 255);
+        assert_eq!(ink(&renderer, 4, 7), 0);
+        assert_eq!(ink(&renderer, 10, 7), 0);
+        assert_eq!(ink(&renderer, 7, 15), 0);
+        assert_eq!(ink(&renderer, 2, 2), 0);
+
+        renderer.set_clip_rect(None);
+        renderer.draw_line([0.0, 15.0], [20.0, 15.0], BLACK, 2.0);
+        assert_eq!(ink(&renderer, 10, 15), 255);
+    }
+
+    #[test]
+    fn batches_blend_overlaps_once() {
+        let translucent = [0.0, 0.0, 0.0, 0.5];
+
+        // Separate calls blend the joint twice
+        let mut separate = canvas(20, 20);
+        separate.draw_line([2.0, 10.0], [10.0, 10.0], translucent, 4.0);
+        separate.draw_line([10.0, 10.0], [18.0, 10.0], translucent, 4.0);
+        assert_eq!(ink(&separate, 10, 10), 191);
+
+        // A batched polyline covers it once
+        let mut batched = canvas(20, 20);
+        batched.draw_lines(&[([2.0, 10.0], [10.0, 10.0]), ([10.0, 10.0], [18.0, 10.0])], translucent, 4.0);
+        assert_eq!(ink(&batched, 10, 10), 127);
+        assert_eq!(ink(&batched, 5, 10), 127);
+
+        // Adjacent mesh triangles leave no seam, whatever their winding
+        let mut mesh = canvas(10, 10);
+        mesh.draw_triangles(&[
+            [[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]],
+            [[10.0, 10.0], [10.0, 0.0], [0.0, 10.0]],
+        ], BLACK);
+        assert!((0..10).all(|i| ink(&mesh, i, 9 - i) == 255));
+
+        let mut points = canvas(20, 20);
+        points.draw_points(&[[5.0, 5.0], [15.0, 15.0]], BLACK, 4.0);
+        assert_eq!(ink(&points, 5, 5), 255);
+        assert_eq!(ink(&points, 15, 15), 255);
+        assert_eq!(ink(&points, 10, 10), 0);
+    }
+
+    #[test]
+    fn falls_back_to_other_fonts_for_missing_glyphs() {
+        let fonts = fonts();
+        assert!(fonts.len() > 1);
+        assert_eq!(font_for(fonts, 'a').0, 0);
+        // U+1F438 FROG FACE is only in the emoji font
+        assert_ne!(font_for(fonts, '\u{1F438}').0, 0);
+
+        let mut renderer = canvas(40, 40);
+        renderer.draw_text("\u{1F438}", [4.0, 4.0], BLACK, 28.0);
+        let drawn = (0..40).flat_map(|x| (0..40).map(move |y| (x, y)))
+            .filter(|&(x, y)| ink(&renderer, x, y) > 128)
+            .count();
+        assert!(drawn > 50);
+        assert!(renderer.text_width("\u{1F438}", 28.0) > 10.0);
+    }
+}
@@ -0,0 +1,112 @@
+//! Drawing with a [`Renderer`] into egui
+//!
+//! The reverse of [`crate::egui_shapes`]: renderer calls become shapes on an
+//! `egui::Painter`, so drawing recorded as [`crate::DrawCommand`]s can be
+//! shown in a view.
+
+use egui::epaint::{Color32, FontId, Mesh, Pos2, Rect, Shape, Stroke};
+use egui::{Align2, Painter, Vec2};
+
+use crate::{Renderer, RendererCapabilities};
+
+/// Renderer drawing onto an egui painter, with its coordinates relative to
+/// an origin such as the top left of a view
+pub struct PainterRenderer {
+    painter: Painter,
+    origin: Vec2,
+    /// The painter's own clip rectangle, which clip rectangles set while
+    /// drawing stay within
+    clip_rect: Rect,
+}
+
+impl PainterRenderer {
+    pub fn new(painter: Painter, origin: Pos2) -> Self {
+        let clip_rect = painter.clip_rect();
+        Self { painter, origin: origin.to_vec2(), clip_rect }
+    }
+
+    fn pos(&self, point: [f32; 2]) -> Pos2 {
+        Pos2::new(point[0], point[1]) + self.origin
+    }
+}
+
+impl Renderer for PainterRenderer {
+    fn begin_frame(&mut self) {}
+
+    fn end_frame(&mut self) {}
+
+    fn draw_line(&mut self, start: [f32; 2], end: [f32; 2], color: [f32; 4], width: f32) {
+        self.painter.line_segment([self.pos(start), self.pos(end)], Stroke::new(width, color32(color)));
+    }
+
+    fn draw_point(&mut self, position: [f32; 2], color: [f32; 4], size: f32) {
+        self.painter.circle_filled(self.pos(position), size / 2.0, color32(color));
+    }
+
+    fn draw_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4], filled: bool) {
+        let rect = Rect::from_two_pos(self.pos(min), self.pos(max));
+        if filled {
+            self.painter.rect_filled(rect, 0.0, color32(color));
+        } else {
+            self.painter.rect_stroke(rect, 0.0, Stroke::new(1.0, color32(color)));
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, position: [f32; 2], color: [f32; 4], size: f32) {
+        self.painter.text(self.pos(position), Align2::LEFT_TOP, text, FontId::proportional(size), color32(color));
+    }
+
+    /// Filled polygons are drawn as convex
+    fn draw_polygon(&mut self, points: &[[f32; 2]], color: [f32; 4], filled: bool) {
+        let points: Vec<Pos2> = points.iter().map(|&point| self.pos(point)).collect();
+        let shape = if filled {
+            Shape::convex_polygon(points, color32(color), Stroke::NONE)
+        } else {
+            Shape::closed_line(points, Stroke::new(1.0, color32(color)))
+        };
+        self.painter.add(shape);
+    }
+
+    fn draw_lines(&mut self, segments: &[([f32; 2], [f32; 2])], color: [f32; 4], width: f32) {
+        let stroke = Stroke::new(width, color32(color));
+        let shapes = segments.iter()
+            .map(|&(start, end)| Shape::line_segment([self.pos(start), self.pos(end)], stroke))
+            .collect();
+        self.painter.add(Shape::Vec(shapes));
+    }
+
+    fn draw_triangles(&mut self, triangles: &[[[f32; 2]; 3]], color: [f32; 4]) {
+        let color = color32(color);
+        let mut mesh = Mesh::default();
+        for triangle in triangles {
+            let first = mesh.vertices.len() as u32;
+            for &point in triangle {
+                mesh.colored_vertex(self.pos(point), color);
+            }
+            mesh.add_triangle(first, first + 1, first + 2);
+        }
+        self.painter.add(Shape::mesh(mesh));
+    }
+
+    fn set_clip_rect(&mut self, rect: Option<([f32; 2], [f32; 2])>) {
+        let clip_rect = match rect {
+            Some((min, max)) => Rect::from_two_pos(self.pos(min), self.pos(max)).intersect(self.clip_rect),
+            None => self.clip_rect,
+        };
+        self.painter.set_clip_rect(clip_rect);
+    }
+
+    fn capabilities(&self) -> RendererCapabilities {
+        RendererCapabilities {
+            max_texture_size: 0,
+            max_vertices: usize::MAX,
+            supports_instancing: false,
+            supports_compute: false,
+        }
+    }
+}
+
+fn color32(color: [f32; 4]) -> Color32 {
+    let [r, g, b, a] = color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    Color32::from_rgba_unmultiplied(r, g, b, a)
+}
@@ -0,0 +1,154 @@
+//! Drawing egui output with a [`Renderer`]
+//!
+//! Views paint through egui; running them in a headless `egui::Context` and
+//! passing the resulting shapes here draws them with any renderer, e.g. into
+//! an image with [`crate::CpuRenderer`] or a document with [`crate::SvgRenderer`].
+
+use egui::epaint::{ClippedShape, Color32, Pos2, Shape, Stroke, TextShape, TextureId};
+
+use crate::Renderer;
+
+/// Segments used to approximate a circle outline
+const CIRCLE_SEGMENTS: usize = 48;
+
+/// Draw `shapes` in order, each clipped to its clip rectangle
+pub fn paint_shapes(renderer: &mut dyn Renderer, shapes: &[ClippedShape]) {
+    for clipped in shapes {
+        let clip = clipped.clip_rect;
+        renderer.set_clip_rect(Some(([clip.min.x, clip.min.y], [clip.max.x, clip.max.y])));
+        paint_shape(renderer, &clipped.shape);
+    }
+    renderer.set_clip_rect(None);
+}
+
+/// Draw one shape
+pub fn paint_shape(renderer: &mut dyn Renderer, shape: &Shape) {
+    match shape {
+        Shape::Noop | Shape::Callback(_) => {}
+        Shape::Vec(shapes) => {
+            for shape in shapes {
+                paint_shape(renderer, shape);
+            }
+        }
+        Shape::Circle(circle) => {
+            if let Some(fill) = rgba(circle.fill) {
+                renderer.draw_point(point(circle.center), fill, circle.radius * 2.0);
+            }
+            let outline: Vec<Pos2> = (0..CIRCLE_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+                    circle.center + circle.radius * egui::vec2(angle.cos(), angle.sin())
+                })
+                .collect();
+            stroke_path(renderer, &outline, true, circle.stroke);
+        }
+        Shape::LineSegment { points, stroke } => {
+            stroke_path(renderer, points, false, *stroke);
+        }
+        Shape::Path(path) => {
+            fill_path(renderer, &path.points, path.closed, path.fill);
+            stroke_path(renderer, &path.points, path.closed, path.stroke);
+        }
+        Shape::Rect(rect) => {
+            // Textured rectangles are images, which renderers can't draw
+            if rect.fill_texture_id == TextureId::default() {
+                if let Some(fill) = rgba(rect.fill) {
+                    renderer.draw_rect(point(rect.rect.min), point(rect.rect.max), fill, true);
+                }
+            }
+            let r = rect.rect;
+            stroke_path(renderer, &[r.left_top(), r.right_top(), r.right_bottom(), r.left_bottom()], true, rect.stroke);
+        }
+        Shape::Text(text) => paint_text(renderer, text),
+        Shape::Mesh(mesh) => {
+            if mesh.texture_id != TextureId::default() {
+                return;
+            }
+            // Triangles are batched by color, in order of first use, so
+            // renderers can fill them without seams between neighbours
+            let mut batches: Vec<(Color32, Vec<[[f32; 2]; 3]>)> = Vec::new();
+            for triangle in mesh.indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| &mesh.vertices[i as usize]);
+                let triangle = [point(a.pos), point(b.pos), point(c.pos)];
+                match batches.iter_mut().find(|(color, _)| *color == a.color) {
+                    Some((_, triangles)) => triangles.push(triangle),
+                    None => batches.push((a.color, vec![triangle])),
+                }
+            }
+            for (color, triangles) in batches {
+                if let Some(color) = rgba(color) {
+                    renderer.draw_triangles(&triangles, color);
+                }
+            }
+        }
+        Shape::QuadraticBezier(bezier) => {
+            let points = bezier.flatten(None);
+            fill_path(renderer, &points, bezier.closed, bezier.fill);
+            stroke_path(renderer, &points, bezier.closed, bezier.stroke);
+        }
+        Shape::CubicBezier(bezier) => {
+            let points = bezier.flatten(None);
+            fill_path(renderer, &points, bezier.closed, bezier.fill);
+            stroke_path(renderer, &points, bezier.closed, bezier.stroke);
+        }
+    }
+}
+
+/// Draw each row of the text, in runs of the same format
+fn paint_text(renderer: &mut dyn Renderer, text: &TextShape) {
+    let galley = &text.galley;
+    for row in &galley.rows {
+        let mut glyphs = row.glyphs.iter().peekable();
+        while let Some(first) = glyphs.next() {
+            let mut run = String::from(first.chr);
+            while let Some(glyph) = glyphs.next_if(|g| g.section_index == first.section_index) {
+                run.push(glyph.chr);
+            }
+            if run.trim().is_empty() {
+                continue;
+            }
+            let Some(section) = galley.job.sections.get(first.section_index as usize) else {
+                continue;
+            };
+            let color = text.override_text_color.unwrap_or(section.format.color);
+            let Some(color) = rgba(color) else {
+                continue;
+            };
+            let position = text.pos + egui::vec2(first.pos.x, row.rect.min.y);
+            renderer.draw_text(&run, point(position), color, section.format.font_id.size);
+        }
+    }
+}
+
+fn fill_path(renderer: &mut dyn Renderer, points: &[Pos2], closed: bool, fill: Color32) {
+    if !closed || points.len() < 3 {
+        return;
+    }
+    if let Some(fill) = rgba(fill) {
+        let points: Vec<[f32; 2]> = points.iter().copied().map(point).collect();
+        renderer.draw_polygon(&points, fill, true);
+    }
+}
+
+fn stroke_path(renderer: &mut dyn Renderer, points: &[Pos2], closed: bool, stroke: Stroke) {
+    let Some(color) = rgba(stroke.color).filter(|_| stroke.width > 0.0) else {
+        return;
+    };
+    let mut segments: Vec<_> = points.windows(2).map(|pair| (point(pair[0]), point(pair[1]))).collect();
+    if closed && points.len() > 2 {
+        segments.push((point(points[points.len() - 1]), point(points[0])));
+    }
+    renderer.draw_lines(&segments, color, stroke.width);
+}
+
+fn point(pos: Pos2) -> [f32; 2] {
+    [pos.x, pos.y]
+}
+
+/// Renderer color for an egui color, or `None` when it's invisible
+fn rgba(color: Color32) -> Option<[f32; 4]> {
+    if color.a() == 0 {
+        return None;
+    }
+    Some(color.to_srgba_unmultiplied().map(|c| c as f32 / 255.0))
+}
@@ -2,6 +2,20 @@
 //! 
 //! This crate provides GPU and CPU rendering capabilities for high-performance
 //! data visualization.
+//!
+//! Coordinates are in pixels with the origin at the top left. Colors are
+//! non-premultiplied sRGB components in `0.0..=1.0`.
+
+mod commands;
+mod cpu;
+mod svg;
+pub mod egui_painter;
+pub mod egui_shapes;
+
+pub use commands::{replay, DrawCommand};
+pub use cpu::CpuRenderer;
+pub use egui_painter::PainterRenderer;
+pub use svg::SvgRenderer;
 
 /// Trait for renderers
 pub trait Renderer: Send {
@@ -20,13 +34,81 @@ pub trait Renderer: Send {
     /// Draw a rectangle
     fn draw_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4], filled: bool);
     
-    /// Draw text
+    /// Draw text with its top left corner at `position`
     fn draw_text(&mut self, text: &str, position: [f32; 2], color: [f32; 4], size: f32);
     
+    /// Draw a polygon. The default strokes its outline, and fills it with
+    /// one-pixel scanlines by the even-odd rule.
+    fn draw_polygon(&mut self, points: &[[f32; 2]], color: [f32; 4], filled: bool) {
+        if filled {
+            self.draw_lines(&scanline_spans(points), color, 1.0);
+        }
+        for (i, &start) in points.iter().enumerate() {
+            let end = points[(i + 1) % points.len()];
+            self.draw_line(start, end, color, 1.0);
+        }
+    }
+    
+    /// Draw many segments of one color and width, such as a polyline
+    fn draw_lines(&mut self, segments: &[([f32; 2], [f32; 2])], color: [f32; 4], width: f32) {
+        for &(start, end) in segments {
+            self.draw_line(start, end, color, width);
+        }
+    }
+    
+    /// Draw many points of one color and size
+    fn draw_points(&mut self, positions: &[[f32; 2]], color: [f32; 4], size: f32) {
+        for &position in positions {
+            self.draw_point(position, color, size);
+        }
+    }
+    
+    /// Fill many triangles of one color, such as a mesh
+    fn draw_triangles(&mut self, triangles: &[[[f32; 2]; 3]], color: [f32; 4]) {
+        for triangle in triangles {
+            self.draw_polygon(triangle, color, true);
+        }
+    }
+    
+    /// Restrict drawing to `[min, max]`, or lift the restriction with `None`
+    fn set_clip_rect(&mut self, _rect: Option<([f32; 2], [f32; 2])>) {}
+    
     /// Get renderer capabilities
     fn capabilities(&self) -> RendererCapabilities;
 }
 
+/// Horizontal spans covering the inside of a polygon, one per pixel row
+fn scanline_spans(points: &[[f32; 2]]) -> Vec<([f32; 2], [f32; 2])> {
+    let mut spans = Vec::new();
+    if points.len() < 3 {
+        return spans;
+    }
+    let (min_y, max_y) = points.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), p| (lo.min(p[1]), hi.max(p[1])));
+    if !min_y.is_finite() || !max_y.is_finite() {
+        return spans;
+    }
+
+    // Sample each row at its pixel center
+    let mut crossings = Vec::new();
+    let mut y = min_y.floor() + 0.5;
+    while y < max_y {
+        crossings.clear();
+        for (i, &a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            // Half-open in y, so a vertex shared by two edges counts once
+            if (a[1] <= y) != (b[1] <= y) {
+                crossings.push(a[0] + (y - a[1]) / (b[1] - a[1]) * (b[0] - a[0]));
+            }
+        }
+        crossings.sort_by(f32::total_cmp);
+        for pair in crossings.chunks_exact(2) {
+            spans.push(([pair[0], y], [pair[1], y]));
+        }
+        y += 1.0;
+    }
+    spans
+}
+
 /// Renderer capabilities
 #[derive(Debug, Clone)]
 pub struct RendererCapabilities {
@@ -36,7 +118,60 @@ pub struct RendererCapabilities {
     pub supports_compute: bool,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the lines drawn through it
+    #[derive(Default)]
+    struct LineRecorder {
+        lines: Vec<([f32; 2], [f32; 2])>,
+    }
+
+    impl Renderer for LineRecorder {
+        fn begin_frame(&mut self) {}
+        fn end_frame(&mut self) {}
+        fn draw_line(&mut self, start: [f32; 2], end: [f32; 2], _color: [f32; 4], _width: f32) {
+            self.lines.push((start, end));
+        }
+        fn draw_point(&mut self, _position: [f32; 2], _color: [f32; 4], _size: f32) {}
+        fn draw_rect(&mut self, _min: [f32; 2], _max: [f32; 2], _color: [f32; 4], _filled: bool) {}
+        fn draw_text(&mut self, _text: &str, _position: [f32; 2], _color: [f32; 4], _size: f32) {}
+        fn capabilities(&self) -> RendererCapabilities {
+            RendererCapabilities { max_texture_size: 0, max_vertices: 0, supports_instancing: false, supports_compute: false }
+        }
+    }
+
+    #[test]
+    fn default_polygons_fill_only_when_asked() {
+        let square = [[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]];
+        let mut outline = LineRecorder::default();
+        outline.draw_polygon(&square, [1.0; 4], false);
+        assert_eq!(outline.lines.len(), 4);
+
+        let mut filled = LineRecorder::default();
+        filled.draw_polygon(&square, [1.0; 4], true);
+        let spans = &filled.lines[..filled.lines.len() - 4];
+        assert_eq!(spans.len(), 4);
+        for (i, &(start, end)) in spans.iter().enumerate() {
+            assert_eq!((start, end), ([0.0, i as f32 + 0.5], [4.0, i as f32 + 0.5]));
+        }
+    }
+
+    #[test]
+    fn scanlines_skip_the_notch_of_concave_polygons() {
+        // A "U" whose notch spans x 1..3 from y 0 to 2
+        let u = [[0.0, 0.0], [1.0, 0.0], [1.0, 2.0], [3.0, 2.0], [3.0, 0.0], [4.0, 0.0], [4.0, 3.0], [0.0, 3.0]];
+        let spans = scanline_spans(&u);
+        assert_eq!(spans, vec![
+            ([0.0, 0.5], [1.0, 0.5]), ([3.0, 0.5], [4.0, 0.5]),
+            ([0.0, 1.5], [1.0, 1.5]), ([3.0, 1.5], [4.0, 1.5]),
+            ([0.0, 2.5], [4.0, 2.5]),
+        ]);
+        assert!(scanline_spans(&[[0.0, 0.0], [1.0, 1.0]]).is_empty());
+    }
+}
+
 // TODO: Implement renderers
 // - GPU renderer using wgpu
-// - CPU fallback renderer
 // - Hybrid renderer that switches based on workload 
\ No newline at end of file
@@ -0,0 +1,275 @@
+//! Renderer that writes SVG documents
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::{Renderer, RendererCapabilities};
+
+/// Renderer that records drawing calls as an SVG document
+pub struct SvgRenderer {
+    width: f32,
+    height: f32,
+    background: Option<[f32; 4]>,
+    /// Elements of the current frame
+    body: String,
+    /// Clip path definitions
+    defs: String,
+    /// Rectangles of the clip paths in `defs`, which are reused by index
+    clip_rects: Vec<([f32; 2], [f32; 2])>,
+    /// Whether a clipped group is open in `body`
+    in_clip_group: bool,
+    clip: Option<([f32; 2], [f32; 2])>,
+}
+
+impl SvgRenderer {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            background: None,
+            body: String::new(),
+            defs: String::new(),
+            clip_rects: Vec::new(),
+            in_clip_group: false,
+            clip: None,
+        }
+    }
+
+    /// Fill the whole document with `color` before anything else
+    pub fn with_background(mut self, color: [f32; 4]) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// The document drawn so far
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = self.width,
+            h = self.height,
+        );
+        if !self.defs.is_empty() {
+            let _ = writeln!(svg, "<defs>\n{}</defs>", self.defs);
+        }
+        if let Some(background) = self.background {
+            let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" {}/>", paint("fill", background));
+        }
+        svg.push_str(&self.body);
+        if self.in_clip_group {
+            svg.push_str("</g>\n");
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
+
+    fn close_clip_group(&mut self) {
+        if self.in_clip_group {
+            self.body.push_str("</g>\n");
+            self.in_clip_group = false;
+        }
+    }
+}
+
+/// `fill`/`stroke` attribute pair for a color
+fn paint(attribute: &str, color: [f32; 4]) -> String {
+    let [r, g, b] = [color[0], color[1], color[2]].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+    if color[3] >= 1.0 {
+        format!("{}=\"#{:02x}{:02x}{:02x}\"", attribute, r, g, b)
+    } else {
+        format!("{a}=\"#{:02x}{:02x}{:02x}\" {a}-opacity=\"{:.3}\"", r, g, b, color[3].max(0.0), a = attribute)
+    }
+}
+
+fn points_attribute(points: &[[f32; 2]]) -> String {
+    points.iter().map(|p| format!("{:.2},{:.2}", p[0], p[1])).collect::<Vec<_>>().join(" ")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl Renderer for SvgRenderer {
+    fn begin_frame(&mut self) {
+        self.body.clear();
+        self.defs.clear();
+        self.clip_rects.clear();
+        self.in_clip_group = false;
+        self.clip = None;
+    }
+
+    fn end_frame(&mut self) {
+        self.close_clip_group();
+    }
+
+    fn draw_line(&mut self, start: [f32; 2], end: [f32; 2], color: [f32; 4], width: f32) {
+        let _ = writeln!(
+            self.body,
+            "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" {} stroke-width=\"{}\" stroke-linecap=\"round\"/>",
+            start[0], start[1], end[0], end[1], paint("stroke", color), width
+        );
+    }
+
+    fn draw_lines(&mut self, segments: &[([f32; 2], [f32; 2])], color: [f32; 4], width: f32) {
+        if segments.is_empty() {
+            return;
+        }
+        // One path, so translucent polylines don't darken at their joints
+        let mut data = String::new();
+        let mut pen: Option<[f32; 2]> = None;
+        for &(start, end) in segments {
+            if pen != Some(start) {
+                let _ = write!(data, "M{:.2},{:.2}", start[0], start[1]);
+            }
+            let _ = write!(data, "L{:.2},{:.2}", end[0], end[1]);
+            pen = Some(end);
+        }
+        let _ = writeln!(
+            self.body,
+            "<path d=\"{}\" fill=\"none\" {} stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>",
+            data, paint("stroke", color), width
+        );
+    }
+
+    fn draw_points(&mut self, positions: &[[f32; 2]], color: [f32; 4], size: f32) {
+        let _ = writeln!(self.body, "<g {}>", paint("fill", color));
+        for position in positions {
+            let _ = writeln!(self.body, "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\"/>", position[0], position[1], size / 2.0);
+        }
+        self.body.push_str("</g>\n");
+    }
+
+    fn draw_point(&mut self, position: [f32; 2], color: [f32; 4], size: f32) {
+        let _ = writeln!(
+            self.body,
+            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" {}/>",
+            position[0], position[1], size / 2.0, paint("fill", color)
+        );
+    }
+
+    fn draw_rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4], filled: bool) {
+        let style = if filled { paint("fill", color) } else { format!("fill=\"none\" {}", paint("stroke", color)) };
+        let _ = writeln!(
+            self.body,
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" {}/>",
+            min[0], min[1], max[0] - min[0], max[1] - min[1], style
+        );
+    }
+
+    fn draw_text(&mut self, text: &str, position: [f32; 2], color: [f32; 4], size: f32) {
+        let _ = writeln!(
+            self.body,
+            "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"Ubuntu, 'Segoe UI', sans-serif\" font-size=\"{}\" dominant-baseline=\"text-before-edge\" xml:space=\"preserve\" {}>{}</text>",
+            position[0], position[1], size, paint("fill", color), escape(text)
+        );
+    }
+
+    fn draw_polygon(&mut self, points: &[[f32; 2]], color: [f32; 4], filled: bool) {
+        let style = if filled { paint("fill", color) } else { format!("fill=\"none\" {}", paint("stroke", color)) };
+        let _ = writeln!(self.body, "<polygon points=\"{}\" {}/>", points_attribute(points), style);
+    }
+
+    fn set_clip_rect(&mut self, rect: Option<([f32; 2], [f32; 2])>) {
+        if rect == self.clip {
+            return;
+        }
+        self.clip = rect;
+        self.close_clip_group();
+        let Some((min, max)) = rect else {
+            return;
+        };
+        // A clip covering the whole document is no clip at all
+        if min[0] <= 0.0 && min[1] <= 0.0 && max[0] >= self.width && max[1] >= self.height {
+            return;
+        }
+        let index = match self.clip_rects.iter().position(|&clip| clip == (min, max)) {
+            Some(index) => index,
+            None => {
+                self.clip_rects.push((min, max));
+                let _ = writeln!(
+                    self.defs,
+                    "<clipPath id=\"clip{}\"><rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\"/></clipPath>",
+                    self.clip_rects.len(), min[0], min[1], max[0] - min[0], max[1] - min[1]
+                );
+                self.clip_rects.len() - 1
+            }
+        };
+        let _ = writeln!(self.body, "<g clip-path=\"url(#clip{})\">", index + 1);
+        self.in_clip_group = true;
+    }
+
+    fn capabilities(&self) -> RendererCapabilities {
+        RendererCapabilities {
+            max_texture_size: 0,
+            max_vertices: usize::MAX,
+            supports_instancing: false,
+            supports_compute: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_become_hex_with_optional_opacity() {
+        assert_eq!(paint("fill", [1.0, 0.0, 0.5, 1.0]), "fill=\"#ff0080\"");
+        assert_eq!(paint("stroke", [2.0, -1.0, 0.0, 0.25]), "stroke=\"#ff0000\" stroke-opacity=\"0.250\"");
+    }
+
+    #[test]
+    fn shapes_are_written_in_order_with_escaped_text() {
+        let mut svg = SvgRenderer::new(100.0, 50.0).with_background([0.0, 0.0, 0.0, 1.0]);
+        svg.begin_frame();
+        svg.draw_rect([1.0, 2.0], [11.0, 7.0], [1.0; 4], false);
+        svg.draw_polygon(&[[0.0, 0.0], [10.0, 0.0], [5.0, 5.0]], [1.0; 4], true);
+        svg.draw_text("a < b & \"c\"", [0.0, 0.0], [1.0; 4], 12.0);
+        svg.end_frame();
+
+        let document = svg.to_svg();
+        assert!(document.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"50\""));
+        assert!(document.ends_with("</svg>\n"));
+        let rect = document.find("<rect x=\"1.00\" y=\"2.00\" width=\"10.00\" height=\"5.00\" fill=\"none\"").unwrap();
+        let polygon = document.find("<polygon points=\"0.00,0.00 10.00,0.00 5.00,5.00\" fill=\"#ffffff\"/>").unwrap();
+        let text = document.find(">a &lt; b &amp; &quot;c&quot;</text>").unwrap();
+        assert!(document.find("<rect width=\"100%\"").unwrap() < rect);
+        assert!(rect < polygon && polygon < text);
+    }
+
+    #[test]
+    fn polylines_share_one_path() {
+        let mut svg = SvgRenderer::new(10.0, 10.0);
+        svg.draw_lines(&[([0.0, 0.0], [1.0, 1.0]), ([1.0, 1.0], [2.0, 0.0]), ([5.0, 5.0], [6.0, 6.0])], [1.0; 4], 2.0);
+        svg.draw_lines(&[], [1.0; 4], 2.0);
+        let document = svg.to_svg();
+        assert_eq!(document.matches("<path").count(), 1);
+        assert!(document.contains("d=\"M0.00,0.00L1.00,1.00L2.00,0.00M5.00,5.00L6.00,6.00\""));
+    }
+
+    #[test]
+    fn clip_rects_are_defined_once_and_grouped() {
+        let mut svg = SvgRenderer::new(100.0, 100.0);
+        svg.begin_frame();
+        let clip = Some(([10.0, 10.0], [50.0, 50.0]));
+        svg.set_clip_rect(clip);
+        svg.draw_point([20.0, 20.0], [1.0; 4], 4.0);
+        svg.set_clip_rect(None);
+        svg.set_clip_rect(clip);
+        svg.draw_point([30.0, 30.0], [1.0; 4], 4.0);
+        // Covering the whole document doesn't clip
+        svg.set_clip_rect(Some(([0.0, 0.0], [100.0, 100.0])));
+        svg.draw_point([90.0, 90.0], [1.0; 4], 4.0);
+        svg.end_frame();
+
+        let document = svg.to_svg();
+        assert_eq!(document.matches("<clipPath").count(), 1);
+        assert_eq!(document.matches("<g clip-path=\"url(#clip1)\">").count(), 2);
+        assert_eq!(document.matches("<g").count(), document.matches("</g>").count());
+        let last_group_end = document.rfind("</g>").unwrap();
+        assert!(document.find("cx=\"90.00\"").unwrap() > last_group_end);
+    }
+}
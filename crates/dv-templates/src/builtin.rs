@@ -0,0 +1,289 @@
+//! Built-in templates
+//!
+//! - time series: measurements over time, with a candlestick chart for
+//!   OHLC prices
+//! - event log: timestamped records described mostly by categories
+//! - metrics: many measurements without a time axis, with a map when rows
+//!   have coordinates
+//! - table: anything else
+
+use arrow::datatypes::Schema;
+use egui_dock::{DockState, NodeIndex};
+use serde_json::json;
+
+use crate::roles::{words, ColumnRole, ColumnRoles};
+use crate::spec::{column, column_at, columns, optional};
+use crate::{Template, TemplateMatcher, ViewSpec};
+
+/// Words in column names typical of event logs
+const EVENT_WORDS: &[&str] = &[
+    "event", "level", "severity", "type", "status", "state", "action", "message", "msg", "code", "source", "user", "category",
+];
+
+pub fn templates() -> Vec<Template> {
+    vec![time_series(), event_log(), metrics(), table()]
+}
+
+fn time_series() -> Template {
+    let mut layout = DockState::new(vec!["trend".to_string(), "candles".to_string()]);
+    let tree = layout.main_surface_mut();
+    let [_, bottom] = tree.split_below(NodeIndex::root(), 0.6, vec!["correlation".to_string()]);
+    tree.split_right(bottom, 0.5, vec!["table".to_string(), "stats".to_string()]);
+    Template {
+        id: "time_series".to_string(),
+        name: "Time Series Dashboard".to_string(),
+        description: "Track metrics over time with multiple synchronized charts".to_string(),
+        matcher: Box::new(TimeSeriesMatcher),
+        layout,
+        view_specs: vec![
+            ViewSpec::new("trend", "TimeSeriesView", "Time Series", json!({
+                "x_column": column(ColumnRole::Temporal),
+                "y_columns": columns(ColumnRole::Numeric, 3),
+                "show_legend": true,
+                "show_grid": true,
+            })),
+            ViewSpec::new("candles", "CandlestickView", "Price", json!({
+                "time_column": column(ColumnRole::Temporal),
+                "open_column": column(ColumnRole::Open),
+                "high_column": column(ColumnRole::High),
+                "low_column": column(ColumnRole::Low),
+                "close_column": column(ColumnRole::Close),
+                "volume_column": optional(column(ColumnRole::Volume)),
+            })),
+            ViewSpec::new("correlation", "ScatterPlotView", "Correlation", json!({
+                "x_column": column_at(ColumnRole::Numeric, 0),
+                "y_column": column_at(ColumnRole::Numeric, 1),
+                "color_column": optional(column(ColumnRole::Categorical)),
+            })),
+            ViewSpec::new("table", "TableView", "Data Table", json!({})),
+            ViewSpec::new("stats", "SummaryStatsView", "Statistics", json!({})),
+        ],
+    }
+}
+
+fn event_log() -> Template {
+    let mut layout = DockState::new(vec!["events".to_string()]);
+    let tree = layout.main_surface_mut();
+    let [_, bottom] = tree.split_below(NodeIndex::root(), 0.55, vec!["breakdown".to_string()]);
+    tree.split_right(bottom, 0.5, vec!["values".to_string(), "stats".to_string()]);
+    Template {
+        id: "event_log".to_string(),
+        name: "Event Log".to_string(),
+        description: "Browse timestamped events and break them down by category".to_string(),
+        matcher: Box::new(EventLogMatcher),
+        layout,
+        view_specs: vec![
+            ViewSpec::new("events", "TableView", "Events", json!({})),
+            ViewSpec::new("breakdown", "SunburstView", "Breakdown", json!({
+                "hierarchy_columns": columns(ColumnRole::Categorical, 2),
+            })),
+            ViewSpec::new("values", "TimeSeriesView", "Values over Time", json!({
+                "x_column": column(ColumnRole::Temporal),
+                "y_columns": columns(ColumnRole::Numeric, 2),
+                "show_legend": true,
+                "show_grid": true,
+            })),
+            ViewSpec::new("stats", "SummaryStatsView", "Statistics", json!({})),
+        ],
+    }
+}
+
+fn metrics() -> Template {
+    let mut layout = DockState::new(vec!["correlations".to_string()]);
+    let tree = layout.main_surface_mut();
+    let [left, right] = tree.split_right(NodeIndex::root(), 0.5, vec!["relationship".to_string(), "map".to_string()]);
+    tree.split_below(left, 0.5, vec!["distribution".to_string()]);
+    tree.split_below(right, 0.5, vec!["stats".to_string(), "table".to_string()]);
+    Template {
+        id: "metrics".to_string(),
+        name: "Metrics Overview".to_string(),
+        description: "Compare many measurements: correlations, distributions and summary statistics".to_string(),
+        matcher: Box::new(MetricsMatcher),
+        layout,
+        view_specs: vec![
+            ViewSpec::new("correlations", "CorrelationMatrixView", "Correlations", json!({
+                "columns": columns(ColumnRole::Numeric, 8),
+            })),
+            ViewSpec::new("relationship", "ScatterPlotView", "Relationship", json!({
+                "x_column": column_at(ColumnRole::Numeric, 0),
+                "y_column": column_at(ColumnRole::Numeric, 1),
+                "color_column": optional(column(ColumnRole::Categorical)),
+            })),
+            ViewSpec::new("map", "GeoPlotView", "Map", json!({
+                "lat_column": column(ColumnRole::Latitude),
+                "lon_column": column(ColumnRole::Longitude),
+                "value_column": optional(column(ColumnRole::Numeric)),
+            })),
+            ViewSpec::new("distribution", "HistogramView", "Distribution", json!({
+                "column": column(ColumnRole::Numeric),
+            })),
+            ViewSpec::new("stats", "SummaryStatsView", "Statistics", json!({})),
+            ViewSpec::new("table", "TableView", "Data Table", json!({})),
+        ],
+    }
+}
+
+fn table() -> Template {
+    let mut layout = DockState::new(vec!["table".to_string()]);
+    let tree = layout.main_surface_mut();
+    let [_, right] = tree.split_right(NodeIndex::root(), 0.6, vec!["stats".to_string()]);
+    tree.split_below(right, 0.5, vec!["categories".to_string(), "distribution".to_string()]);
+    Template {
+        id: "table".to_string(),
+        name: "Data Table".to_string(),
+        description: "Inspect the rows with summary statistics".to_string(),
+        matcher: Box::new(TableMatcher),
+        layout,
+        view_specs: vec![
+            ViewSpec::new("table", "TableView", "Data Table", json!({})),
+            ViewSpec::new("stats", "SummaryStatsView", "Statistics", json!({})),
+            ViewSpec::new("categories", "BarChartView", "By Category", json!({
+                "category_column": column(ColumnRole::Categorical),
+                "value_column": column(ColumnRole::Numeric),
+            })),
+            ViewSpec::new("distribution", "HistogramView", "Distribution", json!({
+                "column": column(ColumnRole::Numeric),
+            })),
+        ],
+    }
+}
+
+/// Numeric columns over a time axis; OHLC prices fit best
+#[derive(Debug, Clone)]
+pub struct TimeSeriesMatcher;
+
+impl TemplateMatcher for TimeSeriesMatcher {
+    fn match_score(&self, schema: &Schema) -> f64 {
+        let roles = ColumnRoles::from_schema(schema);
+        if roles.temporal.is_empty() || roles.numeric.is_empty() {
+            return 0.0;
+        }
+        let ohlc = if roles.ohlc.is_some() { 0.05 } else { 0.0 };
+        0.6 + 0.1 * roles.numeric.len().min(3) as f64 + ohlc
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateMatcher> {
+        Box::new(self.clone())
+    }
+}
+
+/// Timestamps with categories, preferably named like log fields, and few
+/// measurements
+#[derive(Debug, Clone)]
+pub struct EventLogMatcher;
+
+impl TemplateMatcher for EventLogMatcher {
+    fn match_score(&self, schema: &Schema) -> f64 {
+        let roles = ColumnRoles::from_schema(schema);
+        if roles.temporal.is_empty() || roles.categorical.is_empty() {
+            return 0.0;
+        }
+        let named_like_events = roles.categorical.iter()
+            .any(|name| words(name).iter().any(|word| EVENT_WORDS.contains(&word.as_str())));
+        let mut score = 0.45;
+        if named_like_events {
+            score += 0.3;
+        }
+        if roles.numeric.len() >= 2 {
+            score -= 0.25;
+        }
+        score
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateMatcher> {
+        Box::new(self.clone())
+    }
+}
+
+/// Several measurements; less suited when there's a time axis, better with
+/// coordinates
+#[derive(Debug, Clone)]
+pub struct MetricsMatcher;
+
+impl TemplateMatcher for MetricsMatcher {
+    fn match_score(&self, schema: &Schema) -> f64 {
+        let roles = ColumnRoles::from_schema(schema);
+        if roles.numeric.len() < 2 {
+            return 0.0;
+        }
+        let mut score = 0.3 + 0.08 * (roles.numeric.len() - 2).min(5) as f64;
+        if !roles.temporal.is_empty() {
+            score *= 0.6;
+        }
+        if roles.has_geo() {
+            score += 0.15;
+        }
+        score
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateMatcher> {
+        Box::new(self.clone())
+    }
+}
+
+/// Fits anything, as a last resort
+#[derive(Debug, Clone)]
+pub struct TableMatcher;
+
+impl TemplateMatcher for TableMatcher {
+    fn match_score(&self, _schema: &Schema) -> f64 {
+        0.1
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateMatcher> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, TimeUnit};
+    use crate::TemplateLibrary;
+
+    fn schema(fields: &[(&str, DataType)]) -> Schema {
+        Schema::new(fields.iter().map(|(name, data_type)| Field::new(*name, data_type.clone(), true)).collect::<Vec<_>>())
+    }
+
+    fn view_ids(dashboard: &crate::Dashboard) -> Vec<&str> {
+        dashboard.layout.iter_all_tabs().map(|(_, id)| id.as_str()).collect()
+    }
+
+    #[test]
+    fn picks_a_template_by_column_roles() {
+        let library = TemplateLibrary::builtin();
+        let time = DataType::Timestamp(TimeUnit::Millisecond, None);
+        let best = |fields: &[(&str, DataType)]| library.ranked(&schema(fields))[0].0.id.clone();
+
+        assert_eq!(best(&[("time", time.clone()), ("temp", DataType::Float64), ("pressure", DataType::Float64)]), "time_series");
+        assert_eq!(best(&[("time", time.clone()), ("level", DataType::Utf8), ("message", DataType::Utf8)]), "event_log");
+        assert_eq!(best(&[("a", DataType::Float64), ("b", DataType::Float64), ("c", DataType::Int64), ("d", DataType::Float64)]), "metrics");
+        assert_eq!(best(&[("name", DataType::Utf8), ("city", DataType::Utf8)]), "table");
+    }
+
+    #[test]
+    fn leaves_out_views_without_their_columns() {
+        let library = TemplateLibrary::builtin();
+        let time = DataType::Timestamp(TimeUnit::Millisecond, None);
+
+        // One measurement: no scatter plot, and no candlesticks without prices
+        let dashboard = library.dashboard(&schema(&[("time", time.clone()), ("temp", DataType::Float64)])).unwrap();
+        assert_eq!(dashboard.template_id, "time_series");
+        assert_eq!(view_ids(&dashboard), ["trend", "table", "stats"]);
+        let trend = &dashboard.views[0];
+        assert_eq!(trend.config["x_column"], "time");
+        assert_eq!(trend.config["y_columns"], json!(["temp"]));
+
+        let dashboard = library.dashboard(&schema(&[
+            ("date", DataType::Date32),
+            ("open", DataType::Float64),
+            ("high", DataType::Float64),
+            ("low", DataType::Float64),
+            ("close", DataType::Float64),
+        ])).unwrap();
+        assert_eq!(view_ids(&dashboard), ["trend", "candles", "correlation", "table", "stats"]);
+        let candles = dashboard.views.iter().find(|view| view.id == "candles").unwrap();
+        assert_eq!(candles.config["close_column"], "close");
+        assert_eq!(candles.config["volume_column"], json!(null));
+    }
+}
@@ -1,10 +1,21 @@
 //! Template system for automatic dashboard creation
-//! 
+//!
 //! This crate provides pre-built dashboard templates that are automatically
 //! selected based on the data schema.
 
 use arrow::datatypes::Schema;
+use egui_dock::DockState;
+use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::Path;
+
+pub mod builtin;
+pub mod roles;
+pub mod saved;
+pub mod spec;
+
+pub use roles::{ColumnRole, ColumnRoles};
+pub use saved::{SavedLayout, SavedTemplate, TemplateError};
 
 /// Unique identifier for a template
 pub type TemplateId = String;
@@ -16,7 +27,8 @@ pub struct Template {
     pub name: String,
     pub description: String,
     pub matcher: Box<dyn TemplateMatcher>,
-    pub layout: egui_dock::DockState<String>,
+    /// Dock arrangement of the views, by `ViewSpec::id`
+    pub layout: DockState<String>,
     pub view_specs: Vec<ViewSpec>,
 }
 
@@ -32,18 +44,89 @@ impl fmt::Debug for Template {
     }
 }
 
+impl Template {
+    /// How well the template fits `schema` (0.0 to 1.0)
+    pub fn match_score(&self, schema: &Schema) -> f64 {
+        self.matcher.match_score(schema)
+    }
+
+    /// Fill in the template's views with the columns of `schema`. Views
+    /// whose required columns are missing are left out, along with their
+    /// place in the layout. Returns `None` if no view is left.
+    pub fn instantiate(&self, schema: &Schema) -> Option<Dashboard> {
+        let roles = ColumnRoles::from_schema(schema);
+        let mut layout = self.layout.clone();
+        let mut views = Vec::new();
+        for view_spec in &self.view_specs {
+            match spec::resolve(&view_spec.config, &roles) {
+                Some(config) => views.push(ViewSpec { config, ..view_spec.clone() }),
+                None => {
+                    if let Some(location) = layout.find_tab(&view_spec.id) {
+                        layout.remove_tab(location);
+                    }
+                }
+            }
+        }
+        if views.is_empty() {
+            return None;
+        }
+        // Views the layout doesn't place share the first pane
+        for view in &views {
+            if layout.find_tab(&view.id).is_none() {
+                if layout.main_surface().is_empty() {
+                    layout = DockState::new(vec![view.id.clone()]);
+                } else {
+                    layout.push_to_first_leaf(view.id.clone());
+                }
+            }
+        }
+        Some(Dashboard {
+            template_id: self.id.clone(),
+            views,
+            layout,
+        })
+    }
+}
+
 /// Specification for creating a view
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ViewSpec {
+    /// Name of the view within its template, used in the layout
+    pub id: String,
+    /// Type name, as returned by `SpaceView::view_type`
     pub view_type: String,
+    pub title: String,
+    /// Settings as read by `SpaceView::load_config`, possibly with column
+    /// placeholders (see `spec`)
     pub config: serde_json::Value,
 }
 
+impl ViewSpec {
+    pub fn new(id: &str, view_type: &str, title: &str, config: serde_json::Value) -> Self {
+        Self {
+            id: id.to_string(),
+            view_type: view_type.to_string(),
+            title: title.to_string(),
+            config,
+        }
+    }
+}
+
+/// A template applied to a schema: views with concrete columns, and their
+/// arrangement
+#[derive(Debug, Clone)]
+pub struct Dashboard {
+    pub template_id: TemplateId,
+    pub views: Vec<ViewSpec>,
+    /// Dock arrangement by `ViewSpec::id`
+    pub layout: DockState<String>,
+}
+
 /// Trait for template matching
 pub trait TemplateMatcher: Send + Sync {
     /// Calculate how well this template matches the given schema (0.0 to 1.0)
     fn match_score(&self, schema: &Schema) -> f64;
-    
+
     /// Clone the matcher
     fn clone_box(&self) -> Box<dyn TemplateMatcher>;
 }
@@ -54,8 +137,63 @@ impl Clone for Box<dyn TemplateMatcher> {
     }
 }
 
-// TODO: Implement template types
-// - Time series template
-// - Event log template
-// - Metrics dashboard template
-// - Generic table template 
\ No newline at end of file
+/// The templates to choose from
+#[derive(Debug, Clone)]
+pub struct TemplateLibrary {
+    templates: Vec<Template>,
+}
+
+impl Default for TemplateLibrary {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl TemplateLibrary {
+    /// The built-in templates
+    pub fn builtin() -> Self {
+        Self {
+            templates: builtin::templates(),
+        }
+    }
+
+    /// Add a template, replacing any with the same ID
+    pub fn add(&mut self, template: Template) {
+        self.templates.retain(|existing| existing.id != template.id);
+        self.templates.push(template);
+    }
+
+    /// Add the templates saved in `dir`, returning the files that couldn't
+    /// be read
+    pub fn load_dir(&mut self, dir: &Path) -> Vec<TemplateError> {
+        let (templates, errors) = saved::load_dir(dir);
+        for template in templates {
+            self.add(template.into_template());
+        }
+        errors
+    }
+
+    pub fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Template> {
+        self.templates.iter().find(|template| template.id == id)
+    }
+
+    /// Templates that fit `schema` with their scores, best first
+    pub fn ranked(&self, schema: &Schema) -> Vec<(&Template, f64)> {
+        let mut ranked: Vec<(&Template, f64)> = self.templates.iter()
+            .map(|template| (template, template.match_score(schema)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+
+    /// The dashboard of the best fitting template that produces one
+    pub fn dashboard(&self, schema: &Schema) -> Option<Dashboard> {
+        self.ranked(schema).into_iter()
+            .find_map(|(template, _)| template.instantiate(schema))
+    }
+}
@@ -0,0 +1,209 @@
+//! Column roles
+//!
+//! Templates describe the columns they need by role rather than by name, so
+//! a template applies to any file with the right kinds of columns.
+
+use arrow::datatypes::Schema;
+use dv_views::ColumnKind;
+
+/// What a column is used for in a dashboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnRole {
+    /// Times or dates: typed timestamps, or text columns named like one
+    Temporal,
+    /// Measurements to plot
+    Numeric,
+    /// Labels to group or color by
+    Categorical,
+    /// Latitude of a position
+    Latitude,
+    /// Longitude of a position
+    Longitude,
+    /// Opening price of an OHLC bar
+    Open,
+    High,
+    Low,
+    Close,
+    /// Traded volume alongside OHLC prices
+    Volume,
+}
+
+impl ColumnRole {
+    /// Role name used in template placeholders
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColumnRole::Temporal => "temporal",
+            ColumnRole::Numeric => "numeric",
+            ColumnRole::Categorical => "categorical",
+            ColumnRole::Latitude => "latitude",
+            ColumnRole::Longitude => "longitude",
+            ColumnRole::Open => "open",
+            ColumnRole::High => "high",
+            ColumnRole::Low => "low",
+            ColumnRole::Close => "close",
+            ColumnRole::Volume => "volume",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "temporal" => ColumnRole::Temporal,
+            "numeric" => ColumnRole::Numeric,
+            "categorical" => ColumnRole::Categorical,
+            "latitude" => ColumnRole::Latitude,
+            "longitude" => ColumnRole::Longitude,
+            "open" => ColumnRole::Open,
+            "high" => ColumnRole::High,
+            "low" => ColumnRole::Low,
+            "close" => ColumnRole::Close,
+            "volume" => ColumnRole::Volume,
+            _ => return None,
+        })
+    }
+}
+
+/// The columns of a schema grouped by role, in schema order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnRoles {
+    /// Every column, with a role or not
+    pub names: Vec<String>,
+    pub temporal: Vec<String>,
+    /// Numeric columns other than coordinates
+    pub numeric: Vec<String>,
+    pub categorical: Vec<String>,
+    pub latitude: Option<String>,
+    pub longitude: Option<String>,
+    /// Open, high, low and close columns, when all four are present
+    pub ohlc: Option<[String; 4]>,
+    /// Volume column, only detected together with OHLC prices
+    pub volume: Option<String>,
+}
+
+impl ColumnRoles {
+    pub fn from_schema(schema: &Schema) -> Self {
+        let mut roles = ColumnRoles::default();
+        let mut prices: [Option<String>; 4] = Default::default();
+        let mut volume = None;
+        for field in schema.fields() {
+            let name = field.name();
+            roles.names.push(name.clone());
+            let kind = ColumnKind::of(field);
+            if kind == Some(ColumnKind::Temporal) {
+                roles.temporal.push(name.clone());
+            } else if kind == Some(ColumnKind::Numeric) {
+                let words = words(name);
+                let has = |options: &[&str]| words.iter().any(|word| options.contains(&word.as_str()));
+                if roles.latitude.is_none() && has(&["lat", "latitude"]) {
+                    roles.latitude = Some(name.clone());
+                } else if roles.longitude.is_none() && has(&["lon", "lng", "long", "longitude"]) {
+                    roles.longitude = Some(name.clone());
+                }
+                for (slot, options) in prices.iter_mut().zip([["open", "o"], ["high", "h"], ["low", "l"], ["close", "c"]]) {
+                    if slot.is_none() && (has(&options[..1]) || name.eq_ignore_ascii_case(options[1])) {
+                        *slot = Some(name.clone());
+                    }
+                }
+                if volume.is_none() && (has(&["volume", "vol"]) || name.eq_ignore_ascii_case("v")) {
+                    volume = Some(name.clone());
+                }
+                roles.numeric.push(name.clone());
+            } else if kind == Some(ColumnKind::Categorical) {
+                roles.categorical.push(name.clone());
+            }
+        }
+        // Coordinates aren't measurements, but a lone latitude or longitude
+        // is just a number
+        if let (Some(latitude), Some(longitude)) = (&roles.latitude, &roles.longitude) {
+            roles.numeric.retain(|name| name != latitude && name != longitude);
+        } else {
+            roles.latitude = None;
+            roles.longitude = None;
+        }
+        if let [Some(open), Some(high), Some(low), Some(close)] = prices {
+            roles.ohlc = Some([open, high, low, close]);
+            roles.volume = volume;
+        }
+        roles
+    }
+
+    /// Columns with `role`, in schema order
+    pub fn columns(&self, role: ColumnRole) -> Vec<&str> {
+        let ohlc = |i: usize| self.ohlc.as_ref().map(|prices| prices[i].as_str());
+        match role {
+            ColumnRole::Temporal => self.temporal.iter().map(String::as_str).collect(),
+            ColumnRole::Numeric => self.numeric.iter().map(String::as_str).collect(),
+            ColumnRole::Categorical => self.categorical.iter().map(String::as_str).collect(),
+            ColumnRole::Latitude => self.latitude.as_deref().into_iter().collect(),
+            ColumnRole::Longitude => self.longitude.as_deref().into_iter().collect(),
+            ColumnRole::Open => ohlc(0).into_iter().collect(),
+            ColumnRole::High => ohlc(1).into_iter().collect(),
+            ColumnRole::Low => ohlc(2).into_iter().collect(),
+            ColumnRole::Close => ohlc(3).into_iter().collect(),
+            ColumnRole::Volume => self.volume.as_deref().into_iter().collect(),
+        }
+    }
+
+    pub fn has_geo(&self) -> bool {
+        self.latitude.is_some() && self.longitude.is_some()
+    }
+}
+
+/// Lowercase words of a column name, split at punctuation and camel case
+pub(crate) fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        let boundary = !c.is_alphanumeric() || (c.is_uppercase() && previous_lower);
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, TimeUnit};
+
+    #[test]
+    fn finds_roles_by_type_and_name() {
+        let schema = Schema::new(vec![
+            Field::new("timestamp", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+            Field::new("Open", DataType::Float64, false),
+            Field::new("high", DataType::Float64, false),
+            Field::new("low", DataType::Float64, false),
+            Field::new("close", DataType::Float64, false),
+            Field::new("volume", DataType::Int64, false),
+            Field::new("gpsLat", DataType::Float64, true),
+            Field::new("gps_lon", DataType::Float64, true),
+            Field::new("symbol", DataType::Utf8, false),
+            Field::new("trade_date", DataType::Utf8, false),
+        ]);
+        let roles = ColumnRoles::from_schema(&schema);
+        assert_eq!(roles.temporal, ["timestamp", "trade_date"]);
+        assert_eq!(roles.categorical, ["symbol"]);
+        assert_eq!(roles.numeric, ["Open", "high", "low", "close", "volume"]);
+        assert_eq!(roles.ohlc, Some(["Open", "high", "low", "close"].map(String::from)));
+        assert_eq!(roles.volume.as_deref(), Some("volume"));
+        assert_eq!((roles.latitude.as_deref(), roles.longitude.as_deref()), (Some("gpsLat"), Some("gps_lon")));
+
+        // Words, not substrings: "closed" and "flow" aren't prices or coordinates
+        let schema = Schema::new(vec![
+            Field::new("closed", DataType::Float64, false),
+            Field::new("flow", DataType::Float64, false),
+            Field::new("latitude", DataType::Float64, false),
+        ]);
+        let roles = ColumnRoles::from_schema(&schema);
+        assert_eq!(roles.numeric, ["closed", "flow", "latitude"]);
+        assert!(roles.ohlc.is_none() && !roles.has_geo());
+    }
+}
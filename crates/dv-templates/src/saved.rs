@@ -0,0 +1,330 @@
+//! Templates saved by users
+//!
+//! A saved template is a JSON file in the user's template directory holding
+//! the views of a dashboard, their arrangement and the columns of the file it
+//! was saved from. Column names in the view settings are replaced by
+//! placeholders (see `spec`), which can be edited by hand, e.g. to
+//! `{"$match": "temp.*"}` for the first column named like a temperature.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use arrow::datatypes::Schema;
+use dv_views::{SpaceViewId, ViewLayout};
+use egui_dock::{DockState, NodeIndex};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::roles::ColumnRoles;
+use crate::{spec, Template, TemplateId, TemplateMatcher, ViewSpec};
+
+/// Current template file version
+const VERSION: u32 = 1;
+
+/// Errors reading or writing template files
+#[derive(Error, Debug)]
+pub enum TemplateError {
+    #[error("Failed to read {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+
+    #[error("Failed to write {0}: {1}")]
+    Write(PathBuf, std::io::Error),
+
+    #[error("Invalid template {0}: {1}")]
+    Invalid(PathBuf, serde_json::Error),
+
+    #[error("{0} was saved by a newer version (template version {1})")]
+    Version(PathBuf, u32),
+
+    #[error("A template is already saved as {0}")]
+    Exists(PathBuf),
+}
+
+/// A template file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedTemplate {
+    pub version: u32,
+    pub id: TemplateId,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Columns of the file the template was saved from
+    #[serde(default)]
+    pub columns: Vec<String>,
+    pub views: Vec<ViewSpec>,
+    /// Arrangement of the views by `ViewSpec::id`; without one they share a
+    /// pane
+    #[serde(default)]
+    pub layout: Option<SavedLayout>,
+}
+
+/// Arrangement of views, as in `ViewLayout`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavedLayout {
+    Tabs(Vec<String>),
+    Split {
+        horizontal: bool,
+        fraction: f32,
+        first: Box<SavedLayout>,
+        second: Box<SavedLayout>,
+    },
+}
+
+impl SavedLayout {
+    /// `layout` with its views named by `name`; views without a name are
+    /// left out
+    pub fn from_view_layout(layout: &ViewLayout, name: &impl Fn(&SpaceViewId) -> Option<String>) -> Option<Self> {
+        match layout {
+            ViewLayout::Tabs(ids) => {
+                let tabs: Vec<String> = ids.iter().filter_map(name).collect();
+                (!tabs.is_empty()).then_some(SavedLayout::Tabs(tabs))
+            }
+            ViewLayout::Split { horizontal, fraction, first, second } => {
+                match (Self::from_view_layout(first, name), Self::from_view_layout(second, name)) {
+                    (Some(first), Some(second)) => Some(SavedLayout::Split {
+                        horizontal: *horizontal,
+                        fraction: *fraction,
+                        first: Box::new(first),
+                        second: Box::new(second),
+                    }),
+                    (first, second) => first.or(second),
+                }
+            }
+        }
+    }
+
+    pub fn to_dock_state(&self) -> DockState<String> {
+        let mut dock_state = DockState::new(self.first_tabs().to_vec());
+        self.split_into(dock_state.main_surface_mut(), NodeIndex::root());
+        dock_state
+    }
+
+    /// Tabs of the top left pane
+    fn first_tabs(&self) -> &[String] {
+        match self {
+            SavedLayout::Tabs(tabs) => tabs,
+            SavedLayout::Split { first, .. } => first.first_tabs(),
+        }
+    }
+
+    /// Arrange `self` in the pane at `index`, which holds `first_tabs`
+    fn split_into(&self, tree: &mut egui_dock::Tree<String>, index: NodeIndex) {
+        if let SavedLayout::Split { horizontal, fraction, first, second } = self {
+            let tabs = second.first_tabs().to_vec();
+            let [first_index, second_index] = if *horizontal {
+                tree.split_right(index, *fraction, tabs)
+            } else {
+                tree.split_below(index, *fraction, tabs)
+            };
+            first.split_into(tree, first_index);
+            second.split_into(tree, second_index);
+        }
+    }
+}
+
+impl SavedTemplate {
+    /// A template named `name` from views showing a file with `schema`, with
+    /// their columns generalized to placeholders
+    pub fn new(name: &str, description: &str, schema: &Schema, views: Vec<ViewSpec>, layout: Option<SavedLayout>) -> Self {
+        Self {
+            version: VERSION,
+            id: format!("user_{}", file_stem(name)),
+            name: name.to_string(),
+            description: description.to_string(),
+            columns: schema.fields().iter().map(|field| field.name().clone()).collect(),
+            views: views.into_iter()
+                .map(|view| ViewSpec { config: spec::generalize(&view.config, schema), ..view })
+                .collect(),
+            layout,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, TemplateError> {
+        let text = std::fs::read_to_string(path).map_err(|e| TemplateError::Read(path.to_path_buf(), e))?;
+        let template: SavedTemplate = serde_json::from_str(&text)
+            .map_err(|e| TemplateError::Invalid(path.to_path_buf(), e))?;
+        if template.version > VERSION {
+            return Err(TemplateError::Version(path.to_path_buf(), template.version));
+        }
+        Ok(template)
+    }
+
+    /// Write the template to `dir`, in a file named after it. A template
+    /// already saved under that name is only overwritten if `replace` is
+    /// set. Returns the file's path.
+    pub fn save(&self, dir: &Path, replace: bool) -> Result<PathBuf, TemplateError> {
+        std::fs::create_dir_all(dir).map_err(|e| TemplateError::Write(dir.to_path_buf(), e))?;
+        let path = dir.join(format!("{}.json", file_stem(&self.name)));
+        let json = serde_json::to_string_pretty(self).expect("templates serialize to JSON");
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(replace)
+            .truncate(replace)
+            .create_new(!replace)
+            .open(&path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::AlreadyExists => TemplateError::Exists(path.clone()),
+                _ => TemplateError::Write(path.clone(), e),
+            })?;
+        file.write_all(json.as_bytes()).map_err(|e| TemplateError::Write(path.clone(), e))?;
+        Ok(path)
+    }
+
+    pub fn into_template(self) -> Template {
+        let layout = match &self.layout {
+            Some(layout) => layout.to_dock_state(),
+            None => DockState::new(self.views.iter().map(|view| view.id.clone()).collect()),
+        };
+        Template {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            matcher: Box::new(SavedMatcher {
+                views: self.views.clone(),
+                columns: self.columns,
+            }),
+            layout,
+            view_specs: self.views,
+        }
+    }
+}
+
+/// Templates in the `.json` files of `dir`, and the files that couldn't be
+/// read. A missing directory has none.
+pub fn load_dir(dir: &Path) -> (Vec<SavedTemplate>, Vec<TemplateError>) {
+    let mut templates = Vec::new();
+    let mut errors = Vec::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (templates, errors),
+        Err(e) => return (templates, vec![TemplateError::Read(dir.to_path_buf(), e)]),
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        match SavedTemplate::load(&path) {
+            Ok(template) => templates.push(template),
+            Err(e) => errors.push(e),
+        }
+    }
+    (templates, errors)
+}
+
+/// File name for a template called `name`
+fn file_stem(name: &str) -> String {
+    let mut stem = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            stem.extend(c.to_lowercase());
+        } else if !stem.is_empty() && !stem.ends_with('_') {
+            stem.push('_');
+        }
+    }
+    let stem = stem.trim_end_matches('_');
+    if stem.is_empty() { "template".to_string() } else { stem.to_string() }
+}
+
+/// Fits files where its views find their columns, best those with the
+/// columns it was saved from
+#[derive(Debug, Clone)]
+pub struct SavedMatcher {
+    views: Vec<ViewSpec>,
+    columns: Vec<String>,
+}
+
+impl TemplateMatcher for SavedMatcher {
+    fn match_score(&self, schema: &Schema) -> f64 {
+        let roles = ColumnRoles::from_schema(schema);
+        let resolved = self.views.iter().filter(|view| spec::resolve(&view.config, &roles).is_some()).count();
+        if resolved == 0 {
+            return 0.0;
+        }
+        let shared = self.columns.iter().filter(|name| roles.names.contains(name)).count();
+        let all = self.columns.len() + roles.names.len() - shared;
+        let same_columns = if all == 0 { 0.0 } else { shared as f64 / all as f64 };
+        if resolved == self.views.len() {
+            0.8 + 0.2 * same_columns
+        } else {
+            0.4 * resolved as f64 / self.views.len() as f64 + 0.2 * same_columns
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateMatcher> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field, TimeUnit};
+    use serde_json::json;
+    use crate::TemplateLibrary;
+
+    fn run_schema(sensor: &str) -> Schema {
+        Schema::new(vec![
+            Field::new("time", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+            Field::new(sensor, DataType::Float64, true),
+            Field::new("pressure", DataType::Float64, true),
+            Field::new("run_id", DataType::Utf8, false),
+        ])
+    }
+
+    #[test]
+    fn saved_dashboards_apply_to_files_of_the_same_shape() {
+        let views = vec![
+            ViewSpec::new("view1", "TimeSeriesView", "Run", json!({
+                "x_column": "time",
+                "y_columns": ["temp", "pressure"],
+            })),
+            ViewSpec::new("view2", "HistogramView", "Temperature", json!({ "column": "temp", "bins": 30 })),
+            ViewSpec::new("view3", "BarChartView", "Runs", json!({ "category_column": "run_id", "value_column": "pressure" })),
+        ];
+        let layout = SavedLayout::Split {
+            horizontal: false,
+            fraction: 0.6,
+            first: Box::new(SavedLayout::Tabs(vec!["view1".to_string()])),
+            second: Box::new(SavedLayout::Split {
+                horizontal: true,
+                fraction: 0.5,
+                first: Box::new(SavedLayout::Tabs(vec!["view2".to_string()])),
+                second: Box::new(SavedLayout::Tabs(vec!["view3".to_string()])),
+            }),
+        };
+        let saved = SavedTemplate::new("Test Run!", "", &run_schema("temp"), views, Some(layout.clone()));
+        assert_eq!(saved.id, "user_test_run");
+        assert_eq!(saved.views[0].config["y_columns"], json!({ "$columns": "numeric" }));
+        assert_eq!(saved.views[1].config, json!({ "column": { "$column": "numeric" }, "bins": 30 }));
+
+        let dir = std::env::temp_dir().join(format!("dv_templates_{}", std::process::id()));
+        let path = saved.save(&dir, false).unwrap();
+        assert_eq!(path.file_name().unwrap(), "test_run.json");
+
+        // Another template of the same name only replaces it when asked to
+        let renamed = SavedTemplate::new("test run", "Newer", &run_schema("temp"), Vec::new(), None);
+        assert!(matches!(renamed.save(&dir, false), Err(TemplateError::Exists(existing)) if existing == path));
+        assert_eq!(SavedTemplate::load(&path).unwrap().views.len(), 3);
+        renamed.save(&dir, true).unwrap();
+        assert_eq!(SavedTemplate::load(&path).unwrap().description, "Newer");
+        saved.save(&dir, true).unwrap();
+
+        let (loaded, errors) = load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(errors.is_empty());
+
+        let mut library = TemplateLibrary::builtin();
+        library.add(loaded[0].clone().into_template());
+        let dashboard = library.dashboard(&run_schema("temp")).unwrap();
+        assert_eq!(dashboard.template_id, "user_test_run");
+        assert_eq!(dashboard.layout.iter_all_tabs().map(|(_, id)| id.as_str()).collect::<Vec<_>>(), ["view1", "view2", "view3"]);
+        assert_eq!(dashboard.views[0].config["y_columns"], json!(["temp", "pressure"]));
+
+        // Another sensor name still fits through its role
+        let dashboard = library.dashboard(&run_schema("humidity")).unwrap();
+        assert_eq!(dashboard.template_id, "user_test_run");
+        assert_eq!(dashboard.views[1].config["column"], "humidity");
+    }
+}
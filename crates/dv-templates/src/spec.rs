@@ -0,0 +1,214 @@
+//! Role placeholders in view configurations
+//!
+//! A template's view configurations are the `SpaceView::save_config` JSON of
+//! the views, with column names replaced by placeholders that are filled in
+//! from the schema the template is applied to:
+//!
+//! - `{"$column": "numeric"}` is the first numeric column, and
+//!   `{"$column": "numeric", "index": 1}` the second
+//! - `{"$columns": "numeric", "limit": 3}` is a list of up to three, and
+//!   without a limit all of them
+//! - `{"$match": "temp.*"}` is the first column whose whole name matches the
+//!   regular expression, and `{"$matches": "temp.*"}` all of them (also with
+//!   `index` and `limit`)
+//! - with `"optional": true` a missing column becomes `null` (or an empty
+//!   list); otherwise the view is left out of the dashboard
+//!
+//! Roles are the names from `ColumnRole::name`.
+
+use arrow::datatypes::Schema;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+
+use crate::roles::{ColumnRole, ColumnRoles};
+
+const PLACEHOLDER_KEYS: [&str; 4] = ["$column", "$columns", "$match", "$matches"];
+
+/// Placeholder for the first column with `role`
+pub fn column(role: ColumnRole) -> Value {
+    json!({ "$column": role.name() })
+}
+
+/// Placeholder for the column with `role` at `index`
+pub fn column_at(role: ColumnRole, index: usize) -> Value {
+    json!({ "$column": role.name(), "index": index })
+}
+
+/// Placeholder for up to `limit` columns with `role`
+pub fn columns(role: ColumnRole, limit: usize) -> Value {
+    json!({ "$columns": role.name(), "limit": limit })
+}
+
+/// Placeholder for every column with `role`
+pub fn all_columns(role: ColumnRole) -> Value {
+    json!({ "$columns": role.name() })
+}
+
+/// Placeholder for the first column whose name matches `pattern`
+pub fn matching(pattern: &str) -> Value {
+    json!({ "$match": pattern })
+}
+
+/// Mark a placeholder as optional
+pub fn optional(mut placeholder: Value) -> Value {
+    if let Value::Object(fields) = &mut placeholder {
+        fields.insert("optional".to_string(), Value::Bool(true));
+    }
+    placeholder
+}
+
+/// `config` with its placeholders replaced by columns from `roles`, or
+/// `None` when a required placeholder has no column
+pub fn resolve(config: &Value, roles: &ColumnRoles) -> Option<Value> {
+    match config {
+        Value::Object(fields) if PLACEHOLDER_KEYS.iter().any(|key| fields.contains_key(*key)) => {
+            resolve_placeholder(fields, roles)
+        }
+        Value::Object(fields) => fields.iter()
+            .map(|(key, value)| Some((key.clone(), resolve(value, roles)?)))
+            .collect::<Option<Map<_, _>>>()
+            .map(Value::Object),
+        Value::Array(values) => values.iter()
+            .map(|value| resolve(value, roles))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array),
+        value => Some(value.clone()),
+    }
+}
+
+fn resolve_placeholder(fields: &Map<String, Value>, roles: &ColumnRoles) -> Option<Value> {
+    let optional = fields.get("optional").and_then(Value::as_bool).unwrap_or(false);
+    let text = |key: &str| fields.get(key).and_then(Value::as_str);
+    let role = |key: &str| text(key).and_then(ColumnRole::from_name);
+    let matches = |key: &str| {
+        let pattern = Regex::new(&format!("^(?:{})$", text(key)?)).ok()?;
+        Some(roles.names.iter().map(String::as_str).filter(|name| pattern.is_match(name)).collect::<Vec<_>>())
+    };
+    // Unknown roles and invalid patterns never match
+    let (names, single) = if fields.contains_key("$column") {
+        (role("$column").map(|role| roles.columns(role)), true)
+    } else if fields.contains_key("$columns") {
+        (role("$columns").map(|role| roles.columns(role)), false)
+    } else if fields.contains_key("$match") {
+        (matches("$match"), true)
+    } else {
+        (matches("$matches"), false)
+    };
+    let names = names.unwrap_or_default();
+    if single {
+        let index = fields.get("index").and_then(Value::as_u64).unwrap_or(0) as usize;
+        match names.get(index) {
+            Some(name) => Some(json!(name)),
+            None => optional.then_some(Value::Null),
+        }
+    } else {
+        let limit = fields.get("limit").and_then(Value::as_u64).map_or(usize::MAX, |limit| limit as usize);
+        let names: Vec<&str> = names.into_iter().take(limit).collect();
+        (optional || !names.is_empty()).then(|| json!(names))
+    }
+}
+
+/// `config` with the names of columns of `schema` replaced by placeholders,
+/// so that it applies to files with the same kinds of columns. A column is
+/// described by its role and position among the columns with that role, a
+/// list of columns as all or the first few of a role, and columns without a
+/// role by their exact name.
+pub fn generalize(config: &Value, schema: &Schema) -> Value {
+    let roles = ColumnRoles::from_schema(schema);
+    generalize_value(config, &roles)
+}
+
+fn generalize_value(config: &Value, roles: &ColumnRoles) -> Value {
+    match config {
+        Value::String(name) if roles.names.contains(name) => column_placeholder(name, roles),
+        Value::Array(values) => {
+            let names: Option<Vec<&str>> = values.iter()
+                .map(|value| value.as_str().filter(|name| roles.names.iter().any(|column| column == name)))
+                .collect();
+            match names.filter(|names| !names.is_empty()).and_then(|names| list_placeholder(&names, roles)) {
+                Some(placeholder) => placeholder,
+                None => Value::Array(values.iter().map(|value| generalize_value(value, roles)).collect()),
+            }
+        }
+        Value::Object(fields) => Value::Object(
+            fields.iter().map(|(key, value)| (key.clone(), generalize_value(value, roles))).collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+/// Roles from the most to the least specific
+const ROLES: [ColumnRole; 10] = [
+    ColumnRole::Open,
+    ColumnRole::High,
+    ColumnRole::Low,
+    ColumnRole::Close,
+    ColumnRole::Volume,
+    ColumnRole::Latitude,
+    ColumnRole::Longitude,
+    ColumnRole::Temporal,
+    ColumnRole::Numeric,
+    ColumnRole::Categorical,
+];
+
+fn column_placeholder(name: &str, roles: &ColumnRoles) -> Value {
+    for role in ROLES {
+        if let Some(index) = roles.columns(role).iter().position(|column| *column == name) {
+            return if index == 0 { column(role) } else { column_at(role, index) };
+        }
+    }
+    matching(&regex::escape(name))
+}
+
+/// All or the first few columns of a role, when `names` is one of those
+fn list_placeholder(names: &[&str], roles: &ColumnRoles) -> Option<Value> {
+    ROLES.into_iter().find_map(|role| {
+        let with_role = roles.columns(role);
+        if with_role == names {
+            Some(all_columns(role))
+        } else {
+            with_role.starts_with(names).then(|| columns(role, names.len()))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_placeholders_from_roles() {
+        let roles = ColumnRoles {
+            temporal: vec!["time".to_string()],
+            numeric: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            ..Default::default()
+        };
+        let config = json!({
+            "x_column": column(ColumnRole::Temporal),
+            "y_columns": columns(ColumnRole::Numeric, 2),
+            "second": column_at(ColumnRole::Numeric, 1),
+            "color_column": optional(column(ColumnRole::Categorical)),
+            "show_grid": true,
+        });
+        assert_eq!(resolve(&config, &roles), Some(json!({
+            "x_column": "time",
+            "y_columns": ["a", "b"],
+            "second": "b",
+            "color_column": null,
+            "show_grid": true,
+        })));
+
+        assert_eq!(resolve(&json!({ "column": column(ColumnRole::Categorical) }), &roles), None);
+        assert_eq!(resolve(&json!([column_at(ColumnRole::Numeric, 3)]), &roles), None);
+        assert_eq!(resolve(&json!({ "$column": "nonsense" }), &roles), None);
+
+        let roles = ColumnRoles {
+            names: vec!["temp_inlet".to_string(), "pressure".to_string(), "temp_outlet".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(resolve(&matching("temp.*"), &roles), Some(json!("temp_inlet")));
+        assert_eq!(resolve(&json!({ "$matches": "temp_.*" }), &roles), Some(json!(["temp_inlet", "temp_outlet"])));
+        assert_eq!(resolve(&matching("temp"), &roles), None);
+        assert_eq!(resolve(&matching("("), &roles), None);
+    }
+}
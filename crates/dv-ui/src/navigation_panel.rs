@@ -2,11 +2,16 @@
 //! Provides scrubbing, playback controls, and range selection
 
 use egui::{Ui, Response, Sense, Color32, Vec2, Pos2, Rect, Stroke, Rounding, Align2, FontId};
-use dv_core::navigation::{NavigationEngine, NavigationMode, NavigationPosition, NavigationSpec};
+use dv_core::navigation::{NavigationEngine, NavigationMode, NavigationPosition, NavigationRange, NavigationSpec};
+use dv_core::playback::{LoopMode, PlaybackMode};
 use dv_views::{ViewerContext, TimeControl};
 use std::sync::Arc;
 use parking_lot::RwLock;
 
+/// Distance (in points) from the playhead within which a drag scrubs instead
+/// of selecting a range
+const PLAYHEAD_GRAB_RADIUS: f32 = 6.0;
+
 /// Navigation panel widget
 /// Based on Rerun's time panel design
 pub struct NavigationPanel {
@@ -202,13 +207,40 @@ impl NavigationPanel {
                 .max_decimals(1)
         );
         
-        // Loop toggle with icon
+        // Playback mode: fixed rate or real time
+        let mode_button = ui.add_sized(
+            [24.0, 24.0],
+            egui::SelectableLabel::new(time_control.mode == PlaybackMode::RealTime, "⏱")
+        );
+        if mode_button.on_hover_text("Real-time playback (follow timestamps)").clicked() {
+            time_control.mode = match time_control.mode {
+                PlaybackMode::RealTime => PlaybackMode::default(),
+                PlaybackMode::RowsPerSecond(_) => PlaybackMode::RealTime,
+            };
+        }
+        if let PlaybackMode::RowsPerSecond(rate) = &mut time_control.mode {
+            ui.add_sized(
+                [60.0, 20.0],
+                egui::DragValue::new(rate)
+                    .speed(0.1)
+                    .clamp_range(0.1..=1000.0)
+                    .suffix(" rows/s")
+                    .max_decimals(1)
+            );
+        }
+        
+        // Loop toggle cycles off -> repeat -> ping-pong
+        let (loop_icon, loop_text) = match time_control.loop_mode {
+            LoopMode::Off => ("🔁", "Loop playback (off)"),
+            LoopMode::Repeat => ("🔁", "Loop playback (repeat)"),
+            LoopMode::PingPong => ("↔", "Loop playback (ping-pong)"),
+        };
         let loop_button = ui.add_sized(
             [24.0, 24.0],
-            egui::SelectableLabel::new(time_control.looping, "🔁")
+            egui::SelectableLabel::new(time_control.loop_mode != LoopMode::Off, loop_icon)
         );
-        if loop_button.on_hover_text("Loop playback").clicked() {
-            time_control.looping = !time_control.looping;
+        if loop_button.on_hover_text(loop_text).clicked() {
+            time_control.loop_mode = time_control.loop_mode.next();
         }
         
         ui.separator();
@@ -231,6 +263,18 @@ impl NavigationPanel {
             };
             
             ui.label(egui::RichText::new(position_text).strong());
+            
+            // Selection range, with a button to clear it
+            if let Some(range) = nav_ctx.selection_rows() {
+                let (start, end) = range.row_bounds();
+                ui.label(
+                    egui::RichText::new(format!("Selection: rows {}–{}", start + 1, end))
+                        .color(self.config.playhead_color)
+                );
+                if ui.small_button("✕").on_hover_text("Clear selection (Esc)").clicked() {
+                    self.navigation.set_range(None);
+                }
+            }
         });
     }
     
@@ -255,24 +299,66 @@ impl NavigationPanel {
             return;
         }
         
+        let frame_to_x = |frame: usize| rect.left() + frame as f32 / total_rows as f32 * rect.width();
+        let x_to_frame = |x: f32| {
+            let normalized = ((x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+            ((normalized * total_rows as f32) as usize).min(total_rows - 1)
+        };
+        
         // Current position marker
-        let current_pos = nav_context.position.frame_nr() as f32 / total_rows as f32;
-        let marker_x = rect.left() + current_pos * rect.width();
+        let marker_x = frame_to_x(nav_context.position.frame_nr());
+        
+        // Handle interaction: click seeks, dragging the playhead scrubs,
+        // dragging elsewhere selects a range, double-click clears the selection
+        let drag_anchor_id = response.id.with("range_anchor");
+        if response.drag_started() {
+            if let Some(pointer_pos) = response.interact_pointer_pos() {
+                let anchor = if (pointer_pos.x - marker_x).abs() <= PLAYHEAD_GRAB_RADIUS {
+                    None
+                } else {
+                    Some(x_to_frame(pointer_pos.x))
+                };
+                ui.data_mut(|d| d.insert_temp(drag_anchor_id, anchor));
+            }
+        }
+        let drag_anchor: Option<usize> = ui.data(|d| d.get_temp(drag_anchor_id)).flatten();
+        
+        let mut pending_range = None;
+        if let Some(pointer_pos) = response.interact_pointer_pos() {
+            let frame = x_to_frame(pointer_pos.x);
+            if response.double_clicked() {
+                self.navigation.set_range(None);
+            } else if response.clicked() || (response.dragged() && drag_anchor.is_none()) {
+                let _ = self.navigation.seek_to(NavigationPosition::Sequential(frame));
+            } else if let Some(anchor) = drag_anchor {
+                pending_range = Some((anchor.min(frame), anchor.max(frame) + 1));
+            }
+        }
+        if response.drag_released() {
+            if let Some((start, end)) = pending_range {
+                self.navigation.set_range(Some(NavigationRange::rows(start, end)));
+            }
+            ui.data_mut(|d| d.remove::<Option<usize>>(drag_anchor_id));
+        }
+        
+        // Selection range (the one being dragged, or the current one)
+        let selection = pending_range.or_else(|| nav_context.selection_rows().map(|r| r.row_bounds()));
+        if let Some((start, end)) = selection {
+            painter.rect_filled(
+                Rect::from_min_max(
+                    Pos2::new(frame_to_x(start), rect.top()),
+                    Pos2::new(frame_to_x(end), rect.bottom())
+                ),
+                Rounding::ZERO,
+                self.config.selection_color
+            );
+        }
         
         painter.line_segment(
             [Pos2::new(marker_x, rect.top()), Pos2::new(marker_x, rect.bottom())],
             Stroke::new(2.0, self.config.current_time_color)
         );
         
-        // Handle interaction
-        if response.clicked() || response.dragged() {
-            if let Some(pointer_pos) = response.interact_pointer_pos() {
-                let normalized = (pointer_pos.x - rect.left()) / rect.width();
-                let new_frame = (normalized * total_rows as f32) as usize;
-                let _ = self.navigation.seek_to(NavigationPosition::Sequential(new_frame.min(total_rows - 1)));
-            }
-        }
-        
         // Hover
         if let Some(hover_pos) = response.hover_pos() {
             let normalized = (hover_pos.x - rect.left()) / rect.width();
@@ -0,0 +1,306 @@
+//! Undo/redo command history for the workspace
+//!
+//! User actions that change the workspace are recorded as reversible
+//! [`WorkspaceCommand`]s. Structural viewport changes are captured by the
+//! [`Viewport`] itself (see [`Viewport::take_changes`]); navigation seeks and
+//! selection range changes are recorded by the application.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use egui_dock::DockState;
+use serde_json::Value;
+
+use dv_core::navigation::{NavigationPosition, NavigationRange};
+
+use crate::{SpaceView, SpaceViewId, Viewport, ViewerContext};
+
+/// Maximum number of commands kept in the undo stack
+const MAX_HISTORY: usize = 100;
+
+/// Consecutive commands of the same kind within this window are merged,
+/// so a timeline drag or slider drag becomes a single undo step.
+const MERGE_WINDOW: Duration = Duration::from_millis(800);
+
+/// A reversible change to the workspace
+pub enum WorkspaceCommand {
+    /// Navigation seek
+    Seek {
+        from: NavigationPosition,
+        to: NavigationPosition,
+    },
+
+    /// Selection range (filter) change
+    SetRange {
+        from: Option<NavigationRange>,
+        to: Option<NavigationRange>,
+    },
+
+    /// View configuration edit, applied through `SpaceView::load_config`
+    EditConfig {
+        view_id: SpaceViewId,
+        before: Value,
+        after: Value,
+    },
+
+    /// Views created or closed, or the dock layout rearranged
+    Layout(LayoutChange),
+}
+
+/// A structural viewport change.
+///
+/// Holds the "other side" of the change: the dock layout and views that are
+/// not currently in the viewport. Applying the change swaps them with the
+/// viewport's current contents, so undo and redo are the same operation.
+pub struct LayoutChange {
+    pub(crate) label: String,
+    pub(crate) dock_state: DockState<SpaceViewId>,
+    pub(crate) views: Vec<Box<dyn SpaceView>>,
+    pub(crate) remove: Vec<SpaceViewId>,
+}
+
+impl WorkspaceCommand {
+    /// Short human-readable description, e.g. for an "Undo …" menu entry
+    pub fn description(&self) -> String {
+        match self {
+            WorkspaceCommand::Seek { .. } => "Seek".to_string(),
+            WorkspaceCommand::SetRange { to: Some(_), .. } => "Set selection range".to_string(),
+            WorkspaceCommand::SetRange { to: None, .. } => "Clear selection range".to_string(),
+            WorkspaceCommand::EditConfig { .. } => "Edit view settings".to_string(),
+            WorkspaceCommand::Layout(change) => change.label.clone(),
+        }
+    }
+
+    /// Revert the command
+    fn undo(&mut self, viewport: &mut Viewport, ctx: &ViewerContext) {
+        match self {
+            WorkspaceCommand::Seek { from, .. } => {
+                let _ = ctx.navigation.seek_to(from.clone());
+            }
+            WorkspaceCommand::SetRange { from, .. } => {
+                ctx.navigation.set_range(from.clone());
+            }
+            WorkspaceCommand::EditConfig { view_id, before, .. } => {
+                if let Some(view) = viewport.view_mut(view_id) {
+                    view.load_config(before.clone());
+                }
+            }
+            WorkspaceCommand::Layout(change) => viewport.swap_layout(change),
+        }
+    }
+
+    /// Re-apply the command
+    fn redo(&mut self, viewport: &mut Viewport, ctx: &ViewerContext) {
+        match self {
+            WorkspaceCommand::Seek { to, .. } => {
+                let _ = ctx.navigation.seek_to(to.clone());
+            }
+            WorkspaceCommand::SetRange { to, .. } => {
+                ctx.navigation.set_range(to.clone());
+            }
+            WorkspaceCommand::EditConfig { view_id, after, .. } => {
+                if let Some(view) = viewport.view_mut(view_id) {
+                    view.load_config(after.clone());
+                }
+            }
+            WorkspaceCommand::Layout(change) => viewport.swap_layout(change),
+        }
+    }
+
+    /// Try to fold `next` into this command. Returns `false` if the commands
+    /// are unrelated and must be kept as separate undo steps.
+    fn merge(&mut self, next: &WorkspaceCommand) -> bool {
+        match (self, next) {
+            (WorkspaceCommand::Seek { to, .. }, WorkspaceCommand::Seek { to: next_to, .. }) => {
+                *to = next_to.clone();
+                true
+            }
+            (WorkspaceCommand::SetRange { to, .. }, WorkspaceCommand::SetRange { to: next_to, .. }) => {
+                *to = next_to.clone();
+                true
+            }
+            (
+                WorkspaceCommand::EditConfig { view_id, after, .. },
+                WorkspaceCommand::EditConfig { view_id: next_id, after: next_after, .. },
+            ) if view_id == next_id => {
+                *after = next_after.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the command no longer changes anything (e.g. a drag that
+    /// returned to where it started)
+    fn is_noop(&self) -> bool {
+        match self {
+            WorkspaceCommand::Seek { from, to } => from == to,
+            WorkspaceCommand::SetRange { from, to } => from == to,
+            WorkspaceCommand::EditConfig { before, after, .. } => before == after,
+            WorkspaceCommand::Layout(_) => false,
+        }
+    }
+}
+
+/// A recorded command with the time it was last updated
+struct HistoryEntry {
+    command: WorkspaceCommand,
+    updated: Instant,
+}
+
+/// Undo/redo stacks of workspace commands
+pub struct CommandHistory {
+    undo_stack: VecDeque<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+}
+
+impl CommandHistory {
+    /// Create an empty history
+    pub fn new() -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// Record a command that has already been applied
+    pub fn record(&mut self, command: WorkspaceCommand) {
+        self.record_at(command, Instant::now());
+    }
+
+    fn record_at(&mut self, command: WorkspaceCommand, now: Instant) {
+        if command.is_noop() {
+            return;
+        }
+        self.redo_stack.clear();
+
+        if let Some(last) = self.undo_stack.back_mut() {
+            if now.duration_since(last.updated) < MERGE_WINDOW && last.command.merge(&command) {
+                last.updated = now;
+                if last.command.is_noop() {
+                    self.undo_stack.pop_back();
+                }
+                return;
+            }
+        }
+
+        self.undo_stack.push_back(HistoryEntry { command, updated: now });
+        if self.undo_stack.len() > MAX_HISTORY {
+            self.undo_stack.pop_front();
+        }
+    }
+
+    /// Undo the most recent command. Returns its description.
+    pub fn undo(&mut self, viewport: &mut Viewport, ctx: &ViewerContext) -> Option<String> {
+        let mut entry = self.undo_stack.pop_back()?;
+        entry.command.undo(viewport, ctx);
+        viewport.resync_history_snapshots();
+        let description = entry.command.description();
+        self.redo_stack.push(entry);
+        Some(description)
+    }
+
+    /// Redo the most recently undone command. Returns its description.
+    pub fn redo(&mut self, viewport: &mut Viewport, ctx: &ViewerContext) -> Option<String> {
+        let mut entry = self.redo_stack.pop()?;
+        entry.command.redo(viewport, ctx);
+        viewport.resync_history_snapshots();
+        let description = entry.command.description();
+        // Never merge a redone command with later edits
+        entry.updated = Instant::now() - MERGE_WINDOW;
+        self.undo_stack.push_back(entry);
+        Some(description)
+    }
+
+    /// Description of the command `undo` would revert
+    pub fn undo_description(&self) -> Option<String> {
+        self.undo_stack.back().map(|e| e.command.description())
+    }
+
+    /// Description of the command `redo` would re-apply
+    pub fn redo_description(&self) -> Option<String> {
+        self.redo_stack.last().map(|e| e.command.description())
+    }
+
+    /// Whether there is anything to undo
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Whether there is anything to redo
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Forget all recorded commands
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+impl Default for CommandHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seek(from: usize, to: usize) -> WorkspaceCommand {
+        WorkspaceCommand::Seek {
+            from: NavigationPosition::Sequential(from),
+            to: NavigationPosition::Sequential(to),
+        }
+    }
+
+    #[test]
+    fn rapid_seeks_merge_into_one_step() {
+        let mut history = CommandHistory::new();
+        let start = Instant::now();
+        history.record_at(seek(0, 5), start);
+        history.record_at(seek(5, 9), start + Duration::from_millis(100));
+
+        assert_eq!(history.undo_stack.len(), 1);
+        match &history.undo_stack[0].command {
+            WorkspaceCommand::Seek { from, to } => {
+                assert_eq!(*from, NavigationPosition::Sequential(0));
+                assert_eq!(*to, NavigationPosition::Sequential(9));
+            }
+            _ => panic!("expected a seek"),
+        }
+
+        history.record_at(seek(9, 2), start + Duration::from_secs(5));
+        assert_eq!(history.undo_stack.len(), 2);
+    }
+
+    #[test]
+    fn merged_round_trip_is_dropped() {
+        let mut history = CommandHistory::new();
+        let start = Instant::now();
+        history.record_at(seek(3, 4), start);
+        history.record_at(seek(4, 3), start + Duration::from_millis(50));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn config_edits_merge_per_view() {
+        let mut history = CommandHistory::new();
+        let start = Instant::now();
+        let a = SpaceViewId::new_v4();
+        let b = SpaceViewId::new_v4();
+        let edit = |id, before: i32, after: i32| WorkspaceCommand::EditConfig {
+            view_id: id,
+            before: Value::from(before),
+            after: Value::from(after),
+        };
+
+        history.record_at(edit(a, 1, 2), start);
+        history.record_at(edit(a, 2, 3), start + Duration::from_millis(10));
+        history.record_at(edit(b, 1, 2), start + Duration::from_millis(20));
+
+        assert_eq!(history.undo_stack.len(), 2);
+        assert_eq!(history.undo_description().as_deref(), Some("Edit view settings"));
+    }
+}
@@ -1,5 +1,6 @@
 //! View components for the data visualization application
 
+pub mod history;
 pub mod plots;
 pub mod space_view;
 pub mod stats;
@@ -7,14 +8,17 @@ pub mod tables;
 pub mod time_series_view;
 pub mod viewport;
 pub mod polar_view;
+pub mod registry;
 
 // Re-export all components
 pub use space_view::{SpaceView, SpaceViewId, SpaceViewConfig, SelectionState};
-pub use viewport::{Viewport, GridLayoutConfig, GridCell};
+pub use viewport::{Viewport, ViewExport, ViewFrame, ViewLayout, GridLayoutConfig, GridCell};
+pub use history::{CommandHistory, WorkspaceCommand};
 pub use time_series_view::{TimeSeriesView, TimeSeriesConfig};
 pub use tables::{TableView, TableConfig};
 pub use stats::SummaryStatsView;
 pub use polar_view::PolarPlotView;
+pub use registry::{ColumnCount, ColumnField, ColumnKind, ViewDescriptor, ViewFactory, ViewRegistry};
 
 // Re-export from dv_core
 pub use dv_core::{ViewerContext, TimeControl, HoveredData, FrameTime, NavigationEngine}; 
\ No newline at end of file
@@ -3,13 +3,14 @@
 use egui::{Ui, Color32};
 use egui_plot::{Plot, PlotUi, Line, PlotPoints, Points, Legend, Corner, VLine, Polygon, MarkerShape};
 use arrow::record_batch::RecordBatch;
-use arrow::array::{Float64Array, Int64Array, Array};
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, UInt64Array, Array};
 use serde_json::{json, Value};
 use std::collections::HashSet;
+use std::sync::Arc;
 use rand::prelude::*;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 use super::utils::stats::{calculate_quartiles, detect_outliers_iqr, zscore_outliers};
 
 /// Configuration for anomaly detection view
@@ -76,6 +77,7 @@ pub struct AnomalyDetectionView {
     // State
     cached_data: Option<AnomalyData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 /// Cached anomaly detection data
@@ -105,6 +107,7 @@ impl AnomalyDetectionView {
             config: AnomalyDetectionConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -122,11 +125,8 @@ impl AnomalyDetectionView {
         // Get navigation context
         let nav_context = ctx.navigation.get_context();
         
-        // Fetch all data for analysis
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
+        // Fetch all data in the selection range for analysis
+        let range = nav_context.view_range();
         
         let data = ctx.runtime_handle.block_on(data_source.query_range(&range)).ok()?;
         
@@ -492,11 +492,16 @@ impl SpaceView for AnomalyDetectionView {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the plot
@@ -618,6 +623,23 @@ impl SpaceView for AnomalyDetectionView {
         }
     }
     
+    fn result_table(&self) -> Option<RecordBatch> {
+        let data = self.cached_data.as_ref()?;
+        let flagged: HashSet<usize> = data.anomalies.iter().copied().collect();
+        let mut columns = vec![
+            ("index", Arc::new(UInt64Array::from_iter_values(data.indices.iter().map(|&i| i as u64))) as ArrayRef),
+            ("value", Arc::new(Float64Array::from(data.values.clone())) as ArrayRef),
+            ("anomaly", Arc::new(BooleanArray::from_iter((0..data.values.len()).map(|i| Some(flagged.contains(&i))))) as ArrayRef),
+        ];
+        if let Some((lower, upper)) = &data.confidence_bands {
+            columns.push(("lower_band", Arc::new(Float64Array::from(lower.clone())) as ArrayRef));
+            columns.push(("upper_band", Arc::new(Float64Array::from(upper.clone())) as ArrayRef));
+        }
+        RecordBatch::try_from_iter(columns)
+            .map_err(|e| tracing::warn!("Failed to build the anomaly results: {}", e))
+            .ok()
+    }
+    
     fn save_config(&self) -> Value {
         json!({
             "column": self.config.column,
@@ -680,4 +702,40 @@ impl SpaceView for AnomalyDetectionView {
     fn on_frame_update(&mut self, _ctx: &ViewerContext, _dt: f32) {
         // Nothing to update per frame
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+
+    fn data(confidence_bands: Option<(Vec<f64>, Vec<f64>)>) -> AnomalyData {
+        AnomalyData {
+            values: vec![1.0, 2.0, 50.0],
+            indices: vec![0.0, 1.0, 2.0],
+            anomalies: vec![2],
+            statistics: AnomalyStatistics { mean: 17.7, std_dev: 22.9, median: 2.0, mad: 1.0, num_anomalies: 1, anomaly_rate: 1.0 / 3.0 },
+            confidence_bands,
+        }
+    }
+
+    #[test]
+    fn result_table_flags_anomalous_rows() {
+        let mut view = AnomalyDetectionView::new(uuid::Uuid::new_v4(), "Anomalies".to_string());
+        assert!(view.result_table().is_none());
+
+        view.cached_data = Some(data(None));
+        let table = view.result_table().unwrap();
+        let names: Vec<String> = table.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, ["index", "value", "anomaly"]);
+        let flags: Vec<Option<bool>> = table.column(2).as_boolean().iter().collect();
+        assert_eq!(flags, vec![Some(false), Some(false), Some(true)]);
+
+        view.cached_data = Some(data(Some((vec![0.0; 3], vec![10.0; 3]))));
+        assert_eq!(view.result_table().unwrap().num_columns(), 5);
+
+        // Bands that don't match the values can't form a table
+        view.cached_data = Some(data(Some((vec![0.0; 2], vec![10.0; 2]))));
+        assert!(view.result_table().is_none());
+    }
+}
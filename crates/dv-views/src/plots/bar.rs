@@ -6,7 +6,7 @@ use arrow::array::{Float64Array, Int64Array, StringArray, Array};
 use serde_json::{json, Value};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Bar chart configuration
 #[derive(Debug, Clone)]
@@ -50,6 +50,7 @@ pub struct BarChartView {
     // State
     cached_data: Option<BarData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 /// Cached bar chart data
@@ -68,6 +69,7 @@ impl BarChartView {
             config: BarChartConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -96,11 +98,8 @@ impl BarChartView {
         // Get navigation context
         let nav_context = ctx.navigation.get_context();
         
-        // For bar charts, we'll aggregate all data
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
+        // For bar charts, we'll aggregate all data in the selection range
+        let range = nav_context.view_range();
         
         // Fetch data
         let data = match ctx.runtime_handle.block_on(data_source.query_range(&range)) {
@@ -252,11 +251,16 @@ impl SpaceView for BarChartView {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the bar chart
@@ -2,12 +2,14 @@
 
 use egui::{Ui, Color32};
 use egui_plot::{Plot, PlotPoints, Points, Line, Legend, Polygon};
-use arrow::array::{Float64Array, Int64Array, Array};
+use arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray, UInt64Array, Array};
+use arrow::record_batch::RecordBatch;
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 use super::utils::stats::calculate_quartiles;
 
 /// Configuration for box plot
@@ -66,6 +68,7 @@ pub struct BoxPlotView {
     // State
     cached_data: Option<BoxPlotData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 /// Cached box plot data
@@ -94,6 +97,7 @@ impl BoxPlotView {
             config: BoxPlotConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -116,11 +120,8 @@ impl BoxPlotView {
         // Get navigation context
         let nav_context = ctx.navigation.get_context();
         
-        // Fetch all data for statistics
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
+        // Fetch all data in the selection range for statistics
+        let range = nav_context.view_range();
         
         let data = match ctx.runtime_handle.block_on(data_source.query_range(&range)) {
             Ok(d) => {
@@ -365,11 +366,16 @@ impl SpaceView for BoxPlotView {
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
         
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx).and_then(|v| v.into_iter().next());
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the box plot
@@ -404,6 +410,29 @@ impl SpaceView for BoxPlotView {
         }
     }
     
+    fn result_table(&self) -> Option<RecordBatch> {
+        let data = self.cached_data.as_ref()?;
+        let stats = &data.box_stats;
+        let column = |value: fn(&BoxStats) -> f64| Arc::new(Float64Array::from_iter_values(stats.iter().map(value))) as ArrayRef;
+        // Outliers are listed in one text column, since CSV has no lists
+        let outliers = stats.iter().map(|s| {
+            s.outliers.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" ")
+        });
+        RecordBatch::try_from_iter(vec![
+            ("category", Arc::new(StringArray::from_iter_values(&data.categories)) as ArrayRef),
+            ("min", column(|s| s.min)),
+            ("q1", column(|s| s.q1)),
+            ("median", column(|s| s.median)),
+            ("q3", column(|s| s.q3)),
+            ("max", column(|s| s.max)),
+            ("mean", column(|s| s.mean)),
+            ("outlier_count", Arc::new(UInt64Array::from_iter_values(stats.iter().map(|s| s.outliers.len() as u64))) as ArrayRef),
+            ("outliers", Arc::new(StringArray::from_iter_values(outliers)) as ArrayRef),
+        ])
+        .map_err(|e| tracing::warn!("Failed to build the box plot results: {}", e))
+        .ok()
+    }
+    
     fn save_config(&self) -> Value {
         json!({
             "data_source_id": self.config.data_source_id,
@@ -456,3 +485,31 @@ impl SpaceView for BoxPlotView {
         // Nothing to update per frame
     }
 } 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+    use arrow::datatypes::{Float64Type, UInt64Type};
+
+    #[test]
+    fn result_table_has_a_row_per_box() {
+        let mut view = BoxPlotView::new(uuid::Uuid::new_v4(), "Box plot".to_string());
+        assert!(view.result_table().is_none());
+
+        let stats = vec![
+            view.calculate_box_stats(&[1.0, 2.0, 3.0, 4.0, 100.0]).unwrap(),
+            view.calculate_box_stats(&[5.0, 6.0, 7.0]).unwrap(),
+        ];
+        view.cached_data = Some(BoxPlotData {
+            categories: vec!["a".to_string(), "b".to_string()],
+            box_stats: stats,
+        });
+        let table = view.result_table().unwrap();
+        let names: Vec<String> = table.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, ["category", "min", "q1", "median", "q3", "max", "mean", "outlier_count", "outliers"]);
+        assert_eq!(table.column(3).as_primitive::<Float64Type>().values(), &[3.0, 6.0]);
+        assert_eq!(table.column(7).as_primitive::<UInt64Type>().values(), &[1, 0]);
+        assert_eq!(table.column(8).as_string::<i32>().value(0), "100");
+    }
+}
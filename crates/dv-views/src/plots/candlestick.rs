@@ -9,6 +9,7 @@ use serde_json::{json, Value};
 use std::collections::BTreeMap;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+use dv_core::navigation::NavigationRange;
 
 /// Candlestick configuration
 #[derive(Debug, Clone)]
@@ -97,6 +98,7 @@ pub struct CandlestickChart {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     candles: BTreeMap<i64, Candle>,
     moving_averages: Vec<(usize, Vec<[f64; 2]>)>,
     bollinger_bands: Option<(Vec<[f64; 2]>, Vec<[f64; 2]>, Vec<[f64; 2]>)>, // upper, middle, lower
@@ -111,6 +113,7 @@ impl CandlestickChart {
             title,
             config: CandlestickConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             candles: BTreeMap::new(),
             moving_averages: Vec::new(),
             bollinger_bands: None,
@@ -486,12 +489,13 @@ impl SpaceView for CandlestickChart {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
             if let Some(source) = data_sources.values().next() {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch.clone());
                     self.extract_data(&batch);
                 }
@@ -598,10 +602,37 @@ impl SpaceView for CandlestickChart {
     }
     
     fn load_config(&mut self, config: Value) {
-        if let Some(col) = config.get("time_column").and_then(|v| v.as_str()) {
-            self.config.time_column = Some(col.to_string());
+        let column = |key: &str| config.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        if let Some(col) = column("time_column") {
+            self.config.time_column = Some(col);
+        }
+        if let Some(col) = column("open_column") {
+            self.config.open_column = Some(col);
+        }
+        if let Some(col) = column("high_column") {
+            self.config.high_column = Some(col);
+        }
+        if let Some(col) = column("low_column") {
+            self.config.low_column = Some(col);
+        }
+        if let Some(col) = column("close_column") {
+            self.config.close_column = Some(col);
+        }
+        if let Some(col) = column("volume_column") {
+            self.config.volume_column = Some(col);
+        }
+        if let Some(show_volume) = config.get("show_volume").and_then(|v| v.as_bool()) {
+            self.config.show_volume = show_volume;
+        }
+        if let Some(periods) = config.get("show_ma").and_then(|v| v.as_array()) {
+            self.config.show_ma = periods.iter().filter_map(|v| v.as_u64()).map(|p| p as usize).collect();
+        }
+        if let Some(show_bollinger) = config.get("show_bollinger").and_then(|v| v.as_bool()) {
+            self.config.show_bollinger = show_bollinger;
+        }
+        if let Some(show_patterns) = config.get("show_patterns").and_then(|v| v.as_bool()) {
+            self.config.show_patterns = show_patterns;
         }
-        // Load other columns...
     }
     
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
@@ -1,13 +1,16 @@
 //! Contour plot implementation
 
-use egui::{Ui, Color32, Pos2, Vec2, Rect};
-use egui_plot::{Plot, PlotPoints, Line, Legend};
-use arrow::array::{Float64Array, Int64Array, Array};
-use arrow::record_batch::RecordBatch;
+use egui::{Ui, Color32, Pos2, Rect, Stroke, Painter, Align2, FontId, RichText, Sense};
+use egui_plot::{Plot, PlotPoint, PlotPoints, Line, Polygon, Text};
 use serde_json::{json, Value};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::NavigationRange;
+use super::utils::{isobands, isolines, scheme_color, Grid, GridMethod};
+use super::utils::grid::xyz_points;
+
+/// Width of the colorbar beside the plot
+const COLORBAR_WIDTH: f32 = 70.0;
 
 /// Configuration for contour plot
 #[derive(Debug, Clone)]
@@ -18,6 +21,15 @@ pub struct ContourConfig {
     pub z_column: String,
     pub levels: usize,
     pub color_scheme: String,
+    /// How scattered points are put on a grid
+    pub grid_method: GridMethod,
+    /// Grid nodes along each axis, unless the data lies on its own lattice
+    pub grid_resolution: usize,
+    /// Fill the bands between levels
+    pub filled: bool,
+    /// Label lines with their level
+    pub show_labels: bool,
+    pub show_colorbar: bool,
 }
 
 impl Default for ContourConfig {
@@ -29,19 +41,32 @@ impl Default for ContourConfig {
             z_column: String::new(),
             levels: 10,
             color_scheme: "viridis".to_string(),
+            grid_method: GridMethod::Auto,
+            grid_resolution: 60,
+            filled: true,
+            show_labels: true,
+            show_colorbar: true,
         }
     }
 }
 
 /// Cached contour data
 struct ContourData {
+    /// Contour levels, ascending
+    levels: Vec<f64>,
+    z_min: f64,
+    z_max: f64,
     contours: Vec<ContourLine>,
+    /// Regions below the first level, between consecutive levels and above
+    /// the last
+    bands: Vec<Vec<Vec<[f64; 2]>>>,
 }
 
 struct ContourLine {
     level: f64,
-    points: Vec<(f64, f64)>,
-    color: Color32,
+    /// Index of the level
+    index: usize,
+    points: Vec<[f64; 2]>,
 }
 
 /// Contour plot view
@@ -50,7 +75,7 @@ pub struct ContourPlot {
     title: String,
     pub config: ContourConfig,
     cached_data: Option<ContourData>,
-    last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 impl ContourPlot {
@@ -60,7 +85,7 @@ impl ContourPlot {
             title,
             config: ContourConfig::default(),
             cached_data: None,
-            last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -80,93 +105,101 @@ impl ContourPlot {
         
         // Fetch data
         let nav_context = ctx.navigation.get_context();
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
+        let range = nav_context.view_range();
         
         let batch = ctx.runtime_handle.block_on(
             data_source.query_range(&range)
         ).ok()?;
         
-        // Extract columns
-        let x_col = batch.column_by_name(&self.config.x_column)?;
-        let y_col = batch.column_by_name(&self.config.y_column)?;
-        let z_col = batch.column_by_name(&self.config.z_column)?;
-        
-        // Extract numeric values with null handling
-        let x_values: Vec<f64> = Self::extract_numeric_values(x_col);
-        let y_values: Vec<f64> = Self::extract_numeric_values(y_col);
-        let z_values: Vec<f64> = Self::extract_numeric_values(z_col);
-        
-        if x_values.is_empty() || y_values.is_empty() || z_values.is_empty() {
+        // Rows with all three values, gridded
+        let points = xyz_points(&batch, &self.config.x_column, &self.config.y_column, &self.config.z_column)?;
+        let grid = Grid::from_points(&points, self.config.grid_method, self.config.grid_resolution)?;
+        let (z_min, z_max) = grid.z_range()?;
+        if z_max <= z_min {
             return None;
         }
         
-        // Create coordinate triples
-        let coords: Vec<(f64, f64, f64)> = x_values.into_iter()
-            .zip(y_values.into_iter())
-            .zip(z_values.into_iter())
-            .map(|((x, y), z)| (x, y, z))
+        // Levels evenly spaced strictly between the extremes
+        let count = self.config.levels.max(1);
+        let levels: Vec<f64> = (1..=count)
+            .map(|k| z_min + (z_max - z_min) * k as f64 / (count + 1) as f64)
             .collect();
         
-        if coords.is_empty() {
-            return None;
-        }
-        
-        // Generate contour lines
-        let mut contours = Vec::new();
-        let z_min = coords.iter().map(|(_, _, z)| z).cloned().fold(f64::INFINITY, f64::min);
-        let z_max = coords.iter().map(|(_, _, z)| z).cloned().fold(f64::NEG_INFINITY, f64::max);
+        let contours = levels.iter().enumerate()
+            .flat_map(|(index, &level)| {
+                isolines(&grid, level).into_iter().map(move |points| ContourLine { level, index, points })
+            })
+            .collect();
         
-        for i in 0..self.config.levels {
-            let level = z_min + (z_max - z_min) * (i as f64) / (self.config.levels as f64);
-            let color = Self::viridis_color((i as f32) / (self.config.levels as f32));
-            
-            // Find points near this contour level
-            let mut points = Vec::new();
-            for &(x, y, z) in &coords {
-                if (z - level).abs() < (z_max - z_min) / (self.config.levels as f64 * 2.0) {
-                    points.push((x, y));
-                }
-            }
-            
-            if !points.is_empty() {
-                contours.push(ContourLine { level, points, color });
-            }
-        }
+        let bands = if self.config.filled {
+            let mut bounds = vec![f64::NEG_INFINITY];
+            bounds.extend(&levels);
+            bounds.push(f64::INFINITY);
+            bounds.windows(2).map(|band| isobands(&grid, band[0], band[1])).collect()
+        } else {
+            Vec::new()
+        };
         
-        Some(ContourData { contours })
+        Some(ContourData { levels, z_min, z_max, contours, bands })
     }
     
-    fn extract_numeric_values(array: &dyn Array) -> Vec<f64> {
-        if let Some(float_array) = array.as_any().downcast_ref::<Float64Array>() {
-            (0..float_array.len()).filter_map(|i| {
-                if float_array.is_null(i) { None } else { Some(float_array.value(i)) }
-            }).collect()
-        } else if let Some(int_array) = array.as_any().downcast_ref::<Int64Array>() {
-            (0..int_array.len()).filter_map(|i| {
-                if int_array.is_null(i) { None } else { Some(int_array.value(i) as f64) }
-            }).collect()
-        } else if let Some(int_array) = array.as_any().downcast_ref::<arrow::array::Int32Array>() {
-            (0..int_array.len()).filter_map(|i| {
-                if int_array.is_null(i) { None } else { Some(int_array.value(i) as f64) }
-            }).collect()
-        } else if let Some(float_array) = array.as_any().downcast_ref::<arrow::array::Float32Array>() {
-            (0..float_array.len()).filter_map(|i| {
-                if float_array.is_null(i) { None } else { Some(float_array.value(i) as f64) }
-            }).collect()
+    /// Color of band `band` of `count` bands
+    fn band_color(&self, band: usize, count: usize) -> Color32 {
+        scheme_color(&self.config.color_scheme, (band as f32 + 0.5) / count as f32)
+    }
+    
+    /// Color of the line at level `index` of `count` levels
+    fn line_color(&self, index: usize, count: usize) -> Color32 {
+        if self.config.filled {
+            Color32::from_black_alpha(160)
         } else {
-            Vec::new()
+            scheme_color(&self.config.color_scheme, (index as f32 + 1.0) / (count as f32 + 1.0))
         }
     }
     
-    fn viridis_color(t: f32) -> Color32 {
-        let t = t.clamp(0.0, 1.0);
-        let r = (255.0 * (0.267 + 0.003 * t + 1.785 * t * t - 3.876 * t * t * t + 2.291 * t * t * t * t).clamp(0.0, 1.0)) as u8;
-        let g = (255.0 * (0.005 + 1.398 * t - 0.725 * t * t).clamp(0.0, 1.0)) as u8;
-        let b = (255.0 * (0.329 + 0.876 * t - 0.170 * t * t - 0.363 * t * t * t).clamp(0.0, 1.0)) as u8;
-        Color32::from_rgb(r, g, b)
+    /// Levels, one band per level interval, with tick labels at the levels
+    fn draw_colorbar(&self, painter: &Painter, rect: Rect, data: &ContourData) {
+        let text_color = painter.ctx().style().visuals.text_color();
+        let bar = Rect::from_min_max(
+            Pos2::new(rect.min.x + 8.0, rect.min.y + 20.0),
+            Pos2::new(rect.min.x + 24.0, rect.max.y - 20.0),
+        );
+        if bar.height() <= 0.0 {
+            return;
+        }
+        painter.text(Pos2::new(bar.min.x, rect.min.y + 4.0), Align2::LEFT_TOP, &self.config.z_column, FontId::proportional(11.0), text_color);
+        
+        let y_of = |z: f64| bar.max.y - ((z - data.z_min) / (data.z_max - data.z_min)) as f32 * bar.height();
+        let mut bounds = vec![data.z_min];
+        bounds.extend(&data.levels);
+        bounds.push(data.z_max);
+        let bands = bounds.len() - 1;
+        for (band, edges) in bounds.windows(2).enumerate() {
+            let block = Rect::from_x_y_ranges(bar.x_range(), y_of(edges[1])..=y_of(edges[0]));
+            painter.rect_filled(block, 0.0, self.band_color(band, bands));
+        }
+        painter.rect_stroke(bar, 0.0, Stroke::new(1.0, text_color));
+        
+        let decimals = label_decimals(&data.levels);
+        for &level in &data.levels {
+            let y = y_of(level);
+            painter.line_segment([Pos2::new(bar.max.x, y), Pos2::new(bar.max.x + 4.0, y)], Stroke::new(1.0, text_color));
+            painter.text(Pos2::new(bar.max.x + 6.0, y), Align2::LEFT_CENTER, format!("{:.*}", decimals, level), FontId::proportional(10.0), text_color);
+        }
+    }
+}
+
+/// Decimals showing the difference between consecutive levels
+fn label_decimals(levels: &[f64]) -> usize {
+    let step = match levels {
+        [first, second, ..] => second - first,
+        [level] => level.abs(),
+        [] => 1.0,
+    };
+    if step > 0.0 {
+        (1.0 - step.log10().floor()).clamp(0.0, 6.0) as usize
+    } else {
+        2
     }
 }
 
@@ -197,36 +230,63 @@ impl SpaceView for ContourPlot {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // The contours cover the selection range; moving the position
+        // doesn't change them
+        let nav_context = ctx.navigation.get_context();
+        if self.cached_data.is_none() || self.last_selection_range != nav_context.selection_range {
             self.cached_data = self.fetch_data(ctx);
-            self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         if let Some(data) = &self.cached_data {
-            // Create a contour visualization using egui_plot
-            Plot::new(format!("{:?}_contour", self.id))
-                .legend(Legend::default())
-                .show_grid(true)
-                .auto_bounds(egui::Vec2b::new(true, true))
-                .show(ui, |plot_ui| {
-                    // Draw contour lines
-                    for contour in &data.contours {
-                        if !contour.points.is_empty() {
-                            let points: PlotPoints = contour.points.iter()
-                                .map(|&(x, y)| [x, y])
-                                .collect();
-                            
-                            let line = Line::new(points)
-                                .color(contour.color)
-                                .width(2.0)
-                                .name(format!("Level {:.2}", contour.level));
-                            
+            let rect = ui.available_rect_before_wrap();
+            let colorbar_width = if self.config.show_colorbar { COLORBAR_WIDTH } else { 0.0 };
+            let plot_rect = Rect::from_min_max(rect.min, Pos2::new(rect.max.x - colorbar_width, rect.max.y));
+            
+            ui.allocate_ui_at_rect(plot_rect, |ui| {
+                Plot::new(format!("{:?}_contour", self.id))
+                    .show_grid(true)
+                    .auto_bounds(egui::Vec2b::new(true, true))
+                    .show(ui, |plot_ui| {
+                        // Filled bands beneath the lines
+                        for (band, polygons) in data.bands.iter().enumerate() {
+                            let color = self.band_color(band, data.bands.len());
+                            for polygon in polygons {
+                                // Outlined in the fill color, so neighbouring
+                                // pieces leave no seams
+                                plot_ui.polygon(
+                                    Polygon::new(PlotPoints::from(polygon.clone()))
+                                        .fill_color(color)
+                                        .stroke(Stroke::new(1.0, color))
+                                );
+                            }
+                        }
+                        
+                        let decimals = label_decimals(&data.levels);
+                        for contour in &data.contours {
+                            let line = Line::new(PlotPoints::from(contour.points.clone()))
+                                .color(self.line_color(contour.index, data.levels.len()))
+                                .width(1.5)
+                                .name(format!("{} = {:.*}", self.config.z_column, decimals, contour.level));
                             plot_ui.line(line);
+                            
+                            // Long enough lines are labelled at their middle
+                            if self.config.show_labels && contour.points.len() >= 8 {
+                                let [x, y] = contour.points[contour.points.len() / 2];
+                                let label = RichText::new(format!("{:.*}", decimals, contour.level))
+                                    .size(10.0)
+                                    .background_color(Color32::from_white_alpha(200));
+                                plot_ui.text(Text::new(PlotPoint::new(x, y), label).color(Color32::BLACK));
+                            }
                         }
-                    }
-                });
+                    });
+            });
+            
+            if self.config.show_colorbar {
+                let colorbar_rect = Rect::from_min_max(Pos2::new(plot_rect.max.x, rect.min.y), rect.max);
+                self.draw_colorbar(&ui.painter_at(colorbar_rect), colorbar_rect, data);
+            }
+            ui.allocate_rect(rect, Sense::hover());
         } else {
             ui.centered_and_justified(|ui| {
                 ui.label("No data to display");
@@ -247,6 +307,11 @@ impl SpaceView for ContourPlot {
             "z_column": self.config.z_column,
             "levels": self.config.levels,
             "color_scheme": self.config.color_scheme,
+            "grid_method": self.config.grid_method.name(),
+            "grid_resolution": self.config.grid_resolution,
+            "filled": self.config.filled,
+            "show_labels": self.config.show_labels,
+            "show_colorbar": self.config.show_colorbar,
         })
     }
     
@@ -269,6 +334,22 @@ impl SpaceView for ContourPlot {
         if let Some(color_scheme) = config.get("color_scheme").and_then(|v| v.as_str()) {
             self.config.color_scheme = color_scheme.to_string();
         }
+        if let Some(grid_method) = config.get("grid_method").and_then(|v| v.as_str()).and_then(GridMethod::from_name) {
+            self.config.grid_method = grid_method;
+        }
+        if let Some(grid_resolution) = config.get("grid_resolution").and_then(|v| v.as_u64()) {
+            self.config.grid_resolution = (grid_resolution as usize).clamp(2, 500);
+        }
+        if let Some(filled) = config.get("filled").and_then(|v| v.as_bool()) {
+            self.config.filled = filled;
+        }
+        if let Some(show_labels) = config.get("show_labels").and_then(|v| v.as_bool()) {
+            self.config.show_labels = show_labels;
+        }
+        if let Some(show_colorbar) = config.get("show_colorbar").and_then(|v| v.as_bool()) {
+            self.config.show_colorbar = show_colorbar;
+        }
+        self.cached_data = None;
     }
     
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
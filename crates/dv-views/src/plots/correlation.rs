@@ -1,13 +1,14 @@
 //! Correlation matrix view implementation
 
 use egui::{Ui, Color32, Rect, Pos2, Vec2, FontId, Align2, Stroke};
-use arrow::array::{Float64Array, Int64Array, Array};
+use arrow::array::{ArrayRef, Float64Array, Int64Array, StringArray, Array};
 use arrow::record_batch::RecordBatch;
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Configuration for correlation matrix view
 #[derive(Debug, Clone)]
@@ -51,6 +52,7 @@ pub struct CorrelationMatrixView {
     pub config: CorrelationMatrixConfig,
     cached_data: Option<CorrelationData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 impl CorrelationMatrixView {
@@ -61,6 +63,7 @@ impl CorrelationMatrixView {
             config: CorrelationMatrixConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -74,10 +77,7 @@ impl CorrelationMatrixView {
         
         // Fetch data
         let nav_context = ctx.navigation.get_context();
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
+        let range = nav_context.view_range();
         
         let batch = ctx.runtime_handle.block_on(
             data_source.query_range(&range)
@@ -271,10 +271,15 @@ impl SpaceView for CorrelationMatrixView {
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
         // Update data if needed
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         if let Some(data) = &self.cached_data {
@@ -412,6 +417,20 @@ impl SpaceView for CorrelationMatrixView {
         }
     }
     
+    fn result_table(&self) -> Option<RecordBatch> {
+        let data = self.cached_data.as_ref()?;
+        let mut columns = vec![
+            ("column".to_string(), Arc::new(StringArray::from_iter_values(&data.column_names)) as ArrayRef),
+        ];
+        for (j, name) in data.column_names.iter().enumerate() {
+            let values = data.correlation_matrix.iter().map(|row| row[j]);
+            columns.push((name.clone(), Arc::new(Float64Array::from_iter_values(values)) as ArrayRef));
+        }
+        RecordBatch::try_from_iter(columns)
+            .map_err(|e| tracing::warn!("Failed to build the correlation results: {}", e))
+            .ok()
+    }
+    
     fn save_config(&self) -> Value {
         json!({
             "columns": self.config.columns,
@@ -422,9 +441,49 @@ impl SpaceView for CorrelationMatrixView {
     }
     
     fn load_config(&mut self, config: Value) {
-        // TODO: Load config
+        if let Some(columns) = config.get("columns").and_then(|v| v.as_array()) {
+            self.config.columns = columns.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+        }
+        if let Some(method) = config.get("method").and_then(|v| v.as_str()) {
+            self.config.method = match method {
+                "Spearman" => CorrelationMethod::Spearman,
+                "Kendall" => CorrelationMethod::Kendall,
+                _ => CorrelationMethod::Pearson,
+            };
+        }
+        if let Some(show_values) = config.get("show_values").and_then(|v| v.as_bool()) {
+            self.config.show_values = show_values;
+        }
+        if let Some(color_scheme) = config.get("color_scheme").and_then(|v| v.as_str()) {
+            self.config.color_scheme = color_scheme.to_string();
+        }
     }
     
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
     fn on_frame_update(&mut self, _ctx: &ViewerContext, _dt: f32) {}
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+    use arrow::datatypes::Float64Type;
+
+    #[test]
+    fn result_table_is_the_matrix_with_row_names() {
+        let mut view = CorrelationMatrixView::new(uuid::Uuid::new_v4(), "Correlation".to_string());
+        assert!(view.result_table().is_none());
+
+        view.cached_data = Some(CorrelationData {
+            correlation_matrix: vec![vec![1.0, -0.5], vec![-0.5, 1.0]],
+            column_names: vec!["x".to_string(), "y".to_string()],
+        });
+        let table = view.result_table().unwrap();
+        let names: Vec<String> = table.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, ["column", "x", "y"]);
+        assert_eq!(table.column(0).as_string::<i32>().value(1), "y");
+        assert_eq!(table.column(2).as_primitive::<Float64Type>().values(), &[-0.5, 1.0]);
+    }
+}
@@ -7,7 +7,7 @@ use arrow::record_batch::RecordBatch;
 use serde_json::{json, Value};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Configuration for distribution plot
 #[derive(Debug, Clone)]
@@ -61,6 +61,7 @@ pub struct DistributionPlot {
     pub config: DistributionConfig,
     cached_data: Option<DistributionData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 impl DistributionPlot {
@@ -71,6 +72,7 @@ impl DistributionPlot {
             config: DistributionConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -88,10 +90,7 @@ impl DistributionPlot {
         
         // Fetch data
         let nav_context = ctx.navigation.get_context();
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
+        let range = nav_context.view_range();
         
         let batch = ctx.runtime_handle.block_on(
             data_source.query_range(&range)
@@ -209,10 +208,15 @@ impl SpaceView for DistributionPlot {
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
         // Update data if needed
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         if let Some(data) = &self.cached_data {
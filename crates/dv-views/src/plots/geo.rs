@@ -1,17 +1,45 @@
 //! Geographic plot implementation
+//!
+//! Boundaries come from local GeoJSON or shapefiles, such as the Natural
+//! Earth country and coastline files or a site's county polygons, and are
+//! drawn in any of the [`ProjectionType`]s. Choropleths join a data column
+//! to a property of the features.
 
-use egui::{Ui, Color32, Pos2, Vec2, Rect, Stroke, Shape, FontId, Rounding, Align2};
-use egui_plot::{Plot, PlotUi, Points, PlotPoints, Legend, PlotBounds, Text};
+mod basemap;
+mod projection;
+mod shapefile;
+
+use egui::{Ui, Color32, Pos2, Vec2, Rect, Stroke, Shape, Mesh, FontId, Rounding, Align2, Response, Sense};
 use arrow::record_batch::RecordBatch;
 use arrow::array::{Float64Array, StringArray, Array};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
 use serde_json::{json, Value};
-use geo_types::{Point, LineString, Polygon as GeoPolygon};
-use geojson::{GeoJson, Feature, FeatureCollection};
-use rstar::{RTree, RTreeObject, AABB};
+use rstar::{RTree, AABB};
 use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::oneshot::{self, error::TryRecvError};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+use dv_core::data::DataSource;
+use dv_core::navigation::NavigationRange;
 use super::utils::colors::{ColorScheme, viridis_color, plasma_color, diverging_color};
+use basemap::{bounds_of, merge_bounds, ProjectedMap};
+
+pub use basemap::{Basemap, BasemapError, MapFeature};
+pub use projection::{Projection, ProjectionType};
+
+/// Zoom relative to the whole world fitting the view
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 2000.0;
+
+/// Standard parallels of Albers until fitted to the data
+const DEFAULT_PARALLELS: (f64, f64) = (29.5, 45.5);
+
+/// Properties naming features in common boundary files, tried in order
+const NAME_PROPERTIES: [&str; 6] = ["name", "NAME", "NAME_EN", "ADMIN", "NAMELSAD", "NAME_1"];
 
 /// Geographic plot configuration
 #[derive(Debug, Clone)]
@@ -22,35 +50,32 @@ pub struct GeoPlotConfig {
     pub value_column: Option<String>,
     pub label_column: Option<String>,
     pub group_column: Option<String>,
-    
+
     // Map settings
     pub projection: ProjectionType,
     pub show_coastlines: bool,
     pub show_countries: bool,
     pub show_grid: bool,
     pub show_labels: bool,
-    
+    /// GeoJSON or shapefiles drawn under the data, in order
+    pub basemap_files: Vec<String>,
+
     // Visualization type
     pub viz_type: GeoVizType,
     pub marker_size: f32,
     pub heatmap_radius: f32,
     pub color_scheme: ColorScheme,
-    
+    /// Data column whose values name features, for choropleths
+    pub join_column: Option<String>,
+    /// Feature property matched to `join_column`
+    pub join_property: Option<String>,
+
     // Interactivity
     pub enable_zoom: bool,
     pub enable_pan: bool,
     pub show_tooltips: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ProjectionType {
-    Mercator,
-    Robinson,
-    AlbersEqualArea,
-    Orthographic,
-    NaturalEarth,
-}
-
 #[derive(Debug, Clone, PartialEq)]
 pub enum GeoVizType {
     Points,
@@ -60,6 +85,20 @@ pub enum GeoVizType {
     Hexbin,
 }
 
+impl GeoVizType {
+    pub const ALL: [GeoVizType; 5] = [
+        GeoVizType::Points,
+        GeoVizType::Heatmap,
+        GeoVizType::Choropleth,
+        GeoVizType::Lines,
+        GeoVizType::Hexbin,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|viz_type| format!("{:?}", viz_type) == name)
+    }
+}
+
 impl Default for GeoPlotConfig {
     fn default() -> Self {
         Self {
@@ -74,10 +113,13 @@ impl Default for GeoPlotConfig {
             show_countries: true,
             show_grid: true,
             show_labels: true,
+            basemap_files: Vec::new(),
             viz_type: GeoVizType::Points,
             marker_size: 5.0,
             heatmap_radius: 20.0,
             color_scheme: ColorScheme::Viridis,
+            join_column: None,
+            join_property: None,
             enable_zoom: true,
             enable_pan: true,
             show_tooltips: true,
@@ -90,21 +132,65 @@ pub struct GeoPlot {
     id: SpaceViewId,
     title: String,
     pub config: GeoPlotConfig,
-    
+
     // State
     cached_data: Option<RecordBatch>,
-    
+    last_selection_range: Option<NavigationRange>,
+    /// Choropleth values over the selection or the whole source
+    choropleth: Option<Choropleth>,
+    /// Aggregation running on the runtime, and what it's for
+    pending_choropleth: Option<(ChoroplethInputs, oneshot::Receiver<HashMap<String, f64>>)>,
+
     // Map state
     center_lat: f64,
     center_lon: f64,
     zoom_level: f64,
-    
-    // Cached geometry
-    coastlines: Vec<Vec<Pos2>>,
-    countries: Vec<(Vec<Vec<Pos2>>, String)>,
+    /// Drag in progress, in projected units; the projection is only
+    /// recentered when the drag ends, so the basemaps aren't reprojected
+    /// every frame
+    pan_offset: [f64; 2],
+    /// Standard parallels of the Albers projection
+    albers_parallels: (f64, f64),
+    /// Fit the view to the data and basemaps on the next draw
+    needs_fit: bool,
+
+    // Basemaps
+    basemaps: Vec<Basemap>,
+    basemap_error: Option<String>,
+    /// Configured basemap files that failed to load, with the error; they
+    /// stay in the configuration until removed
+    unloaded_basemaps: Vec<(String, String)>,
+    new_basemap_path: String,
+    /// Basemaps under the current projection, rebuilt when it changes
+    projected: Option<ProjectedMap>,
     spatial_index: Option<RTree<GeoPoint>>,
 }
 
+/// What a choropleth aggregates
+#[derive(Clone)]
+struct ChoroplethInputs {
+    /// The source itself, so a reloaded source counts as a change
+    source: Arc<dyn DataSource>,
+    selection: Option<NavigationRange>,
+    join_column: Option<String>,
+    value_column: Option<String>,
+}
+
+impl ChoroplethInputs {
+    fn same_as(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.source, &other.source)
+            && self.selection == other.selection
+            && self.join_column == other.join_column
+            && self.value_column == other.value_column
+    }
+}
+
+/// Choropleth values and what they were aggregated from
+struct Choropleth {
+    inputs: ChoroplethInputs,
+    values: HashMap<String, f64>,
+}
+
 #[derive(Clone)]
 struct GeoPoint {
     lat: f64,
@@ -117,208 +203,431 @@ struct GeoPoint {
 
 impl rstar::RTreeObject for GeoPoint {
     type Envelope = AABB<[f64; 2]>;
-    
+
     fn envelope(&self) -> Self::Envelope {
         AABB::from_point([self.lon, self.lat])
     }
 }
 
+/// Placement of a projected map on the screen
+struct MapTransform {
+    projection: Projection,
+    rect: Rect,
+    /// Projected position at the center of `rect`
+    center: [f64; 2],
+    /// Points per projected unit
+    scale: f64,
+}
+
+impl MapTransform {
+    fn to_screen(&self, [x, y]: [f64; 2]) -> Pos2 {
+        let middle = self.rect.center();
+        Pos2::new(
+            middle.x + ((x - self.center[0]) * self.scale) as f32,
+            middle.y - ((y - self.center[1]) * self.scale) as f32,
+        )
+    }
+
+    fn to_map(&self, pos: Pos2) -> [f64; 2] {
+        let middle = self.rect.center();
+        [
+            self.center[0] + (pos.x - middle.x) as f64 / self.scale,
+            self.center[1] - (pos.y - middle.y) as f64 / self.scale,
+        ]
+    }
+
+    /// Screen position of (`lon`, `lat`), None where the projection hides it
+    fn project(&self, lon: f64, lat: f64) -> Option<Pos2> {
+        self.projection.project(lon, lat).map(|point| self.to_screen(point))
+    }
+
+    /// Least and greatest projected corners of the screen
+    fn visible(&self) -> ([f64; 2], [f64; 2]) {
+        let top_left = self.to_map(self.rect.left_top());
+        let bottom_right = self.to_map(self.rect.right_bottom());
+        ([top_left[0], bottom_right[1]], [bottom_right[0], top_left[1]])
+    }
+
+    fn path(&self, points: &[[f64; 2]]) -> Vec<Pos2> {
+        points.iter().map(|&point| self.to_screen(point)).collect()
+    }
+}
+
 impl GeoPlot {
     pub fn new(id: SpaceViewId, title: String) -> Self {
-        let mut plot = Self {
+        Self {
             id,
             title,
             config: GeoPlotConfig::default(),
             cached_data: None,
+            last_selection_range: None,
+            choropleth: None,
+            pending_choropleth: None,
             center_lat: 0.0,
             center_lon: 0.0,
             zoom_level: 1.0,
-            coastlines: Vec::new(),
-            countries: Vec::new(),
+            pan_offset: [0.0, 0.0],
+            albers_parallels: DEFAULT_PARALLELS,
+            needs_fit: true,
+            basemaps: Vec::new(),
+            basemap_error: None,
+            unloaded_basemaps: Vec::new(),
+            new_basemap_path: String::new(),
+            projected: None,
             spatial_index: None,
+        }
+    }
+
+    /// Load a basemap and add it to the configuration
+    pub fn add_basemap(&mut self, path: &str) {
+        match Basemap::load(path) {
+            Ok(basemap) => {
+                if self.config.join_property.is_none() {
+                    let names = basemap.property_names();
+                    self.config.join_property = NAME_PROPERTIES.iter()
+                        .find(|name| names.iter().any(|n| n == *name))
+                        .map(|name| name.to_string());
+                }
+                if !self.config.basemap_files.iter().any(|file| file == path) {
+                    self.config.basemap_files.push(path.to_string());
+                }
+                self.basemaps.push(basemap);
+                self.basemap_error = None;
+                self.projected = None;
+                self.needs_fit = true;
+            }
+            Err(error) => self.basemap_error = Some(error.to_string()),
+        }
+    }
+
+    fn remove_basemap(&mut self, index: usize) {
+        let basemap = self.basemaps.remove(index);
+        self.config.basemap_files.retain(|file| Path::new(file) != basemap.path);
+        self.projected = None;
+    }
+
+    fn projection(&self) -> Projection {
+        Projection {
+            kind: self.config.projection.clone(),
+            center_lon: self.center_lon,
+            center_lat: self.center_lat,
+            parallels: self.albers_parallels,
+        }
+    }
+
+    /// Projection and placement of the map in `rect`. At zoom 1 the whole
+    /// world fits.
+    fn transform(&self, rect: Rect) -> MapTransform {
+        let projection = self.projection();
+        let [min_x, min_y, max_x, max_y] = bounds_of(&world_outline(&projection)).unwrap_or([-1.0, -1.0, 1.0, 1.0]);
+        let fit = (rect.width() as f64 / (max_x - min_x)).min(rect.height() as f64 / (max_y - min_y));
+        let [x, y] = projection.project(self.center_lon, self.center_lat).unwrap_or([0.0, 0.0]);
+        MapTransform {
+            center: [x + self.pan_offset[0], y + self.pan_offset[1]],
+            scale: fit * self.zoom_level,
+            rect,
+            projection,
+        }
+    }
+
+    /// Center and zoom on the data and basemaps, or show the whole world
+    fn fit(&mut self, points: &[GeoPoint], rect: Rect) {
+        self.needs_fit = false;
+        self.pan_offset = [0.0, 0.0];
+        let positions: Vec<[f64; 2]> = points.iter().map(|point| [point.lon, point.lat]).collect();
+        let bounds = merge_bounds(bounds_of(&positions).into_iter().chain(self.basemaps.iter().filter_map(Basemap::bounds)));
+        let Some([min_lon, min_lat, max_lon, max_lat]) = bounds else {
+            self.center_lon = 0.0;
+            self.center_lat = 0.0;
+            self.zoom_level = 1.0;
+            return;
         };
-        
-        // Load basic world geometry
-        plot.load_world_geometry();
-        plot
-    }
-    
-    fn load_world_geometry(&mut self) {
-        // For now, create simplified world boundaries
-        // In a real implementation, you'd load from Natural Earth data or similar
-        
-        // Simple world outline
-        let world_outline = vec![
-            vec![
-                Pos2::new(-180.0, -90.0),
-                Pos2::new(180.0, -90.0),
-                Pos2::new(180.0, 90.0),
-                Pos2::new(-180.0, 90.0),
-                Pos2::new(-180.0, -90.0),
-            ]
-        ];
-        self.coastlines = world_outline;
-        
-        // Add some example country boundaries
-        // North America simplified
-        let north_america = vec![
-            Pos2::new(-170.0, 70.0),
-            Pos2::new(-50.0, 70.0),
-            Pos2::new(-50.0, 25.0),
-            Pos2::new(-120.0, 25.0),
-            Pos2::new(-170.0, 50.0),
-            Pos2::new(-170.0, 70.0),
-        ];
-        
-        // Europe simplified
-        let europe = vec![
-            Pos2::new(-10.0, 35.0),
-            Pos2::new(40.0, 35.0),
-            Pos2::new(40.0, 70.0),
-            Pos2::new(-10.0, 70.0),
-            Pos2::new(-10.0, 35.0),
-        ];
-        
-        self.countries = vec![
-            (vec![north_america], "North America".to_string()),
-            (vec![europe], "Europe".to_string()),
-        ];
-    }
-    
-    fn project_point(&self, lat: f64, lon: f64, rect: &Rect) -> Pos2 {
-        match self.config.projection {
-            ProjectionType::Mercator => {
-                // Simple Mercator projection
-                let x = (lon + 180.0) / 360.0;
-                let lat_rad = lat.to_radians();
-                let y = 0.5 - (lat_rad.tan() + (1.0 / lat_rad.cos())).ln() / (2.0 * std::f64::consts::PI);
-                
-                Pos2::new(
-                    rect.left() + x as f32 * rect.width(),
-                    rect.top() + y as f32 * rect.height()
-                )
-            }
-            _ => {
-                // Fallback to simple equirectangular
-                let x = (lon + 180.0) / 360.0;
-                let y = (90.0 - lat) / 180.0;
-                
-                Pos2::new(
-                    rect.left() + x as f32 * rect.width(),
-                    rect.top() + y as f32 * rect.height()
-                )
-            }
-        }
-    }
-    
-    fn draw_base_map(&self, ui: &mut Ui, rect: Rect) {
-        let painter = ui.painter_at(rect);
-        
-        // Background
-        painter.rect_filled(rect, Rounding::ZERO, Color32::from_rgb(230, 240, 250));
-        
-        // Draw grid
-        if self.config.show_grid {
-            // Latitude lines
-            for lat in (-90..=90).step_by(30) {
-                let start = self.project_point(lat as f64, -180.0, &rect);
-                let end = self.project_point(lat as f64, 180.0, &rect);
-                painter.line_segment(
-                    [start, end],
-                    Stroke::new(0.5, Color32::from_gray(200))
-                );
-                
-                if self.config.show_labels && lat % 60 == 0 {
-                    painter.text(
-                        Pos2::new(rect.left() + 5.0, (start.y + end.y) / 2.0),
-                        Align2::LEFT_CENTER,
-                        format!("{}°", lat),
-                        FontId::proportional(10.0),
-                        Color32::from_gray(100),
-                    );
+        // A lone point is shown with a degree around it
+        let pad = |min: f64, max: f64| if max - min < 0.01 { (min - 1.0, max + 1.0) } else { (min, max) };
+        let (min_lon, max_lon) = pad(min_lon, max_lon);
+        let (min_lat, max_lat) = pad(min_lat.max(-89.0), max_lat.min(89.0));
+
+        let fitted = Projection::albers(min_lon, min_lat, max_lon, max_lat);
+        self.albers_parallels = fitted.parallels;
+        self.center_lon = fitted.center_lon;
+        self.center_lat = fitted.center_lat;
+        self.zoom_level = 1.0;
+
+        let transform = self.transform(rect);
+        let edges: Vec<[f64; 2]> = (0..=20)
+            .flat_map(|k| {
+                let t = k as f64 / 20.0;
+                let (lon, lat) = (min_lon + t * (max_lon - min_lon), min_lat + t * (max_lat - min_lat));
+                [[lon, min_lat], [lon, max_lat], [min_lon, lat], [max_lon, lat]]
+            })
+            .filter_map(|[lon, lat]| transform.projection.project(lon, lat))
+            .collect();
+        let Some([min_x, min_y, max_x, max_y]) = bounds_of(&edges) else {
+            return;
+        };
+        // Center on the middle of the projected bounds, which differs from
+        // the middle latitude where the projection stretches
+        if let Some((_, lat)) = transform.projection.inverse((min_x + max_x) / 2.0, (min_y + max_y) / 2.0) {
+            self.center_lat = lat;
+        }
+        let needed = ((max_x - min_x) * transform.scale / rect.width() as f64)
+            .max((max_y - min_y) * transform.scale / rect.height() as f64);
+        self.zoom_level = (0.9 / needed.max(1e-12)).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    /// Reproject the basemaps if the projection changed
+    fn update_projected(&mut self, projection: &Projection) {
+        if self.projected.as_ref().is_none_or(|map| map.projection != *projection) {
+            self.projected = Some(ProjectedMap::new(&self.basemaps, projection));
+        }
+    }
+
+    fn scheme_color(&self, t: f32) -> Color32 {
+        match self.config.color_scheme {
+            ColorScheme::Plasma => plasma_color(t),
+            ColorScheme::Diverging => diverging_color(t),
+            _ => viridis_color(t),
+        }
+    }
+
+    fn feature_label<'a>(&self, feature: &'a MapFeature) -> Option<&'a str> {
+        self.config.join_property.iter().map(String::as_str)
+            .chain(NAME_PROPERTIES)
+            .find_map(|key| feature.properties.get(key))
+            .map(String::as_str)
+    }
+
+    /// Choropleth value of a feature, by its join property
+    fn feature_value(&self, feature: &MapFeature, values: &HashMap<String, f64>) -> Option<f64> {
+        let property = self.config.join_property.as_ref()?;
+        values.get(&join_key(feature.properties.get(property)?)).copied()
+    }
+
+    /// Keep the choropleth in step with the selected rows of the source,
+    /// or all of them when nothing is selected. The aggregation runs on the
+    /// runtime; the last values are shown until it finishes.
+    fn update_choropleth(&mut self, ctx: &ViewerContext) {
+        let source = {
+            let data_sources = ctx.data_sources.read();
+            match &self.config.data_source_id {
+                Some(source_id) => data_sources.get(source_id).cloned(),
+                None => data_sources.values().next().cloned(),
+            }
+        };
+        let Some(source) = source else {
+            self.choropleth = None;
+            self.pending_choropleth = None;
+            return;
+        };
+        let inputs = ChoroplethInputs {
+            source,
+            selection: ctx.navigation.get_context().selection_rows(),
+            join_column: self.config.join_column.clone(),
+            value_column: self.config.value_column.clone(),
+        };
+
+        if let Some((done, receiver)) = &mut self.pending_choropleth {
+            match receiver.try_recv() {
+                Ok(values) => {
+                    self.choropleth = Some(Choropleth { inputs: done.clone(), values });
+                    self.pending_choropleth = None;
                 }
+                Err(TryRecvError::Closed) => self.pending_choropleth = None,
+                Err(TryRecvError::Empty) => {}
             }
-            
-            // Longitude lines
-            for lon in (-180..=180).step_by(60) {
-                let start = self.project_point(90.0, lon as f64, &rect);
-                let end = self.project_point(-90.0, lon as f64, &rect);
-                painter.line_segment(
-                    [start, end],
-                    Stroke::new(0.5, Color32::from_gray(200))
-                );
-                
-                if self.config.show_labels && lon % 120 == 0 {
-                    painter.text(
-                        Pos2::new((start.x + end.x) / 2.0, rect.bottom() - 5.0),
-                        Align2::CENTER_BOTTOM,
-                        format!("{}°", lon),
-                        FontId::proportional(10.0),
-                        Color32::from_gray(100),
-                    );
+        }
+        let current = self.choropleth.as_ref().is_some_and(|choropleth| choropleth.inputs.same_as(&inputs))
+            || self.pending_choropleth.as_ref().is_some_and(|(pending, _)| pending.same_as(&inputs));
+        if current {
+            return;
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        let task = inputs.clone();
+        ctx.runtime_handle.spawn(async move {
+            let rows = match task.selection {
+                Some(rows) => rows,
+                None => NavigationRange::rows(0, task.source.row_count().await.unwrap_or(0)),
+            };
+            let values = task.source.query_range(&rows).await
+                .map(|batch| choropleth_values(&batch, task.join_column.as_deref(), task.value_column.as_deref()))
+                .unwrap_or_default();
+            let _ = sender.send(values);
+        });
+        self.pending_choropleth = Some((inputs, receiver));
+    }
+
+    fn draw_base_map(&self, ui: &Ui, transform: &MapTransform) {
+        let painter = ui.painter_at(transform.rect);
+        let ocean = Color32::from_rgb(230, 240, 250);
+
+        // Albers' world is not convex, so its sea fills the view
+        if transform.projection.kind == ProjectionType::AlbersEqualArea {
+            painter.rect_filled(transform.rect, Rounding::ZERO, ocean);
+        } else {
+            painter.rect_filled(transform.rect, Rounding::ZERO, ui.visuals().extreme_bg_color);
+            painter.add(Shape::convex_polygon(transform.path(&world_outline(&transform.projection)), ocean, Stroke::NONE));
+        }
+    }
+
+    /// Features of the basemaps in view, filled with their choropleth color
+    fn draw_basemaps(&self, ui: &Ui, transform: &MapTransform, values: &HashMap<String, f64>) {
+        let Some(map) = &self.projected else {
+            return;
+        };
+        let painter = ui.painter_at(transform.rect);
+        let land = Color32::from_rgb(240, 236, 224);
+        let border = Stroke::new(0.5, Color32::from_gray(150));
+        let coast = Stroke::new(1.0, Color32::from_rgb(50, 50, 150));
+        let choropleth = self.config.viz_type == GeoVizType::Choropleth;
+        let range = value_range(values.values().copied());
+
+        let mut fill = Mesh::default();
+        let mut outlines = Vec::new();
+        let mut labels = Vec::new();
+        let (min, max) = transform.visible();
+        for projected in map.visible(min, max) {
+            let feature = &self.basemaps[projected.basemap].features[projected.feature];
+            let [min_x, min_y, max_x, max_y] = projected.bounds;
+            let size = (max_x - min_x).max(max_y - min_y) * transform.scale;
+            // Smaller than a pixel
+            if size < 1.0 {
+                continue;
+            }
+
+            if self.config.show_countries || choropleth {
+                let color = match (self.feature_value(feature, values), range) {
+                    (Some(value), Some((low, high))) => self.scheme_color(normalize(value, low, high)),
+                    _ => land,
+                };
+                for triangle in &projected.triangles {
+                    let first = fill.vertices.len() as u32;
+                    for &point in triangle {
+                        fill.colored_vertex(transform.to_screen(point), color);
+                    }
+                    fill.add_triangle(first, first + 1, first + 2);
+                }
+                for ring in &projected.rings {
+                    outlines.push(Shape::line(transform.path(ring), border));
+                }
+            }
+            if self.config.show_coastlines {
+                for line in &projected.lines {
+                    outlines.push(Shape::line(transform.path(line), coast));
+                }
+            }
+            for &point in &projected.points {
+                outlines.push(Shape::circle_filled(transform.to_screen(point), 2.0, Color32::from_gray(90)));
+            }
+
+            if self.config.show_labels && size > 80.0 {
+                if let (Some(anchor), Some(label)) = (projected.anchor, self.feature_label(feature)) {
+                    labels.push((transform.to_screen(anchor), label));
                 }
             }
         }
-        
-        // Draw coastlines
-        if self.config.show_coastlines {
-            for coastline in &self.coastlines {
-                let points: Vec<Pos2> = coastline.iter()
-                    .map(|p| self.project_point(p.y as f64, p.x as f64, &rect))
+        painter.add(Shape::mesh(fill));
+        painter.extend(outlines);
+        for (pos, label) in labels {
+            painter.text(pos, Align2::CENTER_CENTER, label, FontId::proportional(11.0), Color32::from_gray(60));
+        }
+    }
+
+    fn draw_graticule(&self, ui: &Ui, transform: &MapTransform) {
+        let painter = ui.painter_at(transform.rect);
+        let rect = transform.rect;
+        let projection = &transform.projection;
+        let stroke = Stroke::new(0.5, Color32::from_gray(200));
+
+        if self.config.show_grid {
+            // Parallels
+            for lat in (-60..=60).step_by(30) {
+                let path: Vec<[f64; 2]> = (0..=360)
+                    .map(|k| [projection.center_lon - 180.0 + k as f64, lat as f64])
                     .collect();
-                
-                if points.len() > 1 {
-                    painter.add(Shape::line(
-                        points,
-                        Stroke::new(1.0, Color32::from_rgb(50, 50, 150))
-                    ));
+                for piece in projection.project_path(&path) {
+                    painter.add(Shape::line(transform.path(&piece), stroke));
+                }
+
+                if self.config.show_labels && lat % 60 == 0 {
+                    if let Some(pos) = transform.project(projection.center_lon, lat as f64) {
+                        painter.text(
+                            Pos2::new(rect.left() + 5.0, pos.y),
+                            Align2::LEFT_CENTER,
+                            format!("{}°", lat),
+                            FontId::proportional(10.0),
+                            Color32::from_gray(100),
+                        );
+                    }
                 }
             }
-        }
-        
-        // Draw countries
-        if self.config.show_countries {
-            for (polygons, name) in &self.countries {
-                for polygon in polygons {
-                    let points: Vec<Pos2> = polygon.iter()
-                        .map(|p| self.project_point(p.y as f64, p.x as f64, &rect))
-                        .collect();
-                    
-                    if points.len() > 2 {
-                        painter.add(Shape::closed_line(
-                            points.clone(),
-                            Stroke::new(0.5, Color32::from_gray(150))
-                        ));
-                        
-                        // Add country label
-                        if self.config.show_labels {
-                            let center = points.iter()
-                                .fold(Pos2::ZERO, |acc, p| acc + p.to_vec2())
-                                / points.len() as f32;
-                            
-                            painter.text(
-                                center,
-                                Align2::CENTER_CENTER,
-                                name,
-                                FontId::proportional(12.0),
-                                Color32::from_gray(80),
-                            );
-                        }
+
+            // Meridians
+            for lon in (-180..180).step_by(30) {
+                let path: Vec<[f64; 2]> = (0..=180).map(|k| [lon as f64, k as f64 - 90.0]).collect();
+                for piece in projection.project_path(&path) {
+                    painter.add(Shape::line(transform.path(&piece), stroke));
+                }
+
+                if self.config.show_labels && lon % 60 == 0 {
+                    if let Some(pos) = transform.project(lon as f64, self.center_lat) {
+                        painter.text(
+                            Pos2::new(pos.x, rect.bottom() - 5.0),
+                            Align2::CENTER_BOTTOM,
+                            format!("{}°", lon),
+                            FontId::proportional(10.0),
+                            Color32::from_gray(100),
+                        );
                     }
                 }
             }
         }
+
+        // Edge of the world
+        if self.config.show_coastlines {
+            painter.add(Shape::closed_line(
+                transform.path(&world_outline(projection)),
+                Stroke::new(1.0, Color32::from_rgb(50, 50, 150)),
+            ));
+        }
     }
-    
-    fn draw_data_points(&self, ui: &mut Ui, rect: Rect, points: &[GeoPoint]) {
-        let painter = ui.painter_at(rect);
-        
+
+    /// Color scale of a choropleth, in the bottom left corner
+    fn draw_legend(&self, ui: &Ui, transform: &MapTransform, values: &HashMap<String, f64>) {
+        let Some((low, high)) = value_range(values.values().copied()) else {
+            return;
+        };
+        let painter = ui.painter_at(transform.rect);
+        let bar = Rect::from_min_size(transform.rect.left_bottom() + Vec2::new(10.0, -36.0), Vec2::new(160.0, 10.0));
+        let steps = 32;
+        for step in 0..steps {
+            let t = step as f32 / (steps - 1) as f32;
+            let left = bar.left() + bar.width() * step as f32 / steps as f32;
+            let cell = Rect::from_min_max(Pos2::new(left, bar.top()), Pos2::new(left + bar.width() / steps as f32 + 0.5, bar.bottom()));
+            painter.rect_filled(cell, Rounding::ZERO, self.scheme_color(t));
+        }
+        painter.rect_stroke(bar, Rounding::ZERO, Stroke::new(0.5, Color32::from_gray(120)));
+
+        let text = |pos: Pos2, align: Align2, value: f64| {
+            painter.text(pos, align, format!("{:.2}", value), FontId::proportional(10.0), Color32::from_gray(60));
+        };
+        text(bar.left_bottom() + Vec2::new(0.0, 2.0), Align2::LEFT_TOP, low);
+        text(bar.right_bottom() + Vec2::new(0.0, 2.0), Align2::RIGHT_TOP, high);
+        let name = self.config.value_column.as_deref().unwrap_or("Count");
+        painter.text(bar.left_top() - Vec2::new(0.0, 2.0), Align2::LEFT_BOTTOM, name, FontId::proportional(10.0), Color32::from_gray(60));
+    }
+
+    fn draw_data_points(&self, ui: &Ui, transform: &MapTransform, points: &[GeoPoint]) {
+        let painter = ui.painter_at(transform.rect);
+
         match self.config.viz_type {
             GeoVizType::Points => {
                 // Draw points as markers
                 for point in points {
-                    let pos = self.project_point(point.lat, point.lon, &rect);
-                    
+                    let Some(pos) = transform.project(point.lon, point.lat) else {
+                        continue;
+                    };
+
                     let color = if let Some(value) = point.value {
                         // Color by value
                         let normalized = (value - 0.0) / 100.0; // TODO: proper normalization
@@ -330,11 +639,11 @@ impl GeoPlot {
                     } else {
                         Color32::from_rgb(255, 100, 100)
                     };
-                    
+
                     // Draw marker
                     painter.circle_filled(pos, self.config.marker_size, color);
                     painter.circle_stroke(pos, self.config.marker_size, Stroke::new(1.0, Color32::WHITE));
-                    
+
                     // Draw label if available
                     if self.config.show_labels {
                         if let Some(label) = &point.label {
@@ -353,9 +662,11 @@ impl GeoPlot {
                 // Create heatmap visualization
                 // For simplicity, we'll draw gradients around each point
                 for point in points {
-                    let pos = self.project_point(point.lat, point.lon, &rect);
+                    let Some(pos) = transform.project(point.lon, point.lat) else {
+                        continue;
+                    };
                     let value = point.value.unwrap_or(1.0);
-                    
+
                     // Draw gradient circle
                     let radius = self.config.heatmap_radius;
                     for r in (0..radius as i32).rev() {
@@ -375,32 +686,34 @@ impl GeoPlot {
                 // Hexagonal binning
                 let hex_size = 20.0;
                 let mut hex_bins: HashMap<(i32, i32), Vec<&GeoPoint>> = HashMap::new();
-                
+
                 // Bin points into hexagons
                 for point in points {
-                    let pos = self.project_point(point.lat, point.lon, &rect);
+                    let Some(pos) = transform.project(point.lon, point.lat) else {
+                        continue;
+                    };
                     let hex_x = (pos.x / (hex_size * 1.5)) as i32;
                     let hex_y = (pos.y / (hex_size * 0.866)) as i32;
                     hex_bins.entry((hex_x, hex_y)).or_default().push(point);
                 }
-                
+
                 // Draw hexagons
                 for ((hx, hy), bin_points) in hex_bins {
                     let center_x = hx as f32 * hex_size * 1.5;
                     let center_y = hy as f32 * hex_size * 0.866;
-                    
+
                     // Calculate average value
                     let avg_value = bin_points.iter()
                         .filter_map(|p| p.value)
                         .sum::<f64>() / bin_points.len() as f64;
-                    
+
                     let normalized = (avg_value / 100.0) as f32; // TODO: proper normalization
                     let color = match self.config.color_scheme {
                         ColorScheme::Viridis => viridis_color(normalized),
                         ColorScheme::Plasma => plasma_color(normalized),
                         _ => Color32::from_rgb(255, 100, 100),
                     };
-                    
+
                     // Draw hexagon
                     let mut hex_points = Vec::new();
                     for i in 0..6 {
@@ -410,7 +723,7 @@ impl GeoPlot {
                             center_y + hex_size * angle.sin()
                         ));
                     }
-                    
+
                     painter.add(Shape::convex_polygon(
                         hex_points,
                         color,
@@ -421,60 +734,88 @@ impl GeoPlot {
             _ => {}
         }
     }
-    
-    fn handle_interaction(&mut self, ui: &mut Ui, rect: Rect, points: &[GeoPoint]) {
-        let response = ui.allocate_rect(rect, egui::Sense::click_and_drag());
-        
-        // Pan
+
+    /// Pan, zoom and reset the view
+    fn handle_interaction(&mut self, ui: &Ui, response: &Response) {
+        // Pan: slide the projected map while dragging, then recenter the
+        // projection on the point that ended up in the middle
         if self.config.enable_pan && response.dragged() {
+            let scale = self.transform(response.rect).scale;
             let delta = response.drag_delta();
-            self.center_lon -= (delta.x as f64 / rect.width() as f64) * 360.0 / self.zoom_level;
-            self.center_lat += (delta.y as f64 / rect.height() as f64) * 180.0 / self.zoom_level;
-            
-            // Clamp values
-            self.center_lat = self.center_lat.clamp(-90.0, 90.0);
-            self.center_lon = ((self.center_lon + 180.0) % 360.0) - 180.0;
-        }
-        
+            self.pan_offset[0] -= delta.x as f64 / scale;
+            self.pan_offset[1] += delta.y as f64 / scale;
+        }
+        if response.drag_released() && self.pan_offset != [0.0, 0.0] {
+            let transform = self.transform(response.rect);
+            if let Some((lon, lat)) = transform.projection.inverse(transform.center[0], transform.center[1]) {
+                self.center_lon = lon;
+                self.center_lat = lat.clamp(-89.0, 89.0);
+            }
+            self.pan_offset = [0.0, 0.0];
+        }
+
         // Zoom
-        if self.config.enable_zoom {
+        if self.config.enable_zoom && response.hovered() {
             let scroll_delta = ui.input(|i| i.scroll_delta.y);
             if scroll_delta != 0.0 {
                 self.zoom_level *= 1.1_f64.powf(scroll_delta as f64 / 100.0);
-                self.zoom_level = self.zoom_level.clamp(0.5, 10.0);
-            }
-        }
-        
-        // Tooltips
-        if self.config.show_tooltips && response.hovered() {
-            if let Some(hover_pos) = response.hover_pos() {
-                // Find nearest point
-                let hover_rel = hover_pos - rect.left_top();
-                
-                let mut nearest_point = None;
-                let mut min_dist = f32::INFINITY;
-                
-                for point in points {
-                    let pos = self.project_point(point.lat, point.lon, &rect);
-                    let dist = (pos - hover_pos).length();
-                    if dist < min_dist && dist < 20.0 {
-                        min_dist = dist;
-                        nearest_point = Some(point);
-                    }
+                self.zoom_level = self.zoom_level.clamp(MIN_ZOOM, MAX_ZOOM);
+            }
+        }
+
+        if response.double_clicked() {
+            self.needs_fit = true;
+        }
+    }
+
+    /// Tooltip for the data point, or else the feature, under the pointer
+    fn show_tooltip(&self, response: Response, transform: &MapTransform, points: &[GeoPoint], values: &HashMap<String, f64>) {
+        if !self.config.show_tooltips {
+            return;
+        }
+        let Some(hover_pos) = response.hover_pos() else {
+            return;
+        };
+
+        if self.config.viz_type != GeoVizType::Choropleth {
+            let mut nearest_point = None;
+            let mut min_dist = f32::INFINITY;
+
+            for point in points {
+                let Some(pos) = transform.project(point.lon, point.lat) else {
+                    continue;
+                };
+                let dist = (pos - hover_pos).length();
+                if dist < min_dist && dist < 20.0 {
+                    min_dist = dist;
+                    nearest_point = Some(point);
                 }
-                
-                if let Some(point) = nearest_point {
-                    let mut tooltip_text = format!("Lat: {:.4}, Lon: {:.4}", point.lat, point.lon);
-                    if let Some(value) = point.value {
-                        tooltip_text.push_str(&format!("\nValue: {:.2}", value));
-                    }
-                    if let Some(label) = &point.label {
-                        tooltip_text.push_str(&format!("\n{}", label));
-                    }
-                    
-                    response.on_hover_text(tooltip_text);
+            }
+
+            if let Some(point) = nearest_point {
+                let mut tooltip_text = format!("Lat: {:.4}, Lon: {:.4}", point.lat, point.lon);
+                if let Some(value) = point.value {
+                    tooltip_text.push_str(&format!("\nValue: {:.2}", value));
                 }
+                if let Some(label) = &point.label {
+                    tooltip_text.push_str(&format!("\n{}", label));
+                }
+
+                response.on_hover_text(tooltip_text);
+                return;
+            }
+        }
+
+        let feature = self.projected.as_ref()
+            .and_then(|map| map.feature_at(transform.to_map(hover_pos)))
+            .map(|projected| &self.basemaps[projected.basemap].features[projected.feature]);
+        if let Some(feature) = feature {
+            let mut tooltip_text = self.feature_label(feature).unwrap_or("Feature").to_string();
+            if let Some(value) = self.feature_value(feature, values) {
+                let name = self.config.value_column.as_deref().unwrap_or("Count");
+                tooltip_text.push_str(&format!("\n{}: {:.2}", name, value));
             }
+            response.on_hover_text(tooltip_text);
         }
     }
 }
@@ -492,22 +833,26 @@ impl SpaceView for GeoPlot {
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
-    
+
     fn display_name(&self) -> &str { &self.title }
     fn view_type(&self) -> &str { "GeoPlotView" }
-    
+
     fn set_data_source(&mut self, source_id: String) {
         self.config.data_source_id = Some(source_id);
         self.cached_data = None; // Clear cache when source changes
+        self.choropleth = None;
+        self.pending_choropleth = None;
     }
-    
+
     fn data_source_id(&self) -> Option<&str> {
         self.config.data_source_id.as_deref()
     }
-    
+
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
 
             let data_source = if let Some(source_id) = &self.config.data_source_id {
@@ -516,53 +861,69 @@ impl SpaceView for GeoPlot {
                 data_sources.values().next()
             };
             if let Some(source) = data_source.as_ref() {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch);
                 }
             }
         }
-        
-        if let Some(batch) = &self.cached_data {
-            // Extract lat/lon data
-            let points = self.extract_geo_points(batch);
-            if points.is_empty() {
-                ui.centered_and_justified(|ui| {
-                    ui.label("No geographic data available. Please configure latitude and longitude columns.");
-                });
-                return;
-            }
-            
-            // Build spatial index if needed
-            if self.spatial_index.is_none() && !points.is_empty() {
-                self.spatial_index = Some(RTree::bulk_load(points.clone()));
-            }
-            
-            // Main plot area
-            let available_rect = ui.available_rect_before_wrap();
-            let plot_rect = Rect::from_min_size(
-                available_rect.left_top() + Vec2::new(10.0, 10.0),
-                available_rect.size() - Vec2::new(20.0, 20.0)
-            );
-            
-            // Draw base map
-            self.draw_base_map(ui, plot_rect);
-            
-            // Draw data
-            self.draw_data_points(ui, plot_rect, &points);
-            
-            // Handle interactions
-            self.handle_interaction(ui, plot_rect, &points);
-            
-            // Draw legend and controls
-            self.draw_controls(ui);
-        } else {
+
+        let Some(batch) = self.cached_data.clone() else {
             ui.centered_and_justified(|ui| {
                 ui.label("Loading geographic data...");
             });
+            return;
+        };
+
+        // Controls
+        self.draw_controls(ui, &batch);
+
+        // Extract lat/lon data
+        let points = self.extract_geo_points(&batch);
+        if points.is_empty() && self.basemaps.is_empty() {
+            ui.centered_and_justified(|ui| {
+                ui.label("No geographic data available. Please configure latitude and longitude columns or load a basemap.");
+            });
+            return;
+        }
+
+        // Build spatial index if needed
+        if self.spatial_index.is_none() && !points.is_empty() {
+            self.spatial_index = Some(RTree::bulk_load(points.clone()));
+        }
+
+        // Main plot area
+        let rect = ui.available_rect_before_wrap();
+        let response = ui.allocate_rect(rect, Sense::click_and_drag());
+        if self.needs_fit {
+            self.fit(&points, rect);
         }
+        self.handle_interaction(ui, &response);
+
+        let transform = self.transform(rect);
+        self.update_projected(&transform.projection);
+        if self.config.viz_type == GeoVizType::Choropleth {
+            self.update_choropleth(ctx);
+            if self.pending_choropleth.is_some() {
+                ui.ctx().request_repaint_after(std::time::Duration::from_millis(50));
+            }
+        }
+        let no_values = HashMap::new();
+        let values = match &self.choropleth {
+            Some(choropleth) if self.config.viz_type == GeoVizType::Choropleth => &choropleth.values,
+            _ => &no_values,
+        };
+
+        self.draw_base_map(ui, &transform);
+        self.draw_basemaps(ui, &transform, values);
+        self.draw_graticule(ui, &transform);
+        if self.config.viz_type == GeoVizType::Choropleth {
+            self.draw_legend(ui, &transform, values);
+        } else {
+            self.draw_data_points(ui, &transform, &points);
+        }
+        self.show_tooltip(response, &transform, &points, values);
     }
-    
+
     fn save_config(&self) -> Value {
         json!({
             "data_source_id": self.config.data_source_id,
@@ -580,15 +941,19 @@ impl SpaceView for GeoPlot {
             "show_countries": self.config.show_countries,
             "show_grid": self.config.show_grid,
             "show_labels": self.config.show_labels,
+            "basemap_files": self.config.basemap_files,
+            "join_column": self.config.join_column,
+            "join_property": self.config.join_property,
             "enable_zoom": self.config.enable_zoom,
             "enable_pan": self.config.enable_pan,
             "show_tooltips": self.config.show_tooltips,
             "center_lat": self.center_lat,
             "center_lon": self.center_lon,
             "zoom_level": self.zoom_level,
+            "albers_parallels": [self.albers_parallels.0, self.albers_parallels.1],
         })
     }
-    
+
     fn load_config(&mut self, config: Value) {
         if let Some(data_source_id) = config.get("data_source_id").and_then(|v| v.as_str()) {
             self.config.data_source_id = Some(data_source_id.to_string());
@@ -608,17 +973,78 @@ impl SpaceView for GeoPlot {
         if let Some(group) = config.get("group_column").and_then(|v| v.as_str()) {
             self.config.group_column = Some(group.to_string());
         }
+        if let Some(projection) = config.get("projection").and_then(|v| v.as_str()).and_then(ProjectionType::from_name) {
+            self.config.projection = projection;
+        }
+        if let Some(viz_type) = config.get("viz_type").and_then(|v| v.as_str()).and_then(GeoVizType::from_name) {
+            self.config.viz_type = viz_type;
+        }
+        if let Some(size) = config.get("marker_size").and_then(|v| v.as_f64()) {
+            self.config.marker_size = size as f32;
+        }
+        if let Some(radius) = config.get("heatmap_radius").and_then(|v| v.as_f64()) {
+            self.config.heatmap_radius = radius as f32;
+        }
+        if let Some(scheme) = config.get("color_scheme").and_then(|v| v.as_str()) {
+            self.config.color_scheme = match scheme {
+                "Plasma" => ColorScheme::Plasma,
+                "Categorical" => ColorScheme::Categorical,
+                "Sequential" => ColorScheme::Sequential,
+                "Diverging" => ColorScheme::Diverging,
+                _ => ColorScheme::Viridis,
+            };
+        }
+        for (key, flag) in [
+            ("show_coastlines", &mut self.config.show_coastlines),
+            ("show_countries", &mut self.config.show_countries),
+            ("show_grid", &mut self.config.show_grid),
+            ("show_labels", &mut self.config.show_labels),
+            ("enable_zoom", &mut self.config.enable_zoom),
+            ("enable_pan", &mut self.config.enable_pan),
+            ("show_tooltips", &mut self.config.show_tooltips),
+        ] {
+            if let Some(value) = config.get(key).and_then(|v| v.as_bool()) {
+                *flag = value;
+            }
+        }
+        if let Some(join) = config.get("join_column").and_then(|v| v.as_str()) {
+            self.config.join_column = Some(join.to_string());
+        }
+        if let Some(property) = config.get("join_property").and_then(|v| v.as_str()) {
+            self.config.join_property = Some(property.to_string());
+        }
+        if let Some(files) = config.get("basemap_files").and_then(|v| v.as_array()) {
+            self.basemaps.clear();
+            self.unloaded_basemaps.clear();
+            self.projected = None;
+            self.config.basemap_files.clear();
+            for file in files.iter().filter_map(|v| v.as_str()) {
+                self.add_basemap(file);
+                // A file that can't be read now is kept, so saving doesn't lose it
+                if let Some(error) = self.basemap_error.take() {
+                    self.config.basemap_files.push(file.to_string());
+                    self.unloaded_basemaps.push((file.to_string(), error));
+                }
+            }
+        }
         if let Some(center_lat) = config.get("center_lat").and_then(|v| v.as_f64()) {
             self.center_lat = center_lat;
+            self.needs_fit = false;
         }
         if let Some(center_lon) = config.get("center_lon").and_then(|v| v.as_f64()) {
             self.center_lon = center_lon;
         }
         if let Some(zoom) = config.get("zoom_level").and_then(|v| v.as_f64()) {
-            self.zoom_level = zoom;
+            self.zoom_level = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+            self.needs_fit = false;
+        }
+        if let Some([first, second]) = config.get("albers_parallels").and_then(|v| v.as_array()).map(Vec::as_slice) {
+            if let (Some(first), Some(second)) = (first.as_f64(), second.as_f64()) {
+                self.albers_parallels = (first, second);
+            }
         }
     }
-    
+
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
     fn on_frame_update(&mut self, _ctx: &ViewerContext, _dt: f32) {}
 }
@@ -626,20 +1052,20 @@ impl SpaceView for GeoPlot {
 impl GeoPlot {
     fn extract_geo_points(&self, batch: &RecordBatch) -> Vec<GeoPoint> {
         let mut points = Vec::new();
-        
+
         // Find columns
         let lat_col_idx = batch.schema().fields().iter()
             .position(|f| f.name() == &self.config.lat_column);
         let lon_col_idx = batch.schema().fields().iter()
             .position(|f| f.name() == &self.config.lon_column);
-            
+
         if lat_col_idx.is_none() || lon_col_idx.is_none() {
             return points;
         }
-        
+
         let lat_col = batch.column(lat_col_idx.unwrap());
         let lon_col = batch.column(lon_col_idx.unwrap());
-        
+
         // Extract lat/lon values
         if let (Some(lat_array), Some(lon_array)) = (
             lat_col.as_any().downcast_ref::<Float64Array>(),
@@ -652,7 +1078,7 @@ impl GeoPlot {
                         .position(|f| f.name() == col_name)
                         .and_then(|idx| batch.column(idx).as_any().downcast_ref::<Float64Array>())
                 });
-            
+
             // Extract optional label column
             let label_array = self.config.label_column.as_ref()
                 .and_then(|col_name| {
@@ -660,7 +1086,7 @@ impl GeoPlot {
                         .position(|f| f.name() == col_name)
                         .and_then(|idx| batch.column(idx).as_any().downcast_ref::<StringArray>())
                 });
-                
+
             // Extract optional group column
             let group_array = self.config.group_column.as_ref()
                 .and_then(|col_name| {
@@ -668,7 +1094,7 @@ impl GeoPlot {
                         .position(|f| f.name() == col_name)
                         .and_then(|idx| batch.column(idx).as_any().downcast_ref::<StringArray>())
                 });
-            
+
             for i in 0..lat_array.len() {
                 let (lat, lon) = (lat_array.value(i), lon_array.value(i));
                 points.push(GeoPoint {
@@ -681,27 +1107,259 @@ impl GeoPlot {
                 });
             }
         }
-        
+
         points
     }
-    
-    fn draw_controls(&self, ui: &mut Ui) {
-        ui.separator();
+
+    fn draw_controls(&mut self, ui: &mut Ui, batch: &RecordBatch) {
+        let columns: Vec<String> = batch.schema().fields().iter().map(|f| f.name().clone()).collect();
+
         ui.horizontal(|ui| {
-            ui.label("Map Controls:");
-            ui.label(format!("Center: {:.2}, {:.2}", self.center_lat, self.center_lon));
+            ui.label("Projection:");
+            egui::ComboBox::from_id_source(format!("{:?}_projection", self.id))
+                .selected_text(self.config.projection.label())
+                .show_ui(ui, |ui| {
+                    for projection in ProjectionType::ALL {
+                        let label = projection.label();
+                        ui.selectable_value(&mut self.config.projection, projection, label);
+                    }
+                });
+
+            ui.label("Type:");
+            egui::ComboBox::from_id_source(format!("{:?}_viz_type", self.id))
+                .selected_text(format!("{:?}", self.config.viz_type))
+                .show_ui(ui, |ui| {
+                    for viz_type in GeoVizType::ALL {
+                        let label = format!("{:?}", viz_type);
+                        ui.selectable_value(&mut self.config.viz_type, viz_type, label);
+                    }
+                });
+
+            ui.checkbox(&mut self.config.show_countries, "Regions");
+            ui.checkbox(&mut self.config.show_coastlines, "Lines");
+            ui.checkbox(&mut self.config.show_grid, "Grid");
+            ui.checkbox(&mut self.config.show_labels, "Labels");
+
+            ui.separator();
             ui.label(format!("Zoom: {:.1}x", self.zoom_level));
-            
             if ui.button("Reset View").clicked() {
-                // Reset will be handled in next frame
+                self.needs_fit = true;
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Basemap:");
+            let edit = ui.add(
+                egui::TextEdit::singleline(&mut self.new_basemap_path)
+                    .hint_text("GeoJSON or .shp file")
+                    .desired_width(220.0),
+            );
+            let entered = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button("Load").clicked() || entered) && !self.new_basemap_path.trim().is_empty() {
+                let path = std::mem::take(&mut self.new_basemap_path);
+                self.add_basemap(path.trim());
+            }
+
+            let mut removed = None;
+            for (index, basemap) in self.basemaps.iter().enumerate() {
+                let button = ui.small_button(format!("{} ✖", basemap.name()))
+                    .on_hover_text(basemap.path.display().to_string());
+                if button.clicked() {
+                    removed = Some(index);
+                }
+            }
+            if let Some(index) = removed {
+                self.remove_basemap(index);
+            }
+
+            let mut forgotten = None;
+            for (index, (file, error)) in self.unloaded_basemaps.iter().enumerate() {
+                let name = Path::new(file).file_name().map_or_else(|| file.clone(), |name| name.to_string_lossy().into_owned());
+                let button = ui.small_button(egui::RichText::new(format!("⚠ {} ✖", name)).color(ui.visuals().error_fg_color))
+                    .on_hover_text(error);
+                if button.clicked() {
+                    forgotten = Some(index);
+                }
+            }
+            if let Some(index) = forgotten {
+                let (file, _) = self.unloaded_basemaps.remove(index);
+                self.config.basemap_files.retain(|configured| *configured != file);
+            }
+
+            if let Some(error) = &self.basemap_error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+
+        if self.config.viz_type == GeoVizType::Choropleth {
+            let mut properties: Vec<String> = self.basemaps.iter().flat_map(Basemap::property_names).collect();
+            properties.sort();
+            properties.dedup();
+
+            ui.horizontal(|ui| {
+                ui.label("Join");
+                choice_combo(ui, format!("{:?}_join_column", self.id), &mut self.config.join_column, &columns, "(none)");
+                ui.label("to property");
+                choice_combo(ui, format!("{:?}_join_property", self.id), &mut self.config.join_property, &properties, "(none)");
+                ui.label("Value:");
+                choice_combo(ui, format!("{:?}_value_column", self.id), &mut self.config.value_column, &columns, "Count");
+            });
+        }
+    }
+}
+
+/// Combo box choosing one of `options`, or none
+fn choice_combo(ui: &mut Ui, id: String, selected: &mut Option<String>, options: &[String], none_label: &str) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(selected.as_deref().unwrap_or(none_label))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(selected, None, none_label);
+            for option in options {
+                ui.selectable_value(selected, Some(option.clone()), option);
             }
-            
-            ui.separator();
-            
-            // Visualization type selector
-            ui.label("Type:");
-            let viz_type_text = format!("{:?}", self.config.viz_type);
-            ui.label(&viz_type_text);
         });
+}
+
+/// Edge of the projected world: the horizon of a globe, otherwise the
+/// meridian opposite the central one and the poles
+fn world_outline(projection: &Projection) -> Vec<[f64; 2]> {
+    if projection.kind == ProjectionType::Orthographic {
+        return (0..=90)
+            .map(|k| {
+                let angle = k as f64 * TAU / 90.0;
+                [angle.cos(), angle.sin()]
+            })
+            .collect();
     }
-} 
\ No newline at end of file
+    let edge = 180.0 - 1e-9;
+    let (west, east) = (projection.center_lon - edge, projection.center_lon + edge);
+    let along = |from: f64, to: f64, steps: usize| (0..=steps).map(move |k| from + (to - from) * k as f64 / steps as f64);
+    along(-90.0, 90.0, 180).map(|lat| [west, lat])
+        .chain(along(west, east, 360).map(|lon| [lon, 90.0]))
+        .chain(along(90.0, -90.0, 180).map(|lat| [east, lat]))
+        .chain(along(east, west, 360).map(|lon| [lon, -90.0]))
+        .filter_map(|[lon, lat]| projection.project(lon, lat))
+        .collect()
+}
+
+/// Mean of the value column for each value of the join column, or the
+/// number of rows without a value column
+fn choropleth_values(batch: &RecordBatch, join_column: Option<&str>, value_column: Option<&str>) -> HashMap<String, f64> {
+    let column = |name: Option<&str>, data_type: &DataType| {
+        name.and_then(|name| batch.column_by_name(name))
+            .and_then(|array| cast(array, data_type).ok())
+    };
+    let Some(keys) = column(join_column, &DataType::Utf8) else {
+        return HashMap::new();
+    };
+    let Some(keys) = keys.as_any().downcast_ref::<StringArray>() else {
+        return HashMap::new();
+    };
+    let values = column(value_column, &DataType::Float64);
+    let values = values.as_ref().and_then(|array| array.as_any().downcast_ref::<Float64Array>());
+
+    let mut sums: HashMap<String, (f64, usize)> = HashMap::new();
+    for row in 0..keys.len() {
+        if keys.is_null(row) {
+            continue;
+        }
+        let value = match values {
+            Some(values) if values.is_valid(row) => values.value(row),
+            Some(_) => continue,
+            None => 0.0,
+        };
+        let sum = sums.entry(join_key(keys.value(row))).or_insert((0.0, 0));
+        sum.0 += value;
+        sum.1 += 1;
+    }
+    sums.into_iter()
+        .map(|(key, (sum, count))| {
+            let value = if values.is_some() { sum / count as f64 } else { count as f64 };
+            (key, value)
+        })
+        .collect()
+}
+
+/// Key matching data values to feature properties regardless of case,
+/// padding and number formatting, so that "01001" matches 1001.0
+fn join_key(value: &str) -> String {
+    let value = value.trim();
+    match value.parse::<f64>() {
+        Ok(number) if number.fract() == 0.0 && number.abs() < 1e15 => format!("{}", number as i64),
+        _ => value.to_lowercase(),
+    }
+}
+
+fn value_range(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values.filter(|v| v.is_finite()).fold(None, |range, v| match range {
+        None => Some((v, v)),
+        Some((low, high)) => Some((f64::min(low, v), f64::max(high, v))),
+    })
+}
+
+fn normalize(value: f64, low: f64, high: f64) -> f32 {
+    if high > low { ((value - low) / (high - low)) as f32 } else { 0.5 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+    use arrow::datatypes::{Field, Schema};
+
+    #[test]
+    fn join_keys_ignore_number_format_case_and_padding() {
+        assert_eq!(join_key("01001"), join_key("1001.0"));
+        assert_eq!(join_key(" Ohio "), join_key("OHIO"));
+        assert_ne!(join_key("1001.5"), join_key("1001"));
+    }
+
+    #[test]
+    fn choropleth_is_mean_or_count_per_key() {
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("fips", DataType::Utf8, true),
+                Field::new("rate", DataType::Int64, true),
+            ])),
+            vec![
+                Arc::new(StringArray::from(vec![Some("01001"), Some("1001"), None, Some("02002"), Some("02002")])),
+                Arc::new(Int64Array::from(vec![Some(2), Some(4), Some(100), Some(6), None])),
+            ],
+        ).unwrap();
+        let counts = choropleth_values(&batch, Some("fips"), None);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["1001"], 2.0);
+        assert_eq!(counts["2002"], 2.0);
+
+        let means = choropleth_values(&batch, Some("fips"), Some("rate"));
+        assert_eq!(means["1001"], 3.0);
+        // Rows without a value don't count toward the mean
+        assert_eq!(means["2002"], 6.0);
+    }
+
+    #[test]
+    fn basemaps_that_fail_to_load_stay_configured() {
+        let mut plot = GeoPlot::new(SpaceViewId::nil(), "Map".to_string());
+        plot.load_config(json!({ "basemap_files": ["/missing/counties.geojson"] }));
+        assert!(plot.basemaps.is_empty());
+        assert_eq!(plot.unloaded_basemaps.len(), 1);
+        assert_eq!(plot.save_config()["basemap_files"], json!(["/missing/counties.geojson"]));
+    }
+
+    #[test]
+    fn projected_map_culls_features_out_of_view() {
+        let point = |lon: f64, lat: f64| MapFeature { points: vec![[lon, lat]], ..Default::default() };
+        let basemap = Basemap { path: "points.geojson".into(), features: vec![point(10.0, 10.0), point(100.0, 40.0)] };
+        let projection = Projection::world(ProjectionType::Mercator);
+        let map = ProjectedMap::new(&[basemap], &projection);
+
+        let min = projection.project(5.0, 5.0).unwrap();
+        let max = projection.project(20.0, 20.0).unwrap();
+        let visible: Vec<usize> = map.visible(min, max).map(|feature| feature.feature).collect();
+        assert_eq!(visible, vec![0]);
+
+        let max = projection.project(120.0, 50.0).unwrap();
+        let visible: Vec<usize> = map.visible(min, max).map(|feature| feature.feature).collect();
+        assert_eq!(visible, vec![0, 1]);
+    }
+}
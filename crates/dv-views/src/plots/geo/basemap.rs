@@ -0,0 +1,318 @@
+//! Basemaps loaded from local GeoJSON and shapefiles
+//!
+//! Features keep their geometry in (lon, lat). Drawing goes through a
+//! [`ProjectedMap`], which projects every feature once per projection and
+//! indexes their projected bounds so only the features in view are drawn.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use ::geo::TriangulateEarcut;
+use geo_types::{Coord, LineString, Polygon};
+use geojson::GeoJson;
+use rstar::{RTree, RTreeObject, AABB};
+use serde_json::Value;
+use thiserror::Error;
+
+use super::projection::Projection;
+use super::shapefile::{self, Shape};
+
+/// Errors loading basemaps
+#[derive(Error, Debug)]
+pub enum BasemapError {
+    #[error("Failed to read {0}: {1}")]
+    Read(PathBuf, std::io::Error),
+
+    #[error("Invalid GeoJSON in {0}: {1}")]
+    GeoJson(PathBuf, String),
+
+    #[error("Invalid shapefile {0}: {1}")]
+    Shapefile(PathBuf, String),
+
+    #[error("Unsupported basemap {0}: expected .geojson, .json or .shp")]
+    Unsupported(PathBuf),
+}
+
+/// A feature of a basemap, such as a country or a county
+#[derive(Debug, Clone, Default)]
+pub struct MapFeature {
+    /// Attributes, with numbers and booleans written out
+    pub properties: HashMap<String, String>,
+    /// Polygons as rings of (lon, lat), the outer ring first
+    pub polygons: Vec<Vec<Vec<[f64; 2]>>>,
+    pub lines: Vec<Vec<[f64; 2]>>,
+    pub points: Vec<[f64; 2]>,
+    /// The polygons cut into triangles, for filling
+    pub triangles: Vec<[[f64; 2]; 3]>,
+}
+
+impl MapFeature {
+    /// Least and greatest (lon, lat) of the feature
+    pub fn bounds(&self) -> Option<[f64; 4]> {
+        let rings = self.polygons.iter().flatten().chain(&self.lines);
+        bounds_of(rings.flatten().chain(&self.points))
+    }
+
+    fn with_shape(mut self, shape: Shape) -> Self {
+        match shape {
+            Shape::Null => {}
+            Shape::Points(points) => self.points.extend(points),
+            Shape::Lines(lines) => self.lines.extend(lines),
+            Shape::Polygons(polygons) => {
+                for polygon in polygons {
+                    self.add_polygon(polygon);
+                }
+            }
+        }
+        self
+    }
+
+    fn add_polygon(&mut self, rings: Vec<Vec<[f64; 2]>>) {
+        let ring = |points: &Vec<[f64; 2]>| LineString::from(points.iter().map(|&[x, y]| Coord { x, y }).collect::<Vec<_>>());
+        let Some((outer, holes)) = rings.split_first() else {
+            return;
+        };
+        let polygon = Polygon::new(ring(outer), holes.iter().map(ring).collect());
+        self.triangles.extend(polygon.earcut_triangles().into_iter().map(|triangle| triangle.to_array().map(|c| [c.x, c.y])));
+        self.polygons.push(rings);
+    }
+
+    fn add_geometry(&mut self, geometry: geojson::Value) {
+        let points = |positions: Vec<Vec<f64>>| -> Vec<[f64; 2]> {
+            positions.into_iter().filter(|p| p.len() >= 2).map(|p| [p[0], p[1]]).collect()
+        };
+        match geometry {
+            geojson::Value::Point(position) => self.points.extend(points(vec![position])),
+            geojson::Value::MultiPoint(positions) => self.points.extend(points(positions)),
+            geojson::Value::LineString(line) => self.lines.push(points(line)),
+            geojson::Value::MultiLineString(lines) => self.lines.extend(lines.into_iter().map(points)),
+            geojson::Value::Polygon(rings) => self.add_polygon(rings.into_iter().map(points).collect()),
+            geojson::Value::MultiPolygon(polygons) => {
+                for rings in polygons {
+                    self.add_polygon(rings.into_iter().map(points).collect());
+                }
+            }
+            geojson::Value::GeometryCollection(geometries) => {
+                for geometry in geometries {
+                    self.add_geometry(geometry.value);
+                }
+            }
+        }
+    }
+}
+
+/// Features loaded from one file
+#[derive(Debug, Clone)]
+pub struct Basemap {
+    pub path: PathBuf,
+    pub features: Vec<MapFeature>,
+}
+
+impl Basemap {
+    /// Load a `.geojson`/`.json` file, or a `.shp` file with the attributes
+    /// of the `.dbf` file beside it, if any. Coordinates must be longitude
+    /// and latitude (WGS 84).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BasemapError> {
+        let path = path.as_ref().to_path_buf();
+        let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+        let features = match extension.as_deref() {
+            Some("geojson" | "json") => {
+                let text = std::fs::read_to_string(&path).map_err(|e| BasemapError::Read(path.clone(), e))?;
+                let geojson: GeoJson = text.parse().map_err(|e: geojson::Error| BasemapError::GeoJson(path.clone(), e.to_string()))?;
+                geojson_features(geojson)
+            }
+            Some("shp") => {
+                let bytes = std::fs::read(&path).map_err(|e| BasemapError::Read(path.clone(), e))?;
+                let shapes = shapefile::read_shapes(&bytes).map_err(|e| BasemapError::Shapefile(path.clone(), e))?;
+                let attributes = match std::fs::read(path.with_extension("dbf")) {
+                    Ok(bytes) => shapefile::read_attributes(&bytes).map_err(|e| BasemapError::Shapefile(path.clone(), e))?,
+                    Err(_) => Vec::new(),
+                };
+                let mut attributes = attributes.into_iter();
+                shapes.into_iter()
+                    .map(|shape| MapFeature { properties: attributes.next().unwrap_or_default(), ..Default::default() }.with_shape(shape))
+                    .collect()
+            }
+            _ => return Err(BasemapError::Unsupported(path)),
+        };
+        Ok(Self { path, features })
+    }
+
+    /// File name for display
+    pub fn name(&self) -> String {
+        self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+
+    /// Least and greatest (lon, lat) of all features
+    pub fn bounds(&self) -> Option<[f64; 4]> {
+        merge_bounds(self.features.iter().filter_map(MapFeature::bounds))
+    }
+
+    /// Names of the properties of the features, sorted
+    pub fn property_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.features.iter().flat_map(|f| f.properties.keys().cloned()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+fn geojson_features(geojson: GeoJson) -> Vec<MapFeature> {
+    let feature = |geometry: Option<geojson::Geometry>, properties: Option<geojson::JsonObject>| {
+        let mut feature = MapFeature {
+            properties: properties.unwrap_or_default().into_iter()
+                .filter_map(|(key, value)| {
+                    let text = match value {
+                        Value::Null => return None,
+                        Value::String(text) => text,
+                        value => value.to_string(),
+                    };
+                    Some((key, text))
+                })
+                .collect(),
+            ..Default::default()
+        };
+        if let Some(geometry) = geometry {
+            feature.add_geometry(geometry.value);
+        }
+        feature
+    };
+    match geojson {
+        GeoJson::FeatureCollection(collection) => collection.features.into_iter()
+            .map(|f| feature(f.geometry, f.properties))
+            .collect(),
+        GeoJson::Feature(f) => vec![feature(f.geometry, f.properties)],
+        GeoJson::Geometry(geometry) => vec![feature(Some(geometry), None)],
+    }
+}
+
+/// A feature projected for drawing
+#[derive(Debug, Clone)]
+pub struct ProjectedFeature {
+    /// Index of the basemap and of the feature within it
+    pub basemap: usize,
+    pub feature: usize,
+    pub triangles: Vec<[[f64; 2]; 3]>,
+    /// Polygon rings, broken at the map's edge
+    pub rings: Vec<Vec<[f64; 2]>>,
+    /// Lines, broken at the map's edge
+    pub lines: Vec<Vec<[f64; 2]>>,
+    pub points: Vec<[f64; 2]>,
+    /// Projected bounds: least x and y, greatest x and y
+    pub bounds: [f64; 4],
+    /// Where a label goes, the centroid of the filled area
+    pub anchor: Option<[f64; 2]>,
+}
+
+impl ProjectedFeature {
+    /// Whether `point` lies in the filled area
+    pub fn contains(&self, point: [f64; 2]) -> bool {
+        self.triangles.iter().any(|&[a, b, c]| {
+            let side = |p: [f64; 2], q: [f64; 2]| (q[0] - p[0]) * (point[1] - p[1]) - (q[1] - p[1]) * (point[0] - p[0]);
+            let (d1, d2, d3) = (side(a, b), side(b, c), side(c, a));
+            !((d1 < 0.0 || d2 < 0.0 || d3 < 0.0) && (d1 > 0.0 || d2 > 0.0 || d3 > 0.0))
+        })
+    }
+}
+
+struct IndexedBounds {
+    index: usize,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for IndexedBounds {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// The features of some basemaps under one projection, indexed by their
+/// projected bounds
+pub struct ProjectedMap {
+    pub projection: Projection,
+    pub features: Vec<ProjectedFeature>,
+    index: RTree<IndexedBounds>,
+}
+
+impl ProjectedMap {
+    pub fn new(basemaps: &[Basemap], projection: &Projection) -> Self {
+        let mut features = Vec::new();
+        for (basemap_index, basemap) in basemaps.iter().enumerate() {
+            for (feature_index, feature) in basemap.features.iter().enumerate() {
+                if let Some(projected) = project_feature(feature, projection) {
+                    features.push(ProjectedFeature { basemap: basemap_index, feature: feature_index, ..projected });
+                }
+            }
+        }
+        let index = RTree::bulk_load(features.iter().enumerate()
+            .map(|(index, feature)| {
+                let [min_x, min_y, max_x, max_y] = feature.bounds;
+                IndexedBounds { index, envelope: AABB::from_corners([min_x, min_y], [max_x, max_y]) }
+            })
+            .collect());
+        Self { projection: projection.clone(), features, index }
+    }
+
+    /// Features whose bounds meet the projected rectangle from `min` to `max`
+    pub fn visible(&self, min: [f64; 2], max: [f64; 2]) -> impl Iterator<Item = &ProjectedFeature> {
+        let mut indices: Vec<usize> = self.index
+            .locate_in_envelope_intersecting(&AABB::from_corners(min, max))
+            .map(|bounds| bounds.index)
+            .collect();
+        // Keep the files' drawing order
+        indices.sort_unstable();
+        indices.into_iter().map(|index| &self.features[index])
+    }
+
+    /// The last drawn feature whose filled area holds `point`
+    pub fn feature_at(&self, point: [f64; 2]) -> Option<&ProjectedFeature> {
+        self.visible(point, point).filter(|feature| feature.contains(point)).last()
+    }
+}
+
+fn project_feature(feature: &MapFeature, projection: &Projection) -> Option<ProjectedFeature> {
+    let mut triangles = Vec::with_capacity(feature.triangles.len());
+    for triangle in &feature.triangles {
+        // Triangles across the map's edge would stretch over the whole map
+        let lons = triangle.map(|[lon, _]| projection.relative_lon(lon));
+        let span = lons.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b)) - lons.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+        if span > 180.0 {
+            continue;
+        }
+        let [a, b, c] = triangle.map(|[lon, lat]| projection.project(lon, lat));
+        if let (Some(a), Some(b), Some(c)) = (a, b, c) {
+            triangles.push([a, b, c]);
+        }
+    }
+    let rings: Vec<Vec<[f64; 2]>> = feature.polygons.iter().flatten().flat_map(|ring| projection.project_path(ring)).collect();
+    let lines: Vec<Vec<[f64; 2]>> = feature.lines.iter().flat_map(|line| projection.project_path(line)).collect();
+    let points: Vec<[f64; 2]> = feature.points.iter().filter_map(|&[lon, lat]| projection.project(lon, lat)).collect();
+
+    let bounds = bounds_of(rings.iter().chain(&lines).flatten().chain(&points))?;
+    let (mut area, mut sum) = (0.0, [0.0, 0.0]);
+    for [a, b, c] in &triangles {
+        let weight = ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])).abs();
+        area += weight;
+        sum[0] += weight * (a[0] + b[0] + c[0]) / 3.0;
+        sum[1] += weight * (a[1] + b[1] + c[1]) / 3.0;
+    }
+    let anchor = (area > 0.0).then(|| [sum[0] / area, sum[1] / area]);
+    Some(ProjectedFeature { basemap: 0, feature: 0, triangles, rings, lines, points, bounds, anchor })
+}
+
+/// Least x and y, and greatest x and y, of some points
+pub fn bounds_of<'a>(points: impl IntoIterator<Item = &'a [f64; 2]>) -> Option<[f64; 4]> {
+    points.into_iter()
+        .filter(|point| point[0].is_finite() && point[1].is_finite())
+        .fold(None, |bounds, &[x, y]| match bounds {
+            None => Some([x, y, x, y]),
+            Some([min_x, min_y, max_x, max_y]) => Some([min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)]),
+        })
+}
+
+/// Bounds holding all of some bounds
+pub fn merge_bounds(bounds: impl IntoIterator<Item = [f64; 4]>) -> Option<[f64; 4]> {
+    bounds.into_iter().reduce(|a, b| [a[0].min(b[0]), a[1].min(b[1]), a[2].max(b[2]), a[3].max(b[3])])
+}
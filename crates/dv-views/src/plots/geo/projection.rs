@@ -0,0 +1,290 @@
+//! Map projections
+//!
+//! Projected coordinates are on a sphere of unit radius, x east and y north.
+
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+/// Latitude beyond which Mercator is cut off
+const MERCATOR_MAX_LAT: f64 = 85.051_128_78;
+
+/// Robinson's table of parallel lengths (X) and distances from the equator
+/// (Y) every 5° of latitude
+const ROBINSON_X: [f64; 19] = [
+    1.0000, 0.9986, 0.9954, 0.9900, 0.9822, 0.9730, 0.9600, 0.9427, 0.9216, 0.8962,
+    0.8679, 0.8350, 0.7986, 0.7597, 0.7186, 0.6732, 0.6213, 0.5722, 0.5322,
+];
+const ROBINSON_Y: [f64; 19] = [
+    0.0000, 0.0620, 0.1240, 0.1860, 0.2480, 0.3100, 0.3720, 0.4340, 0.4958, 0.5571,
+    0.6176, 0.6769, 0.7346, 0.7903, 0.8435, 0.8936, 0.9394, 0.9761, 1.0000,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProjectionType {
+    Mercator,
+    Robinson,
+    AlbersEqualArea,
+    Orthographic,
+    NaturalEarth,
+}
+
+impl ProjectionType {
+    pub const ALL: [ProjectionType; 5] = [
+        ProjectionType::Mercator,
+        ProjectionType::Robinson,
+        ProjectionType::NaturalEarth,
+        ProjectionType::AlbersEqualArea,
+        ProjectionType::Orthographic,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|projection| format!("{:?}", projection) == name)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectionType::Mercator => "Mercator",
+            ProjectionType::Robinson => "Robinson",
+            ProjectionType::AlbersEqualArea => "Albers equal-area",
+            ProjectionType::Orthographic => "Orthographic (globe)",
+            ProjectionType::NaturalEarth => "Natural Earth",
+        }
+    }
+}
+
+/// A projection with its parameters, in degrees
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    pub kind: ProjectionType,
+    /// Central meridian
+    pub center_lon: f64,
+    /// Latitude of origin: the center of the globe for orthographic, the
+    /// origin of y for Albers
+    pub center_lat: f64,
+    /// Standard parallels of Albers
+    pub parallels: (f64, f64),
+}
+
+impl Projection {
+    /// `kind` centered on the equator and the prime meridian
+    pub fn world(kind: ProjectionType) -> Self {
+        Self { kind, center_lon: 0.0, center_lat: 0.0, parallels: (0.0, 0.0) }
+    }
+
+    /// Albers fitted to a region, with standard parallels a sixth of its
+    /// height inside its southern and northern edges
+    pub fn albers(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Self {
+        let height = max_lat - min_lat;
+        Self {
+            kind: ProjectionType::AlbersEqualArea,
+            center_lon: (min_lon + max_lon) / 2.0,
+            center_lat: (min_lat + max_lat) / 2.0,
+            parallels: (min_lat + height / 6.0, max_lat - height / 6.0),
+        }
+    }
+
+    /// Projected position of (`lon`, `lat`), or None where the projection
+    /// doesn't show it (the far side of the globe)
+    pub fn project(&self, lon: f64, lat: f64) -> Option<[f64; 2]> {
+        if !lon.is_finite() || !lat.is_finite() {
+            return None;
+        }
+        let lambda = self.relative_lon(lon).to_radians();
+        let phi = lat.clamp(-90.0, 90.0).to_radians();
+        match self.kind {
+            ProjectionType::Mercator => {
+                let phi = lat.clamp(-MERCATOR_MAX_LAT, MERCATOR_MAX_LAT).to_radians();
+                Some([lambda, (FRAC_PI_4 + phi / 2.0).tan().ln()])
+            }
+            ProjectionType::Robinson => {
+                let (x, y) = robinson_table(lat.abs());
+                Some([0.8487 * x * lambda, 1.3523 * y * lat.signum()])
+            }
+            ProjectionType::NaturalEarth => {
+                let (phi2, phi4) = (phi * phi, phi.powi(4));
+                let x = lambda * (0.8707 - 0.131979 * phi2 + phi4 * (-0.013791 + phi4 * phi2 * (0.003971 - 0.001529 * phi2)));
+                Some([x, natural_earth_y(phi)])
+            }
+            ProjectionType::Orthographic => {
+                let phi0 = self.center_lat.to_radians();
+                let cos_c = phi0.sin() * phi.sin() + phi0.cos() * phi.cos() * lambda.cos();
+                (cos_c >= 0.0).then(|| [
+                    phi.cos() * lambda.sin(),
+                    phi0.cos() * phi.sin() - phi0.sin() * phi.cos() * lambda.cos(),
+                ])
+            }
+            ProjectionType::AlbersEqualArea => match self.albers_constants() {
+                Some((n, c, rho0)) => {
+                    let rho = (c - 2.0 * n * phi.sin()).max(0.0).sqrt() / n;
+                    let theta = n * lambda;
+                    Some([rho * theta.sin(), rho0 - rho * theta.cos()])
+                }
+                // Parallels either side of the equator: the cylindrical limit
+                None => Some([lambda, phi.sin()]),
+            },
+        }
+    }
+
+    /// (lon, lat) of a projected position, or None outside the map
+    pub fn inverse(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let (lambda, phi) = match self.kind {
+            ProjectionType::Mercator => (x, 2.0 * y.exp().atan() - FRAC_PI_2),
+            ProjectionType::Robinson => {
+                let lat = robinson_lat((y / 1.3523).abs())? * y.signum();
+                let (parallel, _) = robinson_table(lat.abs());
+                (x / (0.8487 * parallel), lat.to_radians())
+            }
+            ProjectionType::NaturalEarth => {
+                // y grows with latitude, so bisect for it
+                let (mut low, mut high) = (-FRAC_PI_2, FRAC_PI_2);
+                if y < natural_earth_y(low) || y > natural_earth_y(high) {
+                    return None;
+                }
+                for _ in 0..60 {
+                    let middle = (low + high) / 2.0;
+                    if natural_earth_y(middle) < y { low = middle } else { high = middle }
+                }
+                let phi = (low + high) / 2.0;
+                let (phi2, phi4) = (phi * phi, phi.powi(4));
+                let scale = 0.8707 - 0.131979 * phi2 + phi4 * (-0.013791 + phi4 * phi2 * (0.003971 - 0.001529 * phi2));
+                (x / scale, phi)
+            }
+            ProjectionType::Orthographic => {
+                let rho = (x * x + y * y).sqrt();
+                if rho > 1.0 {
+                    return None;
+                }
+                let phi0 = self.center_lat.to_radians();
+                if rho < 1e-12 {
+                    (0.0, phi0)
+                } else {
+                    let c = rho.asin();
+                    (
+                        (x * c.sin()).atan2(rho * c.cos() * phi0.cos() - y * c.sin() * phi0.sin()),
+                        (c.cos() * phi0.sin() + y * c.sin() * phi0.cos() / rho).asin(),
+                    )
+                }
+            }
+            ProjectionType::AlbersEqualArea => match self.albers_constants() {
+                Some((n, c, rho0)) => {
+                    let sign = n.signum();
+                    let rho = sign * (x * x + (rho0 - y).powi(2)).sqrt();
+                    let theta = (sign * x).atan2(sign * (rho0 - y));
+                    let sin_phi = (c - (rho * n).powi(2)) / (2.0 * n);
+                    (theta / n, sin_phi.clamp(-1.0, 1.0).asin())
+                }
+                None => (x, y.clamp(-1.0, 1.0).asin()),
+            },
+        };
+        let lon = lambda.to_degrees() + self.center_lon;
+        let lat = phi.to_degrees();
+        (lambda.abs() <= PI + 1e-9 && lat.abs() <= 90.0).then(|| (wrap(lon), lat))
+    }
+
+    /// Projected pieces of a path of (lon, lat) points, broken where points
+    /// are hidden and where the path crosses the map's edge, the meridian
+    /// opposite the central one
+    pub fn project_path(&self, path: &[[f64; 2]]) -> Vec<Vec<[f64; 2]>> {
+        let mut pieces = Vec::new();
+        let mut piece: Vec<[f64; 2]> = Vec::new();
+        let mut last_lon = f64::NAN;
+        for &[lon, lat] in path {
+            let relative = self.relative_lon(lon);
+            let projected = self.project(lon, lat);
+            let seam = (relative - last_lon).abs() > 180.0;
+            if projected.is_none() || seam {
+                if piece.len() >= 2 {
+                    pieces.push(std::mem::take(&mut piece));
+                }
+                piece.clear();
+            }
+            if let Some(point) = projected {
+                piece.push(point);
+            }
+            last_lon = relative;
+        }
+        if piece.len() >= 2 {
+            pieces.push(piece);
+        }
+        pieces
+    }
+
+    /// Longitude east of the central meridian, in [-180, 180)
+    pub fn relative_lon(&self, lon: f64) -> f64 {
+        wrap(lon - self.center_lon)
+    }
+
+    /// Albers' cone constant, C and the radius at the origin; None when the
+    /// cone flattens into a cylinder
+    fn albers_constants(&self) -> Option<(f64, f64, f64)> {
+        let (phi1, phi2) = (self.parallels.0.to_radians(), self.parallels.1.to_radians());
+        let n = (phi1.sin() + phi2.sin()) / 2.0;
+        if n.abs() < 1e-6 {
+            return None;
+        }
+        let c = phi1.cos().powi(2) + 2.0 * n * phi1.sin();
+        let rho0 = (c - 2.0 * n * self.center_lat.to_radians().sin()).max(0.0).sqrt() / n;
+        Some((n, c, rho0))
+    }
+}
+
+/// Longitude in [-180, 180)
+fn wrap(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+fn natural_earth_y(phi: f64) -> f64 {
+    let phi2 = phi * phi;
+    phi * (1.007226 + phi2 * (0.015085 + phi2 * phi2 * (-0.044475 + 0.028874 * phi2 - 0.005916 * phi2 * phi2)))
+}
+
+/// Robinson's X and Y at `lat` degrees north, from 0 to 90
+fn robinson_table(lat: f64) -> (f64, f64) {
+    let position = (lat / 5.0).clamp(0.0, 18.0);
+    let index = (position as usize).min(17);
+    let t = position - index as f64;
+    (
+        ROBINSON_X[index] + (ROBINSON_X[index + 1] - ROBINSON_X[index]) * t,
+        ROBINSON_Y[index] + (ROBINSON_Y[index + 1] - ROBINSON_Y[index]) * t,
+    )
+}
+
+/// Latitude at which Robinson's Y is `y`, from 0 to 1
+fn robinson_lat(y: f64) -> Option<f64> {
+    if y > 1.0 + 1e-9 {
+        return None;
+    }
+    let index = ROBINSON_Y.partition_point(|&value| value < y).clamp(1, 18) - 1;
+    let t = (y - ROBINSON_Y[index]) / (ROBINSON_Y[index + 1] - ROBINSON_Y[index]);
+    Some((index as f64 + t.clamp(0.0, 1.0)) * 5.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projections_invert() {
+        let projections = [
+            Projection::world(ProjectionType::Mercator),
+            Projection::world(ProjectionType::Robinson),
+            Projection::world(ProjectionType::NaturalEarth),
+            Projection { center_lon: 10.0, center_lat: 45.0, ..Projection::world(ProjectionType::Orthographic) },
+            Projection::albers(-125.0, 24.0, -66.0, 50.0),
+        ];
+        for projection in &projections {
+            for (lon, lat) in [(0.0_f64, 0.0_f64), (12.5, 41.9), (-3.7, 40.4), (30.0, 60.0)] {
+                let [x, y] = projection.project(lon, lat).unwrap();
+                let (back_lon, back_lat) = projection.inverse(x, y).unwrap();
+                assert!((back_lon - lon).abs() < 1e-6 && (back_lat - lat).abs() < 1e-6,
+                    "{:?} maps ({lon}, {lat}) back to ({back_lon}, {back_lat})", projection.kind);
+            }
+        }
+    }
+
+    #[test]
+    fn globes_hide_their_far_side() {
+        let globe = Projection { center_lon: 0.0, center_lat: 0.0, ..Projection::world(ProjectionType::Orthographic) };
+        assert!(globe.project(90.0, 0.0).is_some());
+        assert!(globe.project(180.0, 0.0).is_none());
+    }
+}
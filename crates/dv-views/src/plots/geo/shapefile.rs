@@ -0,0 +1,277 @@
+//! Reader for ESRI shapefiles
+//!
+//! Reads the geometry of a `.shp` file and the attribute table of its `.dbf`
+//! sidecar. Z and M values are skipped; multipatches are not supported.
+
+use std::collections::HashMap;
+
+/// File code at the start of every `.shp` file
+const FILE_CODE: i32 = 9994;
+
+/// Length of the `.shp` file header
+const HEADER_LEN: usize = 100;
+
+/// Geometry of one record, in (x, y) coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Null,
+    Points(Vec<[f64; 2]>),
+    Lines(Vec<Vec<[f64; 2]>>),
+    /// Polygons as rings, the outer ring first and its holes after it
+    Polygons(Vec<Vec<Vec<[f64; 2]>>>),
+}
+
+/// Shapes of the records of a `.shp` file, in order
+pub fn read_shapes(bytes: &[u8]) -> Result<Vec<Shape>, String> {
+    if bytes.len() < HEADER_LEN || be_i32(bytes, 0) != FILE_CODE {
+        return Err("not a shapefile".to_string());
+    }
+
+    let mut shapes = Vec::new();
+    let mut offset = HEADER_LEN;
+    while offset + 8 <= bytes.len() {
+        // Content length is in 16-bit words
+        let length = be_i32(bytes, offset + 4).max(0) as usize * 2;
+        let content = bytes.get(offset + 8..offset + 8 + length)
+            .ok_or_else(|| format!("record {} is truncated", shapes.len() + 1))?;
+        shapes.push(read_shape(content).map_err(|error| format!("record {}: {error}", shapes.len() + 1))?);
+        offset += 8 + length;
+    }
+    Ok(shapes)
+}
+
+/// Attributes of the records of a `.dbf` file, by field name. Deleted
+/// records keep their place with no attributes.
+pub fn read_attributes(bytes: &[u8]) -> Result<Vec<HashMap<String, String>>, String> {
+    if bytes.len() < 32 {
+        return Err("attribute table is truncated".to_string());
+    }
+    let records = le_u32(bytes, 4) as usize;
+    let header_len = le_u16(bytes, 8) as usize;
+    let record_len = le_u16(bytes, 10) as usize;
+
+    // Field descriptors of 32 bytes each, ended by 0x0D
+    let mut fields = Vec::new();
+    let mut offset = 32;
+    while offset + 32 <= bytes.len().min(header_len) && bytes[offset] != 0x0D {
+        let descriptor = &bytes[offset..offset + 32];
+        let name_len = descriptor[..11].iter().position(|&byte| byte == 0).unwrap_or(11);
+        let name = String::from_utf8_lossy(&descriptor[..name_len]).trim().to_string();
+        fields.push((name, descriptor[16] as usize));
+        offset += 32;
+    }
+
+    // The header's record count isn't trusted until the records fit; each
+    // holds at least its deletion flag
+    let needed = records.checked_mul(record_len.max(1)).and_then(|len| len.checked_add(header_len));
+    if needed.is_none_or(|needed| needed > bytes.len()) {
+        return Err(format!("attribute table is truncated: its header lists {records} records"));
+    }
+
+    let mut attributes = Vec::with_capacity(records);
+    for record in 0..records {
+        let start = header_len + record * record_len;
+        let row = &bytes[start..start + record_len];
+        let mut values = HashMap::new();
+        // Deletion flag, then fixed-width values
+        if row.first() != Some(&b'*') {
+            let mut position = 1;
+            for (name, width) in &fields {
+                let value = row.get(position..position + width).unwrap_or_default();
+                values.insert(name.clone(), String::from_utf8_lossy(value).trim().to_string());
+                position += width;
+            }
+        }
+        attributes.push(values);
+    }
+    Ok(attributes)
+}
+
+fn read_shape(content: &[u8]) -> Result<Shape, String> {
+    if content.len() < 4 {
+        return Err("empty record".to_string());
+    }
+    let points_at = |offset: usize, count: usize| -> Result<Vec<[f64; 2]>, String> {
+        (0..count)
+            .map(|k| {
+                let at = offset + k * 16;
+                if at + 16 > content.len() {
+                    return Err("points are truncated".to_string());
+                }
+                Ok([le_f64(content, at), le_f64(content, at + 8)])
+            })
+            .collect()
+    };
+
+    match le_i32(content, 0) {
+        0 => Ok(Shape::Null),
+        // Point, PointZ, PointM
+        1 | 11 | 21 => Ok(Shape::Points(points_at(4, 1)?)),
+        // MultiPoint: bounding box, count, points
+        8 | 18 | 28 => {
+            let count = read_count(content, 36)?;
+            Ok(Shape::Points(points_at(40, count)?))
+        }
+        // PolyLine and Polygon: bounding box, part and point counts, the
+        // index of each part's first point, points
+        kind @ (3 | 13 | 23 | 5 | 15 | 25) => {
+            let parts = read_count(content, 36)?;
+            let count = read_count(content, 40)?;
+            let starts = (0..parts)
+                .map(|k| read_count(content, 44 + k * 4))
+                .collect::<Result<Vec<_>, _>>()?;
+            let points = points_at(44 + parts * 4, count)?;
+            let rings: Vec<Vec<[f64; 2]>> = (0..parts)
+                .map(|k| {
+                    let end = starts.get(k + 1).copied().unwrap_or(count);
+                    points.get(starts[k]..end.max(starts[k])).unwrap_or_default().to_vec()
+                })
+                .filter(|ring| ring.len() >= 2)
+                .collect();
+            Ok(if kind % 10 == 3 { Shape::Lines(rings) } else { Shape::Polygons(group_rings(rings)) })
+        }
+        kind => Err(format!("unsupported shape type {kind}")),
+    }
+}
+
+/// Polygons from rings, where clockwise rings are outer rings and
+/// counterclockwise rings are holes of the outer ring holding them
+fn group_rings(rings: Vec<Vec<[f64; 2]>>) -> Vec<Vec<Vec<[f64; 2]>>> {
+    let (outer, holes): (Vec<_>, Vec<_>) = rings.into_iter().partition(|ring| signed_area(ring) <= 0.0);
+    if outer.is_empty() {
+        // Wound the wrong way round: take every ring as a polygon
+        return holes.into_iter().map(|ring| vec![ring]).collect();
+    }
+    let mut polygons: Vec<Vec<Vec<[f64; 2]>>> = outer.into_iter().map(|ring| vec![ring]).collect();
+    for hole in holes {
+        let holder = polygons.iter().position(|polygon| contains(&polygon[0], hole[0]));
+        match holder {
+            Some(index) => polygons[index].push(hole),
+            None => polygons.push(vec![hole]),
+        }
+    }
+    polygons
+}
+
+/// Twice the area of a ring, positive when counterclockwise
+fn signed_area(ring: &[[f64; 2]]) -> f64 {
+    (0..ring.len())
+        .map(|k| {
+            let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum()
+}
+
+/// Whether `point` is inside `ring`, by the crossing count
+fn contains(ring: &[[f64; 2]], point: [f64; 2]) -> bool {
+    let mut inside = false;
+    for k in 0..ring.len() {
+        let (a, b) = (ring[k], ring[(k + 1) % ring.len()]);
+        if (a[1] > point[1]) != (b[1] > point[1])
+            && point[0] < a[0] + (point[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0])
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn read_count(bytes: &[u8], offset: usize) -> Result<usize, String> {
+    if offset + 4 > bytes.len() {
+        return Err("record is truncated".to_string());
+    }
+    usize::try_from(le_i32(bytes, offset)).map_err(|_| "negative count".to_string())
+}
+
+fn be_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn le_i32(bytes: &[u8], offset: usize) -> i32 {
+    i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn le_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn le_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn le_f64(bytes: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A polygon record: a clockwise square with a counterclockwise hole
+    fn polygon_file() -> Vec<u8> {
+        let outer: [[f64; 2]; 5] = [[0.0, 0.0], [0.0, 4.0], [4.0, 4.0], [4.0, 0.0], [0.0, 0.0]];
+        let hole: [[f64; 2]; 5] = [[1.0, 1.0], [2.0, 1.0], [2.0, 2.0], [1.0, 2.0], [1.0, 1.0]];
+        let mut content = Vec::new();
+        content.extend(5i32.to_le_bytes());
+        content.extend([0u8; 32]);
+        content.extend(2i32.to_le_bytes());
+        content.extend(10i32.to_le_bytes());
+        content.extend(0i32.to_le_bytes());
+        content.extend(5i32.to_le_bytes());
+        for point in outer.iter().chain(&hole) {
+            content.extend(point[0].to_le_bytes());
+            content.extend(point[1].to_le_bytes());
+        }
+
+        let mut bytes = vec![0u8; HEADER_LEN];
+        bytes[..4].copy_from_slice(&FILE_CODE.to_be_bytes());
+        bytes.extend(1i32.to_be_bytes());
+        bytes.extend((content.len() as i32 / 2).to_be_bytes());
+        bytes.extend(content);
+        bytes
+    }
+
+    #[test]
+    fn holes_join_their_outer_ring() {
+        let shapes = read_shapes(&polygon_file()).unwrap();
+        let [Shape::Polygons(polygons)] = shapes.as_slice() else {
+            panic!("expected one polygon record, got {shapes:?}");
+        };
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].len(), 2);
+        assert_eq!(polygons[0][1][0], [1.0, 1.0]);
+    }
+
+    #[test]
+    fn attributes_are_trimmed_and_deletions_kept() {
+        let mut bytes = vec![0u8; 32];
+        bytes[4..8].copy_from_slice(&2u32.to_le_bytes());
+        bytes[8..10].copy_from_slice(&65u16.to_le_bytes());
+        bytes[10..12].copy_from_slice(&9u16.to_le_bytes());
+        let mut field = [0u8; 32];
+        field[..4].copy_from_slice(b"NAME");
+        field[11] = b'C';
+        field[16] = 8;
+        bytes.extend(field);
+        bytes.push(0x0D);
+        bytes.extend(b" Ohio    ");
+        bytes.extend(b"*Gone    ");
+
+        let attributes = read_attributes(&bytes).unwrap();
+        assert_eq!(attributes[0]["NAME"], "Ohio");
+        assert!(attributes[1].is_empty());
+    }
+
+    #[test]
+    fn truncated_attribute_tables_are_rejected() {
+        let mut bytes = vec![0u8; 33];
+        bytes[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        bytes[8..10].copy_from_slice(&33u16.to_le_bytes());
+        bytes[10..12].copy_from_slice(&9u16.to_le_bytes());
+        bytes[32] = 0x0D;
+
+        let error = read_attributes(&bytes).unwrap_err();
+        assert!(error.contains("truncated"), "{error}");
+    }
+}
@@ -2,12 +2,14 @@
 
 use egui::{Ui, Color32};
 use egui_plot::{Plot, Bar, BarChart, Line, PlotPoints, Legend};
-use arrow::array::{Float64Array, Int64Array, Array};
+use arrow::array::{ArrayRef, Float64Array, Int64Array, UInt64Array, Array};
+use arrow::record_batch::RecordBatch;
 use serde_json::{json, Value};
 use statrs::statistics::Statistics;
+use std::sync::Arc;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Configuration for histogram view
 #[derive(Clone)]
@@ -78,6 +80,7 @@ pub struct HistogramView {
     // State
     cached_data: Option<HistogramData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 /// Cached histogram data
@@ -111,6 +114,7 @@ impl HistogramView {
             config: HistogramConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -129,11 +133,8 @@ impl HistogramView {
             data_sources.values().next()
         }?;
         
-        // Query data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        let batch = ctx.runtime_handle.block_on(
-            data_source.query_at(&nav_pos)
-        ).ok()?;
+        // Query data (the selection range, if one is set)
+        let batch = ctx.query_view_data(data_source.as_ref()).ok()?;
         
         // Get the column
         let column = batch.column_by_name(&self.config.column)?;
@@ -280,11 +281,16 @@ impl SpaceView for HistogramView {
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
         
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_histogram_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the histogram
@@ -378,6 +384,16 @@ impl SpaceView for HistogramView {
         }
     }
     
+    fn result_table(&self) -> Option<RecordBatch> {
+        let bins = &self.cached_data.as_ref()?.bins;
+        RecordBatch::try_from_iter(vec![
+            ("bin_start", Arc::new(Float64Array::from_iter_values(bins.iter().map(|b| b.start))) as ArrayRef),
+            ("bin_end", Arc::new(Float64Array::from_iter_values(bins.iter().map(|b| b.end))) as ArrayRef),
+            ("count", Arc::new(UInt64Array::from_iter_values(bins.iter().map(|b| b.count as u64))) as ArrayRef),
+            ("density", Arc::new(Float64Array::from_iter_values(bins.iter().map(|b| b.density))) as ArrayRef),
+        ]).ok()
+    }
+    
     fn save_config(&self) -> Value {
         json!({
             "column": self.config.column,
@@ -465,4 +481,30 @@ impl SpaceView for HistogramView {
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::AsArray;
+    use arrow::datatypes::{Float64Type, UInt64Type};
+
+    #[test]
+    fn result_table_lists_the_bins() {
+        let mut view = HistogramView::new(uuid::Uuid::new_v4(), "Histogram".to_string());
+        assert!(view.result_table().is_none());
+
+        view.cached_data = Some(HistogramData {
+            bins: vec![
+                Bin { start: 0.0, end: 1.0, count: 3, density: 0.75 },
+                Bin { start: 1.0, end: 2.0, count: 1, density: 0.25 },
+            ],
+            statistics: DataStatistics { mean: 0.9, std_dev: 0.5, min: 0.1, max: 1.5, count: 4 },
+            density_curve: None,
+        });
+        let table = view.result_table().unwrap();
+        let names: Vec<String> = table.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, ["bin_start", "bin_end", "count", "density"]);
+        assert_eq!(table.column(1).as_primitive::<Float64Type>().values(), &[1.0, 2.0]);
+        assert_eq!(table.column(2).as_primitive::<UInt64Type>().values(), &[3, 1]);
+    }
+}
@@ -7,7 +7,7 @@ use serde_json::{json, Value};
 use std::collections::{HashMap, BTreeMap};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Configuration for line plot view
 #[derive(Clone)]
@@ -82,6 +82,7 @@ pub struct LinePlotView {
     // State
     cached_data: Option<LineData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 /// Cached line plot data
@@ -106,6 +107,7 @@ impl LinePlotView {
             config: LinePlotConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -126,16 +128,12 @@ impl LinePlotView {
         // Get navigation context
         let nav_context = ctx.navigation.get_context();
         
-        // Fetch a range of data (all data for now)
-        let total_rows = nav_context.total_rows;
-        let range_size = total_rows.min(10000); // Limit to 10k points for performance
-        let start_row = 0;
+        // Fetch the selection range (or all data if nothing is selected)
+        let (start_row, end_row) = nav_context.view_range().row_bounds();
+        let range_size = (end_row - start_row).min(10000); // Limit to 10k points for performance
         
         // Create a navigation range to fetch data
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(start_row),
-            end: dv_core::navigation::NavigationPosition::Sequential(start_row + range_size),
-        };
+        let range = NavigationRange::rows(start_row, start_row + range_size);
         
         // Fetch data using query_range
         let batch = ctx.runtime_handle.block_on(
@@ -148,11 +146,11 @@ impl LinePlotView {
                 Self::extract_numeric_values(x_array)
             } else {
                 // Use row indices if column not found
-                (0..batch.num_rows()).map(|i| i as f64).collect()
+                (start_row..start_row + batch.num_rows()).map(|i| i as f64).collect()
             }
         } else {
             // Use row indices if no x column specified
-            (0..batch.num_rows()).map(|i| i as f64).collect()
+            (start_row..start_row + batch.num_rows()).map(|i| i as f64).collect()
         };
         
         // Extract categories if specified
@@ -216,7 +214,7 @@ impl LinePlotView {
             
             // Return x_values as the full range for consistent plotting
             Some(LineData { 
-                x_values: (0..range_size).map(|i| i as f64).collect(), 
+                x_values: (start_row..start_row + range_size).map(|i| i as f64).collect(), 
                 y_series,
                 category_map,
             })
@@ -318,11 +316,16 @@ impl SpaceView for LinePlotView {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the plot
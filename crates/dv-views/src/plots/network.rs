@@ -14,6 +14,7 @@ use rand;
 use rand::Rng;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+use dv_core::navigation::NavigationRange;
 use super::utils::colors::{categorical_color, viridis_color, ColorScheme};
 
 /// Network graph configuration
@@ -111,6 +112,7 @@ pub struct NetworkGraph {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     nodes: HashMap<String, NetworkNode>,
     edges: Vec<NetworkEdge>,
     graph: Graph<String, f64, Undirected>,
@@ -134,6 +136,7 @@ impl NetworkGraph {
             title,
             config: NetworkConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             nodes: HashMap::new(),
             edges: Vec::new(),
             graph: Graph::new_undirected(),
@@ -648,14 +651,15 @@ impl SpaceView for NetworkGraph {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
 
             let data_source = data_sources.values().next();
             if let Some(source) = data_source.as_ref() {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch.clone());
                     self.extract_network_data(&batch);
                 }
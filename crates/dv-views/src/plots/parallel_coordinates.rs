@@ -8,6 +8,7 @@ use std::collections::{HashMap, HashSet};
 use ndarray::{Array1, Array2};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+use dv_core::navigation::NavigationRange;
 use super::utils::{ColorScheme, categorical_color, viridis_color, plasma_color};
 
 /// Parallel coordinates configuration
@@ -152,6 +153,7 @@ pub struct ParallelCoordinatesPlot {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     axes: Vec<Axis>,
     lines: Vec<DataLine>,
     
@@ -170,6 +172,7 @@ impl ParallelCoordinatesPlot {
             title,
             config: ParallelCoordinatesConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             axes: Vec::new(),
             lines: Vec::new(),
             hovered_line: None,
@@ -771,8 +774,10 @@ impl SpaceView for ParallelCoordinatesPlot {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
 
             let data_source = if let Some(source_id) = &self.config.data_source_id {
@@ -781,8 +786,7 @@ impl SpaceView for ParallelCoordinatesPlot {
         data_sources.values().next()
     };
             if let Some(source) = data_source.as_ref() {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch.clone());
                     self.extract_data(&batch);
                 }
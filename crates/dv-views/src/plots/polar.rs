@@ -10,6 +10,7 @@ use arrow::record_batch::RecordBatch;
 
 /// Configuration for polar plot
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct PolarPlotConfig {
     pub data_source_id: Option<String>,
     pub angle_column: String,
@@ -76,8 +77,7 @@ impl PolarPlot {
             }
             
             // Get current data
-            let nav_pos = viewer_context.navigation.get_context().position.clone();
-            match viewer_context.runtime_handle.block_on(data_source.query_at(&nav_pos)) {
+            match viewer_context.query_view_data(data_source.as_ref()) {
                 Ok(batch) => {
                     self.render_polar_plot(ui, &batch, &schema);
                 }
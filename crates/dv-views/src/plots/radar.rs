@@ -8,6 +8,7 @@ use serde_json::{json, Value};
 use std::collections::HashMap;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+use dv_core::navigation::NavigationRange;
 use super::utils::{ColorScheme, categorical_color};
 
 /// Radar chart configuration
@@ -123,6 +124,7 @@ pub struct RadarChart {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     series: Vec<RadarSeries>,
     axis_labels: Vec<String>,
     axis_min_max: Vec<(f64, f64)>,
@@ -135,6 +137,7 @@ impl RadarChart {
             title,
             config: RadarConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             series: Vec::new(),
             axis_labels: Vec::new(),
             axis_min_max: Vec::new(),
@@ -489,8 +492,10 @@ impl SpaceView for RadarChart {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
             
             // Get the specific data source for this view
@@ -505,8 +510,7 @@ impl SpaceView for RadarChart {
             };
             
             if let Some(source) = data_source {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch.clone());
                     self.extract_data(&batch);
                 }
@@ -576,6 +580,9 @@ impl SpaceView for RadarChart {
                 .map(|s| s.to_string())
                 .collect();
         }
+        if let Some(column) = config.get("group_column") {
+            self.config.group_column = column.as_str().map(|s| s.to_string());
+        }
     }
     
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
@@ -7,6 +7,7 @@ use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+use dv_core::navigation::NavigationRange;
 use super::utils::{ColorScheme, categorical_color};
 
 /// Sankey diagram configuration
@@ -92,6 +93,7 @@ pub struct SankeyDiagram {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     nodes: HashMap<String, SankeyNode>,
     links: Vec<SankeyLink>,
     layers: Vec<Vec<String>>,
@@ -108,6 +110,7 @@ impl SankeyDiagram {
             title,
             config: SankeyConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             nodes: HashMap::new(),
             links: Vec::new(),
             layers: Vec::new(),
@@ -561,14 +564,15 @@ impl SpaceView for SankeyDiagram {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
 
             let data_source = data_sources.values().next();
             if let Some(source) = data_source.as_ref() {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch.clone());
                     self.extract_flow_data(&batch);
                 }
@@ -7,7 +7,7 @@ use serde_json::{json, Value};
 use std::collections::BTreeMap;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Configuration for scatter plot view
 #[derive(Clone)]
@@ -79,6 +79,7 @@ pub struct ScatterPlotView {
     // State
     cached_data: Option<ScatterData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 /// Cached scatter plot data
@@ -99,6 +100,7 @@ impl ScatterPlotView {
             config: ScatterPlotConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -126,16 +128,12 @@ impl ScatterPlotView {
         // Get navigation context
         let nav_context = ctx.navigation.get_context();
         
-        // Fetch a range of data (all data for now)
-        let total_rows = nav_context.total_rows;
-        let range_size = total_rows.min(10000); // Limit to 10k points for performance
-        let start_row = 0;
+        // Fetch the selection range (or all data if nothing is selected)
+        let (start_row, end_row) = nav_context.view_range().row_bounds();
+        let range_size = (end_row - start_row).min(10000); // Limit to 10k points for performance
         
         // Create a navigation range to fetch data
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(start_row),
-            end: dv_core::navigation::NavigationPosition::Sequential(start_row + range_size),
-        };
+        let range = NavigationRange::rows(start_row, start_row + range_size);
         
         // Fetch data using query_range
         let batch = match ctx.runtime_handle.block_on(
@@ -345,11 +343,16 @@ impl SpaceView for ScatterPlotView {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_plot_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the plot
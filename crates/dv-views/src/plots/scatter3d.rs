@@ -4,11 +4,11 @@ use egui::{Ui, Color32, Rect, Pos2, Vec2, Stroke, FontId, Align2, Response, Sens
 use arrow::record_batch::RecordBatch;
 use arrow::array::Float64Array;
 use serde_json::{json, Value};
-use glam::{Vec3, Mat4, Quat};
-use std::f32::consts::PI;
+use glam::Vec3;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use super::utils::{ColorScheme, viridis_color, plasma_color, categorical_color};
+use dv_core::navigation::NavigationRange;
+use super::utils::{Camera3D, ColorScheme, viridis_color, plasma_color, categorical_color};
 
 /// 3D scatter plot configuration
 #[derive(Debug, Clone)]
@@ -58,50 +58,6 @@ impl Default for Scatter3DConfig {
     }
 }
 
-/// Camera state for 3D view
-#[derive(Debug, Clone)]
-struct Camera3D {
-    position: Vec3,
-    target: Vec3,
-    up: Vec3,
-    fov: f32,
-    yaw: f32,
-    pitch: f32,
-    distance: f32,
-}
-
-impl Default for Camera3D {
-    fn default() -> Self {
-        Self {
-            position: Vec3::new(5.0, 5.0, 5.0),
-            target: Vec3::ZERO,
-            up: Vec3::Y,
-            fov: 45.0_f32.to_radians(),
-            yaw: -PI / 4.0,
-            pitch: PI / 6.0,
-            distance: 10.0,
-        }
-    }
-}
-
-impl Camera3D {
-    fn update_from_angles(&mut self) {
-        self.position = Vec3::new(
-            self.distance * self.yaw.cos() * self.pitch.cos(),
-            self.distance * self.pitch.sin(),
-            self.distance * self.yaw.sin() * self.pitch.cos(),
-        );
-    }
-    
-    fn view_matrix(&self) -> Mat4 {
-        Mat4::look_at_rh(self.position, self.target, self.up)
-    }
-    
-    fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
-        Mat4::perspective_rh(self.fov, aspect_ratio, 0.1, 100.0)
-    }
-}
-
 /// 3D point for rendering
 #[derive(Clone)]
 struct Point3D {
@@ -120,6 +76,7 @@ pub struct Scatter3DPlot {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     camera: Camera3D,
     
     // Interaction state
@@ -135,6 +92,7 @@ impl Scatter3DPlot {
             title,
             config: Scatter3DConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             camera: Camera3D::default(),
             is_rotating: false,
             last_mouse_pos: None,
@@ -236,27 +194,7 @@ impl Scatter3DPlot {
     }
     
     fn project_point(&self, point: Vec3, rect: &Rect) -> Option<(Pos2, f32)> {
-        let aspect_ratio = rect.width() / rect.height();
-        let view_matrix = self.camera.view_matrix();
-        let proj_matrix = self.camera.projection_matrix(aspect_ratio);
-        let mvp = proj_matrix * view_matrix;
-        
-        // Transform point
-        let transformed = mvp * point.extend(1.0);
-        
-        // Perspective divide
-        if transformed.w <= 0.0 {
-            return None; // Behind camera
-        }
-        
-        let ndc = transformed.truncate() / transformed.w;
-        
-        // Convert to screen coordinates
-        let x = (ndc.x + 1.0) * 0.5 * rect.width() + rect.left();
-        let y = (1.0 - ndc.y) * 0.5 * rect.height() + rect.top();
-        let depth = ndc.z;
-        
-        Some((Pos2::new(x, y), depth))
+        self.camera.project(point, rect)
     }
     
     fn draw_axes(&self, ui: &mut Ui, rect: Rect) {
@@ -369,11 +307,7 @@ impl Scatter3DPlot {
             if response.dragged_by(egui::PointerButton::Primary) {
                 if let Some(pos) = response.interact_pointer_pos() {
                     if let Some(last_pos) = self.last_mouse_pos {
-                        let delta = pos - last_pos;
-                        self.camera.yaw -= delta.x * 0.01;
-                        self.camera.pitch = (self.camera.pitch - delta.y * 0.01)
-                            .clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1);
-                        self.camera.update_from_angles();
+                        self.camera.orbit(pos - last_pos);
                     }
                     self.last_mouse_pos = Some(pos);
                 }
@@ -384,9 +318,7 @@ impl Scatter3DPlot {
             // Scroll for zoom
             let scroll_delta = ui.input(|i| i.scroll_delta.y);
             if scroll_delta != 0.0 {
-                self.camera.distance = (self.camera.distance * (1.0 - scroll_delta * 0.001))
-                    .clamp(2.0, 50.0);
-                self.camera.update_from_angles();
+                self.camera.zoom(scroll_delta);
             }
             
             // Click to select point
@@ -481,8 +413,10 @@ impl SpaceView for Scatter3DPlot {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
             
             // Get the specific data source for this view
@@ -497,8 +431,7 @@ impl SpaceView for Scatter3DPlot {
             };
             
             if let Some(source) = data_source {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch);
                 }
             }
@@ -604,10 +537,10 @@ impl SpaceView for Scatter3DPlot {
             self.camera.yaw = yaw as f32;
         }
         if let Some(pitch) = config.get("camera_pitch").and_then(|v| v.as_f64()) {
-            self.camera.pitch = pitch as f32;
+            self.camera.set_pitch(pitch as f32);
         }
         if let Some(distance) = config.get("camera_distance").and_then(|v| v.as_f64()) {
-            self.camera.distance = distance as f32;
+            self.camera.set_distance(distance as f32);
         }
         self.camera.update_from_angles();
     }
@@ -7,6 +7,7 @@ use serde_json::{json, Value};
 use std::collections::{HashMap, VecDeque};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
+use dv_core::navigation::NavigationRange;
 use super::utils::{ColorScheme, categorical_color, diverging_color};
 
 /// Sunburst configuration  
@@ -176,6 +177,7 @@ pub struct SunburstChart {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     root: Option<SunburstNode>,
     
     // Interaction state
@@ -192,6 +194,7 @@ impl SunburstChart {
             title,
             config: SunburstConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             root: None,
             hovered_node: None,
             selected_path: Vec::new(),
@@ -551,14 +554,15 @@ impl SpaceView for SunburstChart {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
 
             let data_source = data_sources.values().next();
             if let Some(source) = data_source.as_ref() {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch.clone());
                     self.build_hierarchy(&batch);
                 }
@@ -618,6 +622,9 @@ impl SpaceView for SunburstChart {
                 .map(|s| s.to_string())
                 .collect();
         }
+        if let Some(column) = config.get("value_column") {
+            self.config.value_column = column.as_str().map(|s| s.to_string());
+        }
     }
     
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
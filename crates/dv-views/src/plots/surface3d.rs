@@ -1,13 +1,52 @@
 //! 3D Surface plot implementation
-//! Since egui doesn't have native 3D support, we'll create a 2D projection
+//! Since egui doesn't have native 3D support, the surface is projected and
+//! its cells painted back to front
 
-use egui::{Ui, Color32, Rect, Pos2, Vec2, Painter, Stroke};
-use arrow::array::{Float64Array, Int64Array, Array};
-use arrow::record_batch::RecordBatch;
+use egui::{Ui, Color32, Rect, Pos2, Stroke, Shape, Mesh, Sense, Align2, FontId};
+use egui::epaint::PathShape;
+use glam::Vec3;
 use serde_json::{json, Value};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
+use super::utils::{scheme_color, Camera3D, Grid, GridMethod};
+use super::utils::grid::xyz_points;
+
+/// Half the width of the plot box along x and y, in scene units
+const BOX_HALF_WIDTH: f32 = 2.0;
+
+/// Half the height of the plot box along z, in scene units
+const BOX_HALF_HEIGHT: f32 = 1.2;
+
+/// Light reaching surfaces facing away from it
+const AMBIENT_LIGHT: f32 = 0.3;
+
+/// Axis the slice plane at the navigation position is perpendicular to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliceAxis {
+    None,
+    X,
+    Y,
+}
+
+impl SliceAxis {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SliceAxis::None => "none",
+            SliceAxis::X => "x",
+            SliceAxis::Y => "y",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(SliceAxis::None),
+            "x" => Some(SliceAxis::X),
+            "y" => Some(SliceAxis::Y),
+            _ => None,
+        }
+    }
+}
 
 /// Configuration for 3D surface plot
 #[derive(Debug, Clone)]
@@ -17,10 +56,16 @@ pub struct Surface3DConfig {
     pub y_column: String,
     pub z_column: String,
     pub color_scheme: String,
-    pub rotation_x: f32,  // X-axis rotation in degrees
-    pub rotation_z: f32,  // Z-axis rotation in degrees
+    /// Axis box with ticks behind the surface
     pub show_grid: bool,
+    /// Outline each cell of the surface
     pub show_wireframe: bool,
+    /// How scattered points are put on a grid
+    pub grid_method: GridMethod,
+    /// Grid nodes along each axis, unless the data lies on its own lattice
+    pub grid_resolution: usize,
+    /// Plane through the row at the navigation position
+    pub slice_axis: SliceAxis,
 }
 
 impl Default for Surface3DConfig {
@@ -31,169 +76,390 @@ impl Default for Surface3DConfig {
             y_column: String::new(),
             z_column: String::new(),
             color_scheme: "viridis".to_string(),
-            rotation_x: 30.0,
-            rotation_z: 45.0,
             show_grid: true,
             show_wireframe: true,
+            grid_method: GridMethod::Auto,
+            grid_resolution: 40,
+            slice_axis: SliceAxis::X,
         }
     }
 }
 
 /// Cached surface data
 struct SurfaceData {
-    grid: Vec<Vec<(f64, f64, f64)>>, // Grid of (x, y, z) points
+    grid: Grid,
     z_min: f64,
     z_max: f64,
 }
 
+impl SurfaceData {
+    /// Scene position of a data point: x and y span the box's width and
+    /// depth and z its height, pointing up
+    fn scene(&self, x: f64, y: f64, z: f64) -> Vec3 {
+        let unit = |value: f64, min: f64, max: f64| {
+            if max > min { ((value - min) / (max - min) * 2.0 - 1.0) as f32 } else { 0.0 }
+        };
+        let grid = &self.grid;
+        Vec3::new(
+            unit(x, grid.xs[0], grid.xs[grid.nx() - 1]) * BOX_HALF_WIDTH,
+            unit(z, self.z_min, self.z_max) * BOX_HALF_HEIGHT,
+            -unit(y, grid.ys[0], grid.ys[grid.ny() - 1]) * BOX_HALF_WIDTH,
+        )
+    }
+
+    /// Scene position of grid node (i, j)
+    fn node(&self, i: usize, j: usize) -> Vec3 {
+        self.scene(self.grid.xs[i], self.grid.ys[j], self.grid.z(i, j))
+    }
+
+    /// The surface where `axis` equals `value`, as data points along the
+    /// other axis; z is NaN across gaps
+    fn section(&self, axis: SliceAxis, value: f64) -> Vec<[f64; 3]> {
+        let grid = &self.grid;
+        // Position of `value` between two of `coordinates`
+        let locate = |coordinates: &[f64]| -> Option<(usize, f64)> {
+            let next = coordinates.partition_point(|&c| c < value).clamp(1, coordinates.len() - 1);
+            let (start, end) = (coordinates[next - 1], coordinates[next]);
+            (value >= coordinates[0] && value <= coordinates[coordinates.len() - 1])
+                .then(|| (next - 1, if end > start { (value - start) / (end - start) } else { 0.0 }))
+        };
+        match axis {
+            SliceAxis::X => {
+                let Some((i, t)) = locate(&grid.xs) else { return Vec::new() };
+                (0..grid.ny())
+                    .map(|j| [value, grid.ys[j], grid.z(i, j) * (1.0 - t) + grid.z(i + 1, j) * t])
+                    .collect()
+            }
+            SliceAxis::Y => {
+                let Some((j, t)) = locate(&grid.ys) else { return Vec::new() };
+                (0..grid.nx())
+                    .map(|i| [grid.xs[i], value, grid.z(i, j) * (1.0 - t) + grid.z(i, j + 1) * t])
+                    .collect()
+            }
+            SliceAxis::None => Vec::new(),
+        }
+    }
+}
+
 /// 3D Surface plot view
 pub struct Surface3DPlot {
     id: SpaceViewId,
     title: String,
     pub config: Surface3DConfig,
     cached_data: Option<SurfaceData>,
+    last_selection_range: Option<NavigationRange>,
+    camera: Camera3D,
+    /// The row at the navigation position, as (x, y, z)
+    current_point: Option<[f64; 3]>,
     last_navigation_pos: Option<NavigationPosition>,
 }
 
 impl Surface3DPlot {
     pub fn new(id: SpaceViewId, title: String) -> Self {
+        let mut camera = Camera3D::default();
+        camera.update_from_angles();
         Self {
             id,
             title,
             config: Surface3DConfig::default(),
             cached_data: None,
+            last_selection_range: None,
+            camera,
+            current_point: None,
             last_navigation_pos: None,
         }
     }
-    
+
     fn fetch_data(&mut self, ctx: &ViewerContext) -> Option<SurfaceData> {
-        if self.config.x_column.is_empty() || 
-           self.config.y_column.is_empty() || 
+        if self.config.x_column.is_empty() ||
+           self.config.y_column.is_empty() ||
            self.config.z_column.is_empty() {
             return None;
         }
-        
+
         let data_sources = ctx.data_sources.read();
         let data_source = if let Some(source_id) = &self.config.data_source_id {
             data_sources.get(source_id)
         } else {
             data_sources.values().next()
         }?;
-        
+
         // Fetch data
         let nav_context = ctx.navigation.get_context();
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
-        
+        let range = nav_context.view_range();
+
         let batch = ctx.runtime_handle.block_on(
             data_source.query_range(&range)
         ).ok()?;
-        
-        // Extract columns
-        let x_col = batch.column_by_name(&self.config.x_column)?;
-        let y_col = batch.column_by_name(&self.config.y_column)?;
-        let z_col = batch.column_by_name(&self.config.z_column)?;
-        
-        // Extract numeric values with null handling
-        let x_values: Vec<f64> = Self::extract_numeric_values(x_col);
-        let y_values: Vec<f64> = Self::extract_numeric_values(y_col);
-        let z_values: Vec<f64> = Self::extract_numeric_values(z_col);
-        
-        if x_values.is_empty() || y_values.is_empty() || z_values.is_empty() {
-            return None;
-        }
-        
-        // Create a simple grid from the data
-        // For simplicity, we'll create a scattered point representation
-        let mut points: Vec<(f64, f64, f64)> = Vec::new();
-        let min_len = x_values.len().min(y_values.len()).min(z_values.len());
-        
-        for i in 0..min_len {
-            points.push((x_values[i], y_values[i], z_values[i]));
-        }
-        
-        // Find unique x and y values to create a grid
-        let mut unique_x: Vec<f64> = x_values.clone();
-        unique_x.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        unique_x.dedup();
-        
-        let mut unique_y: Vec<f64> = y_values.clone();
-        unique_y.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        unique_y.dedup();
-        
-        // Create a grid (for now, just organize points)
-        let grid = vec![points]; // Simplified - treat as single row grid
-        
-        let z_min = z_values.iter().cloned().fold(f64::INFINITY, f64::min);
-        let z_max = z_values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-        
+
+        // Rows with all three values, gridded as for contour plots
+        let points = xyz_points(&batch, &self.config.x_column, &self.config.y_column, &self.config.z_column)?;
+        let grid = Grid::from_points(&points, self.config.grid_method, self.config.grid_resolution)?;
+        let (z_min, z_max) = grid.z_range()?;
+
         Some(SurfaceData {
             grid,
             z_min,
             z_max,
         })
     }
-    
-    fn extract_numeric_values(array: &dyn Array) -> Vec<f64> {
-        if let Some(float_array) = array.as_any().downcast_ref::<Float64Array>() {
-            (0..float_array.len()).filter_map(|i| {
-                if float_array.is_null(i) { None } else { Some(float_array.value(i)) }
-            }).collect()
-        } else if let Some(int_array) = array.as_any().downcast_ref::<Int64Array>() {
-            (0..int_array.len()).filter_map(|i| {
-                if int_array.is_null(i) { None } else { Some(int_array.value(i) as f64) }
-            }).collect()
-        } else if let Some(int_array) = array.as_any().downcast_ref::<arrow::array::Int32Array>() {
-            (0..int_array.len()).filter_map(|i| {
-                if int_array.is_null(i) { None } else { Some(int_array.value(i) as f64) }
-            }).collect()
-        } else if let Some(float_array) = array.as_any().downcast_ref::<arrow::array::Float32Array>() {
-            (0..float_array.len()).filter_map(|i| {
-                if float_array.is_null(i) { None } else { Some(float_array.value(i) as f64) }
-            }).collect()
+
+    /// (x, y, z) of the row at the navigation position
+    fn fetch_current_point(&self, ctx: &ViewerContext) -> Option<[f64; 3]> {
+        let data_sources = ctx.data_sources.read();
+        let data_source = if let Some(source_id) = &self.config.data_source_id {
+            data_sources.get(source_id)
         } else {
-            Vec::new()
+            data_sources.values().next()
+        }?;
+
+        let nav_context = ctx.navigation.get_context();
+        let row = nav_context.position.frame_nr();
+        if row >= nav_context.total_rows {
+            return None;
         }
+        let batch = ctx.runtime_handle.block_on(
+            data_source.query_range(&NavigationRange::rows(row, row + 1))
+        ).ok()?;
+        xyz_points(&batch, &self.config.x_column, &self.config.y_column, &self.config.z_column)?
+            .first()
+            .copied()
     }
-    
-    fn project_3d_to_2d(&self, x: f64, y: f64, z: f64, bounds: &Rect) -> Pos2 {
-        // Simple isometric projection
-        let rx = self.config.rotation_x.to_radians();
-        let rz = self.config.rotation_z.to_radians();
-        
-        // Apply rotations
-        let x1 = x * (rz.cos() as f64) - y * (rz.sin() as f64);
-        let y1 = x * (rz.sin() as f64) + y * (rz.cos() as f64);
-        let z1 = z;
-        
-        let y2 = y1 * (rx.cos() as f64) - z1 * (rx.sin() as f64);
-        let z2 = y1 * (rx.sin() as f64) + z1 * (rx.cos() as f64);
-        
-        // Project to 2D
-        let scale = bounds.width().min(bounds.height()) * 0.3;
-        let proj_x = bounds.center().x + x1 as f32 * scale;
-        let proj_y = bounds.center().y - z2 as f32 * scale + y2 as f32 * scale * 0.5;
-        
-        Pos2::new(proj_x, proj_y)
-    }
-    
+
     fn get_color_for_z(&self, z: f64, z_min: f64, z_max: f64) -> Color32 {
         let t = if z_max > z_min {
             ((z - z_min) / (z_max - z_min)).clamp(0.0, 1.0) as f32
         } else {
             0.5
         };
-        
-        // Viridis color scheme
-        let r = (255.0 * (0.267 + 0.003 * t + 1.785 * t * t - 3.876 * t * t * t + 2.291 * t * t * t * t).clamp(0.0, 1.0)) as u8;
-        let g = (255.0 * (0.005 + 1.398 * t - 0.725 * t * t).clamp(0.0, 1.0)) as u8;
-        let b = (255.0 * (0.329 + 0.876 * t - 0.170 * t * t - 0.363 * t * t * t).clamp(0.0, 1.0)) as u8;
-        Color32::from_rgb(r, g, b)
+        scheme_color(&self.config.color_scheme, t)
+    }
+
+    /// Cells of the surface, shaded by the angle of the light falling on
+    /// them and painted furthest first so nearer cells cover them
+    fn draw_surface(&self, painter: &egui::Painter, rect: Rect, data: &SurfaceData) {
+        let grid = &data.grid;
+        // A light over the viewer's shoulder
+        let light = ((self.camera.position - self.camera.target).normalize() + Vec3::Y * 0.5).normalize();
+
+        let mut cells: Vec<(f32, [Pos2; 4], Color32)> = Vec::new();
+        for j in 0..grid.ny().saturating_sub(1) {
+            for i in 0..grid.nx().saturating_sub(1) {
+                let heights = [grid.z(i, j), grid.z(i + 1, j), grid.z(i + 1, j + 1), grid.z(i, j + 1)];
+                if heights.iter().any(|z| z.is_nan()) {
+                    continue;
+                }
+                let corners = [data.node(i, j), data.node(i + 1, j), data.node(i + 1, j + 1), data.node(i, j + 1)];
+                let projected = corners.map(|corner| self.camera.project(corner, &rect));
+                let [Some(a), Some(b), Some(c), Some(d)] = projected else {
+                    continue;
+                };
+
+                // Lit from either side, as both sides of a surface show
+                let normal = (corners[2] - corners[0]).cross(corners[3] - corners[1]).normalize_or_zero();
+                let intensity = AMBIENT_LIGHT + (1.0 - AMBIENT_LIGHT) * normal.dot(light).abs();
+                let color = self.get_color_for_z(heights.iter().sum::<f64>() / 4.0, data.z_min, data.z_max);
+                let shaded = Color32::from_rgb(
+                    (color.r() as f32 * intensity) as u8,
+                    (color.g() as f32 * intensity) as u8,
+                    (color.b() as f32 * intensity) as u8,
+                );
+
+                let center = corners.iter().copied().sum::<Vec3>() / 4.0;
+                let distance = center.distance(self.camera.position);
+                cells.push((distance, [a.0, b.0, c.0, d.0], shaded));
+            }
+        }
+        cells.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        if self.config.show_wireframe {
+            // Outlines must be covered along with their cells
+            let outline = Stroke::new(0.5, Color32::from_black_alpha(90));
+            for (_, points, color) in cells {
+                painter.add(Shape::convex_polygon(points.to_vec(), color, outline));
+            }
+        } else {
+            let mut mesh = Mesh::default();
+            for (_, points, color) in cells {
+                let first = mesh.vertices.len() as u32;
+                for point in points {
+                    mesh.colored_vertex(point, color);
+                }
+                mesh.add_triangle(first, first + 1, first + 2);
+                mesh.add_triangle(first, first + 2, first + 3);
+            }
+            painter.add(Shape::mesh(mesh));
+        }
+    }
+
+    /// The three faces of the box furthest from the camera, with grid lines
+    /// at the ticks of their axes
+    fn draw_box(&self, painter: &egui::Painter, rect: Rect, data: &SurfaceData) {
+        let grid = &data.grid;
+        let far = self.far_faces();
+        let pane_stroke = Stroke::new(1.0, Color32::from_gray(110));
+        let grid_stroke = Stroke::new(0.5, Color32::from_gray(80));
+        let segment = |start: Vec3, end: Vec3, stroke: Stroke| {
+            if let (Some((start, _)), Some((end, _))) = (self.camera.project(start, &rect), self.camera.project(end, &rect)) {
+                painter.line_segment([start, end], stroke);
+            }
+        };
+
+        let x_ticks = nice_ticks(grid.xs[0], grid.xs[grid.nx() - 1], 5);
+        let y_ticks = nice_ticks(grid.ys[0], grid.ys[grid.ny() - 1], 5);
+        let z_ticks = nice_ticks(data.z_min, data.z_max, 4);
+        // Scene coordinate of each tick along its axis
+        let x_at: Vec<f32> = x_ticks.iter().map(|&x| data.scene(x, grid.ys[0], data.z_min).x).collect();
+        let y_at: Vec<f32> = y_ticks.iter().map(|&y| data.scene(grid.xs[0], y, data.z_min).z).collect();
+        let z_at: Vec<f32> = z_ticks.iter().map(|&z| data.scene(grid.xs[0], grid.ys[0], z).y).collect();
+        let (w, h) = (BOX_HALF_WIDTH, BOX_HALF_HEIGHT);
+
+        // Floor or ceiling, with lines across x and y
+        for &x in &x_at {
+            segment(Vec3::new(x, far.y, -w), Vec3::new(x, far.y, w), grid_stroke);
+        }
+        for &z in &y_at {
+            segment(Vec3::new(-w, far.y, z), Vec3::new(w, far.y, z), grid_stroke);
+        }
+        // Side faces, with lines along the height
+        for &y in &z_at {
+            segment(Vec3::new(far.x, y, -w), Vec3::new(far.x, y, w), grid_stroke);
+            segment(Vec3::new(-w, y, far.z), Vec3::new(w, y, far.z), grid_stroke);
+        }
+        for &x in &x_at {
+            segment(Vec3::new(x, -h, far.z), Vec3::new(x, h, far.z), grid_stroke);
+        }
+        for &z in &y_at {
+            segment(Vec3::new(far.x, -h, z), Vec3::new(far.x, h, z), grid_stroke);
+        }
+
+        // Outlines of the floor or ceiling, the side and the back
+        for face in [
+            [Vec3::new(-w, far.y, -w), Vec3::new(w, far.y, -w), Vec3::new(w, far.y, w), Vec3::new(-w, far.y, w)],
+            [Vec3::new(far.x, -h, -w), Vec3::new(far.x, h, -w), Vec3::new(far.x, h, w), Vec3::new(far.x, -h, w)],
+            [Vec3::new(-w, -h, far.z), Vec3::new(w, -h, far.z), Vec3::new(w, h, far.z), Vec3::new(-w, h, far.z)],
+        ] {
+            for k in 0..4 {
+                segment(face[k], face[(k + 1) % 4], pane_stroke);
+            }
+        }
+    }
+
+    /// Tick labels and axis titles along the box edges nearest the viewer
+    fn draw_tick_labels(&self, painter: &egui::Painter, rect: Rect, data: &SurfaceData) {
+        let grid = &data.grid;
+        let far = self.far_faces();
+        let color = painter.ctx().style().visuals.text_color();
+        let label = |position: Vec3, text: String, size: f32| {
+            if let Some((pos, _)) = self.camera.project(position, &rect) {
+                painter.text(pos, Align2::CENTER_CENTER, text, FontId::proportional(size), color);
+            }
+        };
+        let (x_range, y_range) = ((grid.xs[0], grid.xs[grid.nx() - 1]), (grid.ys[0], grid.ys[grid.ny() - 1]));
+        // Labels stand off the box, on the edges of the floor facing the viewer
+        let (near_x, near_z) = (-far.x * 1.15, -far.z * 1.15);
+
+        let x_ticks = nice_ticks(x_range.0, x_range.1, 5);
+        let x_step = tick_step(&x_ticks);
+        for &x in &x_ticks {
+            label(Vec3::new(data.scene(x, y_range.0, data.z_min).x, far.y, near_z), format_tick(x, x_step), 10.0);
+        }
+        let y_ticks = nice_ticks(y_range.0, y_range.1, 5);
+        let y_step = tick_step(&y_ticks);
+        for &y in &y_ticks {
+            label(Vec3::new(near_x, far.y, data.scene(x_range.0, y, data.z_min).z), format_tick(y, y_step), 10.0);
+        }
+        let z_ticks = nice_ticks(data.z_min, data.z_max, 4);
+        let z_step = tick_step(&z_ticks);
+        for &z in &z_ticks {
+            label(Vec3::new(near_x, data.scene(x_range.0, y_range.0, z).y, far.z), format_tick(z, z_step), 10.0);
+        }
+
+        label(Vec3::new(0.0, far.y, near_z * 1.2), self.config.x_column.clone(), 12.0);
+        label(Vec3::new(near_x * 1.2, far.y, 0.0), self.config.y_column.clone(), 12.0);
+        label(Vec3::new(near_x * 1.2, 0.0, far.z), self.config.z_column.clone(), 12.0);
+    }
+
+    /// The plane through the row at the navigation position, the surface's
+    /// section along it, and the row itself
+    fn draw_slice(&self, painter: &egui::Painter, rect: Rect, data: &SurfaceData, point: [f64; 3]) {
+        let [x, y, _] = point;
+        let at = data.scene(x, y, data.z_min);
+        let (w, h) = (BOX_HALF_WIDTH, BOX_HALF_HEIGHT);
+        let plane = match self.config.slice_axis {
+            SliceAxis::X => [Vec3::new(at.x, -h, -w), Vec3::new(at.x, h, -w), Vec3::new(at.x, h, w), Vec3::new(at.x, -h, w)],
+            SliceAxis::Y => [Vec3::new(-w, -h, at.z), Vec3::new(-w, h, at.z), Vec3::new(w, h, at.z), Vec3::new(w, -h, at.z)],
+            SliceAxis::None => return,
+        };
+        let accent = Color32::from_rgb(255, 152, 0);
+        let projected: Option<Vec<Pos2>> = plane.iter().map(|&corner| Some(self.camera.project(corner, &rect)?.0)).collect();
+        if let Some(points) = projected {
+            painter.add(Shape::convex_polygon(points, Color32::from_white_alpha(25), Stroke::new(1.0, accent.gamma_multiply(0.6))));
+        }
+
+        // The section, broken across gaps in the grid
+        let value = if self.config.slice_axis == SliceAxis::X { x } else { y };
+        let mut run: Vec<Pos2> = Vec::new();
+        for [sx, sy, sz] in data.section(self.config.slice_axis, value).into_iter().chain([[0.0, 0.0, f64::NAN]]) {
+            let projected = (!sz.is_nan()).then(|| self.camera.project(data.scene(sx, sy, sz), &rect)).flatten();
+            match projected {
+                Some((pos, _)) => run.push(pos),
+                None => {
+                    if run.len() >= 2 {
+                        painter.add(PathShape::line(run.clone(), Stroke::new(2.5, accent)));
+                    }
+                    run.clear();
+                }
+            }
+        }
+
+        if let Some((pos, _)) = self.camera.project(data.scene(point[0], point[1], point[2]), &rect) {
+            painter.circle(pos, 4.0, accent, Stroke::new(1.5, Color32::WHITE));
+        }
+    }
+
+    /// Scene coordinates of the box faces furthest from the camera, as
+    /// the x of the far side face, the y of the far floor or ceiling and the
+    /// z of the far front or back face
+    fn far_faces(&self) -> Vec3 {
+        let side = |camera: f32, half: f32| if camera > 0.0 { -half } else { half };
+        Vec3::new(
+            side(self.camera.position.x, BOX_HALF_WIDTH),
+            side(self.camera.position.y, BOX_HALF_HEIGHT),
+            side(self.camera.position.z, BOX_HALF_WIDTH),
+        )
     }
 }
 
+/// About `count` round values spanning `min` to `max`
+fn nice_ticks(min: f64, max: f64, count: usize) -> Vec<f64> {
+    if max <= min || !min.is_finite() || !max.is_finite() {
+        return vec![min];
+    }
+    let raw = (max - min) / count.max(1) as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0].into_iter()
+        .map(|factor| factor * magnitude)
+        .find(|&step| step >= raw)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step + 1e-9).floor() as i64;
+    (first..=last).map(|k| k as f64 * step).collect()
+}
+
+fn tick_step(ticks: &[f64]) -> f64 {
+    match ticks {
+        [first, second, ..] => second - first,
+        _ => 1.0,
+    }
+}
+
+/// `value` with the decimals that tell ticks `step` apart
+fn format_tick(value: f64, step: f64) -> String {
+    let decimals = if step > 0.0 { (-step.log10().floor()).clamp(0.0, 6.0) as usize } else { 2 };
+    format!("{:.*}", decimals, value)
+}
+
 impl SpaceView for Surface3DPlot {
     fn id(&self) -> SpaceViewId { self.id }
     fn title(&self) -> &str {
@@ -207,92 +473,98 @@ impl SpaceView for Surface3DPlot {
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
         self
     }
-    
+
     fn display_name(&self) -> &str { &self.title }
     fn view_type(&self) -> &str { "Surface3DPlot" }
-    
+
     fn set_data_source(&mut self, source_id: String) {
         self.config.data_source_id = Some(source_id);
         self.cached_data = None;
+        self.last_navigation_pos = None;
     }
-    
+
     fn data_source_id(&self) -> Option<&str> {
         self.config.data_source_id.as_deref()
     }
-    
+
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // The surface follows the selection range, the slice the position
+        let nav_context = ctx.navigation.get_context();
+        if self.cached_data.is_none() || self.last_selection_range != nav_context.selection_range {
             self.cached_data = self.fetch_data(ctx);
-            self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range.clone();
+        }
+        if self.last_navigation_pos.as_ref() != Some(&nav_context.position) {
+            self.current_point = self.fetch_current_point(ctx);
+            self.last_navigation_pos = Some(nav_context.position.clone());
         }
-        
+
         // Controls
         ui.horizontal(|ui| {
-            ui.label("Rotation X:");
-            ui.add(egui::Slider::new(&mut self.config.rotation_x, -90.0..=90.0).suffix("°"));
-            ui.label("Rotation Z:");
-            ui.add(egui::Slider::new(&mut self.config.rotation_z, 0.0..=360.0).suffix("°"));
+            ui.checkbox(&mut self.config.show_wireframe, "Wireframe");
+            ui.checkbox(&mut self.config.show_grid, "Axes");
+            ui.label("Slice:");
+            egui::ComboBox::from_id_source(format!("{:?}_slice", self.id))
+                .selected_text(match self.config.slice_axis {
+                    SliceAxis::None => "None",
+                    SliceAxis::X => "Along X",
+                    SliceAxis::Y => "Along Y",
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.config.slice_axis, SliceAxis::None, "None");
+                    ui.selectable_value(&mut self.config.slice_axis, SliceAxis::X, "Along X");
+                    ui.selectable_value(&mut self.config.slice_axis, SliceAxis::Y, "Along Y");
+                });
+            ui.weak("Drag to rotate · Scroll to zoom · Double-click to reset");
         });
-        
-        ui.checkbox(&mut self.config.show_wireframe, "Show wireframe");
-        ui.checkbox(&mut self.config.show_grid, "Show grid");
-        
-        let available_rect = ui.available_rect_before_wrap();
-        let painter = ui.painter_at(available_rect);
-        
-        if let Some(data) = &self.cached_data {
-            // Draw axes
-            if self.config.show_grid {
-                let origin = self.project_3d_to_2d(0.0, 0.0, 0.0, &available_rect);
-                let x_axis = self.project_3d_to_2d(1.0, 0.0, 0.0, &available_rect);
-                let y_axis = self.project_3d_to_2d(0.0, 1.0, 0.0, &available_rect);
-                let z_axis = self.project_3d_to_2d(0.0, 0.0, 1.0, &available_rect);
-                
-                painter.line_segment([origin, x_axis], Stroke::new(2.0, Color32::RED));
-                painter.line_segment([origin, y_axis], Stroke::new(2.0, Color32::GREEN));
-                painter.line_segment([origin, z_axis], Stroke::new(2.0, Color32::BLUE));
-            }
-            
-            // Draw surface points
-            for row in &data.grid {
-                for (x, y, z) in row {
-                    let pos = self.project_3d_to_2d(
-                        (*x - 0.5) * 2.0,  // Normalize to [-1, 1]
-                        (*y - 0.5) * 2.0,
-                        (*z - data.z_min) / (data.z_max - data.z_min) - 0.5,
-                        &available_rect
-                    );
-                    
-                    let color = self.get_color_for_z(*z, data.z_min, data.z_max);
-                    painter.circle_filled(pos, 3.0, color);
-                    
-                    if self.config.show_wireframe {
-                        painter.circle_stroke(pos, 3.0, Stroke::new(1.0, Color32::from_gray(100)));
-                    }
-                }
-            }
-            
-            // Info
-            ui.allocate_space(available_rect.size());
-            ui.separator();
-            ui.horizontal(|ui| {
-                ui.label(format!("Points: {}", data.grid[0].len()));
-                ui.label(format!("Z range: [{:.2}, {:.2}]", data.z_min, data.z_max));
-            });
-        } else {
+
+        let Some(data) = &self.cached_data else {
             ui.centered_and_justified(|ui| {
                 ui.label("No data to display");
-                if self.config.x_column.is_empty() || 
-                   self.config.y_column.is_empty() || 
+                if self.config.x_column.is_empty() ||
+                   self.config.y_column.is_empty() ||
                    self.config.z_column.is_empty() {
                     ui.label("Please configure X, Y, and Z columns");
                 }
             });
+            return;
+        };
+
+        let rect = ui.available_rect_before_wrap();
+        let response = ui.allocate_rect(rect, Sense::click_and_drag());
+        if response.dragged() {
+            self.camera.orbit(response.drag_delta());
+        }
+        if response.hovered() {
+            let scroll = ui.input(|i| i.scroll_delta.y);
+            if scroll != 0.0 {
+                self.camera.zoom(scroll);
+            }
+        }
+        if response.double_clicked() {
+            self.camera = Camera3D::default();
+            self.camera.update_from_angles();
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        if self.config.show_grid {
+            self.draw_box(&painter, rect, data);
+        }
+        self.draw_surface(&painter, rect, data);
+        if let Some(point) = self.current_point {
+            self.draw_slice(&painter, rect, data, point);
+        }
+        if self.config.show_grid {
+            self.draw_tick_labels(&painter, rect, data);
         }
+
+        response.on_hover_text(format!(
+            "Grid: {} × {}\nZ range: [{:.2}, {:.2}]",
+            data.grid.nx(), data.grid.ny(), data.z_min, data.z_max
+        ));
     }
-    
+
     fn save_config(&self) -> Value {
         json!({
             "data_source_id": self.config.data_source_id,
@@ -300,13 +572,17 @@ impl SpaceView for Surface3DPlot {
             "y_column": self.config.y_column,
             "z_column": self.config.z_column,
             "color_scheme": self.config.color_scheme,
-            "rotation_x": self.config.rotation_x,
-            "rotation_z": self.config.rotation_z,
             "show_grid": self.config.show_grid,
             "show_wireframe": self.config.show_wireframe,
+            "grid_method": self.config.grid_method.name(),
+            "grid_resolution": self.config.grid_resolution,
+            "slice_axis": self.config.slice_axis.name(),
+            "camera_yaw": self.camera.yaw,
+            "camera_pitch": self.camera.pitch,
+            "camera_distance": self.camera.distance,
         })
     }
-    
+
     fn load_config(&mut self, config: Value) {
         if let Some(data_source_id) = config.get("data_source_id").and_then(|v| v.as_str()) {
             self.config.data_source_id = Some(data_source_id.to_string());
@@ -323,20 +599,99 @@ impl SpaceView for Surface3DPlot {
         if let Some(color_scheme) = config.get("color_scheme").and_then(|v| v.as_str()) {
             self.config.color_scheme = color_scheme.to_string();
         }
-        if let Some(rotation_x) = config.get("rotation_x").and_then(|v| v.as_f64()) {
-            self.config.rotation_x = rotation_x as f32;
-        }
-        if let Some(rotation_z) = config.get("rotation_z").and_then(|v| v.as_f64()) {
-            self.config.rotation_z = rotation_z as f32;
-        }
         if let Some(show_grid) = config.get("show_grid").and_then(|v| v.as_bool()) {
             self.config.show_grid = show_grid;
         }
         if let Some(show_wireframe) = config.get("show_wireframe").and_then(|v| v.as_bool()) {
             self.config.show_wireframe = show_wireframe;
         }
+        if let Some(grid_method) = config.get("grid_method").and_then(|v| v.as_str()).and_then(GridMethod::from_name) {
+            self.config.grid_method = grid_method;
+        }
+        if let Some(grid_resolution) = config.get("grid_resolution").and_then(|v| v.as_u64()) {
+            self.config.grid_resolution = (grid_resolution as usize).clamp(2, 500);
+        }
+        if let Some(slice_axis) = config.get("slice_axis").and_then(|v| v.as_str()).and_then(SliceAxis::from_name) {
+            self.config.slice_axis = slice_axis;
+        }
+        if let Some(yaw) = config.get("camera_yaw").and_then(|v| v.as_f64()) {
+            self.camera.yaw = yaw as f32;
+        }
+        if let Some(pitch) = config.get("camera_pitch").and_then(|v| v.as_f64()) {
+            self.camera.set_pitch(pitch as f32);
+        }
+        if let Some(distance) = config.get("camera_distance").and_then(|v| v.as_f64()) {
+            self.camera.set_distance(distance as f32);
+        }
+        self.camera.update_from_angles();
+        self.cached_data = None;
+        self.last_navigation_pos = None;
     }
-    
+
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
     fn on_frame_update(&mut self, _ctx: &ViewerContext, _dt: f32) {}
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// z = x + 10y on x 0..=2 and y 0..=1
+    fn plane() -> SurfaceData {
+        let xs = vec![0.0, 1.0, 2.0];
+        let ys = vec![0.0, 1.0];
+        let values = ys.iter().flat_map(|&y| xs.iter().map(move |&x| x + 10.0 * y)).collect();
+        SurfaceData { grid: Grid { xs, ys, values }, z_min: 0.0, z_max: 12.0 }
+    }
+
+    #[test]
+    fn ticks_are_round_and_inside_the_range() {
+        assert_eq!(nice_ticks(0.0, 10.0, 5), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        let fine = nice_ticks(0.13, 0.47, 4);
+        assert_eq!(fine.len(), 3);
+        assert!(fine.iter().zip([0.2, 0.3, 0.4]).all(|(tick, expected)| (tick - expected).abs() < 1e-12));
+        assert_eq!(nice_ticks(3.0, 3.0, 5), vec![3.0]);
+        assert_eq!(tick_step(&nice_ticks(-50.0, 50.0, 4)), 50.0);
+        assert_eq!(format_tick(0.25, 0.05), "0.25");
+        assert_eq!(format_tick(1500.0, 500.0), "1500");
+    }
+
+    #[test]
+    fn sections_interpolate_between_nodes() {
+        let data = plane();
+        assert_eq!(data.section(SliceAxis::X, 0.5), vec![[0.5, 0.0, 0.5], [0.5, 1.0, 10.5]]);
+        assert_eq!(data.section(SliceAxis::Y, 0.5), vec![[0.0, 0.5, 5.0], [1.0, 0.5, 6.0], [2.0, 0.5, 7.0]]);
+        // The last node is inside the surface, not past it
+        assert_eq!(data.section(SliceAxis::X, 2.0), vec![[2.0, 0.0, 2.0], [2.0, 1.0, 12.0]]);
+
+        assert!(data.section(SliceAxis::X, 2.5).is_empty());
+        assert!(data.section(SliceAxis::Y, -0.1).is_empty());
+        assert!(data.section(SliceAxis::None, 0.5).is_empty());
+    }
+
+    #[test]
+    fn far_faces_are_opposite_the_camera() {
+        let mut plot = Surface3DPlot::new(uuid::Uuid::new_v4(), "Surface".to_string());
+        plot.load_config(json!({ "camera_yaw": 0.0, "camera_pitch": 0.5 }));
+        // Camera on +x, above, at z = 0 (which counts as the back)
+        assert_eq!(plot.far_faces(), Vec3::new(-BOX_HALF_WIDTH, -BOX_HALF_HEIGHT, BOX_HALF_WIDTH));
+
+        plot.load_config(json!({ "camera_yaw": std::f64::consts::PI * 0.75, "camera_pitch": -0.5 }));
+        assert_eq!(plot.far_faces(), Vec3::new(BOX_HALF_WIDTH, BOX_HALF_HEIGHT, -BOX_HALF_WIDTH));
+    }
+
+    #[test]
+    fn loaded_cameras_are_clamped_like_orbiting() {
+        let mut plot = Surface3DPlot::new(uuid::Uuid::new_v4(), "Surface".to_string());
+        plot.load_config(json!({ "camera_pitch": 3.0, "camera_distance": 0.0 }));
+        let mut orbited = Camera3D::default();
+        orbited.orbit(egui::Vec2::new(0.0, -10_000.0));
+        orbited.zoom(100_000.0);
+        assert_eq!(plot.camera.pitch, orbited.pitch);
+        assert_eq!(plot.camera.distance, orbited.distance);
+        assert!(plot.camera.position.length() > 1.0);
+
+        let saved = plot.save_config();
+        assert_eq!(saved["camera_pitch"].as_f64().unwrap() as f32, orbited.pitch);
+    }
+}
@@ -3,12 +3,13 @@
 use egui::{Ui, Color32};
 use egui_plot::{Plot, PlotUi, PlotPoints, Line, Legend, Points};
 use arrow::record_batch::RecordBatch;
-use arrow::array::{Float64Array, TimestampSecondArray};
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, TimestampSecondArray};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::NavigationRange;
 use super::utils::colors::{categorical_color, ColorScheme};
 
 /// Time analysis configuration
@@ -98,6 +99,7 @@ pub struct TimeAnalysisPlot {
     
     // State
     cached_data: Option<RecordBatch>,
+    last_selection_range: Option<NavigationRange>,
     decomposition_cache: HashMap<String, Decomposition>,
     anomalies: HashMap<String, Vec<usize>>,
     change_points: HashMap<String, Vec<usize>>,
@@ -110,13 +112,14 @@ impl TimeAnalysisPlot {
             title,
             config: TimeAnalysisConfig::default(),
             cached_data: None,
+            last_selection_range: None,
             decomposition_cache: HashMap::new(),
             anomalies: HashMap::new(),
             change_points: HashMap::new(),
         }
     }
     
-    fn extract_time_series(&mut self, batch: &RecordBatch) -> Option<(Vec<f64>, HashMap<String, Vec<f64>>)> {
+    fn extract_time_series(&self, batch: &RecordBatch) -> Option<(Vec<f64>, HashMap<String, Vec<f64>>)> {
         let mut series_data = HashMap::new();
         
         // Extract time column
@@ -615,6 +618,19 @@ impl TimeAnalysisPlot {
     }
 }
 
+/// Times of `periods` steps after the last of `times`, spaced by their
+/// median step (1 when there is none)
+fn forecast_times(times: &[f64], periods: usize) -> Vec<f64> {
+    let mut steps: Vec<f64> = times.windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .filter(|&step| step > 0.0)
+        .collect();
+    steps.sort_by(f64::total_cmp);
+    let step = steps.get(steps.len() / 2).copied().unwrap_or(1.0);
+    let last = times.last().copied().unwrap_or(0.0);
+    (1..=periods).map(|i| last + step * i as f64).collect()
+}
+
 impl SpaceView for TimeAnalysisPlot {
     fn id(&self) -> SpaceViewId { self.id }
     fn title(&self) -> &str {
@@ -645,12 +661,13 @@ impl SpaceView for TimeAnalysisPlot {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if needed
-        if self.cached_data.is_none() {
+        // Update data if needed (first draw or the selection range changed)
+        let selection_range = ctx.navigation.get_context().selection_range;
+        if self.cached_data.is_none() || self.last_selection_range != selection_range {
+            self.last_selection_range = selection_range;
             let data_sources = ctx.data_sources.read();
             if let Some(source) = data_sources.values().next() {
-                let nav_pos = ctx.navigation.get_context().position.clone();
-                if let Ok(batch) = ctx.runtime_handle.block_on(source.query_at(&nav_pos)) {
+                if let Ok(batch) = ctx.query_view_data(source.as_ref()) {
                     self.cached_data = Some(batch);
                 }
             }
@@ -693,17 +710,12 @@ impl SpaceView for TimeAnalysisPlot {
                         if self.config.show_forecast {
                             for (idx, (name, values)) in series_data.iter().enumerate() {
                                 let color = categorical_color(idx);
-                                let last_time = times.last().copied().unwrap_or(0.0);
                                 
                                 let (forecast, lower, upper) = self.forecast_series(
                                     values, 
                                     self.config.forecast_periods
                                 );
-                                
-                                // Forecast times
-                                let forecast_times: Vec<f64> = (1..=self.config.forecast_periods)
-                                    .map(|i| last_time + i as f64)
-                                    .collect();
+                                let forecast_times = forecast_times(&times, self.config.forecast_periods);
                                 
                                 // Draw forecast line
                                 let forecast_points: PlotPoints = forecast_times.iter()
@@ -766,6 +778,55 @@ impl SpaceView for TimeAnalysisPlot {
         }
     }
     
+    /// One row per time with each series, its decomposition and its flagged
+    /// anomalies and change points, followed by the forecast rows when the
+    /// forecast is shown
+    fn result_table(&self) -> Option<RecordBatch> {
+        let (times, series_data) = self.extract_time_series(self.cached_data.as_ref()?)?;
+        let mut names: Vec<&String> = series_data.keys().collect();
+        names.sort();
+        let forecast_periods = if self.config.show_forecast { self.config.forecast_periods } else { 0 };
+        
+        // Observed rows have no forecast, forecast rows have nothing else
+        let observed = |values: &[f64]| values.iter().map(|&v| Some(v))
+            .chain(std::iter::repeat_n(None, forecast_periods))
+            .collect::<Float64Array>();
+        let flags = |indices: Option<&Vec<usize>>| {
+            let indices: HashSet<usize> = indices.into_iter().flatten().copied().collect();
+            (0..times.len()).map(|i| Some(indices.contains(&i)))
+                .chain(std::iter::repeat_n(None, forecast_periods))
+                .collect::<BooleanArray>()
+        };
+        let forecast = |values: Vec<f64>| std::iter::repeat_n(None, times.len())
+            .chain(values.into_iter().map(Some))
+            .collect::<Float64Array>();
+        
+        let time_values: Vec<f64> = times.iter().copied()
+            .chain(forecast_times(&times, forecast_periods))
+            .collect();
+        let mut columns = vec![(self.config.time_column.clone(), Arc::new(Float64Array::from(time_values)) as ArrayRef)];
+        for name in names {
+            let values = &series_data[name];
+            columns.push((name.clone(), Arc::new(observed(values)) as ArrayRef));
+            if let Some(decomposition) = self.decomposition_cache.get(name) {
+                columns.push((format!("{}_trend", name), Arc::new(observed(&decomposition.trend)) as ArrayRef));
+                columns.push((format!("{}_seasonal", name), Arc::new(observed(&decomposition.seasonal)) as ArrayRef));
+                columns.push((format!("{}_residual", name), Arc::new(observed(&decomposition.residual)) as ArrayRef));
+            }
+            columns.push((format!("{}_anomaly", name), Arc::new(flags(self.anomalies.get(name))) as ArrayRef));
+            columns.push((format!("{}_change_point", name), Arc::new(flags(self.change_points.get(name))) as ArrayRef));
+            if forecast_periods > 0 && !values.is_empty() {
+                let (predicted, lower, upper) = self.forecast_series(values, forecast_periods);
+                columns.push((format!("{}_forecast", name), Arc::new(forecast(predicted)) as ArrayRef));
+                columns.push((format!("{}_forecast_lower", name), Arc::new(forecast(lower)) as ArrayRef));
+                columns.push((format!("{}_forecast_upper", name), Arc::new(forecast(upper)) as ArrayRef));
+            }
+        }
+        RecordBatch::try_from_iter(columns)
+            .map_err(|e| tracing::warn!("Failed to build the time analysis results: {}", e))
+            .ok()
+    }
+    
     fn save_config(&self) -> Value {
         json!({
             "time_column": self.config.time_column,
@@ -809,4 +870,44 @@ impl SpaceView for TimeAnalysisPlot {
     
     fn on_selection_change(&mut self, _ctx: &ViewerContext, _selection: &SelectionState) {}
     fn on_frame_update(&mut self, _ctx: &ViewerContext, _dt: f32) {}
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::Float64Type;
+
+    #[test]
+    fn forecasts_step_by_the_median_interval() {
+        assert_eq!(forecast_times(&[0.0, 10.0, 20.0, 60.0], 2), vec![70.0, 80.0]);
+        assert_eq!(forecast_times(&[5.0], 2), vec![6.0, 7.0]);
+        assert!(forecast_times(&[0.0, 1.0], 0).is_empty());
+    }
+
+    #[test]
+    fn result_table_has_observed_rows_then_forecast_rows() {
+        let mut view = TimeAnalysisPlot::new(uuid::Uuid::new_v4(), "Time analysis".to_string());
+        assert!(view.result_table().is_none());
+
+        view.config.time_column = "t".to_string();
+        view.config.value_columns = vec!["v".to_string()];
+        view.config.show_forecast = true;
+        view.config.forecast_periods = 2;
+        view.cached_data = Some(RecordBatch::try_from_iter(vec![
+            ("t", Arc::new(TimestampSecondArray::from(vec![0, 10, 20, 40])) as ArrayRef),
+            ("v", Arc::new(Float64Array::from(vec![1.0, 2.0, 9.0, 4.0])) as ArrayRef),
+        ]).unwrap());
+        view.anomalies.insert("v".to_string(), vec![2]);
+
+        let table = view.result_table().unwrap();
+        let names: Vec<String> = table.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, ["t", "v", "v_anomaly", "v_change_point", "v_forecast", "v_forecast_lower", "v_forecast_upper"]);
+        assert_eq!(table.column(0).as_primitive::<Float64Type>().values(), &[0.0, 10.0, 20.0, 40.0, 50.0, 60.0]);
+        let anomalies: Vec<Option<bool>> = table.column(2).as_boolean().iter().collect();
+        assert_eq!(anomalies, vec![Some(false), Some(false), Some(true), Some(false), None, None]);
+        let forecast = table.column(4).as_primitive::<Float64Type>();
+        assert_eq!(forecast.null_count(), 4);
+        assert!(forecast.is_valid(4) && forecast.is_valid(5));
+    }
+}
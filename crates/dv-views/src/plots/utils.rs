@@ -1,8 +1,14 @@
 //! Utilities for plot views
 
+pub mod camera;
 pub mod colors;
+pub mod grid;
+pub mod marching_squares;
 pub mod stats;
 
 // Re-export commonly used items
-pub use colors::{categorical_color, viridis_color, plasma_color, diverging_color, ColorScheme};
+pub use camera::Camera3D;
+pub use colors::{categorical_color, viridis_color, plasma_color, diverging_color, scheme_color, ColorScheme};
+pub use grid::{Grid, GridMethod};
+pub use marching_squares::{isobands, isolines};
 pub use stats::{calculate_quartiles, detect_outliers_iqr, zscore_outliers}; 
\ No newline at end of file
@@ -0,0 +1,137 @@
+//! Orbit camera for 3D plots
+
+use std::f32::consts::PI;
+
+use egui::{Pos2, Rect, Vec2};
+use glam::{Mat4, Vec3};
+
+/// Furthest the camera tilts above or below the target, short of the poles
+/// where the view direction would line up with `up`
+const MAX_PITCH: f32 = PI / 2.0 - 0.1;
+
+/// Closest and furthest the camera gets to the target
+const DISTANCE_RANGE: (f32, f32) = (2.0, 50.0);
+
+/// Camera turning around a target, y up
+#[derive(Debug, Clone)]
+pub struct Camera3D {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    pub fov: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,
+}
+
+impl Default for Camera3D {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(5.0, 5.0, 5.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            fov: 45.0_f32.to_radians(),
+            yaw: -PI / 4.0,
+            pitch: PI / 6.0,
+            distance: 10.0,
+        }
+    }
+}
+
+impl Camera3D {
+    pub fn update_from_angles(&mut self) {
+        self.position = Vec3::new(
+            self.distance * self.yaw.cos() * self.pitch.cos(),
+            self.distance * self.pitch.sin(),
+            self.distance * self.yaw.sin() * self.pitch.cos(),
+        );
+    }
+
+    /// Tilt to `pitch` radians, kept short of the poles
+    pub fn set_pitch(&mut self, pitch: f32) {
+        self.pitch = pitch.clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    /// Move to `distance` from the target, within the zoom range
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = distance.clamp(DISTANCE_RANGE.0, DISTANCE_RANGE.1);
+    }
+
+    pub fn view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.position, self.target, self.up)
+    }
+
+    pub fn projection_matrix(&self, aspect_ratio: f32) -> Mat4 {
+        Mat4::perspective_rh(self.fov, aspect_ratio, 0.1, 100.0)
+    }
+
+    /// Screen position of `point` in `rect` and its depth, greater further
+    /// away; None behind the camera
+    pub fn project(&self, point: Vec3, rect: &Rect) -> Option<(Pos2, f32)> {
+        let aspect_ratio = rect.width() / rect.height();
+        let mvp = self.projection_matrix(aspect_ratio) * self.view_matrix();
+
+        // Transform point
+        let transformed = mvp * point.extend(1.0);
+
+        // Perspective divide
+        if transformed.w <= 0.0 {
+            return None; // Behind camera
+        }
+
+        let ndc = transformed.truncate() / transformed.w;
+
+        // Convert to screen coordinates
+        let x = (ndc.x + 1.0) * 0.5 * rect.width() + rect.left();
+        let y = (1.0 - ndc.y) * 0.5 * rect.height() + rect.top();
+
+        Some((Pos2::new(x, y), ndc.z))
+    }
+
+    /// Turn around the target for a mouse drag of `delta` points
+    pub fn orbit(&mut self, delta: Vec2) {
+        self.yaw -= delta.x * 0.01;
+        self.set_pitch(self.pitch - delta.y * 0.01);
+        self.update_from_angles();
+    }
+
+    /// Move toward or away from the target for a scroll of `scroll` points
+    pub fn zoom(&mut self, scroll: f32) {
+        self.set_distance(self.distance * (1.0 - scroll * 0.001));
+        self.update_from_angles();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orbiting_and_zooming_stay_in_range() {
+        let mut camera = Camera3D::default();
+        camera.orbit(Vec2::new(0.0, -10_000.0));
+        assert_eq!(camera.pitch, MAX_PITCH);
+        camera.orbit(Vec2::new(0.0, 10_000.0));
+        assert_eq!(camera.pitch, -MAX_PITCH);
+
+        camera.zoom(100_000.0);
+        assert_eq!(camera.distance, DISTANCE_RANGE.0);
+        camera.zoom(-100_000.0);
+        assert_eq!(camera.distance, DISTANCE_RANGE.1);
+        assert!((camera.position.length() - DISTANCE_RANGE.1).abs() < 1e-3);
+    }
+
+    #[test]
+    fn projects_the_target_to_the_center() {
+        let mut camera = Camera3D::default();
+        camera.update_from_angles();
+        let rect = Rect::from_min_size(Pos2::new(10.0, 20.0), Vec2::new(200.0, 100.0));
+        let (center, depth) = camera.project(Vec3::ZERO, &rect).unwrap();
+        assert!((center - rect.center()).length() < 1e-3);
+
+        // Nearer points are shallower, points behind the camera aren't drawn
+        let (_, nearer) = camera.project(camera.position * 0.5, &rect).unwrap();
+        assert!(nearer < depth);
+        assert!(camera.project(camera.position * 2.0, &rect).is_none());
+    }
+}
@@ -62,6 +62,16 @@ pub fn viridis_color(t: f32) -> Color32 {
     }
 }
 
+/// Color at `t` along a color scheme saved by name, such as `"plasma"`;
+/// viridis for unknown names
+pub fn scheme_color(name: &str, t: f32) -> Color32 {
+    match name {
+        "plasma" => plasma_color(t),
+        "diverging" | "cool_warm" | "red_blue" => diverging_color(t),
+        _ => viridis_color(t),
+    }
+}
+
 /// Plasma color map
 pub fn plasma_color(t: f32) -> Color32 {
     let t = t.clamp(0.0, 1.0);
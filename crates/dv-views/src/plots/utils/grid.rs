@@ -0,0 +1,378 @@
+//! Gridding of scattered (x, y, z) data
+//!
+//! Contour and surface plots need z on a regular grid. Data already measured
+//! on a lattice (such as a designed experiment) keeps its own nodes;
+//! otherwise points are averaged into bins or interpolated by inverse
+//! distance weighting.
+
+use std::collections::HashSet;
+
+use arrow::array::{Array, Float64Array};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use arrow::record_batch::RecordBatch;
+
+/// Lattices with more nodes per axis than this are gridded like scattered data
+const MAX_LATTICE_NODES: usize = 500;
+
+/// Points considered for each node by inverse distance weighting
+const IDW_NEIGHBORS: usize = 12;
+
+/// How scattered points become a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridMethod {
+    /// The points' own lattice when they lie on one, otherwise `Idw`
+    Auto,
+    /// Mean of the points in each cell; cells without points are gaps
+    Binning,
+    /// Inverse distance weighting of the nearest points
+    Idw,
+}
+
+impl GridMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GridMethod::Auto => "auto",
+            GridMethod::Binning => "binning",
+            GridMethod::Idw => "idw",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(GridMethod::Auto),
+            "binning" => Some(GridMethod::Binning),
+            "idw" => Some(GridMethod::Idw),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GridMethod::Auto => "Auto",
+            GridMethod::Binning => "Binning",
+            GridMethod::Idw => "Inverse distance",
+        }
+    }
+}
+
+/// Values of z at the nodes of a rectilinear grid
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    /// Node x coordinates, ascending
+    pub xs: Vec<f64>,
+    /// Node y coordinates, ascending
+    pub ys: Vec<f64>,
+    /// z at node (i, j) is `values[j * xs.len() + i]`; NaN where unknown
+    pub values: Vec<f64>,
+}
+
+impl Grid {
+    pub fn nx(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn ny(&self) -> usize {
+        self.ys.len()
+    }
+
+    /// z at node (i, j), NaN if unknown
+    pub fn z(&self, i: usize, j: usize) -> f64 {
+        self.values[j * self.xs.len() + i]
+    }
+
+    /// Least and greatest known z
+    pub fn z_range(&self) -> Option<(f64, f64)> {
+        self.values.iter()
+            .filter(|z| z.is_finite())
+            .fold(None, |range, &z| match range {
+                None => Some((z, z)),
+                Some((min, max)) => Some((f64::min(min, z), f64::max(max, z))),
+            })
+    }
+
+    /// Grid of `points` with about `resolution` nodes along each axis.
+    /// None with fewer than three points or when they don't span both axes.
+    pub fn from_points(points: &[[f64; 3]], method: GridMethod, resolution: usize) -> Option<Self> {
+        let points: Vec<[f64; 3]> = points.iter()
+            .filter(|point| point.iter().all(|v| v.is_finite()))
+            .copied()
+            .collect();
+        if points.len() < 3 {
+            return None;
+        }
+        let resolution = resolution.max(2);
+        let bounds = Bounds::of(&points)?;
+        match method {
+            GridMethod::Auto => Self::lattice(&points).or_else(|| Some(Self::idw(&points, &bounds, resolution))),
+            GridMethod::Binning => Some(Self::binned(&points, &bounds, resolution)),
+            GridMethod::Idw => Some(Self::idw(&points, &bounds, resolution)),
+        }
+    }
+
+    /// The lattice the points lie on, if they cover nearly all of one.
+    /// Repeated measurements at a node are averaged.
+    fn lattice(points: &[[f64; 3]]) -> Option<Self> {
+        let xs = distinct(points.iter().map(|point| point[0]));
+        let ys = distinct(points.iter().map(|point| point[1]));
+        if xs.len() < 2 || ys.len() < 2 || xs.len() > MAX_LATTICE_NODES || ys.len() > MAX_LATTICE_NODES {
+            return None;
+        }
+        let nodes: HashSet<(u64, u64)> = points.iter().map(|point| (point[0].to_bits(), point[1].to_bits())).collect();
+        if nodes.len() * 10 < xs.len() * ys.len() * 9 {
+            return None;
+        }
+
+        let mut sums = vec![0.0; xs.len() * ys.len()];
+        let mut counts = vec![0usize; xs.len() * ys.len()];
+        for point in points {
+            let i = xs.partition_point(|&x| x < point[0]);
+            let j = ys.partition_point(|&y| y < point[1]);
+            sums[j * xs.len() + i] += point[2];
+            counts[j * xs.len() + i] += 1;
+        }
+        let values = mean(&sums, &counts);
+        Some(Self { xs, ys, values })
+    }
+
+    /// Means of the points in `resolution` × `resolution` cells, at the
+    /// cells' centers
+    fn binned(points: &[[f64; 3]], bounds: &Bounds, resolution: usize) -> Self {
+        let mut sums = vec![0.0; resolution * resolution];
+        let mut counts = vec![0usize; resolution * resolution];
+        for point in points {
+            let (i, j) = bounds.cell(point, resolution);
+            sums[j * resolution + i] += point[2];
+            counts[j * resolution + i] += 1;
+        }
+        let step_x = bounds.width() / resolution as f64;
+        let step_y = bounds.height() / resolution as f64;
+        Self {
+            xs: (0..resolution).map(|i| bounds.min_x + (i as f64 + 0.5) * step_x).collect(),
+            ys: (0..resolution).map(|j| bounds.min_y + (j as f64 + 0.5) * step_y).collect(),
+            values: mean(&sums, &counts),
+        }
+    }
+
+    /// Inverse distance weighted z at `resolution` × `resolution` nodes
+    /// spanning the points. Distances are measured with both axes scaled to
+    /// the unit interval, so columns in different units weigh alike.
+    fn idw(points: &[[f64; 3]], bounds: &Bounds, resolution: usize) -> Self {
+        // Points bucketed by cell to find the nearest ones without
+        // measuring the distance to all of them
+        let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); resolution * resolution];
+        for (index, point) in points.iter().enumerate() {
+            let (i, j) = bounds.cell(point, resolution);
+            buckets[j * resolution + i].push(index);
+        }
+
+        let xs: Vec<f64> = (0..resolution).map(|i| bounds.min_x + bounds.width() * i as f64 / (resolution - 1) as f64).collect();
+        let ys: Vec<f64> = (0..resolution).map(|j| bounds.min_y + bounds.height() * j as f64 / (resolution - 1) as f64).collect();
+        let mut values = Vec::with_capacity(resolution * resolution);
+        let mut nearest: Vec<(f64, usize)> = Vec::new();
+        for &y in &ys {
+            for &x in &xs {
+                let node = [x, y, 0.0];
+                let (ci, cj) = bounds.cell(&node, resolution);
+                let (u, v) = bounds.unit(&node);
+                nearest.clear();
+                let mut measure = |index: usize| {
+                    let (pu, pv) = bounds.unit(&points[index]);
+                    nearest.push(((pu - u).powi(2) + (pv - v).powi(2), index));
+                };
+
+                if points.len() <= IDW_NEIGHBORS {
+                    // Every point is a neighbor; searching the buckets
+                    // would only find them all the slow way
+                    (0..points.len()).for_each(&mut measure);
+                } else {
+                    // Rings of buckets around the node's until enough points
+                    // are found, and one more ring as those may hold closer points
+                    let mut found = 0;
+                    let mut last_ring = resolution;
+                    for ring in 0..resolution {
+                        if ring > last_ring || found == points.len() {
+                            break;
+                        }
+                        for (bi, bj) in ring_cells(ci, cj, ring, resolution) {
+                            let bucket = &buckets[bj * resolution + bi];
+                            bucket.iter().for_each(|&index| measure(index));
+                            found += bucket.len();
+                        }
+                        if found >= IDW_NEIGHBORS && last_ring == resolution {
+                            last_ring = ring + 1;
+                        }
+                    }
+                }
+                nearest.sort_by(|a, b| a.0.total_cmp(&b.0));
+                nearest.truncate(IDW_NEIGHBORS);
+
+                let value = match nearest.first() {
+                    // A point at the node itself
+                    Some(&(distance, index)) if distance < 1e-18 => points[index][2],
+                    _ => {
+                        let (weighted, weights) = nearest.iter().fold((0.0, 0.0), |(weighted, weights), &(distance, index)| {
+                            (weighted + points[index][2] / distance, weights + 1.0 / distance)
+                        });
+                        weighted / weights
+                    }
+                };
+                values.push(value);
+            }
+        }
+        Self { xs, ys, values }
+    }
+}
+
+/// Rows of `batch` with finite values in all three columns, as (x, y, z).
+/// None if a column is missing or not numeric.
+pub fn xyz_points(batch: &RecordBatch, x_column: &str, y_column: &str, z_column: &str) -> Option<Vec<[f64; 3]>> {
+    let column = |name: &str| -> Option<Float64Array> {
+        let array = cast(batch.column_by_name(name)?, &DataType::Float64).ok()?;
+        array.as_any().downcast_ref::<Float64Array>().cloned()
+    };
+    let (x, y, z) = (column(x_column)?, column(y_column)?, column(z_column)?);
+    Some((0..batch.num_rows())
+        .filter(|&row| x.is_valid(row) && y.is_valid(row) && z.is_valid(row))
+        .map(|row| [x.value(row), y.value(row), z.value(row)])
+        .filter(|point| point.iter().all(|v| v.is_finite()))
+        .collect())
+}
+
+struct Bounds {
+    min_x: f64,
+    max_x: f64,
+    min_y: f64,
+    max_y: f64,
+}
+
+impl Bounds {
+    fn of(points: &[[f64; 3]]) -> Option<Self> {
+        let mut bounds = Self { min_x: f64::INFINITY, max_x: f64::NEG_INFINITY, min_y: f64::INFINITY, max_y: f64::NEG_INFINITY };
+        for point in points {
+            bounds.min_x = bounds.min_x.min(point[0]);
+            bounds.max_x = bounds.max_x.max(point[0]);
+            bounds.min_y = bounds.min_y.min(point[1]);
+            bounds.max_y = bounds.max_y.max(point[1]);
+        }
+        (bounds.width() > 0.0 && bounds.height() > 0.0).then_some(bounds)
+    }
+
+    fn width(&self) -> f64 {
+        self.max_x - self.min_x
+    }
+
+    fn height(&self) -> f64 {
+        self.max_y - self.min_y
+    }
+
+    /// Position scaled to the unit square
+    fn unit(&self, point: &[f64; 3]) -> (f64, f64) {
+        ((point[0] - self.min_x) / self.width(), (point[1] - self.min_y) / self.height())
+    }
+
+    /// Cell of `point` when the bounds are split into `resolution` cells per axis
+    fn cell(&self, point: &[f64; 3], resolution: usize) -> (usize, usize) {
+        let (u, v) = self.unit(point);
+        let index = |t: f64| ((t * resolution as f64) as usize).min(resolution - 1);
+        (index(u), index(v))
+    }
+}
+
+/// Cells at Chebyshev distance `ring` from (i, j) within the grid
+fn ring_cells(i: usize, j: usize, ring: usize, resolution: usize) -> impl Iterator<Item = (usize, usize)> {
+    let (i, j, ring) = (i as isize, j as isize, ring as isize);
+    let size = resolution as isize;
+    (j - ring..=j + ring)
+        .flat_map(move |cj| (i - ring..=i + ring).map(move |ci| (ci, cj)))
+        .filter(move |&(ci, cj)| (ci - i).abs().max((cj - j).abs()) == ring)
+        .filter(move |&(ci, cj)| ci >= 0 && cj >= 0 && ci < size && cj < size)
+        .map(|(ci, cj)| (ci as usize, cj as usize))
+}
+
+fn distinct(values: impl Iterator<Item = f64>) -> Vec<f64> {
+    let mut values: Vec<f64> = values.collect();
+    values.sort_by(f64::total_cmp);
+    values.dedup();
+    values
+}
+
+fn mean(sums: &[f64], counts: &[usize]) -> Vec<f64> {
+    sums.iter().zip(counts)
+        .map(|(&sum, &count)| if count == 0 { f64::NAN } else { sum / count as f64 })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_on_a_lattice_keep_their_nodes() {
+        let mut points = Vec::new();
+        for (j, y) in [10.0, 20.0, 30.0].into_iter().enumerate() {
+            for (i, x) in [0.0, 0.5, 1.0, 2.0].into_iter().enumerate() {
+                points.push([x, y, (j * 4 + i) as f64]);
+            }
+        }
+        // A repeated measurement is averaged into its node
+        points.push([0.0, 10.0, 2.0]);
+
+        let grid = Grid::from_points(&points, GridMethod::Auto, 50).unwrap();
+        assert_eq!(grid.xs, vec![0.0, 0.5, 1.0, 2.0]);
+        assert_eq!(grid.ys, vec![10.0, 20.0, 30.0]);
+        assert_eq!(grid.z(0, 0), 1.0);
+        assert_eq!(grid.z(3, 2), 11.0);
+    }
+
+    #[test]
+    fn scattered_points_are_interpolated_at_the_resolution() {
+        let points = [[0.0, 0.0, 1.0], [1.0, 0.3, 2.0], [0.4, 1.0, 3.0], [0.7, 0.6, 4.0]];
+        let grid = Grid::from_points(&points, GridMethod::Auto, 5).unwrap();
+        assert_eq!((grid.nx(), grid.ny()), (5, 5));
+        assert!(grid.values.iter().all(|z| z.is_finite()));
+    }
+
+    #[test]
+    fn empty_bins_are_gaps() {
+        let points = [[0.0, 0.0, 1.0], [0.1, 0.1, 3.0], [1.0, 1.0, 5.0]];
+        let grid = Grid::from_points(&points, GridMethod::Binning, 2).unwrap();
+        assert_eq!(grid.xs, vec![0.25, 0.75]);
+        assert_eq!(grid.z(0, 0), 2.0);
+        assert_eq!(grid.z(1, 1), 5.0);
+        assert!(grid.z(1, 0).is_nan());
+        assert!(grid.z(0, 1).is_nan());
+        assert_eq!(grid.z_range(), Some((2.0, 5.0)));
+    }
+
+    #[test]
+    fn idw_nodes_on_a_point_take_its_value() {
+        // Corners of the grid coincide with the points; the middle is
+        // equidistant from all four
+        let points = [[0.0, 0.0, 1.0], [2.0, 0.0, 2.0], [0.0, 2.0, 3.0], [2.0, 2.0, 6.0]];
+        let grid = Grid::from_points(&points, GridMethod::Idw, 3).unwrap();
+        assert_eq!(grid.z(0, 0), 1.0);
+        assert_eq!(grid.z(2, 0), 2.0);
+        assert_eq!(grid.z(0, 2), 3.0);
+        assert_eq!(grid.z(2, 2), 6.0);
+        assert!((grid.z(1, 1) - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn idw_uses_the_nearest_of_many_points() {
+        // Far more points than neighbors, so the bucket search runs
+        let points: Vec<[f64; 3]> = (0..400)
+            .map(|k| [(k % 20) as f64, (k / 20) as f64, (k % 20) as f64])
+            .collect();
+        let grid = Grid::from_points(&points, GridMethod::Idw, 20).unwrap();
+        for i in 0..20 {
+            assert!((grid.z(i, 7) - i as f64).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn too_few_or_collinear_points_have_no_grid() {
+        assert!(Grid::from_points(&[[0.0, 0.0, 1.0], [1.0, 1.0, 2.0]], GridMethod::Idw, 10).is_none());
+        assert!(Grid::from_points(&[[0.0, 1.0, 1.0], [1.0, 1.0, 2.0], [2.0, 1.0, 3.0]], GridMethod::Idw, 10).is_none());
+    }
+}
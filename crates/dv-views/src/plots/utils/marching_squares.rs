@@ -0,0 +1,276 @@
+//! Contour lines and filled bands of a [`Grid`]
+//!
+//! Lines come from marching squares: each cell whose corners lie on both
+//! sides of the level holds a segment between the crossings on its edges,
+//! found by linear interpolation. Saddle cells, with diagonal corners on the
+//! same side, are resolved by the mean of the corners, and the segments are
+//! joined through shared edges into polylines.
+//!
+//! Bands are convex polygons covering where z lies between two levels. Cells
+//! within one band are merged into strips; cells crossing levels are split
+//! into four triangles about their center, z varying linearly across each.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::grid::Grid;
+
+/// Lines where z equals `level`, as polylines through grid coordinates. A
+/// closed line ends at its first point. Cells with unknown corners have none.
+pub fn isolines(grid: &Grid, level: f64) -> Vec<Vec<[f64; 2]>> {
+    let (nx, ny) = (grid.nx(), grid.ny());
+    let mut segments: Vec<[Edge; 2]> = Vec::new();
+    for j in 0..ny.saturating_sub(1) {
+        for i in 0..nx.saturating_sub(1) {
+            let corners = cell_corners(grid, i, j);
+            if corners.iter().any(|z| z.is_nan()) {
+                continue;
+            }
+            let above = corners.map(|z| z >= level);
+            // Edges of the cell, counterclockwise from the bottom: edge k
+            // joins corner k and corner k + 1
+            let edges = [Edge::horizontal(i, j), Edge::vertical(i + 1, j), Edge::horizontal(i, j + 1), Edge::vertical(i, j)];
+            let crossed: Vec<usize> = (0..4).filter(|&k| above[k] != above[(k + 1) % 4]).collect();
+            match crossed.len() {
+                2 => segments.push([edges[crossed[0]], edges[crossed[1]]]),
+                4 => {
+                    // A saddle: the corners on the same side as the center
+                    // are connected through it, cutting off the other two
+                    let center_above = corners.iter().sum::<f64>() / 4.0 >= level;
+                    for corner in (0..4).filter(|&k| above[k] != center_above) {
+                        segments.push([edges[(corner + 3) % 4], edges[corner]]);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    join(&segments)
+        .into_iter()
+        .map(|chain| chain.iter().map(|edge| edge.crossing(grid, level)).collect())
+        .collect()
+}
+
+/// Regions where `lower <= z < upper`, as convex polygons. Either bound may
+/// be infinite.
+pub fn isobands(grid: &Grid, lower: f64, upper: f64) -> Vec<Vec<[f64; 2]>> {
+    let (nx, ny) = (grid.nx(), grid.ny());
+    let inside = |z: f64| z >= lower && z < upper;
+    let mut polygons = Vec::new();
+    for j in 0..ny.saturating_sub(1) {
+        // First cell of the current strip of cells wholly inside the band
+        let mut strip: Option<usize> = None;
+        for i in 0..nx.saturating_sub(1) {
+            let corners = cell_corners(grid, i, j);
+            let known = corners.iter().all(|z| !z.is_nan());
+            if known && corners.iter().all(|&z| inside(z)) {
+                strip.get_or_insert(i);
+                continue;
+            }
+            if let Some(first) = strip.take() {
+                polygons.push(rectangle(grid, first, i, j));
+            }
+            let all_below = corners.iter().all(|&z| z < lower);
+            let all_above = corners.iter().all(|&z| z >= upper);
+            if !known || all_below || all_above {
+                continue;
+            }
+
+            let points = [
+                [grid.xs[i], grid.ys[j]],
+                [grid.xs[i + 1], grid.ys[j]],
+                [grid.xs[i + 1], grid.ys[j + 1]],
+                [grid.xs[i], grid.ys[j + 1]],
+            ];
+            let center = (
+                [(grid.xs[i] + grid.xs[i + 1]) / 2.0, (grid.ys[j] + grid.ys[j + 1]) / 2.0],
+                corners.iter().sum::<f64>() / 4.0,
+            );
+            for k in 0..4 {
+                let triangle = vec![(points[k], corners[k]), (points[(k + 1) % 4], corners[(k + 1) % 4]), center];
+                let clipped = clip(&clip(&triangle, |z| z >= lower, lower), |z| z < upper, upper);
+                if clipped.len() >= 3 {
+                    polygons.push(clipped.into_iter().map(|(point, _)| point).collect());
+                }
+            }
+        }
+        if let Some(first) = strip {
+            polygons.push(rectangle(grid, first, nx - 1, j));
+        }
+    }
+    polygons
+}
+
+/// Grid edge a contour crosses, named by the node it starts at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Edge {
+    i: usize,
+    j: usize,
+    /// Whether the edge runs from (i, j) to (i + 1, j), rather than to (i, j + 1)
+    horizontal: bool,
+}
+
+impl Edge {
+    fn horizontal(i: usize, j: usize) -> Self {
+        Self { i, j, horizontal: true }
+    }
+
+    fn vertical(i: usize, j: usize) -> Self {
+        Self { i, j, horizontal: false }
+    }
+
+    /// Where z equals `level` along the edge
+    fn crossing(&self, grid: &Grid, level: f64) -> [f64; 2] {
+        let (i, j) = (self.i, self.j);
+        let (end_i, end_j) = if self.horizontal { (i + 1, j) } else { (i, j + 1) };
+        let (start, end) = (grid.z(i, j), grid.z(end_i, end_j));
+        let t = if end != start { ((level - start) / (end - start)).clamp(0.0, 1.0) } else { 0.5 };
+        [
+            grid.xs[i] + t * (grid.xs[end_i] - grid.xs[i]),
+            grid.ys[j] + t * (grid.ys[end_j] - grid.ys[j]),
+        ]
+    }
+}
+
+/// Corners of cell (i, j), counterclockwise from the bottom left
+fn cell_corners(grid: &Grid, i: usize, j: usize) -> [f64; 4] {
+    [grid.z(i, j), grid.z(i + 1, j), grid.z(i + 1, j + 1), grid.z(i, j + 1)]
+}
+
+/// Cells `first..end` of row `j` as one rectangle
+fn rectangle(grid: &Grid, first: usize, end: usize, j: usize) -> Vec<[f64; 2]> {
+    vec![
+        [grid.xs[first], grid.ys[j]],
+        [grid.xs[end], grid.ys[j]],
+        [grid.xs[end], grid.ys[j + 1]],
+        [grid.xs[first], grid.ys[j + 1]],
+    ]
+}
+
+/// Segments chained through shared edges. Every edge is shared by at most
+/// two segments, those of the cells on either side.
+fn join(segments: &[[Edge; 2]]) -> Vec<Vec<Edge>> {
+    let mut by_edge: HashMap<Edge, Vec<usize>> = HashMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        for edge in segment {
+            by_edge.entry(*edge).or_default().push(index);
+        }
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut chains = Vec::new();
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let mut chain: VecDeque<Edge> = segments[start].into_iter().collect();
+        // Forward from the last edge, then backward from the first
+        for forward in [true, false] {
+            loop {
+                let end = if forward { chain[chain.len() - 1] } else { chain[0] };
+                let next = by_edge[&end].iter().copied().find(|&index| !used[index]);
+                let Some(next) = next else {
+                    break;
+                };
+                used[next] = true;
+                let [a, b] = segments[next];
+                let other = if a == end { b } else { a };
+                if forward {
+                    chain.push_back(other);
+                } else {
+                    chain.push_front(other);
+                }
+            }
+        }
+        chains.push(chain.into());
+    }
+    chains
+}
+
+/// The part of a convex polygon, with z at its vertices, where `keep(z)`,
+/// cut where z equals `bound` (Sutherland–Hodgman)
+fn clip(polygon: &[([f64; 2], f64)], keep: impl Fn(f64) -> bool, bound: f64) -> Vec<([f64; 2], f64)> {
+    if !bound.is_finite() {
+        return polygon.to_vec();
+    }
+    let mut clipped = Vec::with_capacity(polygon.len() + 2);
+    for k in 0..polygon.len() {
+        let (start, end) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+        if keep(start.1) {
+            clipped.push(start);
+        }
+        if keep(start.1) != keep(end.1) {
+            let t = (bound - start.1) / (end.1 - start.1);
+            let point = [start.0[0] + t * (end.0[0] - start.0[0]), start.0[1] + t * (end.0[1] - start.0[1])];
+            clipped.push((point, bound));
+        }
+    }
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plots::utils::grid::GridMethod;
+
+    /// z = x² + y² on a 21 × 21 lattice over [-1, 1]²
+    fn bowl() -> Grid {
+        let points: Vec<[f64; 3]> = (0..21)
+            .flat_map(|j| (0..21).map(move |i| (i as f64 / 10.0 - 1.0, j as f64 / 10.0 - 1.0)))
+            .map(|(x, y)| [x, y, x * x + y * y])
+            .collect();
+        Grid::from_points(&points, GridMethod::Auto, 60).unwrap()
+    }
+
+    #[test]
+    fn circles_of_a_bowl_are_closed_polylines() {
+        let grid = bowl();
+        assert_eq!((grid.nx(), grid.ny()), (21, 21));
+
+        let lines = isolines(&grid, 0.25);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.first(), line.last());
+        for point in line {
+            let radius = (point[0] * point[0] + point[1] * point[1]).sqrt();
+            assert!((radius - 0.5).abs() < 0.02, "{point:?} is off the circle");
+        }
+    }
+
+    #[test]
+    fn saddles_follow_the_center() {
+        // Nodes (0, 0) and (1, 1) at z = a, the other two at 0, so the
+        // center, a / 2, is above the level for a = 3 and below for a = 1.1
+        let grid = |a: f64| Grid { xs: vec![0.0, 1.0], ys: vec![0.0, 1.0], values: vec![a, 0.0, 0.0, a] };
+        let high = isolines(&grid(3.0), 1.0);
+        let low = isolines(&grid(1.1), 1.0);
+        assert_eq!((high.len(), low.len()), (2, 2));
+        // A high center connects the high corners, cutting off the low ones,
+        // and a low center the reverse
+        let cuts_corner = |line: &Vec<[f64; 2]>, corner: [f64; 2]| {
+            line.iter().all(|point| (point[0] - corner[0]).abs() + (point[1] - corner[1]).abs() <= 1.0)
+        };
+        assert!(high.iter().any(|line| cuts_corner(line, [1.0, 0.0])));
+        assert!(high.iter().any(|line| cuts_corner(line, [0.0, 1.0])));
+        assert!(low.iter().any(|line| cuts_corner(line, [0.0, 0.0])));
+        assert!(low.iter().any(|line| cuts_corner(line, [1.0, 1.0])));
+    }
+
+    #[test]
+    fn bands_cover_the_grid_once() {
+        let grid = bowl();
+        let levels = [f64::NEG_INFINITY, 0.5, 1.0, f64::INFINITY];
+        let area = |polygon: &Vec<[f64; 2]>| {
+            (0..polygon.len()).map(|k| {
+                let (a, b) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+                a[0] * b[1] - b[0] * a[1]
+            }).sum::<f64>() / 2.0
+        };
+        let total: f64 = levels.windows(2)
+            .flat_map(|bounds| isobands(&grid, bounds[0], bounds[1]))
+            .map(|polygon| area(&polygon))
+            .sum();
+        assert!((total - 4.0).abs() < 1e-9, "bands cover {total}");
+    }
+}
@@ -7,7 +7,7 @@ use serde_json::{json, Value};
 use std::collections::HashMap;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 use super::utils::stats::calculate_quartiles;
 
 /// Configuration for violin plot view
@@ -72,6 +72,7 @@ pub struct ViolinPlotView {
     // State
     cached_data: Option<ViolinData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 /// Cached violin plot data
@@ -96,6 +97,7 @@ impl ViolinPlotView {
             config: ViolinPlotConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -118,11 +120,8 @@ impl ViolinPlotView {
         // Get navigation context
         let nav_context = ctx.navigation.get_context();
         
-        // Fetch all data
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(0),
-            end: dv_core::navigation::NavigationPosition::Sequential(nav_context.total_rows),
-        };
+        // Fetch all data in the selection range
+        let range = nav_context.view_range();
         
         let data = match ctx.runtime_handle.block_on(data_source.query_range(&range)) {
             Ok(d) => {
@@ -406,11 +405,16 @@ impl SpaceView for ViolinPlotView {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the violin plot
@@ -48,6 +48,14 @@ impl SpaceView for PolarPlotView {
         "PolarPlot"
     }
     
+    fn set_data_source(&mut self, source_id: String) {
+        self.polar_plot.config.data_source_id = Some(source_id);
+    }
+    
+    fn data_source_id(&self) -> Option<&str> {
+        self.polar_plot.config.data_source_id.as_deref()
+    }
+    
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
         self.polar_plot.ui(ui, ctx);
     }
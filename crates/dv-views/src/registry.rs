@@ -0,0 +1,482 @@
+//! Registry of view types
+//!
+//! Each view type is registered once with its `SpaceView::view_type` name,
+//! how the dashboard builder presents it, the columns its settings take and a
+//! factory. The builder, saved workspaces and templates create views through
+//! the registry, so a registered view type is available everywhere.
+
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use serde_json::{Map, Value};
+
+use crate::plots::{
+    AnomalyDetectionView, BarChartView, BoxPlotView, CandlestickChart, ContourPlot, CorrelationMatrixView,
+    DistributionPlot, GeoPlot, HeatmapView, HistogramView, LinePlotView, NetworkGraph, ParallelCoordinatesView,
+    RadarChart, SankeyDiagram, Scatter3DView, ScatterPlotView, StreamGraph, SunburstChart, Surface3DPlot,
+    TimeAnalysisPlot, TreemapView, ViolinPlotView,
+};
+use crate::{PolarPlotView, SpaceView, SpaceViewId, SummaryStatsView, TableView, TimeSeriesView};
+
+/// Creates an empty view with the given ID and title
+pub type ViewFactory = Arc<dyn Fn(SpaceViewId, String) -> Box<dyn SpaceView> + Send + Sync>;
+
+/// Kind of column a setting takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Numeric,
+    /// Times or dates: typed timestamps, or text columns named like one
+    Temporal,
+    /// Text and boolean labels
+    Categorical,
+    /// Temporal or numeric: anything that orders rows along an axis
+    Ordered,
+    Any,
+}
+
+impl ColumnKind {
+    /// Kind of `field`, as one of `Numeric`, `Temporal` or `Categorical`
+    pub fn of(field: &Field) -> Option<ColumnKind> {
+        match field.data_type() {
+            DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => Some(ColumnKind::Temporal),
+            DataType::Utf8 | DataType::LargeUtf8 => {
+                let name = field.name().to_lowercase();
+                if name.contains("date") || name.contains("time") {
+                    Some(ColumnKind::Temporal)
+                } else {
+                    Some(ColumnKind::Categorical)
+                }
+            }
+            DataType::Boolean | DataType::Dictionary(_, _) => Some(ColumnKind::Categorical),
+            data_type if data_type.is_numeric() => Some(ColumnKind::Numeric),
+            _ => None,
+        }
+    }
+
+    /// Columns of `schema` of this kind, in schema order (temporal before
+    /// numeric ones for `Ordered`)
+    pub fn columns(self, schema: &Schema) -> Vec<String> {
+        let of_kind = |kind: ColumnKind| schema.fields().iter()
+            .filter(move |field| ColumnKind::of(field) == Some(kind))
+            .map(|field| field.name().clone());
+        match self {
+            ColumnKind::Ordered => of_kind(ColumnKind::Temporal).chain(of_kind(ColumnKind::Numeric)).collect(),
+            ColumnKind::Any => schema.fields().iter().map(|field| field.name().clone()).collect(),
+            kind => of_kind(kind).collect(),
+        }
+    }
+}
+
+/// How many columns a setting takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnCount {
+    One,
+    /// One column or none
+    Optional,
+    /// A list of at least `min` columns, `default` of them picked at first
+    Many { min: usize, default: usize },
+}
+
+/// A setting naming one or more columns
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnField {
+    /// Key in the view's configuration (`SpaceView::load_config`)
+    pub key: String,
+    pub label: String,
+    pub kind: ColumnKind,
+    pub count: ColumnCount,
+    /// Columns whose name contains this are picked first
+    pub hint: Option<String>,
+    /// Whether an optional column is picked by default
+    pub pick_default: bool,
+}
+
+impl ColumnField {
+    pub fn one(key: &str, label: &str, kind: ColumnKind) -> Self {
+        Self::new(key, label, kind, ColumnCount::One)
+    }
+
+    pub fn optional(key: &str, label: &str, kind: ColumnKind) -> Self {
+        Self::new(key, label, kind, ColumnCount::Optional)
+    }
+
+    pub fn many(key: &str, label: &str, kind: ColumnKind, min: usize, default: usize) -> Self {
+        Self::new(key, label, kind, ColumnCount::Many { min, default })
+    }
+
+    fn new(key: &str, label: &str, kind: ColumnKind, count: ColumnCount) -> Self {
+        Self {
+            key: key.to_string(),
+            label: label.to_string(),
+            kind,
+            count,
+            hint: None,
+            pick_default: true,
+        }
+    }
+
+    /// Prefer columns named like `hint`. Optional columns are only picked
+    /// by default when one is.
+    pub fn hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+
+    /// Leave an optional column unset by default
+    pub fn no_default(mut self) -> Self {
+        self.pick_default = false;
+        self
+    }
+
+    /// Whether `value` (from a configuration) satisfies the setting
+    pub fn is_set(&self, value: Option<&Value>) -> bool {
+        match self.count {
+            ColumnCount::One => value.and_then(Value::as_str).is_some_and(|name| !name.is_empty()),
+            ColumnCount::Optional => true,
+            ColumnCount::Many { min, .. } => value.and_then(Value::as_array).is_some_and(|names| names.len() >= min.max(1)),
+        }
+    }
+}
+
+/// A view type
+#[derive(Clone)]
+pub struct ViewDescriptor {
+    /// Type name, as returned by `SpaceView::view_type`
+    pub view_type: String,
+    /// Name shown to users, and the title of new views
+    pub name: String,
+    pub icon: String,
+    /// Heading the dashboard builder lists the view under
+    pub category: String,
+    /// Column settings, in the order the builder shows them
+    pub columns: Vec<ColumnField>,
+    factory: ViewFactory,
+}
+
+impl fmt::Debug for ViewDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ViewDescriptor")
+            .field("view_type", &self.view_type)
+            .field("name", &self.name)
+            .field("icon", &self.icon)
+            .field("category", &self.category)
+            .field("columns", &self.columns)
+            .finish()
+    }
+}
+
+impl ViewDescriptor {
+    pub fn new(
+        view_type: &str,
+        name: &str,
+        icon: &str,
+        category: &str,
+        factory: impl Fn(SpaceViewId, String) -> Box<dyn SpaceView> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            view_type: view_type.to_string(),
+            name: name.to_string(),
+            icon: icon.to_string(),
+            category: category.to_string(),
+            columns: Vec::new(),
+            factory: Arc::new(factory),
+        }
+    }
+
+    /// Add a column setting
+    pub fn column(mut self, field: ColumnField) -> Self {
+        self.columns.push(field);
+        self
+    }
+
+    /// An empty view of this type
+    pub fn create(&self, id: SpaceViewId, title: String) -> Box<dyn SpaceView> {
+        (self.factory)(id, title)
+    }
+
+    /// Whether `config` sets every required column
+    pub fn is_complete(&self, config: &Value) -> bool {
+        self.columns.iter().all(|field| field.is_set(config.get(&field.key)))
+    }
+
+    /// Whether `schema` has the columns the view needs
+    pub fn fits(&self, schema: &Schema) -> bool {
+        self.is_complete(&self.default_config(schema))
+    }
+
+    /// Column settings for showing a file with `schema`: hinted columns
+    /// first, then columns no other setting has taken
+    pub fn default_config(&self, schema: &Schema) -> Value {
+        let mut config = Map::new();
+        let mut taken: Vec<String> = Vec::new();
+        for field in &self.columns {
+            // An optional axis defaults to time, or else row order
+            let kind = match (field.kind, field.count) {
+                (ColumnKind::Ordered, ColumnCount::Optional) => ColumnKind::Temporal,
+                (kind, _) => kind,
+            };
+            let candidates = kind.columns(schema);
+            let hinted = |name: &String| field.hint.as_ref().is_some_and(|hint| name.to_lowercase().contains(hint.as_str()));
+            let mut ordered: Vec<&String> = candidates.iter().filter(|name| hinted(name)).collect();
+            ordered.extend(candidates.iter().filter(|name| !hinted(name) && !taken.contains(name)));
+            let value = match field.count {
+                ColumnCount::One => {
+                    let pick = ordered.first().copied().or(candidates.first());
+                    pick.map_or(Value::Null, |name| Value::String(name.clone()))
+                }
+                ColumnCount::Optional => {
+                    let pick = ordered.first().filter(|name| field.pick_default && (field.hint.is_none() || hinted(name)));
+                    pick.map_or(Value::Null, |name| Value::String((*name).clone()))
+                }
+                ColumnCount::Many { default, .. } => {
+                    Value::Array(ordered.iter().take(default).map(|name| Value::String((*name).clone())).collect())
+                }
+            };
+            match &value {
+                Value::String(name) => taken.push(name.clone()),
+                Value::Array(names) => taken.extend(names.iter().filter_map(Value::as_str).map(str::to_string)),
+                _ => {}
+            }
+            config.insert(field.key.clone(), value);
+        }
+        Value::Object(config)
+    }
+
+    /// The columns `config` sets, in a few words
+    pub fn summary(&self, config: &Value) -> String {
+        self.columns.iter()
+            .filter_map(|field| match config.get(&field.key)? {
+                Value::String(name) if !name.is_empty() => Some(name.clone()),
+                Value::Array(names) if names.len() > 2 => Some(format!("{} columns", names.len())),
+                Value::Array(names) if !names.is_empty() => {
+                    Some(names.iter().filter_map(Value::as_str).collect::<Vec<_>>().join(", "))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// The view types that can be created
+#[derive(Debug, Clone)]
+pub struct ViewRegistry {
+    views: Vec<ViewDescriptor>,
+}
+
+impl Default for ViewRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl ViewRegistry {
+    /// A registry without any view types
+    pub fn empty() -> Self {
+        Self { views: Vec::new() }
+    }
+
+    /// The built-in view types
+    pub fn builtin() -> Self {
+        use ColumnKind::*;
+        let mut registry = Self::empty();
+        let views = [
+            ViewDescriptor::new("TimeSeriesView", "Time Series", "📈", "Basic", |id, title| Box::new(TimeSeriesView::new(id, title)))
+                .column(ColumnField::optional("x_column", "X-Axis", Ordered))
+                .column(ColumnField::many("y_columns", "Y-Axis", Numeric, 1, 1)),
+            ViewDescriptor::new("LinePlotView", "Line Plot", "📉", "Basic", |id, title| Box::new(LinePlotView::new(id, title)))
+                .column(ColumnField::optional("x_column", "X-Axis", Ordered))
+                .column(ColumnField::many("y_columns", "Y-Axis", Numeric, 1, 1))
+                .column(ColumnField::optional("category_column", "Split By", Categorical).no_default()),
+            ViewDescriptor::new("ScatterPlotView", "Scatter Plot", "🎯", "Basic", |id, title| Box::new(ScatterPlotView::new(id, title)))
+                .column(ColumnField::one("x_column", "X-Axis", Numeric))
+                .column(ColumnField::one("y_column", "Y-Axis", Numeric))
+                .column(ColumnField::optional("color_column", "Color By", Categorical)),
+            ViewDescriptor::new("BarChartView", "Bar Chart", "📊", "Basic", |id, title| Box::new(BarChartView::new(id, title)))
+                .column(ColumnField::one("category_column", "Category", Categorical))
+                .column(ColumnField::one("value_column", "Value", Numeric)),
+            ViewDescriptor::new("HistogramView", "Histogram", "📊", "Basic", |id, title| Box::new(HistogramView::new(id, title)))
+                .column(ColumnField::one("column", "Column", Numeric)),
+            ViewDescriptor::new("TableView", "Data Table", "📋", "Basic", |id, title| Box::new(TableView::new(id, title))),
+            ViewDescriptor::new("SummaryStatsView", "Statistics", "📐", "Basic", |id, title| Box::new(SummaryStatsView::new(id, title))),
+            ViewDescriptor::new("BoxPlotView", "Box Plot", "📦", "Statistical", |id, title| Box::new(BoxPlotView::new(id, title)))
+                .column(ColumnField::one("value_column", "Value", Numeric))
+                .column(ColumnField::optional("category_column", "Group By", Categorical)),
+            ViewDescriptor::new("ViolinPlotView", "Violin Plot", "🎻", "Statistical", |id, title| Box::new(ViolinPlotView::new(id, title)))
+                .column(ColumnField::one("value_column", "Value", Numeric))
+                .column(ColumnField::optional("category_column", "Group By", Categorical)),
+            ViewDescriptor::new("HeatmapView", "Heatmap", "🔥", "Statistical", |id, title| Box::new(HeatmapView::new(id, title)))
+                .column(ColumnField::one("x_column", "X-Axis", Categorical))
+                .column(ColumnField::one("y_column", "Y-Axis", Categorical))
+                .column(ColumnField::one("value_column", "Value", Numeric)),
+            ViewDescriptor::new("CorrelationMatrixView", "Correlation Matrix", "🎯", "Statistical", |id, title| Box::new(CorrelationMatrixView::new(id, title)))
+                .column(ColumnField::many("columns", "Columns", Numeric, 2, 5)),
+            ViewDescriptor::new("DistributionPlot", "Distribution", "🔔", "Statistical", |id, title| Box::new(DistributionPlot::new(id, title)))
+                .column(ColumnField::one("column", "Column", Numeric)),
+            ViewDescriptor::new("AnomalyDetectionView", "Anomaly Detection", "⚠️", "Statistical", |id, title| Box::new(AnomalyDetectionView::new(id, title)))
+                .column(ColumnField::one("column", "Column", Numeric)),
+            ViewDescriptor::new("Scatter3DView", "3D Scatter", "🎲", "Advanced", |id, title| Box::new(Scatter3DView::new(id, title)))
+                .column(ColumnField::one("x_column", "X-Axis", Numeric))
+                .column(ColumnField::one("y_column", "Y-Axis", Numeric))
+                .column(ColumnField::one("z_column", "Z-Axis", Numeric)),
+            ViewDescriptor::new("Surface3DPlot", "3D Surface", "🏔️", "Advanced", |id, title| Box::new(Surface3DPlot::new(id, title)))
+                .column(ColumnField::one("x_column", "X-Axis", Numeric))
+                .column(ColumnField::one("y_column", "Y-Axis", Numeric))
+                .column(ColumnField::one("z_column", "Z-Axis", Numeric)),
+            ViewDescriptor::new("PolarPlot", "Polar Plot", "🧭", "Advanced", |id, title| Box::new(PolarPlotView::new(id, title)))
+                .column(ColumnField::one("angle_column", "Angle", Numeric).hint("angle"))
+                .column(ColumnField::one("radius_column", "Radius", Numeric).hint("radius"))
+                .column(ColumnField::optional("category_column", "Group By", Categorical)),
+            ViewDescriptor::new("ContourPlot", "Contour Plot", "🗺️", "Advanced", |id, title| Box::new(ContourPlot::new(id, title)))
+                .column(ColumnField::one("x_column", "X-Axis", Numeric))
+                .column(ColumnField::one("y_column", "Y-Axis", Numeric))
+                .column(ColumnField::one("z_column", "Z Value", Numeric)),
+            ViewDescriptor::new("ParallelCoordinatesView", "Parallel Coordinates", "🌈", "Advanced", |id, title| Box::new(ParallelCoordinatesView::new(id, title)))
+                .column(ColumnField::many("columns", "Axes", Numeric, 2, 5)),
+            ViewDescriptor::new("RadarChartView", "Radar Chart", "🎯", "Advanced", |id, title| Box::new(RadarChart::new(id, title)))
+                .column(ColumnField::many("value_columns", "Axes", Numeric, 3, 5))
+                .column(ColumnField::optional("group_column", "Group By", Categorical)),
+            ViewDescriptor::new("SankeyView", "Sankey Diagram", "🧵", "Hierarchical", |id, title| Box::new(SankeyDiagram::new(id, title)))
+                .column(ColumnField::one("source_column", "Source", Categorical))
+                .column(ColumnField::one("target_column", "Target", Categorical))
+                .column(ColumnField::one("value_column", "Flow", Numeric)),
+            ViewDescriptor::new("TreemapView", "Treemap", "📊", "Hierarchical", |id, title| Box::new(TreemapView::new(id, title)))
+                .column(ColumnField::one("path_column", "Category", Categorical))
+                .column(ColumnField::one("value_column", "Size", Numeric)),
+            ViewDescriptor::new("SunburstView", "Sunburst", "🌞", "Hierarchical", |id, title| Box::new(SunburstChart::new(id, title)))
+                .column(ColumnField::many("hierarchy_columns", "Levels", Categorical, 1, 3))
+                .column(ColumnField::optional("value_column", "Size", Numeric)),
+            ViewDescriptor::new("NetworkView", "Network Graph", "🌐", "Hierarchical", |id, title| Box::new(NetworkGraph::new(id, title)))
+                .column(ColumnField::one("source_column", "Source", Categorical))
+                .column(ColumnField::one("target_column", "Target", Categorical))
+                .column(ColumnField::optional("weight_column", "Weight", Numeric).no_default()),
+            ViewDescriptor::new("TimeAnalysisView", "Time Analysis", "⏳", "Specialized", |id, title| Box::new(TimeAnalysisPlot::new(id, title)))
+                .column(ColumnField::one("time_column", "Time", Ordered))
+                .column(ColumnField::many("value_columns", "Values", Numeric, 1, 3)),
+            ViewDescriptor::new("GeoPlotView", "Geographic Plot", "🌍", "Specialized", |id, title| Box::new(GeoPlot::new(id, title)))
+                .column(ColumnField::one("lat_column", "Latitude", Numeric).hint("lat"))
+                .column(ColumnField::one("lon_column", "Longitude", Numeric).hint("lon"))
+                .column(ColumnField::optional("value_column", "Value", Numeric)),
+            ViewDescriptor::new("StreamGraph", "Stream Graph", "🌊", "Specialized", |id, title| Box::new(StreamGraph::new(id, title)))
+                .column(ColumnField::one("x_column", "Time", Ordered))
+                .column(ColumnField::one("value_column", "Value", Numeric))
+                .column(ColumnField::optional("category_column", "Stream By", Categorical)),
+            ViewDescriptor::new("CandlestickView", "Candlestick Chart", "🕒", "Specialized", |id, title| Box::new(CandlestickChart::new(id, title)))
+                .column(ColumnField::one("time_column", "Time", Ordered))
+                .column(ColumnField::one("open_column", "Open", Numeric).hint("open"))
+                .column(ColumnField::one("high_column", "High", Numeric).hint("high"))
+                .column(ColumnField::one("low_column", "Low", Numeric).hint("low"))
+                .column(ColumnField::one("close_column", "Close", Numeric).hint("close"))
+                .column(ColumnField::optional("volume_column", "Volume", Numeric).hint("volume")),
+        ];
+        for view in views {
+            registry.register(view);
+        }
+        registry
+    }
+
+    /// Add a view type, replacing any registered under the same type name
+    pub fn register(&mut self, view: ViewDescriptor) {
+        match self.views.iter_mut().find(|existing| existing.view_type == view.view_type) {
+            Some(existing) => *existing = view,
+            None => self.views.push(view),
+        }
+    }
+
+    pub fn get(&self, view_type: &str) -> Option<&ViewDescriptor> {
+        self.views.iter().find(|view| view.view_type == view_type)
+    }
+
+    /// View types in registration order
+    pub fn views(&self) -> &[ViewDescriptor] {
+        &self.views
+    }
+
+    /// Builder categories in the order their first view type was registered
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = Vec::new();
+        for view in &self.views {
+            if !categories.contains(&view.category.as_str()) {
+                categories.push(&view.category);
+            }
+        }
+        categories
+    }
+
+    /// An empty view of the type named `view_type`, if it's registered
+    pub fn create(&self, view_type: &str, id: SpaceViewId, title: String) -> Option<Box<dyn SpaceView>> {
+        self.get(view_type).map(|view| view.create(id, title))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::TimeUnit;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    #[test]
+    fn every_builtin_view_reports_its_registered_type() {
+        let registry = ViewRegistry::builtin();
+        for view in registry.views() {
+            let created = view.create(Uuid::new_v4(), view.name.clone());
+            assert_eq!(created.view_type(), view.view_type);
+        }
+        assert_eq!(registry.categories(), ["Basic", "Statistical", "Advanced", "Hierarchical", "Specialized"]);
+    }
+
+    #[test]
+    fn default_settings_pick_distinct_and_hinted_columns() {
+        let schema = Schema::new(vec![
+            Field::new("time", DataType::Timestamp(TimeUnit::Millisecond, None), false),
+            Field::new("longitude", DataType::Float64, false),
+            Field::new("latitude", DataType::Float64, false),
+            Field::new("speed", DataType::Float64, false),
+            Field::new("vehicle", DataType::Utf8, false),
+        ]);
+        let registry = ViewRegistry::builtin();
+        let config = |view_type: &str| registry.get(view_type).unwrap().default_config(&schema);
+
+        assert_eq!(config("ScatterPlotView"), json!({ "x_column": "longitude", "y_column": "latitude", "color_column": "vehicle" }));
+        assert_eq!(config("GeoPlotView"), json!({ "lat_column": "latitude", "lon_column": "longitude", "value_column": "speed" }));
+        assert_eq!(config("TimeSeriesView"), json!({ "x_column": "time", "y_columns": ["longitude"] }));
+        // No price columns: the volume is left out, the prices are guesses
+        let candles = config("CandlestickView");
+        assert_eq!(candles["volume_column"], Value::Null);
+        assert!(registry.get("CandlestickView").unwrap().is_complete(&candles));
+
+        let radar = registry.get("RadarChartView").unwrap();
+        assert!(radar.fits(&schema));
+        assert!(!radar.is_complete(&json!({ "value_columns": ["speed", "latitude"] })));
+        let sankey = registry.get("SankeyView").unwrap();
+        assert!(!sankey.is_complete(&json!({ "source_column": "vehicle", "value_column": "speed" })));
+    }
+
+    #[test]
+    fn every_column_setting_is_read_by_its_view() {
+        for view in ViewRegistry::builtin().views() {
+            for field in &view.columns {
+                let value = match field.count {
+                    ColumnCount::Many { .. } => json!(["first", "second"]),
+                    _ => json!("picked"),
+                };
+                let mut created = view.create(Uuid::new_v4(), view.name.clone());
+                created.load_config(json!({ field.key.as_str(): value }));
+                assert_eq!(created.save_config().get(&field.key), Some(&value), "{} ignores {}", view.view_type, field.key);
+            }
+        }
+    }
+
+    #[test]
+    fn column_kinds_follow_types_and_names() {
+        let kind = |name: &str, data_type: DataType| ColumnKind::of(&Field::new(name, data_type, true));
+        assert_eq!(kind("when", DataType::Timestamp(TimeUnit::Second, None)), Some(ColumnKind::Temporal));
+        assert_eq!(kind("Trade_Date", DataType::Utf8), Some(ColumnKind::Temporal));
+        assert_eq!(kind("symbol", DataType::Utf8), Some(ColumnKind::Categorical));
+        assert_eq!(kind("ok", DataType::Boolean), Some(ColumnKind::Categorical));
+        assert_eq!(kind("timestamp_ms", DataType::Int64), Some(ColumnKind::Numeric));
+        assert_eq!(kind("raw", DataType::Binary), None);
+    }
+}
@@ -1,5 +1,6 @@
 //! Space view abstraction - base trait for all dockable views
 
+use arrow::record_batch::RecordBatch;
 use egui::Ui;
 use serde_json::Value;
 use std::fmt::Debug;
@@ -76,6 +77,13 @@ pub trait SpaceView: Send + Sync {
     /// Called each frame for updates
     fn on_frame_update(&mut self, ctx: &ViewerContext, dt: f32);
     
+    /// The numbers behind what the view draws, such as histogram bins or a
+    /// correlation matrix, as a table for export. `None` for views that draw
+    /// their source rows as they are, or haven't computed anything yet.
+    fn result_table(&self) -> Option<RecordBatch> {
+        None
+    }
+    
     /// Check if this is a time series view
     fn is_time_series(&self) -> bool {
         false
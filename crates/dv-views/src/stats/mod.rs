@@ -7,7 +7,7 @@ use arrow::datatypes::DataType;
 use serde_json::{json, Value};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Summary statistics view
 pub struct SummaryStatsView {
@@ -17,6 +17,7 @@ pub struct SummaryStatsView {
     // Cached statistics
     cached_stats: Option<Vec<ColumnStats>>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
 }
 
 #[derive(Debug, Clone)]
@@ -56,6 +57,7 @@ impl SummaryStatsView {
             title,
             cached_stats: None,
             last_navigation_pos: None,
+            last_selection_range: None,
         }
     }
     
@@ -64,12 +66,10 @@ impl SummaryStatsView {
         let data_sources = ctx.data_sources.read();
         let data_source = data_sources.values().next()?;
         
-        // Get current navigation position
-        let _nav_pos = ctx.navigation.get_context().position.clone();
-        
-        // Query all data - for stats we want the full dataset
+        // Query the selection range, or the full dataset if nothing is selected
+        let range = ctx.navigation.get_context().view_range();
         let batch = ctx.runtime_handle.block_on(
-            data_source.query_at(&NavigationPosition::Sequential(0))
+            data_source.query_range(&range)
         ).ok()?;
         
         let mut stats = Vec::new();
@@ -221,15 +221,28 @@ impl SpaceView for SummaryStatsView {
     }
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
-        // Update stats if navigation changed
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update stats if navigation or the selection range changed
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_stats = self.calculate_stats(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range.clone();
         }
         
         // Display statistics
         if let Some(stats) = &self.cached_stats {
+            let scope = match nav_context.selection_rows() {
+                Some(range) => {
+                    let (start, end) = range.row_bounds();
+                    format!("Selection: rows {}–{}", start + 1, end)
+                }
+                None => "All rows".to_string(),
+            };
+            ui.label(egui::RichText::new(scope).weak());
+            
             ScrollArea::both()
                 .id_source(format!("stats_{:?}", self.id))
                 .show(ui, |ui| {
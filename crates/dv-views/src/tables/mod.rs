@@ -5,7 +5,7 @@ use arrow::record_batch::RecordBatch;
 use serde_json::{json, Value};
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Configuration for table views
 #[derive(Debug, Clone)]
@@ -42,6 +42,7 @@ pub struct TableView {
     // State
     cached_data: Option<RecordBatch>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
     _scroll_state: ScrollState,
     
     // Column visibility
@@ -67,6 +68,7 @@ impl TableView {
             config: TableConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
             _scroll_state: ScrollState::default(),
             column_visibility: std::collections::HashMap::new(),
             current_page: 1,
@@ -85,13 +87,8 @@ impl TableView {
             data_sources.values().next()
         }?;
         
-        // Get current navigation position
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        
-        // Query data at current position
-        ctx.runtime_handle.block_on(
-            data_source.query_at(&nav_pos)
-        ).ok()
+        // Query data at current position (or the selection range, if one is set)
+        ctx.query_view_data(data_source.as_ref()).ok()
     }
     
     fn render_table(&mut self, ui: &mut Ui, data: &RecordBatch, ctx: &ViewerContext) {
@@ -409,11 +406,16 @@ impl SpaceView for TableView {
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
         
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the table
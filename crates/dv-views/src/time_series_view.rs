@@ -2,12 +2,12 @@
 //! Based on Rerun's PlotView
 
 use egui::{Ui, Color32};
-use egui_plot::{Plot, PlotPoints, Line, Legend, Points, LineStyle};
+use egui_plot::{Plot, PlotPoints, Line, Legend, Points, LineStyle, Polygon};
 use arrow::array::{Float64Array, Int64Array};
 use uuid::Uuid;
 
 use crate::{SpaceView, SpaceViewId, SelectionState, ViewerContext};
-use dv_core::navigation::NavigationPosition;
+use dv_core::navigation::{NavigationPosition, NavigationRange};
 
 /// Configuration for time series view
 #[derive(Clone)]
@@ -65,11 +65,17 @@ pub struct TimeSeriesView {
     // State
     cached_data: Option<PlotData>,
     last_navigation_pos: Option<NavigationPosition>,
+    last_selection_range: Option<NavigationRange>,
+    
+    /// X coordinate where a shift-drag range selection started
+    range_drag_start: Option<f64>,
 }
 
 /// Cached plot data
 #[derive(Debug, Clone)]
 struct PlotData {
+    /// Row index of the first point
+    start_row: usize,
     x_values: Vec<f64>,
     series: Vec<SeriesData>,
     x_column: String,
@@ -100,6 +106,8 @@ impl TimeSeriesView {
             config: TimeSeriesConfig::default(),
             cached_data: None,
             last_navigation_pos: None,
+            last_selection_range: None,
+            range_drag_start: None,
         }
     }
     
@@ -126,16 +134,9 @@ impl TimeSeriesView {
             return None;
         }
         
-        // For now, get a reasonable range of data (last 1000 points or all data)
-        let total_rows = nav_context.total_rows;
-        let range_size = total_rows; // Get ALL data, not just last 1000
-        let start_row = 0; // Start from beginning
-        
-        // Create a navigation range to fetch data
-        let range = dv_core::navigation::NavigationRange {
-            start: dv_core::navigation::NavigationPosition::Sequential(start_row),
-            end: dv_core::navigation::NavigationPosition::Sequential(start_row + range_size),
-        };
+        // Fetch the selection range, or all data if nothing is selected
+        let range = nav_context.view_range();
+        let (start_row, _) = range.row_bounds();
         
         // Fetch data using query_range - use the context runtime
         let data = ctx.runtime_handle.block_on(data_source.query_range(&range)).ok()?;
@@ -251,6 +252,7 @@ impl TimeSeriesView {
         }
         
         Some(PlotData {
+            start_row,
             x_values,
             series,
             x_column: x_column_name,
@@ -258,6 +260,18 @@ impl TimeSeriesView {
     }
 }
 
+impl PlotData {
+    /// Row range covering the points with an X value between `a` and `b`,
+    /// or `None` if no point falls in between
+    fn rows_between(&self, a: f64, b: f64) -> Option<NavigationRange> {
+        let (lo, hi) = (a.min(b), a.max(b));
+        let inside = |x: &f64| *x >= lo && *x <= hi;
+        let first = self.x_values.iter().position(inside)?;
+        let last = self.x_values.iter().rposition(inside)?;
+        Some(NavigationRange::rows(self.start_row + first, self.start_row + last + 1))
+    }
+}
+
 impl SpaceView for TimeSeriesView {
     fn id(&self) -> SpaceViewId {
         self.id
@@ -294,17 +308,24 @@ impl SpaceView for TimeSeriesView {
     
     fn ui(&mut self, ctx: &ViewerContext, ui: &mut Ui) {
         
-        // Update data if navigation changed or if we have no cached data
-        let nav_pos = ctx.navigation.get_context().position.clone();
-        if self.cached_data.is_none() || self.last_navigation_pos.as_ref() != Some(&nav_pos) {
+        // Update data if navigation or the selection range changed, or if we have no cached data
+        let nav_context = ctx.navigation.get_context();
+        let nav_pos = nav_context.position.clone();
+        if self.cached_data.is_none()
+            || self.last_navigation_pos.as_ref() != Some(&nav_pos)
+            || self.last_selection_range != nav_context.selection_range
+        {
             self.cached_data = self.fetch_plot_data(ctx);
             self.last_navigation_pos = Some(nav_pos);
+            self.last_selection_range = nav_context.selection_range;
         }
         
         // Draw the plot
         if let Some(plot_data) = &self.cached_data {
-            // Check keyboard modifiers
-            let _modifiers = ui.input(|i| i.modifiers);
+            // Shift-drag selects a range instead of panning
+            let shift_held = ui.input(|i| i.modifiers.shift);
+            let mut range_drag_start = self.range_drag_start;
+            let mut selected_range = None;
             
             // Configure plot with proper axis labels
             let x_axis_name = self.config.x_column.as_deref().unwrap_or("Row Index");
@@ -324,8 +345,8 @@ impl SpaceView for TimeSeriesView {
                 .allow_scroll(true)
                 // Allow zoom with explicit controls
                 .allow_zoom(true)
-                // Allow drag for panning
-                .allow_drag(true)
+                // Allow drag for panning (shift-drag selects a range)
+                .allow_drag(!shift_held)
                 // Right-click drag for box zoom
                 .allow_boxed_zoom(true);
             
@@ -383,6 +404,8 @@ impl SpaceView for TimeSeriesView {
                 ui.label(format!("Series: {}", plot_data.series.len()));
                 ui.separator();
                 ui.label(format!("Points: {}", plot_data.x_values.len()));
+                ui.separator();
+                ui.label(egui::RichText::new("Shift-drag to select a range").weak());
             });
             ui.separator();
             
@@ -401,7 +424,10 @@ impl SpaceView for TimeSeriesView {
                 let nav_context = ctx.navigation.get_context();
                 let cursor_x = match &nav_context.position {
                     NavigationPosition::Sequential(idx) => {
-                        plot_data.x_values.get(*idx).copied().unwrap_or_default()
+                        idx.checked_sub(plot_data.start_row)
+                            .and_then(|i| plot_data.x_values.get(i))
+                            .copied()
+                            .unwrap_or_default()
                     }
                     NavigationPosition::Temporal(ts) => *ts as f64,
                     NavigationPosition::Categorical(_) => 0.0,
@@ -426,7 +452,7 @@ impl SpaceView for TimeSeriesView {
                         // Update cursor position to snap to nearest data point
                         if let Some(index) = plot_data.x_values.iter().position(|&x| x == best_x) {
                             let _ = ctx.navigation.seek_to(
-                                dv_core::navigation::NavigationPosition::Sequential(index)
+                                dv_core::navigation::NavigationPosition::Sequential(plot_data.start_row + index)
                             );
                         }
                     }
@@ -454,6 +480,28 @@ impl SpaceView for TimeSeriesView {
                     }
                 }
                 
+                // SHIFT-DRAG: Select the rows between the drag start and the pointer
+                let response = plot_ui.response();
+                if response.drag_started() && shift_held {
+                    range_drag_start = plot_ui.pointer_coordinate().map(|p| p.x);
+                }
+                if let Some(start_x) = range_drag_start {
+                    let end_x = plot_ui.pointer_coordinate().map(|p| p.x).unwrap_or(start_x);
+                    if response.drag_released() || !response.dragged() {
+                        selected_range = plot_data.rows_between(start_x, end_x);
+                        range_drag_start = None;
+                    } else {
+                        let bounds = plot_ui.plot_bounds();
+                        let (y_min, y_max) = (bounds.min()[1], bounds.max()[1]);
+                        let region = Polygon::new(vec![
+                            [start_x, y_min], [end_x, y_min], [end_x, y_max], [start_x, y_max],
+                        ])
+                        .fill_color(Color32::from_rgb(100, 150, 250).linear_multiply(0.2))
+                        .stroke(egui::Stroke::new(1.0, Color32::from_rgb(100, 150, 250)));
+                        plot_ui.polygon(region);
+                    }
+                }
+                
                 // Draw WHITE vertical marker bar at cursor position - always visible
                 if !plot_data.x_values.is_empty() {
                     if let (Some(x_min), Some(x_max)) = (plot_data.x_values.first(), plot_data.x_values.last()) {
@@ -556,6 +604,11 @@ impl SpaceView for TimeSeriesView {
                     }
                 }
             });
+            
+            self.range_drag_start = range_drag_start;
+            if let Some(range) = selected_range {
+                ctx.navigation.set_range(Some(range));
+            }
         } else {
             // No data message
             ui.centered_and_justified(|ui| {
@@ -3,8 +3,12 @@
 
 use std::collections::HashMap;
 use egui::Ui;
-use egui_dock::{DockArea, DockState, TabViewer};
+use egui_dock::{DockArea, DockState, Node, NodeIndex, SurfaceIndex, TabViewer, Tree};
+use serde_json::Value;
+use dv_core::events::EventBus;
+use dv_core::events::events::{ViewCreated, ViewClosed};
 
+use crate::history::{LayoutChange, WorkspaceCommand};
 use crate::{SpaceView, SpaceViewId, ViewerContext};
 
 /// The main viewport that manages dockable space views
@@ -12,6 +16,57 @@ pub struct Viewport {
     dock_state: DockState<SpaceViewId>,
     space_views: HashMap<SpaceViewId, Box<dyn SpaceView>>,
     time_axis_views: Vec<SpaceViewId>,
+    /// Views added since the last frame, announced as `ViewCreated` events
+    pending_created: Vec<(SpaceViewId, String)>,
+    /// Views replaced by a new layout, announced as `ViewClosed` events
+    pending_closed: Vec<SpaceViewId>,
+    /// Undoable changes not yet collected by `take_changes`
+    pending_changes: Vec<WorkspaceCommand>,
+    /// Dock layout as of the last recorded change
+    settled_dock: DockState<SpaceViewId>,
+    /// View configurations as of the last recorded change
+    settled_configs: HashMap<SpaceViewId, Value>,
+    /// Whether the user has interacted since the configurations were last
+    /// settled. Configurations that change without input (following
+    /// playback, auto-fitting new data) are re-settled rather than recorded.
+    input_since_settle: bool,
+    /// Where each visible view was last drawn
+    view_frames: HashMap<SpaceViewId, ViewFrame>,
+    /// Views the user asked to export, not yet collected by `take_export_requests`
+    export_requests: Vec<(SpaceViewId, ViewExport)>,
+}
+
+/// Arrangement of the views in the main dock area
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViewLayout {
+    /// Views sharing one pane as tabs
+    Tabs(Vec<SpaceViewId>),
+    /// Two layouts side by side (`horizontal`) or one above the other, the
+    /// first taking `fraction` of the space
+    Split {
+        horizontal: bool,
+        fraction: f32,
+        first: Box<ViewLayout>,
+        second: Box<ViewLayout>,
+    },
+}
+
+/// What to export from a view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewExport {
+    /// The view as drawn, as an SVG or PNG image
+    Image,
+    /// The view's result table, or the rows it shows
+    Data,
+}
+
+/// Where a view was drawn in the last frame
+#[derive(Debug, Clone, Copy)]
+pub struct ViewFrame {
+    /// ID of the view's `Ui`, which the IDs of its widgets (and their
+    /// remembered state, like plot zoom) derive from
+    pub ui_id: egui::Id,
+    pub rect: egui::Rect,
 }
 
 impl Viewport {
@@ -20,18 +75,29 @@ impl Viewport {
             dock_state: DockState::new(vec![]),
             space_views: HashMap::new(),
             time_axis_views: Vec::new(),
+            pending_created: Vec::new(),
+            pending_closed: Vec::new(),
+            pending_changes: Vec::new(),
+            settled_dock: DockState::new(vec![]),
+            settled_configs: HashMap::new(),
+            input_since_settle: false,
+            view_frames: HashMap::new(),
+            export_requests: Vec::new(),
         }
     }
     
     /// Add a space view to the viewport
     pub fn add_space_view(&mut self, view: Box<dyn SpaceView>) {
         let id = view.id().clone();
+        let label = format!("Add {}", view.display_name());
+        let dock_before = self.dock_state.clone();
         
         // Track time-series views for cursor synchronization
         if view.view_type() == "TimeSeriesView" {
             self.time_axis_views.push(id.clone());
         }
         
+        self.pending_created.push((id, view.view_type().to_string()));
         self.space_views.insert(id.clone(), view);
         
         // Add to dock state
@@ -42,6 +108,8 @@ impl Viewport {
             // Add subsequent views to the first available leaf
             self.dock_state.push_to_first_leaf(id);
         }
+        
+        self.record_layout_change(label, dock_before, Vec::new(), vec![id]);
     }
     
     /// Create a grid layout from multiple views
@@ -50,23 +118,12 @@ impl Viewport {
             return;
         }
         
-        // Clear existing state
-        self.space_views.clear();
-        self.time_axis_views.clear();
-        
         let view_ids: Vec<SpaceViewId> = views.iter().map(|v| v.id().clone()).collect();
-        
-        // Add all views and track time-series views
-        for view in views {
-            let id = view.id().clone();
-            if view.view_type() == "TimeSeriesView" {
-                self.time_axis_views.push(id.clone());
-            }
-            self.space_views.insert(id, view);
-        }
+        let (dock_before, old_views) = self.replace_views(views);
         
         // Create dock state with grid layout
-        self.dock_state = create_grid_dock_state(view_ids);
+        self.dock_state = create_grid_dock_state(view_ids.clone());
+        self.record_layout_change("Create layout".to_string(), dock_before, old_views, view_ids);
     }
     
     /// Create a grid layout with explicit configuration
@@ -75,8 +132,42 @@ impl Viewport {
             return;
         }
         
+        let view_ids: Vec<SpaceViewId> = views.iter().map(|v| v.id().clone()).collect();
+        let (dock_before, old_views) = self.replace_views(views);
+        
+        // Create dock state based on grid configuration
+        self.dock_state = create_grid_from_config(layout_config);
+        self.record_layout_change("Create layout".to_string(), dock_before, old_views, view_ids);
+    }
+    
+    /// Replace all views with `views` arranged as in `dock_state`. Views it
+    /// doesn't place are added to the first pane.
+    pub fn create_docked_layout(&mut self, views: Vec<Box<dyn SpaceView>>, dock_state: DockState<SpaceViewId>) {
+        if views.is_empty() {
+            return;
+        }
+
+        let view_ids: Vec<SpaceViewId> = views.iter().map(|v| v.id()).collect();
+        let (dock_before, old_views) = self.replace_views(views);
+
+        self.dock_state = dock_state;
+        for id in &view_ids {
+            if self.dock_state.find_tab(id).is_none() {
+                if self.dock_state.main_surface().is_empty() {
+                    self.dock_state = DockState::new(vec![*id]);
+                } else {
+                    self.dock_state.push_to_first_leaf(*id);
+                }
+            }
+        }
+        self.record_layout_change("Create layout".to_string(), dock_before, old_views, view_ids);
+    }
+
+    /// Replace all views, returning the previous dock state and views
+    fn replace_views(&mut self, views: Vec<Box<dyn SpaceView>>) -> (DockState<SpaceViewId>, Vec<Box<dyn SpaceView>>) {
         // Clear existing state
-        self.space_views.clear();
+        let old_views: Vec<Box<dyn SpaceView>> = self.space_views.drain().map(|(_, view)| view).collect();
+        self.pending_closed.extend(old_views.iter().map(|view| view.id()));
         self.time_axis_views.clear();
         
         // Add all views and track time-series views
@@ -85,11 +176,26 @@ impl Viewport {
             if view.view_type() == "TimeSeriesView" {
                 self.time_axis_views.push(id.clone());
             }
+            self.pending_created.push((id, view.view_type().to_string()));
             self.space_views.insert(id, view);
         }
         
-        // Create dock state based on grid configuration
-        self.dock_state = create_grid_from_config(layout_config);
+        (self.dock_state.clone(), old_views)
+    }
+    
+    /// Close every view and forget the layout, announcing the closes on
+    /// `events`. Views whose creation was never announced close silently.
+    pub fn clear(&mut self, events: &EventBus) {
+        let unannounced: Vec<SpaceViewId> = self.pending_created.iter().map(|(id, _)| *id).collect();
+        let closed = self.pending_closed.drain(..)
+            .chain(self.space_views.keys().copied().filter(|id| !unannounced.contains(id)))
+            .collect::<Vec<_>>();
+        for id in closed {
+            events.publish(ViewClosed {
+                view_id: id.to_string(),
+            });
+        }
+        *self = Viewport::new();
     }
     
     /// Check if the viewport has any views
@@ -97,13 +203,160 @@ impl Viewport {
         self.space_views.is_empty()
     }
     
+    /// Views in dock order
+    pub fn views(&self) -> impl Iterator<Item = &dyn SpaceView> + '_ {
+        self.dock_state.iter_all_tabs()
+            .filter_map(|(_, id)| self.space_views.get(id))
+            .map(|view| view.as_ref())
+    }
+
+    /// Arrangement of the dock area, tabs and all
+    pub fn dock_state(&self) -> &DockState<SpaceViewId> {
+        &self.dock_state
+    }
+
+    /// Get a view by ID
+    pub fn view_mut(&mut self, id: &SpaceViewId) -> Option<&mut Box<dyn SpaceView>> {
+        self.space_views.get_mut(id)
+    }
+    
+    /// Arrangement of the views in the main dock area, or `None` when there are none
+    pub fn layout(&self) -> Option<ViewLayout> {
+        layout_of(self.dock_state.main_surface(), NodeIndex::root())
+    }
+    
+    /// Where a view was drawn in the last frame, if it was visible
+    pub fn view_frame(&self, id: &SpaceViewId) -> Option<ViewFrame> {
+        self.view_frames.get(id).copied()
+    }
+    
+    /// Collect the views chosen for export from their tab menus since the last call
+    pub fn take_export_requests(&mut self) -> Vec<(SpaceViewId, ViewExport)> {
+        std::mem::take(&mut self.export_requests)
+    }
+    
+    /// Collect undoable changes made since the last call
+    pub fn take_changes(&mut self) -> Vec<WorkspaceCommand> {
+        std::mem::take(&mut self.pending_changes)
+    }
+    
+    /// Swap the viewport's current layout with the other side of `change`
+    pub(crate) fn swap_layout(&mut self, change: &mut LayoutChange) {
+        let mut removed = Vec::new();
+        for id in change.remove.drain(..) {
+            if let Some(view) = self.space_views.remove(&id) {
+                self.pending_closed.push(id);
+                self.settled_configs.remove(&id);
+                removed.push(view);
+            }
+        }
+        
+        for view in change.views.drain(..) {
+            let id = view.id();
+            self.pending_created.push((id, view.view_type().to_string()));
+            change.remove.push(id);
+            self.space_views.insert(id, view);
+        }
+        change.views = removed;
+        
+        std::mem::swap(&mut self.dock_state, &mut change.dock_state);
+        
+        self.time_axis_views = self.space_views.values()
+            .filter(|view| view.view_type() == "TimeSeriesView")
+            .map(|view| view.id())
+            .collect();
+    }
+    
+    /// Treat the current layout and view configurations as settled, so
+    /// changes applied by undo/redo aren't recorded again
+    pub(crate) fn resync_history_snapshots(&mut self) {
+        self.settled_dock = self.dock_state.clone();
+        self.settled_configs = self.space_views.iter()
+            .map(|(id, view)| (*id, view.save_config()))
+            .collect();
+        self.input_since_settle = false;
+    }
+    
+    fn record_layout_change(
+        &mut self,
+        label: String,
+        dock_state: DockState<SpaceViewId>,
+        views: Vec<Box<dyn SpaceView>>,
+        remove: Vec<SpaceViewId>,
+    ) {
+        self.pending_changes.push(WorkspaceCommand::Layout(LayoutChange {
+            label,
+            dock_state,
+            views,
+            remove,
+        }));
+        self.settled_dock = self.dock_state.clone();
+    }
+    
+    /// Record layout rearrangements and view setting edits made through the
+    /// UI. Called once an interaction has finished, so each interaction
+    /// becomes at most one edit per view.
+    fn detect_changes(&mut self, closed: Vec<Box<dyn SpaceView>>) {
+        if !closed.is_empty() {
+            let label = match closed.as_slice() {
+                [view] => format!("Close {}", view.display_name()),
+                _ => format!("Close {} views", closed.len()),
+            };
+            for view in &closed {
+                self.settled_configs.remove(&view.id());
+            }
+            let dock_before = self.settled_dock.clone();
+            self.record_layout_change(label, dock_before, closed, Vec::new());
+        } else if layout_signature(&self.dock_state) != layout_signature(&self.settled_dock) {
+            let dock_before = self.settled_dock.clone();
+            self.record_layout_change("Rearrange layout".to_string(), dock_before, Vec::new(), Vec::new());
+        }
+        
+        for (id, view) in &self.space_views {
+            let config = view.save_config();
+            match self.settled_configs.get_mut(id) {
+                Some(settled) if *settled != config && !self.input_since_settle => {
+                    *settled = config;
+                }
+                Some(settled) if *settled != config => {
+                    let before = std::mem::replace(settled, config.clone());
+                    self.pending_changes.push(WorkspaceCommand::EditConfig {
+                        view_id: *id,
+                        before,
+                        after: config,
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    // First frame of a new view: take its configuration as the baseline
+                    self.settled_configs.insert(*id, config);
+                }
+            }
+        }
+    }
+    
     /// Draw the viewport
     pub fn ui(&mut self, ui: &mut Ui, viewer_context: &ViewerContext) {
         // Update context with current time axis views
         *viewer_context.time_axis_views.write() = self.time_axis_views.clone();
         
+        // Announce lifecycle changes since the last frame
+        for id in self.pending_closed.drain(..) {
+            viewer_context.events.publish(ViewClosed {
+                view_id: id.to_string(),
+            });
+        }
+        for (id, view_type) in self.pending_created.drain(..) {
+            viewer_context.events.publish(ViewCreated {
+                view_id: id.to_string(),
+                view_type,
+            });
+        }
+        
         // The dock area should fill the available space in the UI
         let available_rect = ui.available_rect_before_wrap();
+        let mut closed = Vec::new();
+        self.view_frames.clear();
         
         ui.allocate_ui(available_rect.size(), |ui| {
             DockArea::new(&mut self.dock_state)
@@ -112,15 +365,100 @@ impl Viewport {
                 .show_tab_name_on_hover(true)
                 .show_inside(ui, &mut ViewportTabViewer {
                     space_views: &mut self.space_views,
+                    closed: &mut closed,
+                    view_frames: &mut self.view_frames,
+                    export_requests: &mut self.export_requests,
                     viewer_context,
                 });
         });
+        
+        // Only record once interactions (drags, scrolling, held keys) have
+        // finished, crediting them with what changed since the last settle
+        let (input, interacting) = ui.input(|i| {
+            let interacting = i.pointer.any_down()
+                || !i.keys_down.is_empty()
+                || i.scroll_delta != egui::Vec2::ZERO
+                || i.zoom_delta() != 1.0;
+            let input = i.events.iter().any(|event| matches!(
+                event,
+                egui::Event::PointerButton { .. } | egui::Event::Key { .. } | egui::Event::Text(_) | egui::Event::Paste(_)
+            ));
+            (input || interacting, interacting)
+        });
+        self.input_since_settle |= input;
+        if !closed.is_empty() || !interacting {
+            self.detect_changes(closed);
+        }
+        if !interacting {
+            self.input_since_settle = false;
+        }
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Structure of a dock layout, ignoring transient state like rects,
+/// scrolling and which tab is active
+#[derive(PartialEq)]
+enum LayoutToken {
+    Empty,
+    Leaf(Vec<SpaceViewId>),
+    Split { horizontal: bool, fraction_pct: i32 },
+}
+
+fn layout_signature(dock_state: &DockState<SpaceViewId>) -> Vec<(usize, LayoutToken)> {
+    dock_state.iter_all_nodes()
+        .map(|(surface, node)| {
+            let token = match node {
+                Node::Empty => LayoutToken::Empty,
+                Node::Leaf { tabs, .. } => LayoutToken::Leaf(tabs.clone()),
+                Node::Horizontal { fraction, .. } => LayoutToken::Split {
+                    horizontal: true,
+                    fraction_pct: (fraction * 100.0).round() as i32,
+                },
+                Node::Vertical { fraction, .. } => LayoutToken::Split {
+                    horizontal: false,
+                    fraction_pct: (fraction * 100.0).round() as i32,
+                },
+            };
+            (surface.0, token)
+        })
+        .collect()
+}
+
+fn layout_of(tree: &Tree<SpaceViewId>, index: NodeIndex) -> Option<ViewLayout> {
+    if index.0 >= tree.len() {
+        return None;
+    }
+    let (horizontal, fraction) = match &tree[index] {
+        Node::Empty => return None,
+        Node::Leaf { tabs, .. } => return (!tabs.is_empty()).then(|| ViewLayout::Tabs(tabs.clone())),
+        Node::Horizontal { fraction, .. } => (true, *fraction),
+        Node::Vertical { fraction, .. } => (false, *fraction),
+    };
+    // A split with an empty side is just the other side
+    match (layout_of(tree, index.left()), layout_of(tree, index.right())) {
+        (Some(first), Some(second)) => Some(ViewLayout::Split {
+            horizontal,
+            fraction,
+            first: Box::new(first),
+            second: Box::new(second),
+        }),
+        (first, second) => first.or(second),
     }
 }
 
 /// Tab viewer for egui_dock
 struct ViewportTabViewer<'a> {
     space_views: &'a mut HashMap<SpaceViewId, Box<dyn SpaceView>>,
+    /// Views closed this frame, kept so the close can be undone
+    closed: &'a mut Vec<Box<dyn SpaceView>>,
+    view_frames: &'a mut HashMap<SpaceViewId, ViewFrame>,
+    export_requests: &'a mut Vec<(SpaceViewId, ViewExport)>,
     viewer_context: &'a ViewerContext,
 }
 
@@ -137,12 +475,32 @@ impl<'a> TabViewer for ViewportTabViewer<'a> {
     
     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
         if let Some(view) = self.space_views.get_mut(tab) {
+            self.view_frames.insert(*tab, ViewFrame {
+                ui_id: ui.id(),
+                rect: ui.available_rect_before_wrap(),
+            });
             view.ui(self.viewer_context, ui);
         }
     }
     
+    fn context_menu(&mut self, ui: &mut Ui, tab: &mut Self::Tab, _surface: SurfaceIndex, _node: NodeIndex) {
+        if ui.button("🖼 Export as SVG...").on_hover_text("Save this view as a vector image").clicked() {
+            self.export_requests.push((*tab, ViewExport::Image));
+            ui.close_menu();
+        }
+        if ui.button("💾 Export data...").on_hover_text("Save the numbers behind this view as CSV or Parquet").clicked() {
+            self.export_requests.push((*tab, ViewExport::Data));
+            ui.close_menu();
+        }
+    }
+    
     fn on_close(&mut self, tab: &mut Self::Tab) -> bool {
-        self.space_views.remove(tab);
+        if let Some(view) = self.space_views.remove(tab) {
+            self.closed.push(view);
+        }
+        self.viewer_context.events.publish(ViewClosed {
+            view_id: tab.to_string(),
+        });
         true
     }
 }
@@ -161,8 +519,6 @@ pub struct GridCell {
 
 /// Create a grid layout for the dock state
 fn create_grid_dock_state(view_ids: Vec<SpaceViewId>) -> DockState<SpaceViewId> {
-    use egui_dock::NodeIndex;
-    
     if view_ids.is_empty() {
         return DockState::new(vec![]);
     }
@@ -259,4 +615,80 @@ fn create_grid_from_config(config: GridLayoutConfig) -> DockState<SpaceViewId> {
         .map(|cell| cell.view_id)
         .collect();
     create_grid_dock_state(view_ids)
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableView;
+
+    #[test]
+    fn layout_follows_the_dock_tree() {
+        let mut viewport = Viewport::new();
+        assert_eq!(viewport.layout(), None);
+
+        let views: Vec<Box<dyn SpaceView>> = (0..3)
+            .map(|i| Box::new(TableView::new(uuid::Uuid::new_v4(), format!("Table {}", i))) as Box<dyn SpaceView>)
+            .collect();
+        let ids: Vec<SpaceViewId> = views.iter().map(|view| view.id()).collect();
+        viewport.create_grid_layout(views);
+
+        // One on top, two below
+        assert_eq!(viewport.layout(), Some(ViewLayout::Split {
+            horizontal: false,
+            fraction: 0.5,
+            first: Box::new(ViewLayout::Tabs(vec![ids[0]])),
+            second: Box::new(ViewLayout::Split {
+                horizontal: true,
+                fraction: 0.5,
+                first: Box::new(ViewLayout::Tabs(vec![ids[1]])),
+                second: Box::new(ViewLayout::Tabs(vec![ids[2]])),
+            }),
+        }));
+    }
+
+    #[test]
+    fn clearing_announces_closed_views() {
+        let events = EventBus::new();
+        let closed = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = closed.clone();
+        let _sub = events.subscribe::<ViewClosed, _>(move |e| sink.lock().push(e.view_id.clone()));
+
+        let mut viewport = Viewport::new();
+        let shown = Box::new(TableView::new(uuid::Uuid::new_v4(), "Shown".to_string()));
+        let shown_id = shown.id();
+        viewport.add_space_view(shown);
+        viewport.pending_created.clear();
+        viewport.add_space_view(Box::new(TableView::new(uuid::Uuid::new_v4(), "Never drawn".to_string())));
+
+        viewport.clear(&events);
+        events.dispatch();
+        assert!(viewport.is_empty());
+        assert_eq!(*closed.lock(), vec![shown_id.to_string()]);
+    }
+
+    #[test]
+    fn only_edits_made_by_input_are_recorded() {
+        let mut viewport = Viewport::new();
+        let view = Box::new(TableView::new(uuid::Uuid::new_v4(), "Table".to_string()));
+        let id = view.id();
+        viewport.add_space_view(view);
+        viewport.detect_changes(Vec::new());
+        assert_eq!(viewport.take_changes().len(), 1);
+
+        // Changes nobody asked for (e.g. following playback) become the new baseline
+        viewport.view_mut(&id).unwrap().load_config(serde_json::json!({ "striped_rows": false }));
+        viewport.detect_changes(Vec::new());
+        assert!(viewport.take_changes().is_empty());
+
+        viewport.input_since_settle = true;
+        viewport.view_mut(&id).unwrap().load_config(serde_json::json!({ "striped_rows": true }));
+        viewport.detect_changes(Vec::new());
+        match viewport.take_changes().as_slice() {
+            [WorkspaceCommand::EditConfig { view_id, before, after }] => {
+                assert_eq!(*view_id, id);
+                assert_eq!((&before["striped_rows"], &after["striped_rows"]), (&Value::Bool(false), &Value::Bool(true)));
+            }
+            _ => panic!("expected one config edit"),
+        }
+    }
+}